@@ -1,15 +1,15 @@
 mod errors;
-pub(crate) mod output;
 pub mod report;
 
-use crate::cli::output::OutputConfig;
 use crate::commands::{Commands, run_commands};
 use clap::Parser;
 use clap::builder::styling::AnsiColor::{Cyan, Green, Red, Yellow};
 use clap::builder::styling::Effects;
 use clap::builder::styling::Styles;
-use console::{colors_enabled, set_colors_enabled};
+use dotenvy::dotenv;
 use errors::display_error;
+use news_core::config::{ConfigOverrides, LoadedConfig};
+use std::path::PathBuf;
 use std::process::exit;
 
 const STYLES: Styles = Styles::styled()
@@ -35,18 +35,39 @@ pub struct Cli {
 
     #[arg(long, global = true)]
     no_color: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Configuration file to read instead of UNINEWS_CONFIG_PATH"
+    )]
+    config: Option<PathBuf>,
 }
 
-pub async fn run() {
-    let cli = Cli::parse();
+impl Cli {
+    fn config_overrides(&self) -> ConfigOverrides {
+        let mut overrides = ConfigOverrides::default().with_file(self.config.clone());
+
+        if self.verbose {
+            overrides = overrides.with_flag("--verbose", "output.verbose", true);
+        }
+
+        if self.no_color {
+            overrides = overrides.with_flag("--no-color", "output.color", "never");
+        }
 
-    if cli.no_color {
-        set_colors_enabled(false);
+        overrides
     }
+}
+
+pub async fn run() {
+    let cli = Cli::parse();
 
-    OutputConfig::init(cli.verbose, colors_enabled());
+    dotenv().ok();
+    let config = LoadedConfig::load(&cli.config_overrides());
 
-    if let Err(e) = run_commands(cli.command).await {
+    if let Err(e) = run_commands(cli.command, config).await {
         display_error(&e);
         exit(1);
     }
@@ -1,6 +1,5 @@
-use crate::cli::output::OutputConfig;
 use crate::report::{ReportFactory, ReportMode, ReportStatus};
-use console::style;
+use console::{colors_enabled, style};
 use std::time::Instant;
 
 pub const INDENT: &str = "  ";
@@ -30,7 +29,7 @@ pub struct Report {
 }
 
 fn print_task() -> String {
-    match OutputConfig::get().colors_enabled {
+    match colors_enabled() {
         true => style(TASK).dim().to_string(),
         false => TASK.to_string(),
     }
@@ -115,7 +114,7 @@ fn skipped_with_text(&mut self, text: impl Into<String> + Send) {
             s => format!(" {}", s),
         };
 
-        let msg = match OutputConfig::get().colors_enabled {
+        let msg = match colors_enabled() {
             true => style(" ...skipped").yellow().bold().to_string(),
             false => " ...skipped".to_string(),
         };
@@ -140,7 +139,7 @@ fn fail(&mut self) {
         }
 
         self.state = ReportState::Failed;
-        self.end_in_time(match OutputConfig::get().colors_enabled {
+        self.end_in_time(match colors_enabled() {
             true => style(FAIL).red().bold().to_string(),
             false => FAIL.to_string(),
         });
@@ -162,7 +161,7 @@ fn finish_with_text(&mut self, text: impl Into<String> + Send) {
 
         self.state = ReportState::Finished;
         self.end_in_time(
-            match OutputConfig::get().colors_enabled {
+            match colors_enabled() {
                 true => style(SUCCESS).green().bold().to_string(),
                 false => SUCCESS.to_string(),
             } + &text,
@@ -1,27 +1,139 @@
 mod collect;
+mod config;
+mod db;
+mod digest;
+mod export;
 mod init;
+mod media;
+mod news;
+mod notify;
+mod rule;
 mod source;
+mod webhook;
 
 use self::collect::{CollectCommand, run_collect};
+use self::config::{ConfigCommand, run_config};
+use self::db::{DbCommand, run_db};
+use self::digest::{DigestCommand, run_digest};
+use self::export::{ExportCommand, run_export};
 use self::init::{InitCommand, init_app};
+use self::media::{MediaCommand, run_media};
+use self::news::{NewsCommand, run_news};
+use self::notify::{NotifyCommand, run_notify};
+use self::rule::{RuleCommand, run_rule};
 use self::source::{SourceCommand, run_source};
+use self::webhook::{WebhookCommand, run_webhook};
 use crate::configure::configure;
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use news_core::config::{ConfigError, LoadedConfig, Settings};
+use news_watch::state::AppState;
+use news_watch::storage::Access;
+use std::sync::Arc;
 
 #[derive(Subcommand)]
 pub enum Commands {
     Collect(CollectCommand),
+    Config(ConfigCommand),
+    Db(DbCommand),
+    Digest(DigestCommand),
+    Export(ExportCommand),
     Init(InitCommand),
+    Media(MediaCommand),
+    News(NewsCommand),
+    Notify(NotifyCommand),
+    Rule(RuleCommand),
     Source(SourceCommand),
+    Webhook(WebhookCommand),
 }
 
-pub async fn run_commands(command: Commands) -> Result<()> {
-    configure()?;
+async fn open_state(settings: &Settings, access: Access) -> Result<Arc<AppState>> {
+    let state = AppState::open(settings, access)
+        .await
+        .context("Failed to open storage")?;
+    Ok(Arc::new(state))
+}
+
+pub async fn run_commands(
+    command: Commands,
+    config: Result<LoadedConfig, ConfigError>,
+) -> Result<()> {
+    // Only `config` commands can run with a broken configuration, to report on it.
+    let config = match (&command, config) {
+        (Commands::Config(_), config) => config,
+        (_, config) => Ok(config.context("Failed to load configuration")?),
+    };
+
+    if let Ok(config) = &config {
+        configure(&config.settings)?;
+    }
+
+    let (command, config) = match (command, config) {
+        (Commands::Config(cmd), config) => {
+            return run_config(cmd, config)
+                .await
+                .context("Config command failed");
+        }
+        (command, config) => (command, config?),
+    };
+
+    let settings = &config.settings;
 
     match command {
-        Commands::Collect(cmd) => run_collect(cmd).await.context("Collect command failed"),
-        Commands::Init(cmd) => init_app(cmd).await.context("Initialization failed"),
-        Commands::Source(cmd) => run_source(cmd).await.context("Source command failed"),
+        Commands::Config(_) => unreachable!("handled above"),
+        Commands::Collect(cmd) => {
+            let state = open_state(settings, Access::ReadWrite).await?;
+            run_collect(cmd, state)
+                .await
+                .context("Collect command failed")
+        }
+        Commands::Db(cmd) => run_db(cmd, settings)
+            .await
+            .context("Database command failed"),
+        Commands::Digest(cmd) => {
+            let state = open_state(settings, Access::ReadOnly).await?;
+            run_digest(cmd, &settings.smtp, state)
+                .await
+                .context("Digest command failed")
+        }
+        Commands::Export(cmd) => {
+            let state = open_state(settings, Access::ReadOnly).await?;
+            run_export(cmd, state)
+                .await
+                .context("Export command failed")
+        }
+        Commands::Init(cmd) => init_app(cmd, settings)
+            .await
+            .context("Initialization failed"),
+        Commands::Media(cmd) => {
+            let state = open_state(settings, Access::ReadWrite).await?;
+            run_media(cmd, state).await.context("Media command failed")
+        }
+        Commands::News(cmd) => {
+            let state = open_state(settings, cmd.access()).await?;
+            run_news(cmd, state).await.context("News command failed")
+        }
+        Commands::Notify(cmd) => {
+            let state = open_state(settings, Access::ReadWrite).await?;
+            run_notify(cmd, state)
+                .await
+                .context("Notify command failed")
+        }
+        Commands::Rule(cmd) => {
+            let state = open_state(settings, Access::ReadWrite).await?;
+            run_rule(cmd, state).await.context("Rule command failed")
+        }
+        Commands::Source(cmd) => {
+            let state = open_state(settings, cmd.access()).await?;
+            run_source(cmd, state)
+                .await
+                .context("Source command failed")
+        }
+        Commands::Webhook(cmd) => {
+            let state = open_state(settings, Access::ReadWrite).await?;
+            run_webhook(cmd, state)
+                .await
+                .context("Webhook command failed")
+        }
     }
 }
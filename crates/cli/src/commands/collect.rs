@@ -1,9 +1,15 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use futures::future::try_join_all;
-use news_core::services::source::GetAllError;
 use news_core::services::source::SourceService;
-use news_watch::cli::{app_state, source_watch};
+use news_watch::cli::watch_source;
+use news_watch::media::watch_media;
+use news_watch::notify::watch_notifications;
+use news_watch::retention::watch_retention;
+use news_watch::state::AppState;
+use news_watch::webhook::watch_webhooks;
+use std::sync::Arc;
+use tokio::try_join;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -19,13 +25,8 @@ pub struct CollectCommand {
     watch: bool, // @todo: implement continuous watching mode that runs source update checks periodically instead of one-time collection
 }
 
-pub async fn run_collect(_cmd: CollectCommand) -> Result<()> {
-    let app_state = app_state().await;
-
-    let sources = app_state
-        .sources()
-        .await
-        .map_err(|e| GetAllError(Box::new(e)))?;
+pub async fn run_collect(_cmd: CollectCommand, app_state: Arc<AppState>) -> Result<()> {
+    let sources = app_state.sources();
 
     let sources_list = sources
         .get_all()
@@ -34,12 +35,46 @@ pub async fn run_collect(_cmd: CollectCommand) -> Result<()> {
 
     let tasks = sources_list
         .into_iter()
-        .map(source_watch)
+        .map(|source| watch_source(app_state.clone(), source))
         .collect::<Vec<_>>();
 
-    try_join_all(tasks)
-        .await
-        .context("Failed to watch content from sources")?;
+    let watch_sources = async {
+        try_join_all(tasks)
+            .await
+            .context("Failed to watch content from sources")
+    };
+
+    let deliver_webhooks = async {
+        watch_webhooks(app_state.clone())
+            .await
+            .context("Failed to deliver webhooks")
+    };
+
+    let send_notifications = async {
+        watch_notifications(app_state.clone())
+            .await
+            .context("Failed to send notifications")
+    };
+
+    let prune_articles = async {
+        watch_retention(app_state.clone())
+            .await
+            .context("Failed to apply retention policy")
+    };
+
+    let clean_media = async {
+        watch_media(app_state.clone())
+            .await
+            .context("Failed to clean up the media cache")
+    };
+
+    try_join!(
+        watch_sources,
+        deliver_webhooks,
+        send_notifications,
+        prune_articles,
+        clean_media
+    )?;
 
     Ok(())
 }
@@ -0,0 +1,40 @@
+mod init;
+mod show;
+mod validate;
+
+use self::init::{InitConfig, init_config};
+use self::show::{ShowConfig, show_config};
+use self::validate::{ValidateConfig, validate_config};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_core::config::{ConfigError, LoadedConfig};
+
+#[derive(Parser, Debug)]
+#[command(about = "Inspect and create configuration files")]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    command: ConfigCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    #[command(about = "Print the effective configuration and where each value comes from")]
+    Show(ShowConfig),
+
+    #[command(about = "Check configuration files and environment for errors")]
+    Validate(ValidateConfig),
+
+    #[command(about = "Write a configuration file with default values")]
+    Init(InitConfig),
+}
+
+pub async fn run_config(
+    cmd: ConfigCommand,
+    config: Result<LoadedConfig, ConfigError>,
+) -> Result<()> {
+    match cmd.command {
+        ConfigCommands::Show(args) => show_config(config?, args),
+        ConfigCommands::Validate(args) => validate_config(config, args).await,
+        ConfigCommands::Init(args) => init_config(args).await,
+    }
+}
@@ -0,0 +1,64 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Args;
+use news_core::config::{Settings, user_config_path};
+use news_sqlite_core::utils::fs::write_to_file;
+use std::path::PathBuf;
+use tokio::fs::try_exists;
+
+const HEADER: &str = "\
+# UniNews configuration.
+#
+# Values are layered: defaults, /etc/uninews/config.toml, the user file,
+# UNINEWS_CONFIG_PATH (or --config), environment variables and CLI flags.
+# Run `uninews config show` to see the effective values and their origins.
+";
+
+#[derive(Debug, Args)]
+pub struct InitConfig {
+    #[arg(
+        long,
+        help = "Where to write the file (default: $XDG_CONFIG_HOME/uninews/config.toml)"
+    )]
+    path: Option<PathBuf>,
+
+    #[arg(short, long, help = "Overwrite an existing file")]
+    force: bool,
+}
+
+pub async fn init_config(args: InitConfig) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let path = args
+                .path
+                .or_else(user_config_path)
+                .ok_or_else(|| anyhow!("Cannot locate the user config directory, pass --path"))?;
+
+            let exists = try_exists(&path)
+                .await
+                .context("Failed to check if configuration file exists")?;
+
+            if exists && !args.force {
+                bail!(
+                    "Configuration file {} already exists, use --force to overwrite it",
+                    path.display()
+                );
+            }
+
+            let defaults = toml::to_string_pretty(&Settings::default())
+                .context("Failed to serialize default configuration")?;
+
+            write_to_file(
+                &path.display().to_string(),
+                &format!("{HEADER}\n{defaults}"),
+            )
+            .await
+            .context("Failed to write configuration file")?;
+
+            task.finish_with_text(format!("Configuration written to {}", path.display()));
+            Ok(())
+        })
+    })
+    .await
+}
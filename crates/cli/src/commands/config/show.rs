@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::Args;
+use news_core::config::LoadedConfig;
+
+#[derive(Debug, Args)]
+pub struct ShowConfig {}
+
+pub fn show_config(config: LoadedConfig, _args: ShowConfig) -> Result<()> {
+    match config.files.is_empty() {
+        true => println!("# No configuration files found"),
+        false => {
+            for file in &config.files {
+                println!("# Loaded {}", file.display());
+            }
+        }
+    }
+
+    for (section, values) in config.entries() {
+        let lines: Vec<(String, String)> = values
+            .into_iter()
+            .map(|entry| {
+                let line = format!("{} = {}", entry.key, entry.value);
+                (line, entry.origin.to_string())
+            })
+            .collect();
+
+        let width = lines.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+
+        println!();
+        println!("[{section}]");
+        for (line, origin) in lines {
+            println!("{line:<width$}  # {origin}");
+        }
+    }
+
+    Ok(())
+}
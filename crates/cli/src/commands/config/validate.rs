@@ -0,0 +1,44 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::config::{ConfigError, LoadedConfig};
+use std::path::PathBuf;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Args)]
+pub struct ValidateConfig {
+    #[arg(help = "Check only this file, on top of the defaults")]
+    path: Option<PathBuf>,
+}
+
+pub async fn validate_config(
+    config: Result<LoadedConfig, ConfigError>,
+    args: ValidateConfig,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let config = match &args.path {
+                Some(path) => LoadedConfig::load_file(path)?,
+                None => config?,
+            };
+
+            EnvFilter::try_new(&config.settings.log.level)
+                .with_context(|| format!("Invalid log.level: {}", config.settings.log.level))?;
+
+            let files: Vec<String> = config
+                .files
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect();
+
+            task.finish_with_text(match files.is_empty() {
+                true => "Configuration is valid (defaults and environment only)".to_string(),
+                false => format!("Configuration is valid: {}", files.join(", ")),
+            });
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,99 @@
+mod backup;
+mod check;
+mod migrate;
+mod prune;
+mod stats;
+mod status;
+mod vacuum;
+
+use self::backup::{BackupDb, backup_db};
+use self::check::{CheckDb, check_db};
+use self::migrate::{MigrateDb, migrate_db};
+use self::prune::{PruneDb, prune_db};
+use self::stats::{StatsDb, stats_db};
+use self::status::{StatusDb, status_db};
+use self::vacuum::{VacuumDb, vacuum_db};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use news_core::config::Settings;
+use news_sqlite_core::db::init::connect_db_pool;
+use news_watch::storage::Access;
+use sqlx::SqlitePool;
+
+#[derive(Parser, Debug)]
+#[command(about = "Maintain the application database")]
+pub struct DbCommand {
+    #[command(subcommand)]
+    command: DbCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommands {
+    #[command(about = "Apply pending schema migrations")]
+    Migrate(MigrateDb),
+
+    #[command(about = "Show applied and pending schema migrations")]
+    Status(StatusDb),
+
+    #[command(about = "Copy the database to a file, safe while collect is running")]
+    Backup(BackupDb),
+
+    #[command(about = "Rebuild the database file to reclaim unused space")]
+    Vacuum(VacuumDb),
+
+    #[command(about = "Check integrity, foreign keys and orphaned rows")]
+    Check(CheckDb),
+
+    #[command(about = "Show row counts and sizes per table and articles per source")]
+    Stats(StatsDb),
+
+    #[command(about = "Delete articles outside the configured retention policy")]
+    Prune(PruneDb),
+}
+
+pub(super) fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{value:.1} {}", UNITS[unit]),
+    }
+}
+
+async fn connect(settings: &Settings) -> Result<SqlitePool> {
+    // Deliberately not `open_db_pool`, which would migrate before `status` could report.
+    connect_db_pool(&settings.database)
+        .await
+        .context("Failed to connect to database")
+}
+
+pub async fn run_db(cmd: DbCommand, settings: &Settings) -> Result<()> {
+    match cmd.command {
+        DbCommands::Migrate(args) => {
+            migrate_db(
+                connect(settings).await?,
+                settings.database.sqlite_path().to_path_buf(),
+                args,
+            )
+            .await
+        }
+        DbCommands::Status(args) => status_db(connect(settings).await?, args).await,
+        DbCommands::Backup(args) => backup_db(connect(settings).await?, args).await,
+        DbCommands::Vacuum(args) => vacuum_db(connect(settings).await?, args).await,
+        DbCommands::Check(args) => check_db(connect(settings).await?, args).await,
+        DbCommands::Stats(args) => stats_db(connect(settings).await?, args).await,
+        // Goes through the application services, which migrate on open.
+        DbCommands::Prune(args) => {
+            let state = super::open_state(settings, Access::ReadWrite).await?;
+            prune_db(&state, args).await
+        }
+    }
+}
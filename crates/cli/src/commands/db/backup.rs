@@ -0,0 +1,60 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use news_sqlite_core::db::maintenance::backup;
+use news_sqlite_core::utils::fs::create_parent_dirs;
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+use tokio::fs::{metadata, remove_file, try_exists};
+
+#[derive(Debug, Args)]
+pub struct BackupDb {
+    #[arg(help = "File to write the copy to")]
+    path: PathBuf,
+
+    #[arg(short, long, help = "Overwrite an existing file")]
+    force: bool,
+}
+
+pub async fn backup_db(pool: SqlitePool, args: BackupDb) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let path = args.path;
+
+            if try_exists(&path)
+                .await
+                .context("Failed to check if backup file exists")?
+            {
+                if !args.force {
+                    bail!(
+                        "Backup file {} already exists, use --force to overwrite it",
+                        path.display()
+                    );
+                }
+
+                remove_file(&path)
+                    .await
+                    .context("Failed to remove existing backup file")?;
+            }
+
+            create_parent_dirs(&path)
+                .await
+                .context("Failed to create parent directories")?;
+
+            backup(&pool, &path)
+                .await
+                .context(format!("Failed to back up database to {}", path.display()))?;
+
+            let bytes = metadata(&path).await.map_or(0, |meta| meta.len());
+
+            task.finish_with_text(format!(
+                "Database copied to {} ({})",
+                path.display(),
+                super::format_bytes(i64::try_from(bytes).unwrap_or(i64::MAX))
+            ));
+            Ok(())
+        })
+    })
+    .await
+}
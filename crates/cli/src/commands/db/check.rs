@@ -0,0 +1,45 @@
+use anyhow::{Result, bail};
+use clap::Args;
+use news_sqlite_core::db::maintenance::check;
+use sqlx::SqlitePool;
+
+#[derive(Debug, Args)]
+pub struct CheckDb {}
+
+pub async fn check_db(pool: SqlitePool, _args: CheckDb) -> Result<()> {
+    let report = check(&pool).await?;
+
+    match report.integrity.is_empty() {
+        true => println!("integrity: ok"),
+        false => {
+            for message in &report.integrity {
+                println!("integrity: {message}");
+            }
+        }
+    }
+
+    match report.foreign_keys.is_empty() {
+        true => println!("foreign keys: ok"),
+        false => {
+            for violation in &report.foreign_keys {
+                let rowid = violation
+                    .rowid
+                    .map_or_else(|| "?".to_string(), |rowid| rowid.to_string());
+                println!(
+                    "foreign keys: {} row {rowid} references missing {}",
+                    violation.table, violation.parent
+                );
+            }
+        }
+    }
+
+    for orphans in &report.orphans {
+        println!("orphans: {} {}", orphans.count, orphans.description);
+    }
+
+    if !report.is_ok() {
+        bail!("Database check found problems");
+    }
+
+    Ok(())
+}
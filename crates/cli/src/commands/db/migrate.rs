@@ -0,0 +1,31 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::Result;
+use clap::Args;
+use news_sqlite_core::db::migrate::migrate;
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct MigrateDb {}
+
+pub async fn migrate_db(pool: SqlitePool, db_path: PathBuf, _args: MigrateDb) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let outcome = migrate(&pool, &db_path).await?;
+
+            let text = match (outcome.applied.len(), outcome.backup) {
+                (0, _) => "Database schema is up to date".to_string(),
+                (count, Some(backup)) => format!(
+                    "Applied {count} migrations, previous database saved to {}",
+                    backup.display()
+                ),
+                (count, None) => format!("Applied {count} migrations"),
+            };
+
+            task.finish_with_text(text);
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::source::SourceService;
+use news_watch::retention::prune;
+use news_watch::state::AppState;
+use std::collections::HashMap;
+
+#[derive(Debug, Args)]
+pub struct PruneDb {
+    #[arg(long, help = "Only report what the retention policy would delete")]
+    dry_run: bool,
+}
+
+pub async fn prune_db(app_state: &AppState, args: PruneDb) -> Result<()> {
+    let report = prune(app_state, args.dry_run)
+        .await
+        .context("Failed to apply retention policy")?;
+
+    let sources = app_state
+        .sources()
+        .get_all()
+        .await
+        .context("Failed to load content sources list")?
+        .into_iter()
+        .map(|source| (source.id(), source.url().to_string()))
+        .collect::<HashMap<_, _>>();
+
+    if !report.sources.is_empty() {
+        println!("{:>10} SOURCE", "ARTICLES");
+        for pruned in &report.sources {
+            let name = sources
+                .get(&pruned.source_id)
+                .cloned()
+                .unwrap_or_else(|| pruned.source_id.to_string());
+            println!("{:>10} {name}", pruned.articles);
+        }
+        println!();
+    }
+
+    let verb = if args.dry_run {
+        "Would prune"
+    } else {
+        "Pruned"
+    };
+    println!(
+        "{verb} {} articles from {} sources",
+        report.total(),
+        report.sources.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use clap::Args;
+use news_sqlite_core::db::maintenance::stats;
+use sqlx::SqlitePool;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Args)]
+pub struct StatsDb {}
+
+pub async fn stats_db(pool: SqlitePool, _args: StatsDb) -> Result<()> {
+    let stats = stats(&pool).await?;
+
+    println!("Database size: {}", super::format_bytes(stats.bytes));
+
+    println!();
+    println!("{:<28} {:>10} {:>12}", "TABLE", "ROWS", "SIZE");
+    for table in &stats.tables {
+        let size = table
+            .bytes
+            .map_or_else(|| "-".to_string(), super::format_bytes);
+        println!("{:<28} {:>10} {size:>12}", table.name, table.rows);
+    }
+
+    println!();
+    println!("{:<9} {:>10} {:<16} SOURCE", "KIND", "ARTICLES", "LATEST");
+    for source in &stats.sources {
+        let latest = source.latest.map_or_else(
+            || "-".to_string(),
+            |at| at.format(DATETIME_FORMAT).to_string(),
+        );
+        println!(
+            "{:<9} {:>10} {latest:<16} {}",
+            source.kind, source.articles, source.name
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use clap::Args;
+use news_sqlite_core::db::migrate::{MigrationStatus, migration_states};
+use sqlx::SqlitePool;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Args)]
+pub struct StatusDb {}
+
+pub async fn status_db(pool: SqlitePool, _args: StatusDb) -> Result<()> {
+    let states = migration_states(&pool).await?;
+
+    for state in &states {
+        let (status, at) = match state.status {
+            MigrationStatus::Applied(at) => ("applied", at.format(DATETIME_FORMAT).to_string()),
+            MigrationStatus::Pending => ("pending", String::new()),
+            MigrationStatus::Failed(at) => ("failed", at.format(DATETIME_FORMAT).to_string()),
+            MigrationStatus::Unknown(at) => ("unknown", at.format(DATETIME_FORMAT).to_string()),
+        };
+
+        println!(
+            "{} {status:<8} {at:<19} {}",
+            state.version, state.description
+        );
+    }
+
+    let count = |expected: fn(&MigrationStatus) -> bool| {
+        states
+            .iter()
+            .filter(|state| expected(&state.status))
+            .count()
+    };
+
+    println!(
+        "{} applied, {} pending, {} failed, {} unknown to this build",
+        count(|status| matches!(status, MigrationStatus::Applied(_))),
+        count(|status| matches!(status, MigrationStatus::Pending)),
+        count(|status| matches!(status, MigrationStatus::Failed(_))),
+        count(|status| matches!(status, MigrationStatus::Unknown(_))),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_sqlite_core::db::maintenance::vacuum;
+use sqlx::SqlitePool;
+
+#[derive(Debug, Args)]
+pub struct VacuumDb {}
+
+pub async fn vacuum_db(pool: SqlitePool, _args: VacuumDb) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let (before, after) = vacuum(&pool).await.context("Failed to vacuum database")?;
+
+            task.finish_with_text(format!(
+                "Database vacuumed: {} -> {}",
+                super::format_bytes(before),
+                super::format_bytes(after)
+            ));
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,146 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use chrono::{TimeDelta, Utc};
+use clap::{Parser, ValueEnum};
+use news_core::config::SmtpSettings;
+use news_core::services::digest::DigestService;
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use news_core::utils::duration::parse_duration;
+use news_digest::render::{Grouping, render_digest};
+use news_digest::smtp::{SmtpRelay, send_digest};
+use news_watch::state::AppState;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(about = "Send an email digest of recently collected articles")]
+pub struct DigestCommand {
+    #[arg(
+        long,
+        default_value = "24h",
+        value_parser = parse_duration,
+        help = "Only include articles collected within this period (e.g. 24h, 7d)"
+    )]
+    since: Duration,
+
+    #[arg(
+        long,
+        required = true,
+        help = "Recipient email address (can be repeated)"
+    )]
+    to: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "source",
+        help = "Put articles in sections by source or by the tags rules gave them"
+    )]
+    group_by: GroupBy,
+
+    #[arg(long, help = "Print the digest instead of sending it")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GroupBy {
+    Source,
+    Tag,
+}
+
+pub async fn run_digest(
+    cmd: DigestCommand,
+    smtp: &SmtpSettings,
+    state: Arc<AppState>,
+) -> Result<()> {
+    let smtp = smtp.clone();
+
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let sources = state.sources();
+            let news = state.news();
+            let digests = state.digests().await?;
+
+            let mut recipients = cmd.to;
+            recipients.sort();
+            recipients.dedup();
+            let watermark_key = recipients.join(",");
+
+            let since = TimeDelta::from_std(cmd.since).context("Digest period is too long")?;
+            let window_start = Utc::now() - since;
+
+            let after = match digests
+                .get_watermark(&watermark_key)
+                .await
+                .context("Failed to load digest watermark")?
+            {
+                Some(watermark) => watermark.max(window_start),
+                None => window_start,
+            };
+
+            let articles: Vec<_> = news
+                .get_created_after(after)
+                .await
+                .context("Failed to load articles")?
+                .into_iter()
+                .collect();
+
+            let Some(sent_until) = articles.iter().map(|article| article.created_at).max() else {
+                task.skipped_with_text(format!("No new articles for {watermark_key}"));
+                return Ok(());
+            };
+
+            let sources: Vec<_> = sources
+                .get_all()
+                .await
+                .context("Failed to load content sources list")?
+                .into_iter()
+                .collect();
+
+            let details = match cmd.group_by {
+                GroupBy::Source => HashMap::new(),
+                GroupBy::Tag => {
+                    let ids: Vec<_> = articles.iter().map(|article| article.id).collect();
+                    news.details(&ids)
+                        .await
+                        .context("Failed to load article tags")?
+                }
+            };
+            let grouping = match cmd.group_by {
+                GroupBy::Source => Grouping::Source,
+                GroupBy::Tag => Grouping::Tag(&details),
+            };
+
+            let email = render_digest(&sources, &articles, grouping)
+                .await
+                .context("Failed to render digest")?;
+
+            if cmd.dry_run {
+                println!("Subject: {}\n\n{}", email.subject, email.text);
+                task.finish_with_text("Dry run, digest was not sent");
+                return Ok(());
+            }
+
+            let relay = SmtpRelay::from_settings(&smtp, state.secrets())
+                .context("SMTP is not configured")?;
+            let subject = email.subject.clone();
+
+            send_digest(&relay, &recipients, email)
+                .await
+                .context(format!("Failed to send digest to {watermark_key}"))?;
+
+            digests
+                .set_watermark(&watermark_key, sent_until)
+                .await
+                .context("Digest was sent, but its watermark was not saved")?;
+
+            task.finish_with_text(format!("Sent \"{subject}\" to {watermark_key}"));
+
+            Ok(())
+        })
+    })
+    .await
+}
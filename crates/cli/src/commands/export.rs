@@ -0,0 +1,26 @@
+mod html;
+
+use self::html::{ExportHtml, export_html};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_watch::state::AppState;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(about = "Export collected articles into other formats")]
+pub struct ExportCommand {
+    #[command(subcommand)]
+    command: ExportCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportCommands {
+    #[command(about = "Render collected articles as a static HTML site")]
+    Html(ExportHtml),
+}
+
+pub async fn run_export(cmd: ExportCommand, state: Arc<AppState>) -> Result<()> {
+    match cmd.command {
+        ExportCommands::Html(args) => export_html(args, state).await,
+    }
+}
@@ -0,0 +1,98 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::models::news::Article;
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use news_export::html::{HtmlExportOptions, export_html as render_site};
+use news_watch::media::localize_images;
+use news_watch::state::AppState;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Debug, Args)]
+pub struct ExportHtml {
+    #[arg(short, long, help = "Directory to write the site into")]
+    out: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Directory with templates overriding the built-in ones (base.html, index.html, day.html, source.html, article.html, style.css)"
+    )]
+    templates: Option<PathBuf>,
+}
+
+pub async fn export_html(args: ExportHtml, state: Arc<AppState>) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let sources = state.sources();
+            let news = state.news();
+
+            let sources: Vec<_> = sources
+                .get_all()
+                .await
+                .context("Failed to load content sources list")?
+                .into_iter()
+                .collect();
+
+            let stored: Vec<_> = news
+                .get_all()
+                .await
+                .context("Failed to load articles")?
+                .into_iter()
+                .collect();
+
+            let mut articles = Vec::with_capacity(stored.len());
+            for article in stored {
+                let description = localize_images(&state, &article.description)
+                    .await
+                    .context("Failed to read the media index")?;
+                let content = match &article.content {
+                    Some(content) => Some(
+                        localize_images(&state, content)
+                            .await
+                            .context("Failed to read the media index")?,
+                    ),
+                    None => None,
+                };
+                articles.push(Article {
+                    description,
+                    content,
+                    ..article
+                });
+            }
+
+            let ids: Vec<_> = articles.iter().map(|article| article.id).collect();
+            let attachments = news
+                .attachments(&ids)
+                .await
+                .context("Failed to load attachments")?;
+
+            let options = HtmlExportOptions {
+                out_dir: args.out,
+                templates_dir: args.templates,
+                media_dir: Some(state.media_settings().dir.clone()),
+            };
+
+            let summary = render_site(&options, &sources, &articles, &attachments)
+                .await
+                .context(format!(
+                    "Failed to export site to {}",
+                    options.out_dir.display()
+                ))?;
+
+            task.finish_with_text(format!(
+                "Site exported to {}: {} pages written, {} unchanged, {} removed",
+                options.out_dir.display(),
+                summary.written,
+                summary.unchanged,
+                summary.removed,
+            ));
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -2,8 +2,10 @@
 use crate::report::{ReportExt, ReportStatus};
 use anyhow::{Context, Result};
 use clap::Parser;
-use news_sqlite_core::utils::fs::{create_parent_dirs, get_db_path, to_db_uri};
-use sqlx::{SqlitePool, migrate};
+use news_core::config::{DatabaseSettings, Settings};
+use news_sqlite_core::db::init::connect_db_pool;
+use news_sqlite_core::db::migrate::MIGRATOR;
+use news_sqlite_core::utils::fs::create_parent_dirs;
 use std::path::Path;
 use tokio::fs::{remove_file, try_exists};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, stdin, stdout};
@@ -51,6 +53,18 @@ async fn remove_database_if_exists(db_path: &Path, should_remove: bool) -> Resul
         remove_file(db_path)
             .await
             .context("Failed to remove existing database file")?;
+
+        // WAL journal files left by the previous database would be replayed into the new one.
+        for suffix in ["-wal", "-shm"] {
+            let mut sidecar = db_path.as_os_str().to_os_string();
+            sidecar.push(suffix);
+
+            if try_exists(&sidecar).await.unwrap_or(false) {
+                remove_file(&sidecar)
+                    .await
+                    .context("Failed to remove existing database journal file")?;
+            }
+        }
     }
     Ok(())
 }
@@ -62,12 +76,11 @@ async fn create_database_directories(db_path: &Path) -> Result<()> {
     Ok(())
 }
 
-async fn connect_and_migrate(db_path: &Path, task_migrate: &Report) -> Result<()> {
+async fn connect_and_migrate(task_migrate: &Report, database: DatabaseSettings) -> Result<()> {
     let db = task_migrate
         .sub_oneline("Connecting database", |_| {
-            let db_uri = to_db_uri(db_path);
             Box::pin(async move {
-                SqlitePool::connect(&db_uri)
+                connect_db_pool(&database)
                     .await
                     .context("Failed to connect to database")
             })
@@ -77,7 +90,7 @@ async fn connect_and_migrate(db_path: &Path, task_migrate: &Report) -> Result<()
     task_migrate
         .sub_oneline("Applying database migrations", |_| {
             Box::pin(async move {
-                migrate!("../../migrations")
+                MIGRATOR
                     .run(&db)
                     .await
                     .context("Failed to run database migrations")
@@ -88,10 +101,12 @@ async fn connect_and_migrate(db_path: &Path, task_migrate: &Report) -> Result<()
     Ok(())
 }
 
-pub async fn init_app(args: InitCommand) -> Result<()> {
+pub async fn init_app(args: InitCommand, settings: &Settings) -> Result<()> {
+    let database = settings.database.clone();
+
     Report::complex("Initializing database", |task_main| {
         Box::pin(async move {
-            let db_path = get_db_path();
+            let db_path = database.sqlite_path().to_path_buf();
 
             let db_file_exists = try_exists(&db_path)
                 .await
@@ -128,8 +143,7 @@ pub async fn init_app(args: InitCommand) -> Result<()> {
 
             task_main
                 .sub_complex("Running database migrations", |task_migrate| {
-                    let db_path = db_path.clone();
-                    Box::pin(async move { connect_and_migrate(&db_path, task_migrate).await })
+                    Box::pin(async move { connect_and_migrate(task_migrate, database).await })
                 })
                 .await?;
 
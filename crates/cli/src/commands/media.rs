@@ -0,0 +1,34 @@
+mod download;
+mod gc;
+
+use self::download::{DownloadMedia, download_media};
+use self::gc::{GcMedia, gc_media};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_watch::state::AppState;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(about = "Maintain the local cache of article images and fetch attachments")]
+pub struct MediaCommand {
+    #[command(subcommand)]
+    command: MediaCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MediaCommands {
+    #[command(about = "Remove cached files no article references any more")]
+    Gc(GcMedia),
+
+    #[command(
+        about = "Fetch the attachments of articles, such as podcast episodes, to a directory"
+    )]
+    Download(DownloadMedia),
+}
+
+pub async fn run_media(cmd: MediaCommand, state: Arc<AppState>) -> Result<()> {
+    match cmd.command {
+        MediaCommands::Gc(args) => gc_media(&state, args).await,
+        MediaCommands::Download(args) => download_media(&state, args).await,
+    }
+}
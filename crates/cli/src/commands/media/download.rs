@@ -0,0 +1,44 @@
+use crate::commands::db::format_bytes;
+use crate::commands::source::resolve_source;
+use anyhow::{Context, Result};
+use clap::Args;
+use news_watch::media::download_attachments;
+use news_watch::state::AppState;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct DownloadMedia {
+    #[arg(short, long, help = "Directory to write the attachments into")]
+    out: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Only fetch the attachments of this source (Atom feed URL or Telegram username)"
+    )]
+    source: Option<String>,
+}
+
+pub async fn download_media(app_state: &AppState, args: DownloadMedia) -> Result<()> {
+    let source = match &args.source {
+        Some(key) => Some(resolve_source(app_state.sources().as_ref(), key).await?),
+        None => None,
+    };
+
+    let report = download_attachments(app_state, &args.out, source)
+        .await
+        .context(format!(
+            "Failed to download attachments to {}",
+            args.out.display()
+        ))?;
+
+    println!(
+        "Downloaded {} attachments, {}; {} already present, {} failed",
+        report.files,
+        format_bytes(i64::try_from(report.bytes).unwrap_or(i64::MAX)),
+        report.skipped,
+        report.failed,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::commands::db::format_bytes;
+use anyhow::{Context, Result};
+use clap::Args;
+use news_watch::media::collect_garbage;
+use news_watch::state::AppState;
+
+#[derive(Debug, Args)]
+pub struct GcMedia {
+    #[arg(long, help = "Only report which files would be removed")]
+    dry_run: bool,
+}
+
+pub async fn gc_media(app_state: &AppState, args: GcMedia) -> Result<()> {
+    let report = collect_garbage(app_state, args.dry_run)
+        .await
+        .context("Failed to clean up the media cache")?;
+
+    let verb = if args.dry_run {
+        "Would remove"
+    } else {
+        "Removed"
+    };
+    println!(
+        "{verb} {} unreferenced files, {}",
+        report.files,
+        format_bytes(i64::try_from(report.bytes).unwrap_or(i64::MAX))
+    );
+
+    Ok(())
+}
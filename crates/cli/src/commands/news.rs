@@ -0,0 +1,75 @@
+mod history;
+mod list;
+mod read;
+mod show;
+mod star;
+
+use self::history::{ArticleHistory, article_history};
+use self::list::{ListArticles, list_articles};
+use self::read::{ReadArticle, read_article};
+use self::show::{ShowArticle, show_article};
+use self::star::{StarArticle, star_article};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_watch::state::AppState;
+use news_watch::storage::Access;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(about = "Work with collected articles")]
+pub struct NewsCommand {
+    #[command(subcommand)]
+    command: NewsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NewsCommands {
+    #[command(about = "List articles, newest first", visible_aliases = ["ls"])]
+    List(ListArticles),
+
+    #[command(about = "Show an article with its attachments")]
+    Show(ShowArticle),
+
+    #[command(about = "Show upstream edits of an article as a word diff")]
+    History(ArticleHistory),
+
+    #[command(about = "Star an article so that pruning keeps it")]
+    Star(StarArticle),
+
+    #[command(about = "Remove the star from an article")]
+    Unstar(StarArticle),
+
+    #[command(about = "Mark an article as read")]
+    Read(ReadArticle),
+
+    #[command(about = "Mark an article as unread")]
+    Unread(ReadArticle),
+}
+
+impl NewsCommand {
+    pub const fn access(&self) -> Access {
+        match self.command {
+            NewsCommands::List(_) | NewsCommands::Show(_) | NewsCommands::History(_) => {
+                Access::ReadOnly
+            }
+            NewsCommands::Star(_)
+            | NewsCommands::Unstar(_)
+            | NewsCommands::Read(_)
+            | NewsCommands::Unread(_) => Access::ReadWrite,
+        }
+    }
+}
+
+pub async fn run_news(cmd: NewsCommand, state: Arc<AppState>) -> Result<()> {
+    let news = state.news();
+
+    match cmd.command {
+        NewsCommands::List(args) => list_articles(news, args).await,
+        NewsCommands::Show(args) => show_article(news, args).await,
+        NewsCommands::History(args) => article_history(news, args).await,
+        NewsCommands::Star(args) => star_article(news, args, true).await,
+        NewsCommands::Unstar(args) => star_article(news, args, false).await,
+        NewsCommands::Read(args) => read_article(news, args, true).await,
+        NewsCommands::Unread(args) => read_article(news, args, false).await,
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use news_core::models::news::ArticleRevision;
+use news_core::services::news::NewsService;
+use similar::{ChangeTag, TextDiff};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Args)]
+pub struct ArticleHistory {
+    id: Uuid,
+}
+
+fn marked(tag: ChangeTag, words: &str) -> String {
+    match tag {
+        ChangeTag::Equal => words.to_string(),
+        ChangeTag::Delete => style(format!("[-{words}-]")).red().to_string(),
+        ChangeTag::Insert => style(format!("{{+{words}+}}")).green().to_string(),
+    }
+}
+
+/// Word-level diff in the style of `git diff --word-diff`: runs of removed and
+/// added words are wrapped in `[-...-]` and `{+...+}`.
+fn word_diff(old: &str, new: &str) -> String {
+    let diff = TextDiff::from_words(old, new);
+
+    let mut output = String::new();
+    let mut run: Option<(ChangeTag, String)> = None;
+
+    for change in diff.iter_all_changes() {
+        match &mut run {
+            Some((tag, words)) if *tag == change.tag() => words.push_str(change.value()),
+            _ => {
+                if let Some((tag, words)) = run.take() {
+                    output.push_str(&marked(tag, &words));
+                }
+                run = Some((change.tag(), change.value().to_string()));
+            }
+        }
+    }
+
+    if let Some((tag, words)) = run {
+        output.push_str(&marked(tag, &words));
+    }
+
+    output
+}
+
+fn fields(revision: &ArticleRevision) -> [(&'static str, &str); 3] {
+    [
+        ("title", &revision.title),
+        ("description", &revision.description),
+        ("content", revision.content.as_deref().unwrap_or_default()),
+    ]
+}
+
+fn print_header(number: usize, revision: &ArticleRevision) {
+    let stored = revision.created_at.format(DATETIME_FORMAT);
+    let header = match revision.replaced_at {
+        Some(replaced) => format!(
+            "Revision {number}, stored {stored}, replaced {}",
+            replaced.format(DATETIME_FORMAT)
+        ),
+        None => format!("Revision {number} (current), stored {stored}"),
+    };
+    println!("{}", style(header).bold());
+}
+
+pub async fn article_history(
+    news: Arc<impl NewsService + 'static>,
+    args: ArticleHistory,
+) -> Result<()> {
+    let revisions = news
+        .revisions(args.id)
+        .await
+        .context(format!("Failed to load history of article: {}", args.id))?;
+
+    let Some(first) = revisions.first() else {
+        return Ok(());
+    };
+
+    print_header(1, first);
+    for (name, value) in fields(first) {
+        if !value.is_empty() {
+            println!("{name}: {value}");
+        }
+    }
+
+    for (index, pair) in revisions.windows(2).enumerate() {
+        let (old, new) = (&pair[0], &pair[1]);
+
+        println!();
+        print_header(index + 2, new);
+        for ((name, old), (_, new)) in fields(old).into_iter().zip(fields(new)) {
+            if old != new {
+                println!("{name}: {}", word_diff(old, new));
+            }
+        }
+    }
+
+    if revisions.len() == 1 {
+        println!();
+        println!("No upstream edits recorded");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::news::NewsService;
+use news_core::utils::language::is_supported;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Args)]
+pub struct ListArticles {
+    #[arg(
+        long,
+        help = "Show one article per story, the first one stored, with the number of its duplicates"
+    )]
+    collapse_duplicates: bool,
+
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 50,
+        help = "Number of articles to show, newest first"
+    )]
+    limit: usize,
+
+    #[arg(
+        long,
+        value_parser = parse_language,
+        help = "Show only articles detected in this language, by ISO 639-1 code such as en"
+    )]
+    lang: Option<String>,
+
+    #[arg(long, help = "Show the summary of long articles under their title")]
+    summary: bool,
+}
+
+fn parse_language(value: &str) -> Result<String, String> {
+    let code = value.to_lowercase();
+    if is_supported(&code) {
+        Ok(code)
+    } else {
+        Err(format!("unknown language code: {value}"))
+    }
+}
+
+pub async fn list_articles(
+    news: Arc<impl NewsService + 'static>,
+    args: ListArticles,
+) -> Result<()> {
+    let articles: Vec<_> = news
+        .get_all()
+        .await
+        .context("Failed to load articles")?
+        .into_iter()
+        .collect();
+
+    let languages = match &args.lang {
+        Some(_) => {
+            let ids: Vec<Uuid> = articles.iter().map(|article| article.id).collect();
+            news.details(&ids)
+                .await
+                .context("Failed to load article details")?
+        }
+        None => HashMap::new(),
+    };
+    let in_language = |id: &Uuid| {
+        args.lang.as_ref().is_none_or(|lang| {
+            languages
+                .get(id)
+                .and_then(|details| details.language.as_ref())
+                == Some(lang)
+        })
+    };
+
+    // Duplicates of each shown article, and the shown article of each
+    // duplicate. Stories collapse onto their first article in the language,
+    // so a story whose primary is in another language is still listed.
+    let mut duplicates: HashMap<Uuid, usize> = HashMap::new();
+    let mut primaries: HashMap<Uuid, Uuid> = HashMap::new();
+    if args.collapse_duplicates {
+        for story in news.stories().await.context("Failed to load stories")? {
+            let mut members = story.articles.iter().filter(|id| in_language(id));
+            let Some(&shown) = members.next() else {
+                continue;
+            };
+            let mut count = 0;
+            for article in members {
+                primaries.insert(*article, shown);
+                count += 1;
+            }
+            if count > 0 {
+                duplicates.insert(shown, count);
+            }
+        }
+    }
+
+    for article in articles
+        .into_iter()
+        .filter(|article| !primaries.contains_key(&article.id) && in_language(&article.id))
+        .take(args.limit)
+    {
+        let line = format!(
+            "{} {} {}",
+            article.id,
+            article.created_at.format(DATETIME_FORMAT),
+            article.display_title()
+        );
+
+        match duplicates.get(&article.id) {
+            Some(1) => println!("{line} (+1 duplicate)"),
+            Some(count) => println!("{line} (+{count} duplicates)"),
+            None => println!("{line}"),
+        }
+        if let Some(summary) = article.summary.as_ref().filter(|_| args.summary) {
+            println!("    {summary}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::news::NewsService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct ReadArticle {
+    id: Uuid,
+}
+
+pub async fn read_article(
+    news: Arc<impl NewsService + 'static>,
+    args: ReadArticle,
+    read: bool,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            news.set_read(args.id, read)
+                .await
+                .context(format!("Failed to update article: {}", args.id))?;
+
+            let action = if read { "read" } else { "unread" };
+            task.finish_with_text(format!("Article marked {action} successfully: {}", args.id));
+            Ok(())
+        })
+    })
+    .await
+}
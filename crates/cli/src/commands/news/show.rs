@@ -0,0 +1,138 @@
+use crate::commands::db::format_bytes;
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use console::style;
+use news_core::models::news::Attachment;
+use news_core::services::news::NewsService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Args)]
+pub struct ShowArticle {
+    id: Uuid,
+}
+
+/// Playing time as `h:mm:ss`, or `m:ss` under an hour.
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    match hours {
+        0 => format!("{minutes}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+fn print_attachment(attachment: &Attachment) {
+    println!("  {}", attachment.url);
+
+    let details: Vec<String> = [
+        attachment.content_type.clone(),
+        attachment
+            .length
+            .map(|length| format_bytes(i64::try_from(length).unwrap_or(i64::MAX))),
+        attachment
+            .duration
+            .map(|duration| format_duration(duration.as_secs())),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if !details.is_empty() {
+        println!("    {}", details.join(", "));
+    }
+    if let Some(thumbnail) = &attachment.thumbnail {
+        println!("    thumbnail: {thumbnail}");
+    }
+}
+
+pub async fn show_article(news: Arc<impl NewsService + 'static>, args: ShowArticle) -> Result<()> {
+    let Some(article) = news
+        .get_all()
+        .await
+        .context("Failed to load articles")?
+        .into_iter()
+        .find(|article| article.id == args.id)
+    else {
+        bail!("Article not found: {}", args.id);
+    };
+
+    let attachments = news
+        .attachments(&[article.id])
+        .await
+        .context(format!(
+            "Failed to load attachments of article: {}",
+            args.id
+        ))?
+        .remove(&article.id)
+        .unwrap_or_default();
+
+    let details = news
+        .details(&[article.id])
+        .await
+        .context(format!("Failed to load details of article: {}", args.id))?
+        .remove(&article.id)
+        .unwrap_or_default();
+
+    println!("{}", style(article.display_title()).bold());
+    println!("source: {}", article.parent_id);
+    println!("stored: {}", article.created_at.format(DATETIME_FORMAT));
+    if article.updated_at != article.created_at {
+        println!("updated: {}", article.updated_at.format(DATETIME_FORMAT));
+    }
+    if let Some(language) = &details.language {
+        println!("language: {language}");
+    }
+    for (label, values) in [
+        ("authors", &details.authors),
+        ("categories", &details.categories),
+        ("tags", &details.tags),
+    ] {
+        if !values.is_empty() {
+            println!("{label}: {}", values.join(", "));
+        }
+    }
+    if let Some(story) = news
+        .stories()
+        .await
+        .context("Failed to load stories")?
+        .into_iter()
+        .find(|story| story.articles.contains(&article.id))
+    {
+        let others: Vec<String> = story
+            .articles
+            .iter()
+            .filter(|id| **id != article.id)
+            .map(Uuid::to_string)
+            .collect();
+        println!("duplicates: {}", others.join(", "));
+    }
+    let marks: Vec<&str> = [(details.read, "read"), (details.starred, "starred")]
+        .into_iter()
+        .filter_map(|(set, mark)| set.then_some(mark))
+        .collect();
+    if !marks.is_empty() {
+        println!("marked: {}", marks.join(", "));
+    }
+
+    if !article.description.is_empty() {
+        println!();
+        println!("{}", article.description);
+    }
+    if let Some(content) = &article.content {
+        println!();
+        println!("{content}");
+    }
+
+    if !attachments.is_empty() {
+        println!();
+        println!("{}", style("Attachments").bold());
+        for attachment in &attachments {
+            print_attachment(attachment);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::news::NewsService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct StarArticle {
+    id: Uuid,
+}
+
+pub async fn star_article(
+    news: Arc<impl NewsService + 'static>,
+    args: StarArticle,
+    starred: bool,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            news.set_starred(args.id, starred)
+                .await
+                .context(format!("Failed to update article: {}", args.id))?;
+
+            let action = if starred { "starred" } else { "unstarred" };
+            task.finish_with_text(format!("Article {action} successfully: {}", args.id));
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,46 @@
+mod add;
+mod list;
+mod remove;
+mod test;
+
+use self::add::{AddRoute, add_route};
+use self::list::{ListRoutes, list_routes};
+use self::remove::{RemoveRoute, remove_route};
+use self::test::{TestRoute, test_route};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_watch::state::AppState;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(about = "Manage chat and push notifications about new articles")]
+pub struct NotifyCommand {
+    #[command(subcommand)]
+    command: NotifyCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NotifyCommands {
+    #[command(about = "List configured notification routes", visible_aliases = ["ls"])]
+    List(ListRoutes),
+
+    #[command(about = "Add a notification route")]
+    Add(Box<AddRoute>),
+
+    #[command(about = "Remove a notification route", visible_aliases = ["rm"])]
+    Remove(RemoveRoute),
+
+    #[command(about = "Send a sample notification through a route")]
+    Test(TestRoute),
+}
+
+pub async fn run_notify(cmd: NotifyCommand, state: Arc<AppState>) -> Result<()> {
+    let routes = state.notifications().await?;
+
+    match cmd.command {
+        NotifyCommands::List(args) => list_routes(routes, args).await,
+        NotifyCommands::Add(args) => add_route(routes, state.sources(), *args).await,
+        NotifyCommands::Remove(args) => remove_route(routes, args).await,
+        NotifyCommands::Test(args) => test_route(routes, state, args).await,
+    }
+}
@@ -0,0 +1,255 @@
+use crate::cli::report::Report;
+use crate::commands::source::resolve_source;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result, bail};
+use clap::{Args, Subcommand};
+use news_core::models::notifier::{NotificationRouteDraft, NotifierConfig, RouteFilter};
+use news_core::models::source::request::references_secret;
+use news_core::services::notifier::NotificationRouteService;
+use news_core::services::source::SourceService;
+use news_sqlite_core::utils::parse::parse_url;
+use news_watch::notify::validate_template;
+use std::sync::Arc;
+use url::Url;
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+const NTFY_SERVER: &str = "https://ntfy.sh";
+
+#[derive(Debug, Args)]
+pub struct AddRoute {
+    #[command(subcommand)]
+    notifier: AddNotifier,
+}
+
+#[derive(Debug, Subcommand)]
+enum AddNotifier {
+    #[command(about = "Send messages with a Telegram bot")]
+    Telegram(AddTelegram),
+
+    #[command(about = "Post to a Slack incoming webhook")]
+    Slack(AddSlack),
+
+    #[command(about = "Post to a Matrix room")]
+    Matrix(AddMatrix),
+
+    #[command(about = "Publish to an ntfy topic")]
+    Ntfy(AddNtfy),
+
+    #[command(about = "Push to a Gotify server")]
+    Gotify(AddGotify),
+}
+
+#[derive(Debug, Args)]
+struct RouteArgs {
+    #[arg(
+        long,
+        help = "Only notify about articles of this source (Atom feed URL or Telegram username)"
+    )]
+    source: Option<String>,
+
+    #[arg(
+        long = "keyword",
+        help = "Only notify when the title or text contains the keyword (can be repeated)"
+    )]
+    keywords: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Only notify about articles with this tag, given by a rule"
+    )]
+    tag: Option<String>,
+
+    #[arg(
+        long,
+        help = "MiniJinja message template with title, description, text, source and id"
+    )]
+    template: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct AddTelegram {
+    #[arg(
+        long,
+        help = "Bot token issued by @BotFather, as a secret reference such as '${TELEGRAM_BOT_TOKEN}'"
+    )]
+    bot_token: String,
+
+    #[arg(long, help = "Chat ID or @channel username to post to")]
+    chat_id: String,
+
+    #[arg(long, value_parser = parse_url, default_value = TELEGRAM_API_URL)]
+    api_url: Url,
+
+    #[command(flatten)]
+    route: RouteArgs,
+}
+
+#[derive(Debug, Args)]
+struct AddSlack {
+    #[arg(help = "Incoming webhook URL, as a secret reference such as '${SLACK_WEBHOOK_URL}'")]
+    webhook_url: String,
+
+    #[command(flatten)]
+    route: RouteArgs,
+}
+
+#[derive(Debug, Args)]
+struct AddMatrix {
+    #[arg(long, value_parser = parse_url, help = "Homeserver base URL")]
+    homeserver: Url,
+
+    #[arg(
+        long,
+        help = "Access token of the posting user, as a secret reference such as '${MATRIX_TOKEN}'"
+    )]
+    access_token: String,
+
+    #[arg(long, help = "Room ID, such as !abc:example.org")]
+    room_id: String,
+
+    #[command(flatten)]
+    route: RouteArgs,
+}
+
+#[derive(Debug, Args)]
+struct AddNtfy {
+    topic: String,
+
+    #[arg(long, value_parser = parse_url, default_value = NTFY_SERVER)]
+    server: Url,
+
+    #[arg(
+        long,
+        help = "Access token for protected topics, as a secret reference such as '${NTFY_TOKEN}'"
+    )]
+    token: Option<String>,
+
+    #[command(flatten)]
+    route: RouteArgs,
+}
+
+#[derive(Debug, Args)]
+struct AddGotify {
+    #[arg(long, value_parser = parse_url, help = "Gotify server base URL")]
+    server: Url,
+
+    #[arg(
+        long,
+        help = "Application token, as a secret reference such as '${GOTIFY_TOKEN}'"
+    )]
+    token: String,
+
+    #[arg(long, help = "Message priority (default: application priority)")]
+    priority: Option<i64>,
+
+    #[command(flatten)]
+    route: RouteArgs,
+}
+
+impl AddNotifier {
+    fn into_parts(self) -> (NotifierConfig, RouteArgs) {
+        match self {
+            Self::Telegram(args) => (
+                NotifierConfig::Telegram {
+                    api_url: args.api_url,
+                    bot_token: args.bot_token,
+                    chat_id: args.chat_id,
+                },
+                args.route,
+            ),
+            Self::Slack(args) => (
+                NotifierConfig::Slack {
+                    webhook_url: args.webhook_url,
+                },
+                args.route,
+            ),
+            Self::Matrix(args) => (
+                NotifierConfig::Matrix {
+                    homeserver: args.homeserver,
+                    access_token: args.access_token,
+                    room_id: args.room_id,
+                },
+                args.route,
+            ),
+            Self::Ntfy(args) => (
+                NotifierConfig::Ntfy {
+                    server: args.server,
+                    topic: args.topic,
+                    token: args.token,
+                },
+                args.route,
+            ),
+            Self::Gotify(args) => (
+                NotifierConfig::Gotify {
+                    server: args.server,
+                    token: args.token,
+                    priority: args.priority,
+                },
+                args.route,
+            ),
+        }
+    }
+}
+
+/// Keeps credentials out of the database: only references to secrets, resolved
+/// when a notification is sent, are stored.
+fn check_credentials(config: &NotifierConfig) -> Result<()> {
+    if config
+        .credentials()
+        .into_iter()
+        .any(|value| !references_secret(value))
+    {
+        bail!(
+            "Credentials of {} routes must reference a secret as ${{NAME}}, set in the environment or in http.secrets_file",
+            config.kind()
+        );
+    }
+    Ok(())
+}
+
+pub async fn add_route(
+    routes: Arc<impl NotificationRouteService + 'static>,
+    sources: Arc<impl SourceService + 'static>,
+    args: AddRoute,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let (config, route) = args.notifier.into_parts();
+            check_credentials(&config)?;
+
+            if let Some(template) = &route.template {
+                validate_template(template)?;
+            }
+
+            let source_id = match &route.source {
+                Some(key) => Some(resolve_source(sources.as_ref(), key).await?),
+                None => None,
+            };
+
+            let target = config.target();
+            let draft = NotificationRouteDraft {
+                config,
+                filter: RouteFilter {
+                    source_id,
+                    keywords: route.keywords,
+                    tag: route.tag,
+                },
+                template: route.template,
+            };
+
+            let route = routes
+                .add(draft)
+                .await
+                .context(format!("Failed to add notification route: {target}"))?;
+
+            task.finish_with_text(format!(
+                "Notification route {} added: {} ({target})",
+                route.id,
+                route.config.kind()
+            ));
+
+            Ok(())
+        })
+    })
+    .await
+}
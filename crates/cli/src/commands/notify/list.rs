@@ -0,0 +1,41 @@
+use anyhow::Result;
+use clap::Args;
+use news_core::services::notifier::NotificationRouteService;
+use std::sync::Arc;
+
+#[derive(Debug, Args)]
+pub struct ListRoutes {}
+
+pub async fn list_routes(
+    routes: Arc<impl NotificationRouteService + 'static>,
+    _args: ListRoutes,
+) -> Result<()> {
+    for route in routes.get_all().await? {
+        let mut source = route
+            .filter
+            .source_id
+            .map_or_else(|| "all sources".to_string(), |id| format!("source {id}"));
+
+        if let Some(tag) = &route.filter.tag {
+            source.push_str(&format!(", tag {tag}"));
+        }
+
+        let keywords = match route.filter.keywords.is_empty() {
+            true => "any keywords".to_string(),
+            false => route.filter.keywords.join(", "),
+        };
+
+        println!(
+            "{} = {} ({})",
+            route.id,
+            route.config.kind(),
+            route.config.target()
+        );
+        println!("{:>38} {source}, {keywords}", "filter:");
+
+        if let Some(template) = &route.template {
+            println!("{:>38} {}", "template:", template.escape_debug());
+        }
+    }
+    Ok(())
+}
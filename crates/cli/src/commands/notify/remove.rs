@@ -0,0 +1,33 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::notifier::NotificationRouteService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct RemoveRoute {
+    id: Uuid,
+}
+
+pub async fn remove_route(
+    routes: Arc<impl NotificationRouteService + 'static>,
+    args: RemoveRoute,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            routes
+                .drop_by_id(args.id)
+                .await
+                .context(format!("Failed to remove notification route: {}", args.id))?;
+
+            task.finish_with_text(format!(
+                "Notification route removed successfully: {}",
+                args.id
+            ));
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,42 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::models::notifier::Notification;
+use news_core::services::notifier::NotificationRouteService;
+use news_watch::notify::notifier_for;
+use news_watch::state::AppState;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct TestRoute {
+    id: Uuid,
+}
+
+pub async fn test_route(
+    routes: Arc<impl NotificationRouteService + 'static>,
+    state: Arc<AppState>,
+    args: TestRoute,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let route = routes.get_by_id(args.id).await?;
+            let target = route.config.target();
+
+            let notification = Notification {
+                title: "UniNews test notification".to_string(),
+                message: "This is a test notification sent by uninews notify test.".to_string(),
+            };
+
+            notifier_for(&route.config, state.secrets())?
+                .notify(&notification)
+                .await
+                .context(format!("Test notification to {target} failed"))?;
+
+            task.finish_with_text(format!("Test notification sent to {target}"));
+            Ok(())
+        })
+    })
+    .await
+}
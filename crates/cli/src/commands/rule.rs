@@ -0,0 +1,46 @@
+mod add;
+mod list;
+mod remove;
+mod test;
+
+use self::add::{AddRule, add_rule};
+use self::list::{ListRules, list_rules};
+use self::remove::{RemoveRule, remove_rule};
+use self::test::{TestRule, test_rule};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_watch::state::AppState;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(about = "Manage rules that drop, mark, star or tag collected items")]
+pub struct RuleCommand {
+    #[command(subcommand)]
+    command: RuleCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RuleCommands {
+    #[command(about = "List rules in the order they are applied", visible_aliases = ["ls"])]
+    List(ListRules),
+
+    #[command(about = "Add a rule, applied after the existing ones")]
+    Add(AddRule),
+
+    #[command(about = "Remove a rule", visible_aliases = ["rm"])]
+    Remove(RemoveRule),
+
+    #[command(about = "Show which stored articles a rule matches")]
+    Test(TestRule),
+}
+
+pub async fn run_rule(cmd: RuleCommand, state: Arc<AppState>) -> Result<()> {
+    let rules = state.rules().await?;
+
+    match cmd.command {
+        RuleCommands::List(args) => list_rules(rules, args).await,
+        RuleCommands::Add(args) => add_rule(rules, state.sources(), args).await,
+        RuleCommands::Remove(args) => remove_rule(rules, args).await,
+        RuleCommands::Test(args) => test_rule(rules, state.news(), args).await,
+    }
+}
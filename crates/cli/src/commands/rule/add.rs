@@ -0,0 +1,165 @@
+use crate::cli::report::Report;
+use crate::commands::source::resolve_source;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use news_core::models::rule::{RuleAction, RuleDraft, RuleField, RuleFilter, RulePattern};
+use news_core::services::rule::RuleService;
+use news_core::services::source::SourceService;
+use news_watch::rules::validate_pattern;
+use std::sync::Arc;
+
+#[derive(Debug, Args)]
+pub struct AddRule {
+    #[command(subcommand)]
+    action: AddAction,
+}
+
+#[derive(Debug, Subcommand)]
+enum AddAction {
+    #[command(about = "Do not store matching items")]
+    Drop(FilterArgs),
+
+    #[command(about = "Mark matching articles as read")]
+    Read(FilterArgs),
+
+    #[command(about = "Star matching articles, so that pruning keeps them")]
+    Star(FilterArgs),
+
+    #[command(about = "Add a tag to matching articles")]
+    Tag(AddTag),
+}
+
+#[derive(Debug, Args)]
+struct AddTag {
+    name: String,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FieldArg {
+    Title,
+    Description,
+    Author,
+    Category,
+}
+
+impl From<FieldArg> for RuleField {
+    fn from(field: FieldArg) -> Self {
+        match field {
+            FieldArg::Title => Self::Title,
+            FieldArg::Description => Self::Description,
+            FieldArg::Author => Self::Author,
+            FieldArg::Category => Self::Category,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct FilterArgs {
+    #[arg(
+        long,
+        help = "Only apply to items of this source (Atom feed URL or Telegram username)"
+    )]
+    source: Option<String>,
+
+    #[arg(long, help = "Only apply to articles tagged by an earlier rule")]
+    with_tag: Option<String>,
+
+    #[arg(
+        long = "field",
+        value_enum,
+        help = "Field the patterns are matched against (can be repeated, default: all)"
+    )]
+    fields: Vec<FieldArg>,
+
+    #[arg(
+        long = "include",
+        help = "Only match when a field contains the keyword (can be repeated)"
+    )]
+    include: Vec<String>,
+
+    #[arg(
+        long = "include-regex",
+        help = "Only match when a field matches the regular expression (can be repeated)"
+    )]
+    include_regex: Vec<String>,
+
+    #[arg(
+        long = "exclude",
+        help = "Never match when a field contains the keyword (can be repeated)"
+    )]
+    exclude: Vec<String>,
+
+    #[arg(
+        long = "exclude-regex",
+        help = "Never match when a field matches the regular expression (can be repeated)"
+    )]
+    exclude_regex: Vec<String>,
+}
+
+fn patterns(keywords: Vec<String>, regexes: Vec<String>) -> Vec<RulePattern> {
+    keywords
+        .into_iter()
+        .map(RulePattern::Keyword)
+        .chain(regexes.into_iter().map(RulePattern::Regex))
+        .collect()
+}
+
+impl AddAction {
+    fn into_parts(self) -> (RuleAction, FilterArgs) {
+        match self {
+            Self::Drop(filter) => (RuleAction::Drop, filter),
+            Self::Read(filter) => (RuleAction::MarkRead, filter),
+            Self::Star(filter) => (RuleAction::Star, filter),
+            Self::Tag(args) => (RuleAction::Tag(args.name), args.filter),
+        }
+    }
+}
+
+pub async fn add_rule(
+    rules: Arc<impl RuleService + 'static>,
+    sources: Arc<impl SourceService + 'static>,
+    args: AddRule,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let (action, args) = args.action.into_parts();
+
+            let include = patterns(args.include, args.include_regex);
+            let exclude = patterns(args.exclude, args.exclude_regex);
+            for pattern in include.iter().chain(&exclude) {
+                validate_pattern(pattern)?;
+            }
+
+            let source_id = match &args.source {
+                Some(key) => Some(resolve_source(sources.as_ref(), key).await?),
+                None => None,
+            };
+
+            let kind = action.kind();
+            let draft = RuleDraft {
+                action,
+                filter: RuleFilter {
+                    source_id,
+                    tag: args.with_tag,
+                    fields: args.fields.into_iter().map(RuleField::from).collect(),
+                    include,
+                    exclude,
+                },
+            };
+
+            let rule = rules
+                .add(draft)
+                .await
+                .context(format!("Failed to add {kind} rule"))?;
+
+            task.finish_with_text(format!("Rule {} added: {kind}", rule.id));
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use clap::Args;
+use news_core::models::rule::{Rule, RuleAction, RulePattern};
+use news_core::services::rule::RuleService;
+use std::sync::Arc;
+
+#[derive(Debug, Args)]
+pub struct ListRules {}
+
+fn action(rule: &Rule) -> String {
+    match &rule.action {
+        RuleAction::Tag(tag) => format!("tag {tag}"),
+        action => action.kind().to_string(),
+    }
+}
+
+fn pattern(pattern: &RulePattern) -> String {
+    match pattern {
+        RulePattern::Keyword(keyword) => format!("\"{}\"", keyword.escape_debug()),
+        RulePattern::Regex(regex) => format!("/{regex}/"),
+    }
+}
+
+fn patterns(patterns: &[RulePattern]) -> String {
+    patterns.iter().map(pattern).collect::<Vec<_>>().join(", ")
+}
+
+pub async fn list_rules(rules: Arc<impl RuleService + 'static>, _args: ListRules) -> Result<()> {
+    for rule in rules.get_all().await? {
+        let filter = &rule.filter;
+
+        let mut scope = vec![
+            filter
+                .source_id
+                .map_or_else(|| "all sources".to_string(), |id| format!("source {id}")),
+        ];
+        if let Some(tag) = &filter.tag {
+            scope.push(format!("tagged {tag}"));
+        }
+
+        let fields = match filter.fields.is_empty() {
+            true => "all fields".to_string(),
+            false => filter
+                .fields
+                .iter()
+                .map(|field| field.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+
+        println!("{} = {}", rule.id, action(&rule));
+        println!("{:>38} {} ({fields})", "scope:", scope.join(", "));
+        if !filter.include.is_empty() {
+            println!("{:>38} {}", "include:", patterns(&filter.include));
+        }
+        if !filter.exclude.is_empty() {
+            println!("{:>38} {}", "exclude:", patterns(&filter.exclude));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::rule::RuleService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct RemoveRule {
+    id: Uuid,
+}
+
+pub async fn remove_rule(rules: Arc<impl RuleService + 'static>, args: RemoveRule) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            rules
+                .drop_by_id(args.id)
+                .await
+                .context(format!("Failed to remove rule: {}", args.id))?;
+
+            task.finish_with_text(format!("Rule removed successfully: {}", args.id));
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::news::NewsService;
+use news_core::services::rule::RuleService;
+use news_sqlite_core::utils::html::html_to_text;
+use news_watch::rules::{RuleSet, Subject};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct TestRule {
+    id: Uuid,
+}
+
+/// Prints the stored articles the rule matches, as if they were collected now
+/// with the tags they have. Nothing is changed.
+pub async fn test_rule(
+    rules: Arc<impl RuleService + 'static>,
+    news: Arc<impl NewsService + 'static>,
+    args: TestRule,
+) -> Result<()> {
+    let rule = rules
+        .get_by_id(args.id)
+        .await
+        .context(format!("Failed to load rule: {}", args.id))?;
+    let rules = RuleSet::new([rule]);
+
+    let articles: Vec<_> = news
+        .get_all()
+        .await
+        .context("Failed to load articles")?
+        .into_iter()
+        .collect();
+    let ids: Vec<Uuid> = articles.iter().map(|article| article.id).collect();
+    let details = news
+        .details(&ids)
+        .await
+        .context("Failed to load article details")?;
+
+    let mut matches = 0;
+    for article in &articles {
+        let details = details.get(&article.id).cloned().unwrap_or_default();
+        let text = html_to_text(&article.description).await?;
+        let outcome = rules.evaluate(&Subject {
+            source_id: article.parent_id,
+            title: &article.title,
+            text: &text,
+            authors: &details.authors,
+            categories: &details.categories,
+            tags: &details.tags,
+        });
+
+        if !outcome.matched.is_empty() {
+            matches += 1;
+            println!("{} {}", article.id, article.title);
+        }
+    }
+
+    println!(
+        "{matches} of {} stored articles match rule {}",
+        articles.len(),
+        args.id
+    );
+
+    Ok(())
+}
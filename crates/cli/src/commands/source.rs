@@ -5,10 +5,13 @@
 use self::add::{AddCommand, add_source};
 use self::list::{ArgsList, list_sources};
 use self::remove::{RemoveCommand, remove_source};
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow};
 use clap::{Parser, Subcommand};
-use news_sqlite_core::services::source::SqliteSourceService;
+use news_core::services::source::SourceService;
+use news_watch::state::AppState;
+use news_watch::storage::Access;
 use std::sync::Arc;
+use uuid::Uuid;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -28,12 +31,33 @@ pub enum SourceCommands {
     Remove(RemoveCommand),
 }
 
-pub async fn run_source(cmd: SourceCommand) -> Result<()> {
-    let source_service = Arc::new(SqliteSourceService::try_new().await?);
+impl SourceCommand {
+    pub const fn access(&self) -> Access {
+        match self.command {
+            SourceCommands::List(_) => Access::ReadOnly,
+            _ => Access::ReadWrite,
+        }
+    }
+}
+
+pub async fn run_source(cmd: SourceCommand, state: Arc<AppState>) -> Result<()> {
+    let source_service = state.sources();
 
     match cmd.command {
         SourceCommands::Add(cmd) => add_source(source_service, cmd).await,
-        SourceCommands::Remove(cmd) => remove_source(source_service, cmd).await,
+        SourceCommands::Remove(cmd) => remove_source(state, cmd).await,
         SourceCommands::List(args) => list_sources(source_service, args).await,
     }
 }
+
+/// Finds a configured source by its Atom feed URL or Telegram username.
+pub async fn resolve_source(sources: &impl SourceService, key: &str) -> Result<Uuid> {
+    sources
+        .get_all()
+        .await
+        .context("Failed to load content sources list")?
+        .into_iter()
+        .find(|source| source.matches_key(key))
+        .map(|source| source.id())
+        .ok_or_else(|| anyhow!("Source {key} is not configured"))
+}
@@ -3,6 +3,7 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use news_core::models::source::atom::AtomDraft;
+use news_core::models::source::request::{BasicAuth, Header, RequestSettings};
 use news_core::services::source::SourceDraft::Atom;
 use news_core::services::source::{AddError, SourceService};
 use news_sqlite_core::utils::parse::parse_url;
@@ -13,6 +14,38 @@
 pub struct AddAtom {
     #[arg(value_parser = parse_url)]
     url: Url,
+
+    #[arg(
+        long = "header",
+        value_name = "NAME: VALUE",
+        help = "Header sent with every request (can be repeated)"
+    )]
+    headers: Vec<Header>,
+
+    #[arg(long, value_name = "USER:PASSWORD", help = "HTTP basic authentication")]
+    basic_auth: Option<BasicAuth>,
+
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        help = "Token sent as `Authorization: Bearer`"
+    )]
+    bearer_token: Option<String>,
+
+    #[arg(
+        long = "cookie",
+        value_name = "NAME=VALUE",
+        value_parser = parse_cookie,
+        help = "Cookie sent from the first request on (can be repeated)"
+    )]
+    cookies: Vec<String>,
+}
+
+fn parse_cookie(s: &str) -> Result<String, String> {
+    match s.split_once('=') {
+        Some((name, _)) if !name.trim().is_empty() => Ok(s.to_string()),
+        _ => Err("expected NAME=VALUE".to_string()),
+    }
 }
 
 pub async fn add_atom_source(
@@ -21,7 +54,12 @@ pub async fn add_atom_source(
 ) -> Result<()> {
     Report::silent(move |task| {
         Box::pin(async move {
-            let draft = AtomDraft::new(args.url);
+            let draft = AtomDraft::new(args.url).with_request(RequestSettings {
+                headers: args.headers,
+                basic_auth: args.basic_auth,
+                bearer_token: args.bearer_token,
+                cookies: args.cookies,
+            });
             let url = draft.url.to_string();
 
             let result = sources.add(Atom(draft)).await;
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::Args;
 use news_core::models::source::SourceEnum::{Atom, Telegram};
+use news_core::models::source::request::RequestSettings;
 use news_core::services::source::SourceService;
 use std::sync::Arc;
 
@@ -12,11 +13,38 @@ pub async fn list_sources(
     _args: ArgsList,
 ) -> Result<()> {
     for source in sources.get_all().await? {
-        let (name, url) = match source {
-            Atom(src) => ("Atom/RSS", src.url),
-            Telegram(src) => ("Telegram", src.public_url),
+        let (name, id, url, request) = match source {
+            Atom(src) => ("Atom/RSS", src.id, src.url, src.request),
+            Telegram(src) => (
+                "Telegram",
+                src.id,
+                src.public_url,
+                RequestSettings::default(),
+            ),
         };
         println!("{:>12} = {}", name, url);
+        println!("{:>12}   id {}", "", id);
+
+        for line in request_lines(&request.redacted()) {
+            println!("{:>12}   {}", "", line);
+        }
     }
     Ok(())
 }
+
+/// Request settings of a source, one per line; secrets must be redacted.
+fn request_lines(request: &RequestSettings) -> Vec<String> {
+    let headers = request.headers.iter().map(|h| format!("header {h}"));
+    let basic_auth = request.basic_auth.iter().map(|a| format!("basic auth {a}"));
+    let bearer = request
+        .bearer_token
+        .iter()
+        .map(|t| format!("bearer token {t}"));
+    let cookies = request.cookies.iter().map(|c| format!("cookie {c}"));
+
+    headers
+        .chain(basic_auth)
+        .chain(bearer)
+        .chain(cookies)
+        .collect()
+}
@@ -3,9 +3,11 @@
 
 use self::atom::{RemoveAtom, remove_atom_source};
 use self::telegram::{RemoveTelegram, remove_telegram_source};
-use anyhow::Result;
+use crate::commands::source::resolve_source;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use news_core::services::source::SourceService;
+use news_sqlite_core::db::maintenance::forget_source;
+use news_watch::state::AppState;
 use std::sync::Arc;
 
 #[derive(Parser, Debug)]
@@ -27,12 +29,35 @@ pub enum RemoveCommands {
     Telegram(RemoveTelegram),
 }
 
-pub async fn remove_source(
-    sources: Arc<impl SourceService + 'static>,
-    command: RemoveCommand,
-) -> Result<()> {
+impl RemoveCommands {
+    fn key(&self) -> String {
+        match self {
+            Self::Atom(args) => args.url.to_string(),
+            Self::Telegram(args) => args.username.clone(),
+        }
+    }
+}
+
+pub async fn remove_source(state: Arc<AppState>, command: RemoveCommand) -> Result<()> {
+    let sources = state.sources();
+    // Looked up first: once removed, the source cannot be resolved anymore.
+    let source_id = resolve_source(sources.as_ref(), &command.command.key())
+        .await
+        .ok();
+
     match command.command {
         RemoveCommands::Atom(args) => remove_atom_source(sources, args).await,
         RemoveCommands::Telegram(args) => remove_telegram_source(sources, args).await,
+    }?;
+
+    // Webhooks, notification routes and rules stay in the local database
+    // even when sources are kept in Postgres, so nothing cascades to them.
+    if let Some(id) = source_id {
+        let pool = state.storage().sqlite().pool().await?;
+        forget_source(&pool, id).await.context(
+            "Failed to remove the webhooks, notification routes and rules of the source",
+        )?;
     }
+
+    Ok(())
 }
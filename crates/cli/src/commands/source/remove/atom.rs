@@ -12,7 +12,7 @@
 #[derive(Debug, Args)]
 pub struct RemoveAtom {
     #[arg(value_parser = parse_url)]
-    url: Url,
+    pub(super) url: Url,
 }
 
 pub async fn remove_atom_source(
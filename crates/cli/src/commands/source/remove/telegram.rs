@@ -11,7 +11,7 @@
 #[derive(Debug, Args)]
 pub struct RemoveTelegram {
     #[arg(value_parser = parse_telegram_username)]
-    username: String,
+    pub(super) username: String,
 }
 
 pub async fn remove_telegram_source(
@@ -0,0 +1,52 @@
+mod add;
+mod list;
+mod remove;
+mod replay;
+mod test;
+
+use self::add::{AddWebhook, add_webhook};
+use self::list::{ListWebhooks, list_webhooks};
+use self::remove::{RemoveWebhook, remove_webhook};
+use self::replay::{ReplayWebhook, replay_webhook};
+use self::test::{TestWebhook, test_webhook};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use news_watch::state::AppState;
+use std::sync::Arc;
+
+#[derive(Parser, Debug)]
+#[command(about = "Manage outbound webhooks notified about new and updated articles")]
+pub struct WebhookCommand {
+    #[command(subcommand)]
+    command: WebhookCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WebhookCommands {
+    #[command(about = "List configured webhooks and their delivery queue", visible_aliases = ["ls"])]
+    List(ListWebhooks),
+
+    #[command(about = "Add a webhook endpoint")]
+    Add(AddWebhook),
+
+    #[command(about = "Remove a webhook endpoint and its queued deliveries", visible_aliases = ["rm"])]
+    Remove(RemoveWebhook),
+
+    #[command(about = "Send a signed test payload to a webhook endpoint")]
+    Test(TestWebhook),
+
+    #[command(about = "Put failed (or any single) deliveries back into the queue")]
+    Replay(ReplayWebhook),
+}
+
+pub async fn run_webhook(cmd: WebhookCommand, state: Arc<AppState>) -> Result<()> {
+    let webhooks = state.webhooks().await?;
+
+    match cmd.command {
+        WebhookCommands::List(args) => list_webhooks(webhooks, args).await,
+        WebhookCommands::Add(args) => add_webhook(webhooks, state.sources(), args).await,
+        WebhookCommands::Remove(args) => remove_webhook(webhooks, args).await,
+        WebhookCommands::Test(args) => test_webhook(webhooks, args).await,
+        WebhookCommands::Replay(args) => replay_webhook(webhooks, args).await,
+    }
+}
@@ -0,0 +1,85 @@
+use crate::cli::report::Report;
+use crate::commands::source::resolve_source;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::models::webhook::{WebhookDraft, WebhookEvent};
+use news_core::services::source::SourceService;
+use news_core::services::webhook::WebhookService;
+use news_sqlite_core::utils::parse::parse_url;
+use std::sync::Arc;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct AddWebhook {
+    #[arg(value_parser = parse_url)]
+    url: Url,
+
+    #[arg(long, help = "Secret used to sign payloads (generated when omitted)")]
+    secret: Option<String>,
+
+    #[arg(
+        long = "event",
+        help = "Event to deliver: article.created or article.updated (can be repeated, default: all)"
+    )]
+    events: Vec<WebhookEvent>,
+
+    #[arg(
+        long,
+        help = "Only deliver events of this source (Atom feed URL or Telegram username)"
+    )]
+    source: Option<String>,
+
+    #[arg(
+        long,
+        help = "Only deliver events of articles with this tag, given by a rule"
+    )]
+    tag: Option<String>,
+}
+
+fn generate_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub async fn add_webhook(
+    webhooks: Arc<impl WebhookService + 'static>,
+    sources: Arc<impl SourceService + 'static>,
+    args: AddWebhook,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let source_id = match &args.source {
+                Some(key) => Some(resolve_source(sources.as_ref(), key).await?),
+                None => None,
+            };
+
+            let events = match args.events.is_empty() {
+                true => WebhookEvent::ALL.to_vec(),
+                false => args.events,
+            };
+
+            let url = args.url.to_string();
+            let draft = WebhookDraft {
+                url: args.url,
+                secret: args.secret.unwrap_or_else(generate_secret),
+                events,
+                source_id,
+                tag: args.tag,
+            };
+
+            let webhook = webhooks
+                .add(draft)
+                .await
+                .context(format!("Failed to add webhook: {url}"))?;
+
+            task.finish_with_text(format!(
+                "Webhook {} added for {url}, signing secret: {}",
+                webhook.id, webhook.secret
+            ));
+
+            Ok(())
+        })
+    })
+    .await
+}
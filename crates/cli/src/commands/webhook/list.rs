@@ -0,0 +1,30 @@
+use anyhow::Result;
+use clap::Args;
+use news_core::services::webhook::WebhookService;
+use std::sync::Arc;
+
+#[derive(Debug, Args)]
+pub struct ListWebhooks {}
+
+pub async fn list_webhooks(
+    webhooks: Arc<impl WebhookService + 'static>,
+    _args: ListWebhooks,
+) -> Result<()> {
+    for (webhook, stats) in webhooks.get_all().await? {
+        let events: Vec<&str> = webhook.events.iter().map(|e| e.as_str()).collect();
+        let mut scope = webhook
+            .source_id
+            .map_or_else(|| "all sources".to_string(), |id| format!("source {id}"));
+        if let Some(tag) = &webhook.tag {
+            scope.push_str(&format!(", tag {tag}"));
+        }
+
+        println!("{} = {}", webhook.id, webhook.url);
+        println!("{:>38} {} ({})", "events:", events.join(", "), scope);
+        println!(
+            "{:>38} {} pending, {} delivered, {} failed",
+            "queue:", stats.pending, stats.delivered, stats.failed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::webhook::WebhookService;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct RemoveWebhook {
+    id: Uuid,
+}
+
+pub async fn remove_webhook(
+    webhooks: Arc<impl WebhookService + 'static>,
+    args: RemoveWebhook,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            webhooks
+                .drop_by_id(args.id)
+                .await
+                .context(format!("Failed to remove webhook: {}", args.id))?;
+
+            task.finish_with_text(format!("Webhook removed successfully: {}", args.id));
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,55 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::webhook::{ReplayCriteria, WebhookService};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct ReplayWebhook {
+    #[arg(
+        required_unless_present = "failed",
+        help = "Delivery to send again, whatever its status"
+    )]
+    delivery: Option<Uuid>,
+
+    #[arg(
+        long,
+        conflicts_with = "delivery",
+        help = "Re-queue all failed deliveries"
+    )]
+    failed: bool,
+
+    #[arg(
+        long,
+        requires = "failed",
+        help = "Only re-queue failed deliveries of this webhook"
+    )]
+    webhook: Option<Uuid>,
+}
+
+pub async fn replay_webhook(
+    webhooks: Arc<impl WebhookService + 'static>,
+    args: ReplayWebhook,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let criteria = match args.delivery {
+                Some(id) => ReplayCriteria::Delivery(id),
+                None => ReplayCriteria::Failed(args.webhook),
+            };
+
+            let requeued = webhooks
+                .replay(criteria)
+                .await
+                .context("Failed to re-queue webhook deliveries")?;
+
+            task.finish_with_text(format!(
+                "{requeued} deliveries re-queued, they are sent by a running `uninews collect`"
+            ));
+            Ok(())
+        })
+    })
+    .await
+}
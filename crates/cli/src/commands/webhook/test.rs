@@ -0,0 +1,35 @@
+use crate::cli::report::Report;
+use crate::report::{ReportExt, ReportStatus};
+use anyhow::{Context, Result};
+use clap::Args;
+use news_core::services::webhook::WebhookService;
+use news_watch::webhook::send_test;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub struct TestWebhook {
+    id: Uuid,
+}
+
+pub async fn test_webhook(
+    webhooks: Arc<impl WebhookService + 'static>,
+    args: TestWebhook,
+) -> Result<()> {
+    Report::silent(move |task| {
+        Box::pin(async move {
+            let webhook = webhooks.get_by_id(args.id).await?;
+
+            let status = send_test(&webhook)
+                .await
+                .context(format!("Test delivery to {} failed", webhook.url))?;
+
+            task.finish_with_text(format!(
+                "Test delivery to {} accepted with {status}",
+                webhook.url
+            ));
+            Ok(())
+        })
+    })
+    .await
+}
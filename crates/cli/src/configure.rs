@@ -1,26 +1,56 @@
-use crate::cli::output::OutputConfig;
 use anyhow::{Context, Result};
-use dotenvy::{dotenv, var};
+use console::{colors_enabled, set_colors_enabled};
+use news_core::config::{ColorMode, LogFormat, LogOutput, Settings};
+use std::fs::OpenOptions;
+use std::sync::Mutex;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
-pub fn init_logger() -> Result<()> {
-    let log_level = match OutputConfig::is_verbose() {
+fn install(subscriber: impl Subscriber + Send + Sync + 'static) -> Result<()> {
+    tracing::subscriber::set_global_default(subscriber)
+        .context("Failed to set global default subscriber")
+}
+
+pub fn init_logger(settings: &Settings) -> Result<()> {
+    let log_level = match settings.output.verbose {
         true => "debug".to_string(),
-        false => var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+        false => settings.log.level.clone(),
     };
 
-    let subscriber = FmtSubscriber::builder()
+    let (writer, is_terminal) = match &settings.log.output {
+        LogOutput::Stderr => (BoxMakeWriter::new(std::io::stderr), true),
+        LogOutput::Stdout => (BoxMakeWriter::new(std::io::stdout), true),
+        LogOutput::File(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            (BoxMakeWriter::new(Mutex::new(file)), false)
+        }
+    };
+
+    let builder = FmtSubscriber::builder()
         .with_thread_names(true)
         .with_target(false)
-        .with_env_filter(EnvFilter::new(log_level))
-        .finish();
+        .with_ansi(is_terminal && colors_enabled())
+        .with_writer(writer)
+        .with_env_filter(EnvFilter::new(log_level));
 
-    tracing::subscriber::set_global_default(subscriber)
-        .context("Failed to set global default subscriber")
+    match settings.log.format {
+        LogFormat::Full => install(builder.finish()),
+        LogFormat::Compact => install(builder.compact().finish()),
+        LogFormat::Json => install(builder.json().finish()),
+    }
 }
 
-pub fn configure() -> Result<()> {
-    init_logger()?;
-    dotenv().ok();
-    Ok(())
+pub fn configure(settings: &Settings) -> Result<()> {
+    match settings.output.color {
+        ColorMode::Auto => {}
+        ColorMode::Always => set_colors_enabled(true),
+        ColorMode::Never => set_colors_enabled(false),
+    }
+
+    init_logger(settings)
 }
@@ -0,0 +1,12 @@
+mod loader;
+mod settings;
+
+pub use loader::{
+    ConfigEntry, ConfigError, ConfigOverrides, LoadedConfig, Origin, config_paths, user_config_path,
+};
+pub use settings::{
+    ColorMode, DatabaseSettings, DatabaseUrl, DuplicateSettings, HttpSettings, JournalMode,
+    LogFormat, LogOutput, LogSettings, MediaSettings, OutputSettings, RetentionSettings, Settings,
+    SmtpSecurity, SmtpSettings, SourceHttpSettings, SourceRetentionSettings, SourceWatchSettings,
+    Synchronous, WatchSettings, names_source,
+};
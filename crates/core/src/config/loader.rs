@@ -0,0 +1,330 @@
+use super::settings::Settings;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env::var;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use toml::{Table, Value};
+
+const SYSTEM_CONFIG_PATH: &str = "/etc/uninews/config.toml";
+const CONFIG_PATH_ENV: &str = "UNINEWS_CONFIG_PATH";
+
+/// Environment variables mapped one-to-one onto configuration keys.
+const ENV_VARS: &[(&str, &str)] = &[
+    ("UNINEWS_DB_PATH", "database.path"),
+    ("UNINEWS_DB_URL", "database.url"),
+    ("UNINEWS_POLL_INTERVAL", "watch.poll_interval"),
+    ("UNINEWS_MAX_BACKOFF", "watch.max_backoff"),
+    ("UNINEWS_HTTP_USER_AGENT", "http.user_agent"),
+    ("UNINEWS_HTTP_TIMEOUT", "http.timeout"),
+    ("UNINEWS_HTTP_CONNECT_TIMEOUT", "http.connect_timeout"),
+    ("UNINEWS_HTTP_READ_TIMEOUT", "http.read_timeout"),
+    ("UNINEWS_HTTP_PROXY", "http.proxy"),
+    ("UNINEWS_HTTP_SECRETS_FILE", "http.secrets_file"),
+    ("UNINEWS_MEDIA_DIR", "media.dir"),
+    ("RUST_LOG", "log.level"),
+    ("UNINEWS_LOG_FORMAT", "log.format"),
+    ("UNINEWS_LOG_OUTPUT", "log.output"),
+    ("UNINEWS_SMTP_HOST", "smtp.host"),
+    ("UNINEWS_SMTP_PORT", "smtp.port"),
+    ("UNINEWS_SMTP_SECURITY", "smtp.security"),
+    ("UNINEWS_SMTP_USERNAME", "smtp.username"),
+    ("UNINEWS_SMTP_PASSWORD", "smtp.password"),
+    ("UNINEWS_SMTP_FROM", "smtp.from"),
+];
+
+/// Where the effective value of a configuration key comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Flag(&'static str),
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Env(name) => write!(f, "env {name}"),
+            Self::Flag(flag) => write!(f, "flag {flag}"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read configuration file {0}")]
+    Read(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to parse configuration file {0}")]
+    Parse(PathBuf, #[source] toml::de::Error),
+
+    #[error("Invalid configuration from {0}")]
+    Invalid(Origin, #[source] toml::de::Error),
+}
+
+/// Values given on the command line, the last and strongest layer.
+#[derive(Debug, Default)]
+pub struct ConfigOverrides {
+    /// Configuration file to read instead of `UNINEWS_CONFIG_PATH`.
+    pub file: Option<PathBuf>,
+    flags: Vec<(&'static str, &'static str, Value)>,
+}
+
+impl ConfigOverrides {
+    #[must_use]
+    pub fn with_file(mut self, file: Option<PathBuf>) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Sets `key` to `value` on behalf of the command-line `flag`.
+    #[must_use]
+    pub fn with_flag(
+        mut self,
+        flag: &'static str,
+        key: &'static str,
+        value: impl Into<Value>,
+    ) -> Self {
+        self.flags.push((flag, key, value.into()));
+        self
+    }
+}
+
+/// The per-user configuration file: `$XDG_CONFIG_HOME/uninews/config.toml`,
+/// falling back to `~/.config/uninews/config.toml`.
+#[must_use]
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            var("HOME")
+                .ok()
+                .map(|home| Path::new(&home).join(".config"))
+        })?;
+
+    Some(config_home.join("uninews").join("config.toml"))
+}
+
+/// Configuration files in the order they are applied, each paired with
+/// whether it must exist. Only an explicitly requested file is required.
+#[must_use]
+pub fn config_paths(overrides: &ConfigOverrides) -> Vec<(PathBuf, bool)> {
+    let mut paths = vec![(PathBuf::from(SYSTEM_CONFIG_PATH), false)];
+
+    if let Some(path) = user_config_path() {
+        paths.push((path, false));
+    }
+
+    let explicit = overrides
+        .file
+        .clone()
+        .or_else(|| var(CONFIG_PATH_ENV).ok().map(PathBuf::from));
+
+    if let Some(path) = explicit {
+        paths.push((path, true));
+    }
+
+    paths
+}
+
+pub struct ConfigEntry<'a> {
+    pub key: String,
+    pub value: Value,
+    pub origin: &'a Origin,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub settings: Settings,
+    /// Configuration files that were found and applied.
+    pub files: Vec<PathBuf>,
+    origins: BTreeMap<String, Origin>,
+}
+
+impl LoadedConfig {
+    /// Layers defaults, the system and user configuration files, the file from
+    /// `UNINEWS_CONFIG_PATH` (or `overrides.file`), environment variables and
+    /// command-line flags, each overriding the previous ones key by key.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError`] naming the file or variable that holds an
+    /// unreadable, malformed or invalid value.
+    pub fn load(overrides: &ConfigOverrides) -> Result<Self, ConfigError> {
+        let mut layers = Layers::new();
+
+        for (path, is_required) in config_paths(overrides) {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound && !is_required => continue,
+                Err(e) => return Err(ConfigError::Read(path, e)),
+            };
+
+            let table: Table =
+                toml::from_str(&content).map_err(|e| ConfigError::Parse(path.clone(), e))?;
+
+            layers.apply(table, &Origin::File(path.clone()))?;
+            layers.files.push(path);
+        }
+
+        for (name, key) in ENV_VARS {
+            if let Ok(value) = var(name) {
+                layers.apply(table_of(key, Value::String(value)), &Origin::Env(name))?;
+            }
+        }
+
+        // Conventional switches rather than plain values.
+        if var("NO_COLOR").is_ok_and(|value| !value.is_empty()) {
+            layers.apply(
+                table_of("output.color", "never".into()),
+                &Origin::Env("NO_COLOR"),
+            )?;
+        }
+
+        if let Ok(value) = var("DEBUG") {
+            layers.apply(
+                table_of("output.verbose", switch(value)),
+                &Origin::Env("DEBUG"),
+            )?;
+        }
+
+        for (flag, key, value) in &overrides.flags {
+            layers.apply(table_of(key, value.clone()), &Origin::Flag(flag))?;
+        }
+
+        Ok(Self {
+            settings: layers.settings,
+            files: layers.files,
+            origins: layers.origins,
+        })
+    }
+
+    /// Loads a single configuration file on top of the defaults only.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError`] if the file is unreadable, malformed or invalid.
+    pub fn load_file(path: &Path) -> Result<Self, ConfigError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| ConfigError::Read(path.to_path_buf(), e))?;
+        let table: Table =
+            toml::from_str(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+
+        let mut layers = Layers::new();
+        layers.apply(table, &Origin::File(path.to_path_buf()))?;
+        layers.files.push(path.to_path_buf());
+
+        Ok(Self {
+            settings: layers.settings,
+            files: layers.files,
+            origins: layers.origins,
+        })
+    }
+
+    /// Effective values grouped by section, each with its origin.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(String, Vec<ConfigEntry<'_>>)> {
+        let Ok(table) = Table::try_from(&self.settings) else {
+            return Vec::new();
+        };
+
+        table
+            .into_iter()
+            .filter_map(|(section, values)| match values {
+                Value::Table(values) => Some((section, values)),
+                _ => None,
+            })
+            .map(|(section, values)| {
+                let values = values
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let origin = self
+                            .origins
+                            .get(&format!("{section}.{key}"))
+                            .unwrap_or(&Origin::Default);
+                        ConfigEntry { key, value, origin }
+                    })
+                    .collect();
+                (section, values)
+            })
+            .collect()
+    }
+}
+
+struct Layers {
+    merged: Table,
+    settings: Settings,
+    files: Vec<PathBuf>,
+    origins: BTreeMap<String, Origin>,
+}
+
+impl Layers {
+    fn new() -> Self {
+        let settings = Settings::default();
+
+        Self {
+            merged: Table::try_from(&settings).unwrap_or_default(),
+            settings,
+            files: Vec::new(),
+            origins: BTreeMap::new(),
+        }
+    }
+
+    /// Merges `layer` and re-validates, so errors point at the layer that caused them.
+    fn apply(&mut self, layer: Table, origin: &Origin) -> Result<(), ConfigError> {
+        merge(&mut self.merged, layer, "", origin, &mut self.origins);
+
+        self.settings = Settings::deserialize(Value::Table(self.merged.clone()))
+            .map_err(|e| ConfigError::Invalid(origin.clone(), e))?;
+
+        Ok(())
+    }
+}
+
+fn merge(
+    base: &mut Table,
+    layer: Table,
+    prefix: &str,
+    origin: &Origin,
+    origins: &mut BTreeMap<String, Origin>,
+) {
+    for (key, value) in layer {
+        let path = match prefix {
+            "" => key.clone(),
+            _ => format!("{prefix}.{key}"),
+        };
+
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(layer)) => {
+                merge(base, layer, &path, origin, origins);
+            }
+            (_, value) => {
+                origins.insert(path, origin.clone());
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The boolean spelled by an on/off environment variable such as `DEBUG=0`.
+/// Anything else is kept as a string, which then fails validation.
+fn switch(value: String) -> Value {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => true.into(),
+        "" | "0" | "false" | "no" | "off" => false.into(),
+        _ => value.into(),
+    }
+}
+
+fn table_of(key: &str, value: Value) -> Table {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            Table::from_iter([(head.to_string(), Value::Table(table_of(rest, value)))])
+        }
+        None => Table::from_iter([(key.to_string(), value)]),
+    }
+}
@@ -0,0 +1,622 @@
+use crate::models::source::request::{REDACTED, references_secret};
+use crate::utils::language::is_supported;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
+
+/// Whether `name`, the `source` of a per-source entry, names the source with
+/// `id` and `key`. Entries name a source by its key or by its id, which unlike
+/// a feed URL stays the same when the feed moves.
+#[must_use]
+pub fn names_source(name: &str, id: Uuid, key: &str) -> bool {
+    name == key || Uuid::parse_str(name).is_ok_and(|name| name == id)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub database: DatabaseSettings,
+    pub watch: WatchSettings,
+    pub http: HttpSettings,
+    pub log: LogSettings,
+    pub output: OutputSettings,
+    pub retention: RetentionSettings,
+    pub media: MediaSettings,
+    pub duplicates: DuplicateSettings,
+    pub smtp: SmtpSettings,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    #[default]
+    Wal,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Synchronous {
+    Off,
+    #[default]
+    Normal,
+    Full,
+    Extra,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    /// Path to the `SQLite` database file.
+    pub path: PathBuf,
+    /// Database to use instead of `path`, selected by the URL scheme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<DatabaseUrl>,
+    pub journal_mode: JournalMode,
+    pub synchronous: Synchronous,
+    /// How long a connection waits for a lock held by another one before failing.
+    #[serde(with = "duration")]
+    pub busy_timeout: Duration,
+    pub foreign_keys: bool,
+    /// Size of the pool used by commands that write.
+    pub max_connections: u32,
+    /// Size of the read-only pool used by commands that only query.
+    pub max_read_connections: u32,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("data/app.sqlite"),
+            url: None,
+            journal_mode: JournalMode::default(),
+            synchronous: Synchronous::default(),
+            busy_timeout: Duration::from_secs(5),
+            foreign_keys: true,
+            max_connections: 4,
+            max_read_connections: 8,
+        }
+    }
+}
+
+impl DatabaseSettings {
+    /// The `SQLite` file: the one of a `sqlite:` URL, otherwise `path`. Also used
+    /// with a Postgres URL by the features Postgres does not store yet.
+    #[must_use]
+    pub fn sqlite_path(&self) -> &Path {
+        match &self.url {
+            Some(DatabaseUrl::Sqlite(path)) => path,
+            _ => &self.path,
+        }
+    }
+
+    #[must_use]
+    pub fn postgres_url(&self) -> Option<&str> {
+        match &self.url {
+            Some(DatabaseUrl::Postgres(url)) => Some(url),
+            _ => None,
+        }
+    }
+}
+
+/// `sqlite:<path>` (or `sqlite://<path>`) or `postgres://...` (or `postgresql://...`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseUrl {
+    Sqlite(PathBuf),
+    Postgres(String),
+}
+
+impl Display for DatabaseUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sqlite(path) => write!(f, "sqlite://{}", path.display()),
+            Self::Postgres(url) => f.write_str(url),
+        }
+    }
+}
+
+impl Serialize for DatabaseUrl {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DatabaseUrl {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let url = String::deserialize(deserializer)?;
+
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            return Ok(Self::Postgres(url));
+        }
+
+        match url
+            .strip_prefix("sqlite://")
+            .or_else(|| url.strip_prefix("sqlite:"))
+        {
+            Some(path) if !path.is_empty() => Ok(Self::Sqlite(PathBuf::from(path))),
+            _ => Err(serde::de::Error::custom(format!(
+                "unsupported database URL '{url}', expected sqlite:<path> or postgres://..."
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchSettings {
+    /// Delay between two successful checks of a source.
+    #[serde(with = "duration")]
+    pub poll_interval: Duration,
+    /// Upper bound of the delay after repeated failures.
+    #[serde(with = "duration")]
+    pub max_backoff: Duration,
+    /// Delay before fetching again a page whose full content could not be
+    /// fetched or extracted.
+    #[serde(with = "duration")]
+    pub full_content_retry: Duration,
+    /// Settings of single sources.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceWatchSettings>,
+}
+
+impl Default for WatchSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(10 * 60),
+            full_content_retry: Duration::from_secs(6 * 60 * 60),
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl WatchSettings {
+    /// Whether the articles of the source with `id` and `key` are completed
+    /// with the main text of their linked page.
+    #[must_use]
+    pub fn full_content(&self, id: Uuid, key: &str) -> bool {
+        self.sources
+            .iter()
+            .any(|settings| names_source(&settings.source, id, key) && settings.full_content)
+    }
+
+    /// Languages the articles of the source with `id` and `key` are
+    /// restricted to; empty when it keeps every language.
+    #[must_use]
+    pub fn languages(&self, id: Uuid, key: &str) -> &[String] {
+        self.sources
+            .iter()
+            .find(|settings| {
+                names_source(&settings.source, id, key) && !settings.languages.is_empty()
+            })
+            .map_or(&[], |settings| settings.languages.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceWatchSettings {
+    /// Feed URL of an Atom source, username of a Telegram channel or id of
+    /// either.
+    pub source: String,
+    /// Fetch the page each new article links to and store its main text as the
+    /// article content; for feeds that only carry summaries.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub full_content: bool,
+    /// Only store the items detected in these languages, as ISO 639-1 codes
+    /// such as `en`; items whose language is not detected are kept.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "language_codes"
+    )]
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpSettings {
+    pub user_agent: String,
+    /// Total time allowed for a single request, including the body.
+    #[serde(with = "duration")]
+    pub timeout: Duration,
+    #[serde(with = "duration")]
+    pub connect_timeout: Duration,
+    /// Longest wait for the next part of a response.
+    #[serde(with = "duration")]
+    pub read_timeout: Duration,
+    /// Responses with a larger body, in bytes after decompression, are rejected.
+    pub max_body_size: u64,
+    pub gzip: bool,
+    pub brotli: bool,
+    /// HTTP, HTTPS or SOCKS5 proxy of every request; unset falls back to the
+    /// `HTTP_PROXY` and `HTTPS_PROXY` environment variables.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<Url>,
+    /// PEM files with certificate authorities trusted besides the system ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ca_certificates: Vec<PathBuf>,
+    /// Redirects followed by one request before it fails.
+    pub max_redirects: usize,
+    /// TOML file of `NAME = "value"` secrets referenced by sources as `${NAME}`,
+    /// when no environment variable of that name is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets_file: Option<PathBuf>,
+    /// How long a host's `robots.txt` is trusted before it is fetched again.
+    #[serde(with = "duration")]
+    pub robots_ttl: Duration,
+    /// Settings of single sources, replacing the global ones they set.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceHttpSettings>,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            user_agent: format!("uninews/{}", env!("CARGO_PKG_VERSION")),
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(20),
+            max_body_size: 16 * 1024 * 1024,
+            gzip: true,
+            brotli: true,
+            proxy: None,
+            ca_certificates: Vec::new(),
+            max_redirects: 10,
+            secrets_file: None,
+            robots_ttl: Duration::from_secs(24 * 60 * 60),
+            sources: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceHttpSettings {
+    /// Feed URL of an Atom source, username of a Telegram channel or id of
+    /// either.
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<Url>,
+    /// Poll the source even where `robots.txt` disallows it, and without its
+    /// `Crawl-delay`; for feeds the publisher allowed us to poll.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_robots: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Full,
+    Compact,
+    Json,
+}
+
+/// Where log records are written: `stderr`, `stdout` or a file path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LogOutput {
+    #[default]
+    Stderr,
+    Stdout,
+    File(PathBuf),
+}
+
+impl Serialize for LogOutput {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Stderr => serializer.serialize_str("stderr"),
+            Self::Stdout => serializer.serialize_str("stdout"),
+            Self::File(path) => path.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LogOutput {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "stderr" => Self::Stderr,
+            "stdout" => Self::Stdout,
+            path => Self::File(PathBuf::from(path)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSettings {
+    /// `tracing` env-filter directives, e.g. `info` or `uninews=debug,sqlx=warn`.
+    pub level: String,
+    pub format: LogFormat,
+    pub output: LogOutput,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::default(),
+            output: LogOutput::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputSettings {
+    pub color: ColorMode,
+    /// Print debug logs and detailed task output.
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// Delete articles stored longer ago than this; unset keeps them forever.
+    #[serde(with = "optional_duration", skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<Duration>,
+    /// Keep only this many of the newest articles of each source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_source: Option<u32>,
+    /// Never delete starred articles.
+    pub keep_starred: bool,
+    /// How often `collect` prunes while it runs.
+    #[serde(with = "duration")]
+    pub interval: Duration,
+    /// Limits of single sources, replacing the global ones they set.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceRetentionSettings>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            max_age: None,
+            max_per_source: None,
+            keep_starred: true,
+            interval: Duration::from_secs(60 * 60),
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl RetentionSettings {
+    /// Whether no limit is set, globally or for any source.
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age.is_none()
+            && self.max_per_source.is_none()
+            && self
+                .sources
+                .iter()
+                .all(|source| source.max_age.is_none() && source.max_per_source.is_none())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceRetentionSettings {
+    /// Feed URL of an Atom source, username of a Telegram channel or id of
+    /// either.
+    pub source: String,
+    #[serde(
+        default,
+        with = "optional_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_age: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_source: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MediaSettings {
+    /// Download the images of new articles, which rendering points at the copies.
+    pub enabled: bool,
+    /// Directory of the cached files, named by the SHA-256 of their content.
+    pub dir: PathBuf,
+    /// Larger files are not cached, in bytes.
+    pub max_file_size: u64,
+    /// No file is added once the cached files take this many bytes.
+    pub max_total_size: u64,
+    /// Types of the files that are cached, as read from their first bytes.
+    pub content_types: Vec<String>,
+    /// Larger attachments are not downloaded by `media download`, in bytes.
+    pub max_attachment_size: u64,
+    /// How often `collect` removes the files no stored image maps to.
+    #[serde(with = "duration")]
+    pub gc_interval: Duration,
+}
+
+impl Default for MediaSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from("data/media"),
+            max_file_size: 5 * 1024 * 1024,
+            max_total_size: 1024 * 1024 * 1024,
+            content_types: [
+                "image/jpeg",
+                "image/png",
+                "image/gif",
+                "image/webp",
+                "image/avif",
+            ]
+            .map(String::from)
+            .to_vec(),
+            max_attachment_size: 4 * 1024 * 1024 * 1024,
+            gc_interval: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuplicateSettings {
+    /// New articles are compared with the articles stored this long before.
+    #[serde(with = "duration")]
+    pub window: Duration,
+    /// Largest number of differing `SimHash` bits of two duplicate texts;
+    /// `0` only groups identical texts.
+    pub max_distance: u32,
+}
+
+impl Default for DuplicateSettings {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(3 * 24 * 60 * 60),
+            max_distance: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with `STARTTLS` (port 587).
+    #[default]
+    Starttls,
+    /// Implicit TLS from the first byte (port 465).
+    Tls,
+    /// Unencrypted connection, only meant for local SMTP sinks (port 25).
+    None,
+}
+
+impl SmtpSecurity {
+    #[must_use]
+    pub const fn default_port(self) -> u16 {
+        match self {
+            Self::Starttls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+/// Server `uninews digest` sends its emails through.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmtpSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Unset uses the usual port of `security`.
+    #[serde(
+        deserialize_with = "optional_port",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub port: Option<u16>,
+    pub security: SmtpSecurity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// A `${NAME}` reference to a secret, or the password itself.
+    #[serde(serialize_with = "redacted", skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Sender address, e.g. `UniNews <uninews@example.com>`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+/// A port given as a TOML integer or, by an environment variable, as a string.
+fn optional_port<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u16>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Port {
+        Number(u16),
+        Text(String),
+    }
+
+    match Port::deserialize(deserializer)? {
+        Port::Number(port) => Ok(Some(port)),
+        Port::Text(port) => port.trim().parse().map(Some).map_err(|_| {
+            serde::de::Error::custom(format!("invalid port '{port}', expected 0 to 65535"))
+        }),
+    }
+}
+
+/// Shows a `${NAME}` reference as it is and hides any other value.
+fn redacted<S: serde::Serializer>(
+    value: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) if references_secret(value) => serializer.serialize_str(value),
+        Some(_) => serializer.serialize_str(REDACTED),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Lowercased ISO 639-1 codes, each of a language that can be detected.
+fn language_codes<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|code| {
+            let lowercase = code.to_lowercase();
+            if is_supported(&lowercase) {
+                Ok(lowercase)
+            } else {
+                Err(serde::de::Error::custom(format!(
+                    "unknown language code '{code}', expected an ISO 639-1 code such as en"
+                )))
+            }
+        })
+        .collect()
+}
+
+mod duration {
+    use crate::utils::duration::{format_duration, parse_duration};
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_duration(*value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_duration(&value).map_err(de::Error::custom)
+    }
+}
+
+mod optional_duration {
+    use crate::utils::duration::{format_duration, parse_duration};
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&format_duration(*value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_duration(&value).map(Some).map_err(de::Error::custom)
+    }
+}
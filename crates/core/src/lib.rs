@@ -1,3 +1,5 @@
+pub mod config;
 pub mod errors;
 pub mod models;
 pub mod services;
+pub mod utils;
@@ -1,5 +1,10 @@
+pub mod media;
 pub mod news;
+pub mod notifier;
+pub mod retention;
+pub mod rule;
 pub mod source;
+pub mod webhook;
 
 pub trait ExternalEntity {
     fn source_key(&self) -> &str;
@@ -0,0 +1,84 @@
+/// Path under which rendered articles reference cached files, as in
+/// `src="/media/<sha256>.jpg"` or, in Markdown, `![](/media/<sha256>.jpg)`.
+/// Exports point it to where they copy the files.
+pub const MEDIA_PATH: &str = "/media/";
+
+/// A file of the media cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaFile {
+    /// Hex-encoded SHA-256 of the content, followed by an extension.
+    pub name: String,
+    pub content_type: String,
+    pub size: u64,
+}
+
+/// What the media cache knows of a remote URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachedMedia {
+    File(MediaFile),
+    /// Fetching or storing it failed, and is not tried again until failures
+    /// are forgotten.
+    Failed,
+}
+
+/// Whether `name` can be the name of a cached file, which also makes it safe
+/// to join to the media directory.
+#[must_use]
+pub fn is_media_name(name: &str) -> bool {
+    let Some((hash, extension)) = name.split_once('.') else {
+        return false;
+    };
+
+    hash.len() == 64
+        && hash
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        && (1..=4).contains(&extension.len())
+        && extension
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
+/// Cached files `text` references through an HTML attribute or a Markdown
+/// link starting with [`MEDIA_PATH`], as byte ranges of their names.
+fn reference_ranges(text: &str) -> Vec<(usize, usize)> {
+    text.match_indices(MEDIA_PATH)
+        .filter_map(|(at, _)| {
+            let ends: &[char] = match text[..at].chars().next_back()? {
+                '"' => &['"'],
+                '(' => &[')', ' '],
+                _ => return None,
+            };
+            let start = at + MEDIA_PATH.len();
+            let end = text[start..]
+                .find(ends)
+                .map_or(text.len(), |len| start + len);
+            is_media_name(&text[start..end]).then_some((start, end))
+        })
+        .collect()
+}
+
+/// Names of the cached files `text` references, in order and with repeats.
+pub fn media_references(text: &str) -> impl Iterator<Item = &str> {
+    reference_ranges(text)
+        .into_iter()
+        .map(|(start, end)| &text[start..end])
+}
+
+/// `text` with its references to cached files pointing under `path` instead
+/// of [`MEDIA_PATH`], such as `../media/` for a page one directory deep.
+#[must_use]
+pub fn relocate_media(text: &str, path: &str) -> String {
+    let mut relocated = String::with_capacity(text.len());
+    let mut copied = 0;
+
+    for (start, end) in reference_ranges(text) {
+        relocated.push_str(&text[copied..start - MEDIA_PATH.len()]);
+        relocated.push_str(path);
+        relocated.push_str(&text[start..end]);
+        copied = end;
+    }
+    relocated.push_str(&text[copied..]);
+
+    relocated
+}
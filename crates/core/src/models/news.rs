@@ -1,4 +1,8 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+use url::Url;
 use uuid::Uuid;
 
 #[async_trait]
@@ -9,4 +13,165 @@ pub trait News: Send + Sync {
     fn title(&self) -> &str;
     fn description(&self) -> &str;
     fn content(&self) -> &Option<String>;
+    /// Files published with the item, in the order of the feed.
+    fn attachments(&self) -> &[Attachment];
+    /// Names of the authors, in the order of the feed.
+    fn authors(&self) -> &[String];
+    /// Categories the feed files the item under, in its order.
+    fn categories(&self) -> &[String];
+    /// Page the item is about, such as the web page of a feed entry.
+    fn link(&self) -> Option<&Url>;
+    /// ISO 639-1 code of the language of the text, `None` when unknown.
+    fn language(&self) -> Option<&str>;
+    /// Sentences picked from a long text, `None` when it is short enough to be
+    /// read whole.
+    fn summary(&self) -> Option<&str>;
+    /// Title taken from the text, for items whose feed gives no real one.
+    fn headline(&self) -> Option<&str>;
+    /// Marks given by rules, stored with the article when it is created.
+    fn marks(&self) -> &ArticleMarks;
+}
+
+/// Read and starred state and tags of an article.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArticleMarks {
+    pub read: bool,
+    pub starred: bool,
+    /// Sorted, without repeats.
+    pub tags: Vec<String>,
+}
+
+/// A file published with an article, such as the audio of a podcast episode
+/// or the video of a channel entry. Sizes and durations are as announced by
+/// the feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub url: Url,
+    pub content_type: Option<String>,
+    /// Size in bytes.
+    pub length: Option<u64>,
+    /// Playing time, in whole seconds.
+    pub duration: Option<Duration>,
+    pub thumbnail: Option<Url>,
+}
+
+/// What is known of a stored article besides its text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArticleDetails {
+    /// As announced by the feed, replaced when it changes them.
+    pub authors: Vec<String>,
+    pub categories: Vec<String>,
+    /// Added by rules, sorted; kept when the feed changes the article.
+    pub tags: Vec<String>,
+    pub read: bool,
+    pub starred: bool,
+    /// ISO 639-1 code, as detected when the article was last stored.
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Article {
+    pub id: Uuid,
+    pub parent_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub title: String,
+    pub description: String,
+    pub content: Option<String>,
+    /// Sentences picked from a long text when the article was last stored,
+    /// `None` when it is short enough to be read whole.
+    pub summary: Option<String>,
+    /// Title taken from the text, preferred over `title` for display.
+    pub headline: Option<String>,
+}
+
+impl Article {
+    /// The headline when there is one, else the title.
+    #[must_use]
+    pub fn display_title(&self) -> &str {
+        self.headline.as_deref().unwrap_or(&self.title)
+    }
+}
+
+/// What duplicate detection knows of a stored article.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleFingerprint {
+    pub article_id: Uuid,
+    pub parent_id: Uuid,
+    /// As given by the feed when the article was last stored.
+    pub link: Option<Url>,
+    /// `SimHash` of the text; `None` when it is too short to compare, or the
+    /// article has no story yet.
+    pub simhash: Option<u64>,
+    /// `None` until the article is assigned to a story.
+    pub story_id: Option<Uuid>,
+}
+
+/// Articles about the same story, usually from several sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Story {
+    pub id: Uuid,
+    /// The first of `articles`.
+    pub primary: Uuid,
+    /// Every stored article of the story, in the order they were stored.
+    pub articles: Vec<Uuid>,
+}
+
+impl Story {
+    /// Groups `(story_id, article_id)` pairs, given in the order the articles
+    /// were stored, into the stories of more than one article, the most
+    /// recent primary article first.
+    pub fn group(members: impl IntoIterator<Item = (Uuid, Uuid)>) -> Vec<Self> {
+        let mut stories: Vec<Self> = Vec::new();
+        let mut positions: HashMap<Uuid, usize> = HashMap::new();
+
+        for (story_id, article_id) in members {
+            match positions.get(&story_id) {
+                Some(&position) => stories[position].articles.push(article_id),
+                None => {
+                    positions.insert(story_id, stories.len());
+                    stories.push(Self {
+                        id: story_id,
+                        primary: article_id,
+                        articles: vec![article_id],
+                    });
+                }
+            }
+        }
+
+        stories.retain(|story| story.articles.len() > 1);
+        stories.reverse();
+        stories
+    }
+}
+
+/// One version of an article's text.
+#[derive(Debug, Clone)]
+pub struct ArticleRevision {
+    pub article_id: Uuid,
+    /// When this version was stored.
+    pub created_at: DateTime<Utc>,
+    /// When an upstream edit replaced it; `None` for the current version.
+    pub replaced_at: Option<DateTime<Utc>>,
+
+    pub title: String,
+    pub description: String,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Updated,
+}
+
+/// An article as stored by [`crate::services::news::NewsService::update`] when it
+/// was inserted or its content differs from the stored version.
+#[derive(Debug, Clone)]
+pub struct ArticleChange {
+    pub kind: ChangeKind,
+    pub article: Article,
+    /// Marks of the article once the change is stored.
+    pub marks: ArticleMarks,
 }
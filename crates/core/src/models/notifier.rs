@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+/// Where and how a route pushes its notifications.
+///
+/// Credentials, and the Slack webhook URL which is one, are stored as
+/// `${NAME}` references to secrets resolved when a notification is sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    /// Telegram Bot API `sendMessage`.
+    Telegram {
+        api_url: Url,
+        bot_token: String,
+        chat_id: String,
+    },
+    /// Slack incoming webhook.
+    Slack { webhook_url: String },
+    /// Matrix client-server API `m.room.message`.
+    Matrix {
+        homeserver: Url,
+        access_token: String,
+        room_id: String,
+    },
+    /// ntfy topic publishing.
+    Ntfy {
+        server: Url,
+        topic: String,
+        token: Option<String>,
+    },
+    /// Gotify application message.
+    Gotify {
+        server: Url,
+        token: String,
+        priority: Option<i64>,
+    },
+}
+
+impl NotifierConfig {
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Telegram { .. } => "telegram",
+            Self::Slack { .. } => "slack",
+            Self::Matrix { .. } => "matrix",
+            Self::Ntfy { .. } => "ntfy",
+            Self::Gotify { .. } => "gotify",
+        }
+    }
+
+    /// Credentials of the notifier, which must reference secrets.
+    #[must_use]
+    pub fn credentials(&self) -> Vec<&str> {
+        match self {
+            Self::Telegram { bot_token, .. } => vec![bot_token],
+            Self::Slack { webhook_url } => vec![webhook_url],
+            Self::Matrix { access_token, .. } => vec![access_token],
+            Self::Ntfy { token, .. } => token.iter().map(String::as_str).collect(),
+            Self::Gotify { token, .. } => vec![token],
+        }
+    }
+
+    /// Destination without credentials, safe to print.
+    #[must_use]
+    pub fn target(&self) -> String {
+        match self {
+            Self::Telegram { chat_id, .. } => format!("chat {chat_id}"),
+            Self::Slack { webhook_url } => match Url::parse(webhook_url) {
+                Ok(url) => url.host_str().unwrap_or_default().to_string(),
+                Err(_) => webhook_url.clone(),
+            },
+            Self::Matrix {
+                homeserver,
+                room_id,
+                ..
+            } => format!("{room_id} on {homeserver}"),
+            Self::Ntfy { server, topic, .. } => format!("{topic} on {server}"),
+            Self::Gotify { server, .. } => server.to_string(),
+        }
+    }
+}
+
+/// Which new articles a route is interested in. Empty lists match everything.
+#[derive(Debug, Clone, Default)]
+pub struct RouteFilter {
+    pub source_id: Option<Uuid>,
+    pub keywords: Vec<String>,
+    /// Only articles a rule gave this tag, `None` for all.
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NotificationRoute {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+
+    pub config: NotifierConfig,
+    pub filter: RouteFilter,
+    /// `MiniJinja` template of the message body, `None` for the default one.
+    pub template: Option<String>,
+}
+
+pub struct NotificationRouteDraft {
+    pub config: NotifierConfig,
+    pub filter: RouteFilter,
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+}
+
+/// A rendered notification waiting to be sent through its route.
+#[derive(Debug, Clone)]
+pub struct QueuedNotification {
+    pub id: Uuid,
+    pub route_id: Uuid,
+    pub config: NotifierConfig,
+    pub notification: Notification,
+    /// Failed attempts so far.
+    pub attempts: i64,
+}
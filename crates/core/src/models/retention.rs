@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How many articles of a source are kept; `None` limits nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionLimits {
+    /// Articles stored longer ago than this are deleted.
+    pub max_age: Option<Duration>,
+    /// Only this many of the newest articles are kept.
+    pub max_count: Option<u32>,
+}
+
+impl RetentionLimits {
+    #[must_use]
+    pub const fn is_unlimited(&self) -> bool {
+        self.max_age.is_none() && self.max_count.is_none()
+    }
+
+    /// Fills the limits left unset from `fallback`.
+    #[must_use]
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            max_age: self.max_age.or(fallback.max_age),
+            max_count: self.max_count.or(fallback.max_count),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Limits of every source without its own entry in `sources`.
+    pub default: RetentionLimits,
+    /// Per-source limits, falling back to `default` for those they leave unset.
+    pub sources: HashMap<Uuid, RetentionLimits>,
+    /// Starred articles are never deleted and do not count towards `max_count`.
+    pub keep_starred: bool,
+}
+
+impl RetentionPolicy {
+    #[must_use]
+    pub fn limits_for(&self, source_id: Uuid) -> RetentionLimits {
+        self.sources
+            .get(&source_id)
+            .map_or(self.default, |limits| limits.or(self.default))
+    }
+
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        self.default.is_unlimited() && self.sources.values().all(RetentionLimits::is_unlimited)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrunedSource {
+    pub source_id: Uuid,
+    pub articles: u64,
+}
+
+/// Articles deleted by a prune run (or that would be, for a dry run).
+#[derive(Debug, Clone, Default)]
+pub struct PruneReport {
+    pub sources: Vec<PrunedSource>,
+}
+
+impl PruneReport {
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.sources.iter().map(|source| source.articles).sum()
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Part of an item a pattern is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleField {
+    Title,
+    /// Text of the description, without its markup.
+    Description,
+    Author,
+    Category,
+}
+
+impl RuleField {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Description => "description",
+            Self::Author => "author",
+            Self::Category => "category",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RulePattern {
+    /// Case-insensitive substring.
+    Keyword(String),
+    /// Regular expression in the syntax of the `regex` crate, case-sensitive
+    /// unless it starts with `(?i)`.
+    Regex(String),
+}
+
+/// What a rule does to the items it matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleAction {
+    /// The item is not stored.
+    Drop,
+    MarkRead,
+    Star,
+    Tag(String),
+}
+
+impl RuleAction {
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Drop => "drop",
+            Self::MarkRead => "read",
+            Self::Star => "star",
+            Self::Tag(_) => "tag",
+        }
+    }
+}
+
+/// Which items a rule matches. An item matches when it is in scope, one of
+/// the `include` patterns (if any) is found in one of the `fields`, and none
+/// of the `exclude` patterns is.
+#[derive(Debug, Clone, Default)]
+pub struct RuleFilter {
+    /// Only items of this source; `None` for every source.
+    pub source_id: Option<Uuid>,
+    /// Only items carrying this tag, added by an earlier rule.
+    pub tag: Option<String>,
+    /// Fields the patterns are matched against; empty for all of them.
+    pub fields: Vec<RuleField>,
+    pub include: Vec<RulePattern>,
+    pub exclude: Vec<RulePattern>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+
+    pub action: RuleAction,
+    pub filter: RuleFilter,
+}
+
+pub struct RuleDraft {
+    pub action: RuleAction,
+    pub filter: RuleFilter,
+}
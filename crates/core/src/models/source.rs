@@ -1,9 +1,11 @@
 pub mod atom;
+pub mod request;
 pub mod telegram;
 
 use self::atom::AtomSource;
 use self::telegram::TelegramSource;
 use async_trait::async_trait;
+use url::Url;
 use uuid::Uuid;
 
 #[async_trait]
@@ -19,3 +21,46 @@ pub enum SourceEnum {
     Atom(AtomSource),
     Telegram(TelegramSource),
 }
+
+impl SourceEnum {
+    #[must_use]
+    pub const fn id(&self) -> Uuid {
+        match self {
+            Self::Atom(src) => src.id,
+            Self::Telegram(src) => src.id,
+        }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Atom(_) => "atom",
+            Self::Telegram(_) => "telegram",
+        }
+    }
+
+    #[must_use]
+    pub const fn url(&self) -> &Url {
+        match self {
+            Self::Atom(src) => &src.url,
+            Self::Telegram(src) => &src.public_url,
+        }
+    }
+
+    /// Name users refer to this source by on the command line: the feed URL
+    /// of an Atom source or the username of a Telegram channel.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Atom(src) => src.url.as_str(),
+            Self::Telegram(src) => &src.username,
+        }
+    }
+
+    /// Whether `key` names this source the way users refer to it on the command
+    /// line: the feed URL of an Atom source or the username of a Telegram channel.
+    #[must_use]
+    pub fn matches_key(&self, key: &str) -> bool {
+        self.key() == key
+    }
+}
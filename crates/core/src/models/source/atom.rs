@@ -1,4 +1,5 @@
 use crate::models::ExternalEntity;
+use crate::models::source::request::RequestSettings;
 use chrono::{DateTime, Utc};
 use url::Url;
 use uuid::Uuid;
@@ -9,27 +10,43 @@ pub struct AtomSource {
     pub created_at: DateTime<Utc>,
 
     pub url: Url,
+    pub request: RequestSettings,
 }
 
 impl AtomSource {
     #[must_use]
-    pub const fn new(id: Uuid, created_at: DateTime<Utc>, url: Url) -> Self {
+    pub fn new(id: Uuid, created_at: DateTime<Utc>, url: Url) -> Self {
         Self {
             id,
             url,
             created_at,
+            request: RequestSettings::default(),
         }
     }
+
+    #[must_use]
+    pub fn with_request(self, request: RequestSettings) -> Self {
+        Self { request, ..self }
+    }
 }
 
 pub struct AtomDraft {
     pub url: Url,
+    pub request: RequestSettings,
 }
 
 impl AtomDraft {
     #[must_use]
     pub fn new(url: Url) -> Self {
-        Self { url }
+        Self {
+            url,
+            request: RequestSettings::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_request(self, request: RequestSettings) -> Self {
+        Self { request, ..self }
     }
 }
 
@@ -0,0 +1,172 @@
+use crate::errors::InvalidArgument;
+use http::{HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Shown instead of secrets stored as plain text.
+pub const REDACTED: &str = "***";
+
+/// Headers and credentials sent with every request polling a source.
+///
+/// Values may reference secrets as `${NAME}`; only the reference is stored and
+/// it is resolved from the environment or the secrets file when the source is
+/// polled.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequestSettings {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<Header>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub basic_auth: Option<BasicAuth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<String>,
+    /// Cookies as `name=value`, sent from the first request on.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cookies: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BasicAuth {
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// Whether `value` takes its secret from a `${NAME}` reference, so showing it
+/// reveals nothing.
+#[must_use]
+pub fn references_secret(value: &str) -> bool {
+    value.split("${").skip(1).any(|rest| {
+        rest.split_once('}')
+            .is_some_and(|(name, _)| !name.is_empty())
+    })
+}
+
+fn redact(value: &str) -> String {
+    match references_secret(value) {
+        true => value.to_string(),
+        false => REDACTED.to_string(),
+    }
+}
+
+impl RequestSettings {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Copy with every value that is not a `${NAME}` reference replaced by
+    /// [`REDACTED`]; header and cookie names and the user name are kept.
+    #[must_use]
+    pub fn redacted(&self) -> Self {
+        Self {
+            headers: self
+                .headers
+                .iter()
+                .map(|header| Header {
+                    name: header.name.clone(),
+                    value: redact(&header.value),
+                })
+                .collect(),
+            basic_auth: self.basic_auth.as_ref().map(|auth| BasicAuth {
+                username: auth.username.clone(),
+                password: auth.password.as_deref().map(redact),
+            }),
+            bearer_token: self.bearer_token.as_deref().map(redact),
+            cookies: self
+                .cookies
+                .iter()
+                .map(|cookie| match cookie.split_once('=') {
+                    Some((name, value)) => format!("{name}={}", redact(value)),
+                    None => redact(cookie),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Never prints secrets, so sources can be logged.
+impl fmt::Debug for RequestSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redacted = self.redacted();
+        f.debug_struct("RequestSettings")
+            .field("headers", &redacted.headers)
+            .field("basic_auth", &redacted.basic_auth)
+            .field("bearer_token", &redacted.bearer_token)
+            .field("cookies", &redacted.cookies)
+            .finish()
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+impl FromStr for Header {
+    type Err = InvalidArgument;
+
+    /// Parses `Name: value`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| InvalidArgument {
+            name: "header".to_string(),
+            value: s.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let (name, value) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("expected `Name: value`"))?;
+        let (name, value) = (name.trim(), value.trim());
+
+        HeaderName::from_str(name).map_err(|_| invalid("invalid header name"))?;
+        HeaderValue::from_str(value).map_err(|_| invalid("invalid header value"))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.password {
+            Some(password) => write!(f, "{}:{password}", self.username),
+            None => write!(f, "{}", self.username),
+        }
+    }
+}
+
+impl FromStr for BasicAuth {
+    type Err = InvalidArgument;
+
+    /// Parses `user:password`, or `user` without a password.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (username, password) = match s.split_once(':') {
+            Some((username, password)) => (username, Some(password.to_string())),
+            None => (s, None),
+        };
+
+        if username.is_empty() {
+            return Err(InvalidArgument {
+                name: "basic_auth".to_string(),
+                value: REDACTED.to_string(),
+                reason: "expected `user:password`".to_string(),
+            });
+        }
+
+        Ok(Self {
+            username: username.to_string(),
+            password,
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use crate::models::news::ChangeKind;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WebhookEvent {
+    ArticleCreated,
+    ArticleUpdated,
+}
+
+impl WebhookEvent {
+    pub const ALL: [Self; 2] = [Self::ArticleCreated, Self::ArticleUpdated];
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::ArticleCreated => "article.created",
+            Self::ArticleUpdated => "article.updated",
+        }
+    }
+}
+
+impl From<ChangeKind> for WebhookEvent {
+    fn from(kind: ChangeKind) -> Self {
+        match kind {
+            ChangeKind::Created => Self::ArticleCreated,
+            ChangeKind::Updated => Self::ArticleUpdated,
+        }
+    }
+}
+
+impl Display for WebhookEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| {
+                format!("unknown event '{s}', expected article.created or article.updated")
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+
+    pub url: Url,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub source_id: Option<Uuid>,
+    /// Only articles with this tag, given by a rule.
+    pub tag: Option<String>,
+}
+
+pub struct WebhookDraft {
+    pub url: Url,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub source_id: Option<Uuid>,
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DeliveryStats {
+    pub pending: i64,
+    pub delivered: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub url: Url,
+    pub secret: String,
+    pub event: String,
+    pub payload: String,
+    pub attempts: i64,
+}
+
+/// Body of a webhook request. It is serialized once when the event is queued and
+/// sent (and signed) byte for byte on every attempt.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub event: &'a str,
+    pub occurred_at: DateTime<Utc>,
+    pub article: ArticlePayload<'a>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArticlePayload<'a> {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub content: Option<&'a str>,
+    pub read: bool,
+    pub starred: bool,
+    pub tags: &'a [String],
+}
@@ -1,14 +1,52 @@
+pub mod digest;
+pub mod media;
 pub mod news;
+pub mod notifier;
+pub mod retention;
+pub mod rule;
 pub mod source;
+pub mod webhook;
 
 use crate::errors::Internal;
+use crate::models::source::request::RequestSettings;
 use async_trait::async_trait;
-use reqwest::Response;
+use http::{HeaderMap, StatusCode};
+use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
 use url::Url;
+use uuid::Uuid;
 
-pub type HttpResponse = Response;
+/// A response read in full, independent of the client that fetched it.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// Address the body was served from, after redirects.
+    pub url: Url,
+    /// Set to `url` when every redirect on the way was permanent (301 or 308),
+    /// so the source should be polled there from now on.
+    pub moved_to: Option<Url>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// A `200 OK` response without headers.
+    pub fn new(url: Url, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            url,
+            moved_to: None,
+            body: body.into(),
+        }
+    }
+
+    /// Body decoded as UTF-8, with invalid sequences replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
 
 #[derive(Error, Debug)]
 #[error(transparent)]
@@ -17,14 +55,57 @@
 #[async_trait]
 pub trait HttpUpdateHandle: Send + Sync {
     fn url(&self) -> &Url;
-    async fn handle(&self, response: Response) -> Result<(), HandleError>;
+
+    /// Key of the source in per-source settings: the feed URL of an Atom
+    /// source or the username of a Telegram channel.
+    fn source_key(&self) -> &str {
+        self.url().as_str()
+    }
+
+    /// Id of the source, which per-source settings may name it by instead of
+    /// its key; unlike a feed URL, it stays the same when the feed moves.
+    fn source_id(&self) -> Option<Uuid> {
+        None
+    }
+
+    /// Headers and credentials stored with the source, if any.
+    fn request(&self) -> Option<&RequestSettings> {
+        None
+    }
+
+    async fn handle(&self, response: HttpResponse) -> Result<(), HandleError>;
 }
 
 #[derive(Error, Debug)]
 #[error(transparent)]
 pub struct WatchError(#[from] Internal);
 
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct FetchError(#[from] Internal);
+
 #[async_trait]
 pub trait HttpService: Send + Sync {
     async fn watch_changes(&self, handler: Arc<dyn HttpUpdateHandle>) -> Result<(), WatchError>;
+
+    /// Fetches `url` on behalf of the source polled by `source`, such as the
+    /// page an article links to, with the same politeness and credentials.
+    /// Only successful responses are returned.
+    async fn get(
+        &self,
+        source: &dyn HttpUpdateHandle,
+        url: &Url,
+    ) -> Result<HttpResponse, FetchError>;
+
+    /// Fetches `url` like [`HttpService::get`], writing the body to `path` as
+    /// it arrives instead of holding it in memory, and returns its size.
+    /// Bodies over `max_size` bytes fail; a partly written file is left for
+    /// the caller to remove.
+    async fn download(
+        &self,
+        source: &dyn HttpUpdateHandle,
+        url: &Url,
+        path: &Path,
+        max_size: u64,
+    ) -> Result<u64, FetchError>;
 }
@@ -0,0 +1,21 @@
+use crate::errors::Internal;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct WatermarkError(#[from] pub Internal);
+
+#[async_trait]
+pub trait DigestService: Send + Sync {
+    /// Creation time of the newest article already sent to `recipient`.
+    async fn get_watermark(&self, recipient: &str)
+    -> Result<Option<DateTime<Utc>>, WatermarkError>;
+
+    async fn set_watermark(
+        &self,
+        recipient: &str,
+        sent_until: DateTime<Utc>,
+    ) -> Result<(), WatermarkError>;
+}
@@ -0,0 +1,27 @@
+use crate::errors::Internal;
+use crate::models::media::{CachedMedia, MediaFile};
+use async_trait::async_trait;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct MediaIndexError(#[from] pub Internal);
+
+/// Index of the media cache: which remote URL is stored in which file. The
+/// files themselves are kept by the caller.
+#[async_trait]
+pub trait MediaService: Send + Sync {
+    async fn find(&self, url: &Url) -> Result<Option<CachedMedia>, MediaIndexError>;
+    async fn insert(&self, url: &Url, file: &MediaFile) -> Result<(), MediaIndexError>;
+    /// Records that `url` could not be cached, so it is not fetched on every poll.
+    async fn insert_failure(&self, url: &Url) -> Result<(), MediaIndexError>;
+
+    /// Bytes taken by the distinct files of the index.
+    async fn total_size(&self) -> Result<u64, MediaIndexError>;
+
+    /// Forgets the URLs stored in one of the files `names`.
+    async fn forget(&self, names: &[String]) -> Result<(), MediaIndexError>;
+    /// Forgets recorded failures, so their URLs are tried again.
+    async fn forget_failures(&self) -> Result<u64, MediaIndexError>;
+}
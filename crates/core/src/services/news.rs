@@ -1,6 +1,11 @@
 use crate::errors::Internal;
-use crate::models::news::News;
+use crate::models::news::{
+    Article, ArticleChange, ArticleDetails, ArticleFingerprint, ArticleRevision, Attachment, News,
+    Story,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
 use thiserror::Error;
@@ -19,7 +24,69 @@ pub enum UpdateError {
     Internal(Internal),
 }
 
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct GetAllError(#[from] pub Internal);
+
+#[derive(Error, Debug)]
+pub enum RevisionsError {
+    #[error("Article {0} not found")]
+    NotFound(Uuid),
+
+    #[error(transparent)]
+    Internal(#[from] Internal),
+}
+
+#[derive(Error, Debug)]
+pub enum MarkError {
+    #[error("Article {0} not found")]
+    NotFound(Uuid),
+
+    #[error(transparent)]
+    Internal(#[from] Internal),
+}
+
 #[async_trait]
 pub trait NewsService: Send + Sync {
-    async fn update(&self, news: &[Arc<impl News>]) -> Result<(), UpdateError>;
+    /// Stores `news`, returning only the articles that were inserted or modified.
+    async fn update(&self, news: &[Arc<impl News>]) -> Result<Vec<ArticleChange>, UpdateError>;
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Article>, GetAllError>;
+    async fn get_created_after(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<impl IntoIterator<Item = Article>, GetAllError>;
+    /// Every version of the article, oldest first and the current one last.
+    async fn revisions(&self, id: Uuid) -> Result<Vec<ArticleRevision>, RevisionsError>;
+    /// Starred articles survive pruning when the retention policy keeps them.
+    async fn set_starred(&self, id: Uuid, starred: bool) -> Result<(), MarkError>;
+    async fn set_read(&self, id: Uuid, read: bool) -> Result<(), MarkError>;
+    /// Adds `tag` to the article; adding a tag it already has does nothing.
+    async fn add_tag(&self, id: Uuid, tag: &str) -> Result<(), MarkError>;
+    /// Stored content of the articles with the given source keys, by source
+    /// key; articles that are unknown or have no content are left out.
+    async fn contents(&self, source_keys: &[&str]) -> Result<HashMap<String, String>, GetAllError>;
+    /// Attachments of the articles `ids`, in the order of their feed entry.
+    /// Articles without attachments are left out.
+    async fn attachments(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Attachment>>, GetAllError>;
+    /// Labels and marks of the articles `ids`; unknown articles are left out.
+    async fn details(&self, ids: &[Uuid]) -> Result<HashMap<Uuid, ArticleDetails>, GetAllError>;
+    /// Fingerprints of the articles created after `after`, oldest first.
+    async fn fingerprints(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<ArticleFingerprint>, GetAllError>;
+    /// Puts the article in the story `story_id`, along with the `SimHash` its
+    /// text was matched with.
+    async fn set_story(
+        &self,
+        id: Uuid,
+        story_id: Uuid,
+        simhash: Option<u64>,
+    ) -> Result<(), MarkError>;
+    /// Stories of more than one stored article, the most recent primary
+    /// article first.
+    async fn stories(&self) -> Result<Vec<Story>, GetAllError>;
 }
@@ -0,0 +1,52 @@
+use crate::errors::Internal;
+use crate::models::notifier::{
+    Notification, NotificationRoute, NotificationRouteDraft, QueuedNotification,
+};
+use crate::services::webhook::QueueError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+#[error("Failed to notify via {notifier}: {message}")]
+pub struct NotifyError {
+    pub notifier: &'static str,
+    pub message: String,
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+#[derive(Error, Debug)]
+pub enum RouteError {
+    #[error("Notification route {0} not found")]
+    NotFound(Uuid),
+
+    #[error(transparent)]
+    Internal(#[from] Internal),
+}
+
+#[async_trait]
+pub trait NotificationRouteService: Send + Sync {
+    async fn add(&self, draft: NotificationRouteDraft) -> Result<NotificationRoute, RouteError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<NotificationRoute, RouteError>;
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = NotificationRoute>, RouteError>;
+    async fn drop_by_id(&self, id: Uuid) -> Result<(), RouteError>;
+
+    /// Queues each notification for the route it is paired with, all or none.
+    async fn enqueue(&self, notifications: &[(Uuid, Notification)]) -> Result<(), QueueError>;
+    /// Queued notifications whose next attempt is due, oldest first.
+    async fn get_due(&self, limit: u32) -> Result<Vec<QueuedNotification>, QueueError>;
+    /// Removes a notification from the queue once it is sent.
+    async fn mark_sent(&self, id: Uuid) -> Result<(), QueueError>;
+    /// Records a failed attempt; `retry_at` of `None` gives up on the
+    /// notification and removes it from the queue.
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), QueueError>;
+}
@@ -0,0 +1,20 @@
+use crate::errors::Internal;
+use crate::models::retention::{PruneReport, RetentionPolicy};
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct PruneError(#[from] pub Internal);
+
+#[async_trait]
+pub trait RetentionService: Send + Sync {
+    /// Deletes the articles `policy` no longer keeps, together with their identity
+    /// mappings. Their items are remembered and never stored again, even while
+    /// they are still in their feed. A `dry_run` only counts them.
+    async fn prune(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<PruneReport, PruneError>;
+}
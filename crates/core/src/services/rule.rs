@@ -0,0 +1,23 @@
+use crate::errors::Internal;
+use crate::models::rule::{Rule, RuleDraft};
+use async_trait::async_trait;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum RuleError {
+    #[error("Rule {0} not found")]
+    NotFound(Uuid),
+
+    #[error(transparent)]
+    Internal(#[from] Internal),
+}
+
+/// Rules applied to collected items, in the order they were added.
+#[async_trait]
+pub trait RuleService: Send + Sync {
+    async fn add(&self, draft: RuleDraft) -> Result<Rule, RuleError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<Rule, RuleError>;
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Rule>, RuleError>;
+    async fn drop_by_id(&self, id: Uuid) -> Result<(), RuleError>;
+}
@@ -4,6 +4,7 @@
 use crate::models::source::telegram::TelegramDraft;
 use async_trait::async_trait;
 use thiserror::Error;
+use url::Url;
 use uuid::Uuid;
 
 pub enum SourceDraft {
@@ -37,6 +38,18 @@ pub enum GetError {
 #[error(transparent)]
 pub struct DropError(#[from] pub Internal);
 
+#[derive(Error, Debug)]
+pub enum MoveError {
+    #[error("Atom source {0} not found")]
+    NotFound(Uuid),
+
+    #[error("Source with source_key={0} already exists")]
+    AlreadyExists(String),
+
+    #[error(transparent)]
+    Internal(#[from] Internal),
+}
+
 pub type DeleteCriteria = SourceDraft;
 
 #[async_trait]
@@ -45,4 +58,7 @@ pub trait SourceService: Send + Sync {
     async fn get_by_id(&self, id: Uuid) -> Result<SourceEnum, GetError>;
     async fn get_all(&self) -> Result<impl IntoIterator<Item = SourceEnum>, GetAllError>;
     async fn drop_by(&self, criteria: DeleteCriteria) -> Result<(), DropError>;
+    /// Points the Atom source `id` at `url`, keeping its articles; used when
+    /// its feed moved permanently.
+    async fn move_atom(&self, id: Uuid, url: Url) -> Result<(), MoveError>;
 }
@@ -0,0 +1,51 @@
+use crate::errors::Internal;
+use crate::models::webhook::{Delivery, DeliveryStats, Webhook, WebhookDraft};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("Webhook {0} not found")]
+    NotFound(Uuid),
+
+    #[error(transparent)]
+    Internal(#[from] Internal),
+}
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct QueueError(#[from] pub Internal);
+
+pub enum ReplayCriteria {
+    /// A single delivery, whatever its current status.
+    Delivery(Uuid),
+    /// Every failed delivery, optionally only of one webhook.
+    Failed(Option<Uuid>),
+}
+
+#[async_trait]
+pub trait WebhookService: Send + Sync {
+    async fn add(&self, draft: WebhookDraft) -> Result<Webhook, WebhookError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<Webhook, WebhookError>;
+    async fn get_all(
+        &self,
+    ) -> Result<impl IntoIterator<Item = (Webhook, DeliveryStats)>, WebhookError>;
+    async fn drop_by_id(&self, id: Uuid) -> Result<(), WebhookError>;
+
+    /// Pending deliveries whose next attempt is due, oldest first.
+    async fn get_due(&self, limit: u32) -> Result<Vec<Delivery>, QueueError>;
+    async fn mark_delivered(&self, id: Uuid) -> Result<(), QueueError>;
+    /// Records a failed attempt; `retry_at` of `None` gives up on the delivery.
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), QueueError>;
+    /// Puts deliveries back into the queue, returning how many were re-queued.
+    async fn replay(&self, criteria: ReplayCriteria) -> Result<u64, QueueError>;
+    /// Deletes deliveries that succeeded before `before`, returning how many.
+    async fn prune_delivered(&self, before: DateTime<Utc>) -> Result<u64, QueueError>;
+}
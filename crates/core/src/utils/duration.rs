@@ -0,0 +1,57 @@
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ParseDurationError {
+    #[error("{0} is not a valid duration; use a number with a unit, e.g. 90s, 15m, 24h, 7d or 2w.")]
+    Invalid(String),
+
+    #[error("{0} has an unknown duration unit; supported units are s, m, h, d and w.")]
+    UnknownUnit(String),
+}
+
+pub fn parse_duration(value: &str) -> Result<Duration, ParseDurationError> {
+    let value = value.trim();
+    let split_at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split_at);
+
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| ParseDurationError::Invalid(value.to_string()))?;
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        "" => return Err(ParseDurationError::Invalid(value.to_string())),
+        _ => return Err(ParseDurationError::UnknownUnit(value.to_string())),
+    };
+
+    amount
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(|| ParseDurationError::Invalid(value.to_string()))
+}
+
+/// Formats `duration` with the largest unit [`parse_duration`] accepts that
+/// represents it exactly, e.g. `90s`, `15m` or `2w`.
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    let (amount, unit) = [
+        (7 * 24 * 60 * 60, "w"),
+        (24 * 60 * 60, "d"),
+        (60 * 60, "h"),
+        (60, "m"),
+    ]
+    .into_iter()
+    .find(|(multiplier, _)| secs > 0 && secs.is_multiple_of(*multiplier))
+    .map_or((secs, "s"), |(multiplier, unit)| (secs / multiplier, unit));
+
+    format!("{amount}{unit}")
+}
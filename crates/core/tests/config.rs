@@ -0,0 +1,274 @@
+//! Loads configuration files the way the CLI does at startup.
+
+use news_core::config::{
+    ColorMode, ConfigError, ConfigOverrides, LoadedConfig, Origin, SmtpSecurity,
+};
+use news_core::models::source::request::REDACTED;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use toml::Value;
+use uuid::Uuid;
+
+/// The environment is shared by the whole process, so loading takes turns.
+static ENV: Mutex<()> = Mutex::new(());
+
+/// Writes `content` to a configuration file of its own.
+fn config_file(content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("create directory");
+
+    let path = dir.join("config.toml");
+    std::fs::write(&path, content).expect("write configuration");
+    path
+}
+
+#[test]
+fn source_languages_are_lowercased() {
+    let path = config_file(
+        r#"
+[[watch.sources]]
+source = "durov"
+languages = ["EN", "de"]
+"#,
+    );
+
+    let config = LoadedConfig::load_file(&path).expect("valid configuration");
+
+    let id = Uuid::now_v7();
+    assert_eq!(config.settings.watch.languages(id, "durov"), ["en", "de"]);
+}
+
+#[test]
+fn unknown_source_languages_are_rejected() {
+    let path = config_file(
+        r#"
+[[watch.sources]]
+source = "durov"
+languages = ["en", "english"]
+"#,
+    );
+
+    let error = LoadedConfig::load_file(&path).expect_err("invalid configuration");
+
+    assert!(matches!(&error, ConfigError::Invalid(Origin::File(file), _) if *file == path));
+    let source = std::error::Error::source(&error)
+        .expect("cause")
+        .to_string();
+    assert!(
+        source.contains("unknown language code 'english'"),
+        "{source}"
+    );
+}
+
+/// Loads the configuration with `vars` as the only variables it reads and an
+/// empty user configuration directory.
+fn load_with_env(
+    vars: &[(&str, &str)],
+    overrides: &ConfigOverrides,
+) -> Result<LoadedConfig, ConfigError> {
+    let _guard = ENV.lock().unwrap_or_else(PoisonError::into_inner);
+    let config_home = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+
+    let read = |name: &str| {
+        name.starts_with("UNINEWS_") || ["RUST_LOG", "NO_COLOR", "DEBUG"].contains(&name)
+    };
+    let inherited: Vec<_> = std::env::vars().filter(|(name, _)| read(name)).collect();
+
+    // SAFETY: only this function changes the environment, under `ENV`.
+    unsafe {
+        for (name, _) in &inherited {
+            std::env::remove_var(name);
+        }
+        std::env::set_var("XDG_CONFIG_HOME", &config_home);
+        for (name, value) in vars {
+            std::env::set_var(name, value);
+        }
+    }
+
+    let config = LoadedConfig::load(overrides);
+
+    // SAFETY: as above.
+    unsafe {
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+        for (name, value) in inherited {
+            std::env::set_var(name, value);
+        }
+    }
+
+    config
+}
+
+/// The effective value of `section.key` and where it comes from.
+fn entry(config: &LoadedConfig, section: &str, key: &str) -> (Value, Origin) {
+    config
+        .entries()
+        .into_iter()
+        .filter(|(name, _)| name == section)
+        .flat_map(|(_, values)| values)
+        .find(|entry| entry.key == key)
+        .map(|entry| (entry.value, entry.origin.clone()))
+        .expect("configuration key")
+}
+
+#[test]
+fn flags_override_env_which_overrides_files() {
+    let path = config_file(
+        r#"
+[http]
+user_agent = "from-file"
+timeout = "20s"
+
+[watch]
+poll_interval = "10m"
+"#,
+    );
+    let overrides = ConfigOverrides::default()
+        .with_file(Some(path.clone()))
+        .with_flag("--user-agent", "http.user_agent", "from-flag");
+
+    let config = load_with_env(
+        &[
+            ("UNINEWS_HTTP_USER_AGENT", "from-env"),
+            ("UNINEWS_HTTP_TIMEOUT", "40s"),
+        ],
+        &overrides,
+    )
+    .expect("valid configuration");
+
+    assert_eq!(config.files, std::slice::from_ref(&path));
+    assert_eq!(config.settings.http.user_agent, "from-flag");
+    assert_eq!(config.settings.http.timeout, Duration::from_secs(40));
+    assert_eq!(
+        config.settings.watch.poll_interval,
+        Duration::from_secs(600)
+    );
+
+    assert_eq!(
+        entry(&config, "http", "user_agent"),
+        ("from-flag".into(), Origin::Flag("--user-agent"))
+    );
+    assert_eq!(
+        entry(&config, "http", "timeout").1,
+        Origin::Env("UNINEWS_HTTP_TIMEOUT")
+    );
+    assert_eq!(
+        entry(&config, "watch", "poll_interval").1,
+        Origin::File(path)
+    );
+    assert_eq!(entry(&config, "http", "gzip").1, Origin::Default);
+}
+
+#[test]
+fn switches_are_reported_under_their_variable() {
+    let config = load_with_env(&[("NO_COLOR", "1")], &ConfigOverrides::default())
+        .expect("valid configuration");
+
+    assert!(config.files.is_empty());
+    assert_eq!(config.settings.output.color, ColorMode::Never);
+    assert_eq!(entry(&config, "output", "color").1, Origin::Env("NO_COLOR"));
+    assert_eq!(
+        entry(&config, "output", "color").1.to_string(),
+        "env NO_COLOR"
+    );
+
+    let overrides = ConfigOverrides::default().with_flag("--verbose", "output.verbose", true);
+    let config = load_with_env(&[("DEBUG", "")], &overrides).expect("valid configuration");
+
+    assert!(config.settings.output.verbose);
+    assert_eq!(
+        entry(&config, "output", "verbose").1.to_string(),
+        "flag --verbose"
+    );
+}
+
+#[test]
+fn the_config_path_flag_replaces_the_variable() {
+    let from_env = config_file("[http]\nuser_agent = \"from-env-file\"\n");
+    let from_flag = config_file("[http]\nuser_agent = \"from-flag-file\"\n");
+    let env_path = from_env.to_str().expect("UTF-8 path");
+
+    let config = load_with_env(
+        &[("UNINEWS_CONFIG_PATH", env_path)],
+        &ConfigOverrides::default(),
+    )
+    .expect("valid configuration");
+    assert_eq!(config.files, std::slice::from_ref(&from_env));
+    assert_eq!(config.settings.http.user_agent, "from-env-file");
+
+    let overrides = ConfigOverrides::default().with_file(Some(from_flag.clone()));
+    let config = load_with_env(&[("UNINEWS_CONFIG_PATH", env_path)], &overrides)
+        .expect("valid configuration");
+    assert_eq!(config.files, std::slice::from_ref(&from_flag));
+    assert_eq!(
+        entry(&config, "http", "user_agent"),
+        ("from-flag-file".into(), Origin::File(from_flag))
+    );
+}
+
+#[test]
+fn invalid_values_name_the_layer_they_come_from() {
+    let error = load_with_env(
+        &[("UNINEWS_POLL_INTERVAL", "soon")],
+        &ConfigOverrides::default(),
+    )
+    .expect_err("invalid variable");
+    assert!(matches!(
+        error,
+        ConfigError::Invalid(Origin::Env("UNINEWS_POLL_INTERVAL"), _)
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Invalid configuration from env UNINEWS_POLL_INTERVAL"
+    );
+
+    let overrides = ConfigOverrides::default().with_flag("--color", "output.color", "sometimes");
+    let error = load_with_env(&[], &overrides).expect_err("invalid flag");
+    assert_eq!(error.to_string(), "Invalid configuration from flag --color");
+
+    let missing = config_file("").with_file_name("missing.toml");
+    let overrides = ConfigOverrides::default().with_file(Some(missing.clone()));
+    let error = load_with_env(&[], &overrides).expect_err("missing file");
+    assert!(matches!(error, ConfigError::Read(file, _) if file == missing));
+}
+
+#[test]
+fn debug_is_read_as_a_boolean() {
+    for (value, verbose) in [("1", true), ("true", true), ("0", false), ("false", false)] {
+        let config = load_with_env(&[("DEBUG", value)], &ConfigOverrides::default())
+            .expect("valid configuration");
+        assert_eq!(config.settings.output.verbose, verbose, "DEBUG={value}");
+    }
+
+    let error = load_with_env(&[("DEBUG", "sometimes")], &ConfigOverrides::default())
+        .expect_err("invalid switch");
+    assert!(matches!(
+        error,
+        ConfigError::Invalid(Origin::Env("DEBUG"), _)
+    ));
+}
+
+#[test]
+fn smtp_settings_come_from_files_and_env() {
+    let path = config_file(
+        r#"
+[smtp]
+host = "smtp.example.com"
+port = 465
+security = "tls"
+username = "uninews"
+password = "hunter2"
+"#,
+    );
+    let overrides = ConfigOverrides::default().with_file(Some(path));
+
+    let config =
+        load_with_env(&[("UNINEWS_SMTP_PORT", "2525")], &overrides).expect("valid configuration");
+
+    assert_eq!(config.settings.smtp.port, Some(2525));
+    assert_eq!(config.settings.smtp.security, SmtpSecurity::Tls);
+    assert_eq!(config.settings.smtp.password.as_deref(), Some("hunter2"));
+    assert_eq!(entry(&config, "smtp", "password").0, Value::from(REDACTED));
+}
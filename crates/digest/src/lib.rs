@@ -0,0 +1,4 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+pub mod render;
+pub mod smtp;
@@ -0,0 +1,187 @@
+use futures::future::try_join_all;
+use minijinja::Environment;
+use news_core::models::news::{Article, ArticleDetails};
+use news_core::models::source::SourceEnum;
+use news_sqlite_core::utils::html::{ConvertError, html_to_text};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::LazyLock;
+use thiserror::Error;
+use uuid::Uuid;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+
+static TEMPLATES: LazyLock<Environment<'static>> = LazyLock::new(|| {
+    let mut env = Environment::new();
+    env.add_template("digest.html", include_str!("../templates/digest.html"))
+        .expect("built-in digest.html template must compile");
+    env.add_template("digest.txt", include_str!("../templates/digest.txt"))
+        .expect("built-in digest.txt template must compile");
+    env
+});
+
+#[derive(Error, Debug)]
+pub enum RenderError {
+    #[error("Failed to convert article {0} to plain text")]
+    PlainText(Uuid, #[source] ConvertError),
+
+    #[error("Failed to render digest template {0}")]
+    Template(&'static str, #[source] minijinja::Error),
+}
+
+pub struct DigestEmail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// How the articles of a digest are put into sections.
+#[derive(Debug, Clone, Copy)]
+pub enum Grouping<'a> {
+    /// One section per source.
+    Source,
+    /// One section per tag given by rules, with the tags of each article.
+    /// Articles with several tags are in each of their sections, articles
+    /// without tags in a last one.
+    Tag(&'a HashMap<Uuid, ArticleDetails>),
+}
+
+#[derive(Serialize)]
+struct ArticleView<'a> {
+    title: &'a str,
+    /// URL of the source, shown when sections are not sources.
+    source: Option<&'a str>,
+    description: &'a str,
+    /// Shown instead of the description of long articles.
+    summary: Option<&'a str>,
+    text: String,
+    created_at: String,
+}
+
+#[derive(Serialize)]
+struct GroupView<'a> {
+    name: String,
+    /// Kind and URL of the source the section is about.
+    kind: Option<&'static str>,
+    url: Option<String>,
+    articles: Vec<ArticleView<'a>>,
+}
+
+async fn article_view<'a>(
+    article: &'a Article,
+    source: Option<&'a str>,
+) -> Result<ArticleView<'a>, RenderError> {
+    let text = html_to_text(&article.description)
+        .await
+        .map_err(|e| RenderError::PlainText(article.id, e))?;
+
+    Ok(ArticleView {
+        title: article.display_title(),
+        source,
+        description: &article.description,
+        summary: article.summary.as_deref(),
+        text: text.trim().to_string(),
+        created_at: article.created_at.format(DATETIME_FORMAT).to_string(),
+    })
+}
+
+/// Renders a multipart digest of `articles`, in sections by `grouping`.
+///
+/// Source sections are ordered by source URL and tag sections by tag; articles
+/// inside a section go from the newest to the oldest. Articles of unknown
+/// sources are skipped.
+///
+/// # Errors
+/// Returns [`RenderError`] if an article cannot be converted to plain text or a
+/// template fails to render.
+pub async fn render_digest(
+    sources: &[SourceEnum],
+    articles: &[Article],
+    grouping: Grouping<'_>,
+) -> Result<DigestEmail, RenderError> {
+    let sources: HashMap<Uuid, (&SourceEnum, String)> = sources
+        .iter()
+        .map(|source| (source.id(), (source, source.url().to_string())))
+        .collect();
+    let articles: Vec<(&Article, &(&SourceEnum, String))> = articles
+        .iter()
+        .filter_map(|article| Some((article, sources.get(&article.parent_id)?)))
+        .collect();
+
+    // Keyed so that the untagged section sorts last.
+    let mut sections: BTreeMap<(bool, &str), Vec<_>> = BTreeMap::new();
+    for item @ (article, (_, url)) in &articles {
+        let keys: Vec<&str> = match grouping {
+            Grouping::Source => vec![url],
+            Grouping::Tag(details) => details
+                .get(&article.id)
+                .map(|details| details.tags.iter().map(String::as_str).collect())
+                .unwrap_or_default(),
+        };
+
+        if keys.is_empty() {
+            sections.entry((true, "Untagged")).or_default().push(item);
+        }
+        for key in keys {
+            sections.entry((false, key)).or_default().push(item);
+        }
+    }
+
+    let mut groups = Vec::with_capacity(sections.len());
+
+    for ((_, name), mut items) in sections {
+        items.sort_by_key(|(article, _)| Reverse(article.created_at));
+
+        let (kind, url, show_source) = match grouping {
+            Grouping::Source => {
+                let (source, url) = items[0].1;
+                (Some(source.kind()), Some(url.clone()), false)
+            }
+            Grouping::Tag(_) => (None, None, true),
+        };
+
+        let views = items
+            .into_iter()
+            .map(|(article, (_, url))| article_view(article, show_source.then_some(url.as_str())));
+
+        groups.push(GroupView {
+            name: name.to_string(),
+            kind,
+            url,
+            articles: try_join_all(views).await?,
+        });
+    }
+
+    let total = articles.len();
+    let source_count = articles
+        .iter()
+        .map(|(article, _)| article.parent_id)
+        .collect::<HashSet<_>>()
+        .len();
+
+    let subject = format!(
+        "UniNews digest: {total} new {} from {source_count} {}",
+        if total == 1 { "article" } else { "articles" },
+        if source_count == 1 {
+            "source"
+        } else {
+            "sources"
+        },
+    );
+
+    let context = minijinja::context! { subject => &subject, total => total, groups => &groups };
+
+    let render = |name: &'static str| {
+        TEMPLATES
+            .get_template(name)
+            .and_then(|template| template.render(&context))
+            .map_err(|e| RenderError::Template(name, e))
+    };
+
+    Ok(DigestEmail {
+        html: render("digest.html")?,
+        text: render("digest.txt")?,
+        subject,
+    })
+}
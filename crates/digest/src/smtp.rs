@@ -0,0 +1,134 @@
+use crate::render::DigestEmail;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use news_core::config::{SmtpSecurity, SmtpSettings};
+use news_sqlite_core::utils::secrets::Secrets;
+use thiserror::Error;
+
+/// An SMTP server and everything needed to send through it.
+#[derive(Debug, Clone)]
+pub struct SmtpRelay {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub credentials: Option<(String, String)>,
+    pub from: String,
+}
+
+#[derive(Error, Debug)]
+pub enum SmtpSettingsError {
+    #[error("{0} is not set")]
+    Missing(&'static str),
+
+    #[error("smtp.password: secret {0} is neither an environment variable nor in the secrets file")]
+    Secret(String),
+}
+
+impl SmtpRelay {
+    /// The relay configured by `settings`, with its password resolved.
+    ///
+    /// # Errors
+    /// Returns [`SmtpSettingsError`] if a required setting is missing or the
+    /// password names an unknown secret.
+    pub fn from_settings(
+        settings: &SmtpSettings,
+        secrets: &Secrets,
+    ) -> Result<Self, SmtpSettingsError> {
+        let host = settings
+            .host
+            .clone()
+            .ok_or(SmtpSettingsError::Missing("smtp.host"))?;
+        let from = settings
+            .from
+            .clone()
+            .ok_or(SmtpSettingsError::Missing("smtp.from"))?;
+
+        let credentials = match (&settings.username, &settings.password) {
+            (Some(username), Some(password)) => {
+                let password = secrets
+                    .resolve(password)
+                    .map_err(SmtpSettingsError::Secret)?;
+                Some((username.clone(), password))
+            }
+            (Some(_), None) => return Err(SmtpSettingsError::Missing("smtp.password")),
+            (None, _) => None,
+        };
+
+        Ok(Self {
+            host,
+            port: settings
+                .port
+                .unwrap_or_else(|| settings.security.default_port()),
+            security: settings.security,
+            credentials,
+            from,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SendError {
+    #[error("Invalid email address '{0}'")]
+    Address(String, #[source] lettre::address::AddressError),
+
+    #[error("Failed to build digest email")]
+    Build(#[source] lettre::error::Error),
+
+    #[error("Failed to send digest email via {0}")]
+    Transport(String, #[source] lettre::transport::smtp::Error),
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, SendError> {
+    address
+        .parse()
+        .map_err(|e| SendError::Address(address.to_string(), e))
+}
+
+/// Sends the digest as a `multipart/alternative` (plain text + HTML) email.
+///
+/// # Errors
+/// Returns [`SendError`] if an address is invalid, the message cannot be built
+/// or the SMTP server rejects it.
+pub async fn send_digest(
+    relay: &SmtpRelay,
+    recipients: &[String],
+    email: DigestEmail,
+) -> Result<(), SendError> {
+    let mut builder = Message::builder()
+        .from(mailbox(&relay.from)?)
+        .subject(email.subject);
+
+    for recipient in recipients {
+        builder = builder.to(mailbox(recipient)?);
+    }
+
+    let message = builder
+        .multipart(MultiPart::alternative_plain_html(email.text, email.html))
+        .map_err(SendError::Build)?;
+
+    let address = format!("{}:{}", relay.host, relay.port);
+    let transport_error = |e| SendError::Transport(address.clone(), e);
+
+    let mut transport = match relay.security {
+        SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&relay.host)
+            .map_err(transport_error)?,
+        SmtpSecurity::Tls => {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&relay.host).map_err(transport_error)?
+        }
+        SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&relay.host),
+    }
+    .port(relay.port);
+
+    if let Some((username, password)) = &relay.credentials {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    transport
+        .build()
+        .send(message)
+        .await
+        .map_err(transport_error)?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Renders digests of a few articles in sections by source and by tag.
+
+use chrono::{TimeZone, Utc};
+use news_core::models::news::{Article, ArticleDetails};
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::AtomSource;
+use news_digest::render::{Grouping, render_digest};
+use std::collections::HashMap;
+use url::Url;
+use uuid::Uuid;
+
+fn feed(url: &str) -> SourceEnum {
+    SourceEnum::Atom(AtomSource::new(
+        Uuid::now_v7(),
+        Utc::now(),
+        Url::parse(url).expect("valid URL"),
+    ))
+}
+
+fn article(source: &SourceEnum, title: &str, hour: u32) -> Article {
+    let created_at = Utc
+        .with_ymd_and_hms(2026, 10, 19, hour, 0, 0)
+        .single()
+        .expect("valid date");
+
+    Article {
+        id: Uuid::now_v7(),
+        parent_id: source.id(),
+        created_at,
+        updated_at: created_at,
+        title: title.to_string(),
+        description: format!("<p>{title}</p>"),
+        content: None,
+        summary: None,
+        headline: None,
+    }
+}
+
+fn tagged(tags: &[&str]) -> ArticleDetails {
+    ArticleDetails {
+        tags: tags.iter().map(ToString::to_string).collect(),
+        ..ArticleDetails::default()
+    }
+}
+
+/// Section headings of a plain-text digest, in order.
+fn headings(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter(|line| line.starts_with("== "))
+        .collect()
+}
+
+#[tokio::test]
+async fn digests_have_a_section_per_source() {
+    let blog = feed("https://blog.example.com/feed.xml");
+    let news = feed("https://news.example.com/feed.xml");
+    let articles = [
+        article(&news, "Tide pools", 9),
+        article(&blog, "Lichens", 10),
+        article(&news, "Kelp forests", 11),
+    ];
+
+    let email = render_digest(&[blog, news], &articles, Grouping::Source)
+        .await
+        .expect("rendered");
+
+    assert_eq!(
+        email.subject,
+        "UniNews digest: 3 new articles from 2 sources"
+    );
+    assert_eq!(
+        headings(&email.text),
+        [
+            "== https://blog.example.com/feed.xml (atom, 1) ==",
+            "== https://news.example.com/feed.xml (atom, 2) ==",
+        ]
+    );
+    assert!(email.text.find("Kelp forests") < email.text.find("Tide pools"));
+}
+
+#[tokio::test]
+async fn digests_have_a_section_per_tag() {
+    let blog = feed("https://blog.example.com/feed.xml");
+    let news = feed("https://news.example.com/feed.xml");
+    let articles = [
+        article(&news, "Tide pools", 9),
+        article(&blog, "Lichens", 10),
+        article(&news, "Kelp forests", 11),
+    ];
+    let details = HashMap::from([
+        (articles[0].id, tagged(&["ocean"])),
+        (articles[2].id, tagged(&["botany", "ocean"])),
+    ]);
+
+    let email = render_digest(&[blog, news], &articles, Grouping::Tag(&details))
+        .await
+        .expect("rendered");
+
+    assert_eq!(
+        email.subject,
+        "UniNews digest: 3 new articles from 2 sources"
+    );
+    assert_eq!(
+        headings(&email.text),
+        ["== botany (1) ==", "== ocean (2) ==", "== Untagged (1) =="]
+    );
+    assert!(
+        email
+            .text
+            .contains("* Lichens\n  2026-10-19 10:00 UTC, https://blog.example.com/feed.xml")
+    );
+    assert!(email.html.contains(
+        "<h2 style=\"font-size: 1rem; border-bottom: 1px solid #d0d7de;\">\n      ocean"
+    ));
+}
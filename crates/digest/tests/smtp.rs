@@ -0,0 +1,172 @@
+//! Sends digests to a local SMTP sink and checks the conversation.
+
+use news_core::config::{SmtpSecurity, SmtpSettings};
+use news_digest::render::DigestEmail;
+use news_digest::smtp::{SendError, SmtpRelay, SmtpSettingsError, send_digest};
+use news_sqlite_core::utils::secrets::Secrets;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+/// What a client told the sink in one session.
+#[derive(Debug, Default)]
+struct Session {
+    mail_from: String,
+    rcpt_to: Vec<String>,
+    data: String,
+}
+
+/// Accepts one SMTP session on a local port and sends what was received to the
+/// returned channel. Every `RCPT TO` of an address in `rejected` is refused.
+async fn sink(rejected: &'static [&'static str]) -> (u16, oneshot::Receiver<Session>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let port = listener.local_addr().expect("addr").port();
+    let (done, received) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.expect("accept");
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut session = Session::default();
+
+        writer
+            .write_all(b"220 sink ESMTP\r\n")
+            .await
+            .expect("greet");
+        while let Ok(Some(line)) = lines.next_line().await {
+            let command = line.to_ascii_uppercase();
+            let reply = if command.starts_with("EHLO") {
+                "250 sink\r\n"
+            } else if command.starts_with("MAIL FROM:") {
+                session.mail_from = line[10..].to_string();
+                "250 OK\r\n"
+            } else if command.starts_with("RCPT TO:") {
+                let address = line[8..].trim_matches(['<', '>']).to_string();
+                if rejected.contains(&address.as_str()) {
+                    "550 No such user\r\n"
+                } else {
+                    session.rcpt_to.push(address);
+                    "250 OK\r\n"
+                }
+            } else if command == "DATA" {
+                writer
+                    .write_all(b"354 End with .\r\n")
+                    .await
+                    .expect("reply");
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line == "." {
+                        break;
+                    }
+                    session.data.push_str(&line);
+                    session.data.push('\n');
+                }
+                "250 Queued\r\n"
+            } else if command == "QUIT" {
+                let _ = writer.write_all(b"221 Bye\r\n").await;
+                break;
+            } else {
+                "502 Not implemented\r\n"
+            };
+            writer.write_all(reply.as_bytes()).await.expect("reply");
+        }
+
+        let _ = done.send(session);
+    });
+
+    (port, received)
+}
+
+fn relay(port: u16) -> SmtpRelay {
+    SmtpRelay {
+        host: "127.0.0.1".to_string(),
+        port,
+        security: SmtpSecurity::None,
+        credentials: None,
+        from: "UniNews <uninews@localhost>".to_string(),
+    }
+}
+
+fn email() -> DigestEmail {
+    DigestEmail {
+        subject: "UniNews digest: 1 new article from 1 source".to_string(),
+        html: "<p>Tide pools</p>".to_string(),
+        text: "Tide pools".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn digests_are_delivered_as_multipart_emails() {
+    let (port, received) = sink(&[]).await;
+    let recipients = ["me@localhost".to_string(), "team@localhost".to_string()];
+
+    send_digest(&relay(port), &recipients, email())
+        .await
+        .expect("digest sent");
+    let session = received.await.expect("session recorded");
+
+    assert_eq!(session.mail_from, "<uninews@localhost>");
+    assert_eq!(session.rcpt_to, recipients);
+    assert!(
+        session
+            .data
+            .contains("Subject: UniNews digest: 1 new article from 1 source")
+    );
+    assert!(session.data.contains("multipart/alternative"));
+    assert!(session.data.contains("Content-Type: text/plain"));
+    assert!(session.data.contains("Content-Type: text/html"));
+    assert!(session.data.contains("<p>Tide pools</p>"));
+}
+
+#[tokio::test]
+async fn rejected_recipients_fail_the_send() {
+    let (port, _received) = sink(&["nobody@localhost"]).await;
+    let recipients = ["nobody@localhost".to_string()];
+
+    let result = send_digest(&relay(port), &recipients, email()).await;
+
+    assert!(
+        matches!(result, Err(SendError::Transport(relay, _)) if relay == format!("127.0.0.1:{port}"))
+    );
+}
+
+#[test]
+fn relays_are_built_from_the_smtp_settings() {
+    let settings = SmtpSettings {
+        host: Some("smtp.example.com".to_string()),
+        username: Some("uninews".to_string()),
+        password: Some("hunter2".to_string()),
+        from: Some("uninews@example.com".to_string()),
+        ..SmtpSettings::default()
+    };
+
+    let relay = SmtpRelay::from_settings(&settings, &Secrets::default()).expect("valid settings");
+
+    assert_eq!(relay.port, 587);
+    assert_eq!(relay.security, SmtpSecurity::Starttls);
+    assert_eq!(
+        relay.credentials,
+        Some(("uninews".to_string(), "hunter2".to_string()))
+    );
+
+    let error = SmtpRelay::from_settings(
+        &SmtpSettings {
+            password: Some("${UNINEWS_TEST_UNKNOWN_SECRET}".to_string()),
+            ..settings.clone()
+        },
+        &Secrets::default(),
+    )
+    .expect_err("unknown secret");
+    assert!(
+        matches!(error, SmtpSettingsError::Secret(name) if name == "UNINEWS_TEST_UNKNOWN_SECRET")
+    );
+
+    let error = SmtpRelay::from_settings(
+        &SmtpSettings {
+            host: None,
+            ..settings
+        },
+        &Secrets::default(),
+    )
+    .expect_err("no host");
+    assert!(matches!(error, SmtpSettingsError::Missing("smtp.host")));
+}
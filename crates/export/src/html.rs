@@ -0,0 +1,211 @@
+mod manifest;
+mod pages;
+mod templates;
+
+use self::manifest::{Manifest, ManifestError};
+use self::pages::build_pages;
+use self::templates::{TemplateError, Templates};
+use news_core::models::media::{media_references, relocate_media};
+use news_core::models::news::{Article, Attachment};
+use news_core::models::source::SourceEnum;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::fs::{copy, create_dir_all, remove_file, try_exists, write};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Directory of the cached images inside the output directory. Articles are
+/// only rendered into pages one directory deep, which reach it as
+/// [`MEDIA_PAGE_PATH`].
+const MEDIA_DIR: &str = "media";
+const MEDIA_PAGE_PATH: &str = "../media/";
+
+pub struct HtmlExportOptions {
+    pub out_dir: PathBuf,
+    pub templates_dir: Option<PathBuf>,
+    /// Media cache whose files the articles reference; they are copied into
+    /// the site. Without it, cached images are left out.
+    pub media_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    pub written: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+#[derive(Error, Debug)]
+pub enum HtmlExportError {
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+
+    #[error("Failed to render page {0}")]
+    Render(String, #[source] minijinja::Error),
+
+    #[error("Failed to write page {0}")]
+    Write(String, #[source] std::io::Error),
+
+    #[error("Failed to copy media file {0}")]
+    Copy(String, #[source] std::io::Error),
+
+    #[error("Failed to remove stale page {0}")]
+    Remove(String, #[source] std::io::Error),
+}
+
+async fn write_page(out_dir: &Path, path: &str, content: &str) -> Result<(), HtmlExportError> {
+    let target = out_dir.join(path);
+
+    if let Some(parent) = target.parent() {
+        create_dir_all(parent)
+            .await
+            .map_err(|e| HtmlExportError::Write(path.to_string(), e))?;
+    }
+
+    write(&target, content)
+        .await
+        .map_err(|e| HtmlExportError::Write(path.to_string(), e))
+}
+
+/// Copies the cached files `articles` reference from `media_dir` into the
+/// site, skipping those copied by the previous export. Files are named by
+/// their content, so the name is their fingerprint.
+async fn copy_media(
+    options: &HtmlExportOptions,
+    media_dir: &Path,
+    articles: &[Article],
+    previous: &Manifest,
+    current: &mut Manifest,
+    summary: &mut ExportSummary,
+) -> Result<(), HtmlExportError> {
+    let names: BTreeSet<&str> = articles
+        .iter()
+        .flat_map(|article| {
+            media_references(&article.description).chain(
+                article
+                    .content
+                    .iter()
+                    .flat_map(|content| media_references(content)),
+            )
+        })
+        .collect();
+
+    for name in names {
+        let path = format!("{MEDIA_DIR}/{name}");
+        let target = options.out_dir.join(&path);
+
+        let is_unchanged = previous.contains(&path) && try_exists(&target).await.unwrap_or(false);
+        if is_unchanged {
+            summary.unchanged += 1;
+        } else {
+            let origin = media_dir.join(name);
+            if !try_exists(&origin).await.unwrap_or(false) {
+                warn!("[export_html=\"{path}\"] not in the media cache, left out");
+                continue;
+            }
+
+            create_dir_all(options.out_dir.join(MEDIA_DIR))
+                .await
+                .map_err(|e| HtmlExportError::Copy(path.clone(), e))?;
+            copy(&origin, &target)
+                .await
+                .map_err(|e| HtmlExportError::Copy(path.clone(), e))?;
+            summary.written += 1;
+        }
+
+        current.insert(path, name.to_string());
+    }
+
+    Ok(())
+}
+
+/// Renders stored articles into a browsable static site.
+///
+/// The site contains an index grouped by day, one page per day, one page per
+/// source and one page per article. Pages whose rendering input did not change
+/// since the previous export (tracked in a manifest inside `out_dir`) are left
+/// untouched, and pages that are no longer produced are removed. Cached images
+/// the articles reference are copied into `media/`, and so are removed once
+/// no article references them. The `attachments` of an article, such as the
+/// audio of a podcast episode, are linked from its page but not copied.
+///
+/// # Errors
+/// Returns [`HtmlExportError`] if templates cannot be loaded or rendered, or if
+/// the output directory cannot be written.
+pub async fn export_html<H: BuildHasher + Sync>(
+    options: &HtmlExportOptions,
+    sources: &[SourceEnum],
+    articles: &[Article],
+    attachments: &HashMap<Uuid, Vec<Attachment>, H>,
+) -> Result<ExportSummary, HtmlExportError> {
+    let templates = Templates::load(options.templates_dir.as_deref()).await?;
+    let previous = Manifest::load(&options.out_dir).await?;
+
+    let mut current = Manifest::default();
+    let mut summary = ExportSummary::default();
+
+    if let Some(media_dir) = &options.media_dir {
+        copy_media(
+            options,
+            media_dir,
+            articles,
+            &previous,
+            &mut current,
+            &mut summary,
+        )
+        .await?;
+    }
+
+    // Stored HTML references cached files from the root of a site.
+    let articles: Vec<Article> = articles
+        .iter()
+        .map(|article| Article {
+            description: relocate_media(&article.description, MEDIA_PAGE_PATH),
+            content: article
+                .content
+                .as_deref()
+                .map(|content| relocate_media(content, MEDIA_PAGE_PATH)),
+            ..article.clone()
+        })
+        .collect();
+
+    for page in build_pages(sources, &articles, attachments) {
+        let fingerprint = templates.fingerprint(page.template, &page.context);
+
+        let is_unchanged = previous.get(&page.path) == Some(&fingerprint)
+            && try_exists(options.out_dir.join(&page.path))
+                .await
+                .unwrap_or(false);
+
+        if is_unchanged {
+            summary.unchanged += 1;
+        } else {
+            let content = templates
+                .render(page.template, &page.context)
+                .map_err(|e| HtmlExportError::Render(page.path.clone(), e))?;
+
+            write_page(&options.out_dir, &page.path, &content).await?;
+            debug!("[export_html=\"{}\"] page written", page.path);
+            summary.written += 1;
+        }
+
+        current.insert(page.path, fingerprint);
+    }
+
+    for stale in previous.paths().filter(|path| !current.contains(path)) {
+        match remove_file(options.out_dir.join(stale)).await {
+            Ok(()) => summary.removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(HtmlExportError::Remove(stale.clone(), e)),
+        }
+    }
+
+    current.save(&options.out_dir).await?;
+
+    Ok(summary)
+}
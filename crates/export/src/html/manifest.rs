@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use thiserror::Error;
+use tokio::fs::{create_dir_all, read, write};
+
+const MANIFEST_FILE: &str = ".uninews-export.json";
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("Failed to read export manifest {0}")]
+    Read(String, #[source] std::io::Error),
+
+    #[error("Failed to parse export manifest {0}")]
+    Parse(String, #[source] serde_json::Error),
+
+    #[error("Failed to write export manifest {0}")]
+    Write(String, #[source] std::io::Error),
+}
+
+/// Fingerprints of the pages produced by the previous export, keyed by the page
+/// path relative to the output directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pages: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub async fn load(out_dir: &Path) -> Result<Self, ManifestError> {
+        let path = out_dir.join(MANIFEST_FILE);
+
+        let content = match read(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(ManifestError::Read(path.display().to_string(), e)),
+        };
+
+        serde_json::from_slice(&content)
+            .map_err(|e| ManifestError::Parse(path.display().to_string(), e))
+    }
+
+    pub async fn save(&self, out_dir: &Path) -> Result<(), ManifestError> {
+        let path = out_dir.join(MANIFEST_FILE);
+        let write_error = |e| ManifestError::Write(path.display().to_string(), e);
+
+        let content = serde_json::to_vec_pretty(self)
+            .map_err(|e| write_error(std::io::Error::new(ErrorKind::InvalidData, e)))?;
+
+        create_dir_all(out_dir).await.map_err(write_error)?;
+        write(&path, content).await.map_err(write_error)
+    }
+
+    pub fn get(&self, path: &str) -> Option<&String> {
+        self.pages.get(path)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.pages.contains_key(path)
+    }
+
+    pub fn insert(&mut self, path: String, fingerprint: String) {
+        self.pages.insert(path, fingerprint);
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &String> {
+        self.pages.keys()
+    }
+}
@@ -0,0 +1,257 @@
+use chrono::NaiveDate;
+use news_core::models::news::{Article, Attachment};
+use news_core::models::source::SourceEnum;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use url::Url;
+use uuid::Uuid;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+
+pub struct Page {
+    pub path: String,
+    pub template: &'static str,
+    pub context: Value,
+}
+
+/// A source as linked from the pages of its articles. Its article count is
+/// left out, so a new article does not change every page of the source.
+#[derive(Serialize, Clone)]
+struct SourceLink {
+    id: String,
+    kind: &'static str,
+    url: String,
+    page: String,
+}
+
+/// A source as listed on its own page and the index.
+#[derive(Serialize)]
+struct SourceView {
+    #[serde(flatten)]
+    link: SourceLink,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct ArticleView<'a> {
+    id: String,
+    title: &'a str,
+    description: &'a str,
+    /// Listed instead of the description of long articles.
+    summary: Option<&'a str>,
+    content: Option<&'a str>,
+    created_at: String,
+    updated_at: String,
+    date: String,
+    page: String,
+    source: Option<SourceLink>,
+    attachments: Vec<AttachmentView<'a>>,
+}
+
+#[derive(Serialize)]
+struct AttachmentView<'a> {
+    url: &'a str,
+    /// `audio` or `video` when the file can be played in the page, `file`
+    /// otherwise.
+    kind: &'static str,
+    content_type: Option<&'a str>,
+    length: Option<u64>,
+    duration: Option<String>,
+    thumbnail: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct DayView {
+    date: String,
+    page: String,
+    count: usize,
+}
+
+fn source_page(id: Uuid) -> String {
+    format!("sources/{id}.html")
+}
+
+fn article_page(id: Uuid) -> String {
+    format!("articles/{id}.html")
+}
+
+fn day_page(date: NaiveDate) -> String {
+    format!("days/{}.html", date.format(DATE_FORMAT))
+}
+
+/// Playing time as `h:mm:ss`, or `m:ss` under an hour.
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    match hours {
+        0 => format!("{minutes}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+fn attachment_view(attachment: &Attachment) -> AttachmentView<'_> {
+    let content_type = attachment.content_type.as_deref();
+    let kind = match content_type.and_then(|value| value.split_once('/')) {
+        Some(("audio", _)) => "audio",
+        Some(("video", _)) => "video",
+        _ => "file",
+    };
+
+    AttachmentView {
+        url: attachment.url.as_str(),
+        kind,
+        content_type,
+        length: attachment.length,
+        duration: attachment
+            .duration
+            .map(|duration| format_duration(duration.as_secs())),
+        thumbnail: attachment.thumbnail.as_ref().map(Url::as_str),
+    }
+}
+
+fn article_view<'a, H: BuildHasher>(
+    article: &'a Article,
+    sources: &HashMap<Uuid, SourceLink>,
+    attachments: &'a HashMap<Uuid, Vec<Attachment>, H>,
+) -> ArticleView<'a> {
+    ArticleView {
+        id: article.id.to_string(),
+        title: article.display_title(),
+        description: &article.description,
+        summary: article.summary.as_deref(),
+        content: article.content.as_deref(),
+        created_at: article.created_at.format(DATETIME_FORMAT).to_string(),
+        updated_at: article.updated_at.format(DATETIME_FORMAT).to_string(),
+        date: article.created_at.format(DATE_FORMAT).to_string(),
+        page: article_page(article.id),
+        source: sources.get(&article.parent_id).cloned(),
+        attachments: attachments
+            .get(&article.id)
+            .map(|items| items.iter().map(attachment_view).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Lays out the whole site: which pages exist, which template renders each of
+/// them and with which context. Paths are relative to the output directory.
+pub fn build_pages<H: BuildHasher>(
+    sources: &[SourceEnum],
+    articles: &[Article],
+    attachments: &HashMap<Uuid, Vec<Attachment>, H>,
+) -> Vec<Page> {
+    let mut articles: Vec<&Article> = articles.iter().collect();
+    articles.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+
+    let mut by_source: HashMap<Uuid, Vec<&Article>> = HashMap::new();
+    let mut by_day: BTreeMap<NaiveDate, Vec<&Article>> = BTreeMap::new();
+
+    for article in &articles {
+        by_source
+            .entry(article.parent_id)
+            .or_default()
+            .push(article);
+        by_day
+            .entry(article.created_at.date_naive())
+            .or_default()
+            .push(article);
+    }
+
+    let source_links: HashMap<Uuid, SourceLink> = sources
+        .iter()
+        .map(|source| {
+            let id = source.id();
+            let link = SourceLink {
+                id: id.to_string(),
+                kind: source.kind(),
+                url: source.url().to_string(),
+                page: source_page(id),
+            };
+            (id, link)
+        })
+        .collect();
+
+    let mut source_list: Vec<(&Uuid, SourceView)> = source_links
+        .iter()
+        .map(|(id, link)| {
+            let view = SourceView {
+                link: link.clone(),
+                count: by_source.get(id).map_or(0, Vec::len),
+            };
+            (id, view)
+        })
+        .collect();
+    source_list.sort_by(|(_, a), (_, b)| a.link.url.cmp(&b.link.url));
+
+    let days: Vec<DayView> = by_day
+        .iter()
+        .rev()
+        .map(|(date, items)| DayView {
+            date: date.format(DATE_FORMAT).to_string(),
+            page: day_page(*date),
+            count: items.len(),
+        })
+        .collect();
+
+    let mut pages = vec![
+        Page {
+            path: "style.css".to_string(),
+            template: "style.css",
+            context: json!({}),
+        },
+        Page {
+            path: "index.html".to_string(),
+            template: "index.html",
+            context: json!({
+                "root": "",
+                "days": days,
+                "sources": source_list.iter().map(|(_, view)| view).collect::<Vec<_>>(),
+            }),
+        },
+    ];
+
+    pages.extend(by_day.iter().map(|(date, items)| {
+        let items: Vec<ArticleView> = items
+            .iter()
+            .map(|article| article_view(article, &source_links, attachments))
+            .collect();
+
+        Page {
+            path: day_page(*date),
+            template: "day.html",
+            context: json!({
+                "root": "../",
+                "date": date.format(DATE_FORMAT).to_string(),
+                "articles": items,
+            }),
+        }
+    }));
+
+    pages.extend(source_list.iter().map(|(id, source)| {
+        let items: Vec<ArticleView> = by_source
+            .get(id)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|article| article_view(article, &source_links, attachments))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Page {
+            path: source.link.page.clone(),
+            template: "source.html",
+            context: json!({ "root": "../", "source": source, "articles": items }),
+        }
+    }));
+
+    pages.extend(articles.iter().map(|article| Page {
+        path: article_page(article.id),
+        template: "article.html",
+        context: json!({ "root": "../", "article": article_view(article, &source_links, attachments) }),
+    }));
+
+    pages
+}
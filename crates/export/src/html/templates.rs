@@ -0,0 +1,85 @@
+use minijinja::Environment;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::ErrorKind;
+use std::path::Path;
+use thiserror::Error;
+use tokio::fs::read_to_string;
+
+const DEFAULT_TEMPLATES: [(&str, &str); 6] = [
+    ("base.html", include_str!("../../templates/base.html")),
+    ("index.html", include_str!("../../templates/index.html")),
+    ("day.html", include_str!("../../templates/day.html")),
+    ("source.html", include_str!("../../templates/source.html")),
+    ("article.html", include_str!("../../templates/article.html")),
+    ("style.css", include_str!("../../templates/style.css")),
+];
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Failed to read template override {0}")]
+    Read(String, #[source] std::io::Error),
+
+    #[error("Failed to compile template {0}")]
+    Compile(String, #[source] minijinja::Error),
+}
+
+/// Site templates: the built-in defaults, each optionally replaced by a file with
+/// the same name from a user-provided directory.
+pub struct Templates {
+    env: Environment<'static>,
+    fingerprint: String,
+}
+
+impl Templates {
+    pub async fn load(overrides_dir: Option<&Path>) -> Result<Self, TemplateError> {
+        let mut env = Environment::new();
+        let mut hasher = Sha256::new();
+
+        for (name, default_source) in DEFAULT_TEMPLATES {
+            let source = match overrides_dir {
+                Some(dir) => read_override(&dir.join(name)).await?,
+                None => None,
+            }
+            .unwrap_or_else(|| default_source.to_string());
+
+            hasher.update(name.as_bytes());
+            hasher.update(source.as_bytes());
+
+            env.add_template_owned(name, source)
+                .map_err(|e| TemplateError::Compile(name.to_string(), e))?;
+        }
+
+        Ok(Self {
+            env,
+            fingerprint: format!("{:x}", hasher.finalize()),
+        })
+    }
+
+    /// Hash of everything that affects the rendered output of a page.
+    pub fn fingerprint(&self, template: &str, context: &impl Serialize) -> String {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.fingerprint.as_bytes());
+        hasher.update(template.as_bytes());
+        hasher.update(serde_json::to_vec(context).unwrap_or_default());
+
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn render(
+        &self,
+        template: &str,
+        context: &impl Serialize,
+    ) -> Result<String, minijinja::Error> {
+        self.env.get_template(template)?.render(context)
+    }
+}
+
+async fn read_override(path: &Path) -> Result<Option<String>, TemplateError> {
+    match read_to_string(path).await {
+        Ok(source) => Ok(Some(source)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(TemplateError::Read(path.display().to_string(), e)),
+    }
+}
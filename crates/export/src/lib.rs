@@ -0,0 +1,3 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+pub mod html;
@@ -0,0 +1,149 @@
+//! Exports a few articles twice into the same directory and checks which
+//! pages the second export writes.
+
+use chrono::{TimeZone, Utc};
+use news_core::models::news::Article;
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::AtomSource;
+use news_export::html::{ExportSummary, HtmlExportOptions, export_html};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use url::Url;
+use uuid::Uuid;
+
+/// Written over every page, so the pages an export leaves alone keep it.
+const UNTOUCHED: &str = "untouched";
+
+fn feed(url: &str) -> SourceEnum {
+    SourceEnum::Atom(AtomSource::new(
+        Uuid::now_v7(),
+        Utc::now(),
+        Url::parse(url).expect("valid URL"),
+    ))
+}
+
+fn article(source: &SourceEnum, title: &str, day: u32, hour: u32) -> Article {
+    let created_at = Utc
+        .with_ymd_and_hms(2026, 10, day, hour, 0, 0)
+        .single()
+        .expect("valid date");
+
+    Article {
+        id: Uuid::now_v7(),
+        parent_id: source.id(),
+        created_at,
+        updated_at: created_at,
+        title: title.to_string(),
+        description: format!("<p>{title}</p>"),
+        content: None,
+        summary: None,
+        headline: None,
+    }
+}
+
+fn options() -> HtmlExportOptions {
+    HtmlExportOptions {
+        out_dir: std::env::temp_dir().join(format!("uninews-export-{}", Uuid::now_v7())),
+        templates_dir: None,
+        media_dir: None,
+    }
+}
+
+async fn export(
+    options: &HtmlExportOptions,
+    sources: &[SourceEnum],
+    articles: &[Article],
+) -> ExportSummary {
+    export_html(options, sources, articles, &HashMap::<Uuid, Vec<_>>::new())
+        .await
+        .expect("export")
+}
+
+/// Paths of the pages under `dir`, relative to it, without the manifest.
+fn pages(dir: &Path) -> Vec<PathBuf> {
+    let mut pages = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(current) = dirs.pop() {
+        for entry in std::fs::read_dir(current).expect("read directory") {
+            let path = entry.expect("directory entry").path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_none_or(|extension| extension != "json") {
+                pages.push(path.strip_prefix(dir).expect("inside dir").to_path_buf());
+            }
+        }
+    }
+
+    pages
+}
+
+fn mark_untouched(dir: &Path) {
+    for page in pages(dir) {
+        std::fs::write(dir.join(page), UNTOUCHED).expect("write page");
+    }
+}
+
+/// Pages rewritten since [`mark_untouched`].
+fn rewritten(dir: &Path) -> BTreeSet<String> {
+    pages(dir)
+        .into_iter()
+        .filter(|page| std::fs::read_to_string(dir.join(page)).expect("read page") != UNTOUCHED)
+        .map(|page| page.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn unchanged_input_writes_no_pages() {
+    let options = options();
+    let blog = feed("https://blog.example.com/feed.xml");
+    let sources = [blog];
+    let articles = [
+        article(&sources[0], "Tide pools", 18, 9),
+        article(&sources[0], "Lichens", 19, 10),
+    ];
+
+    let first = export(&options, &sources, &articles).await;
+    // Style, index, two days, one source and two articles.
+    assert_eq!((first.written, first.unchanged, first.removed), (7, 0, 0));
+
+    mark_untouched(&options.out_dir);
+    let second = export(&options, &sources, &articles).await;
+
+    assert_eq!(
+        (second.written, second.unchanged, second.removed),
+        (0, 7, 0)
+    );
+    assert!(rewritten(&options.out_dir).is_empty());
+}
+
+#[tokio::test]
+async fn a_new_article_only_rewrites_the_pages_listing_it() {
+    let options = options();
+    let blog = feed("https://blog.example.com/feed.xml");
+    let news = feed("https://news.example.com/feed.xml");
+    let mut articles = vec![
+        article(&blog, "Tide pools", 18, 9),
+        article(&news, "Kelp forests", 18, 10),
+        article(&blog, "Lichens", 19, 11),
+    ];
+    let sources = [blog, news];
+    export(&options, &sources, &articles).await;
+
+    mark_untouched(&options.out_dir);
+    let added = article(&sources[0], "Barnacles", 19, 12);
+    let added_page = format!("articles/{}.html", added.id);
+    articles.push(added);
+    let summary = export(&options, &sources, &articles).await;
+
+    assert_eq!(
+        rewritten(&options.out_dir),
+        BTreeSet::from([
+            added_page,
+            "days/2026-10-19.html".to_string(),
+            "index.html".to_string(),
+            format!("sources/{}.html", sources[0].id()),
+        ])
+    );
+    assert_eq!((summary.written, summary.removed), (4, 0));
+}
@@ -0,0 +1,8 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum MemoryServiceError {
+    #[error("Source {0} does not exist")]
+    UnknownSource(Uuid),
+}
@@ -0,0 +1,2 @@
+pub mod news;
+pub mod source;
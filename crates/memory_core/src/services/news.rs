@@ -0,0 +1,362 @@
+use crate::errors::MemoryServiceError;
+use crate::store::{MemoryStore, StoredArticle, UpsertMapping, UuidGroup};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use news_core::models::news::{
+    Article, ArticleChange, ArticleDetails, ArticleFingerprint, ArticleMarks, ArticleRevision,
+    Attachment, ChangeKind, News, Story,
+};
+use news_core::services::news::{GetAllError, MarkError, NewsService, RevisionsError, UpdateError};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+pub struct MemoryNewsService {
+    store: MemoryStore,
+}
+
+impl MemoryNewsService {
+    #[must_use]
+    pub const fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+
+    /// When the article was starred, `None` if it is not starred or unknown.
+    #[must_use]
+    pub fn starred_at(&self, id: Uuid) -> Option<DateTime<Utc>> {
+        self.store
+            .lock()
+            .articles
+            .get(&id)
+            .and_then(|stored| stored.starred_at)
+    }
+}
+
+impl StoredArticle {
+    fn new(id: Uuid, news: &impl News, now: DateTime<Utc>) -> Self {
+        Self {
+            article: Article {
+                id,
+                parent_id: news.parent_id(),
+                created_at: now,
+                updated_at: now,
+                title: news.title().to_string(),
+                description: news.description().to_string(),
+                content: news.content().clone(),
+                summary: news.summary().map(str::to_string),
+                headline: news.headline().map(str::to_string),
+            },
+            starred_at: news.marks().starred.then_some(now),
+            read_at: news.marks().read.then_some(now),
+            revisions: Vec::new(),
+            attachments: news.attachments().to_vec(),
+            authors: news.authors().to_vec(),
+            categories: news.categories().to_vec(),
+            tags: news.marks().tags.clone(),
+            link: news.link().cloned(),
+            language: news.language().map(str::to_string),
+            simhash: None,
+            story_id: None,
+        }
+    }
+
+    fn marks(&self) -> ArticleMarks {
+        ArticleMarks {
+            read: self.read_at.is_some(),
+            starred: self.starred_at.is_some(),
+            tags: self.tags.clone(),
+        }
+    }
+
+    /// Applies `news` over the stored version, keeping the replaced text as a
+    /// revision. Returns `None` when nothing changed.
+    fn replace(&mut self, news: &impl News, now: DateTime<Utc>) -> Option<&Article> {
+        let article = &mut self.article;
+        let text_changed = article.title != news.title()
+            || article.description != news.description()
+            || article.content != *news.content();
+
+        if !text_changed && article.parent_id == news.parent_id() {
+            return None;
+        }
+
+        if text_changed {
+            self.revisions.push(ArticleRevision {
+                article_id: article.id,
+                created_at: article.updated_at,
+                replaced_at: Some(now),
+                title: article.title.clone(),
+                description: article.description.clone(),
+                content: article.content.clone(),
+            });
+        }
+
+        article.parent_id = news.parent_id();
+        article.title = news.title().to_string();
+        article.description = news.description().to_string();
+        article.content.clone_from(news.content());
+        article.updated_at = now;
+
+        Some(article)
+    }
+}
+
+fn newest_first(articles: impl DoubleEndedIterator<Item = Article>) -> Vec<Article> {
+    let mut articles: Vec<_> = articles.rev().collect();
+    articles.sort_by_key(|article| Reverse(article.created_at));
+    articles
+}
+
+#[async_trait]
+impl NewsService for MemoryNewsService {
+    async fn update(&self, news: &[Arc<impl News>]) -> Result<Vec<ArticleChange>, UpdateError> {
+        let now = Utc::now();
+        let mut data = self.store.lock();
+
+        // The SQL backends reject the whole batch on a foreign key violation.
+        if let Some(news) = news
+            .iter()
+            .find(|news| !data.sources.contains_key(&news.parent_id()))
+        {
+            return Err(UpdateError::UpdateItem {
+                id: Uuid::nil(),
+                title: news.title().to_string(),
+                error: Box::new(MemoryServiceError::UnknownSource(news.parent_id())),
+            });
+        }
+
+        let mut modified: HashMap<Uuid, usize> = HashMap::new();
+        let mut changes = Vec::new();
+
+        for news in news {
+            let news = news.as_ref();
+
+            let (id, kind) = match data.upsert_mapping(UuidGroup::News, news.source_key()) {
+                UpsertMapping::New(id) => (id, ChangeKind::Created),
+                UpsertMapping::Existing(id) => (id, ChangeKind::Updated),
+            };
+
+            let article = match data.articles.get_mut(&id) {
+                Some(stored) => {
+                    news.attachments().clone_into(&mut stored.attachments);
+                    news.authors().clone_into(&mut stored.authors);
+                    news.categories().clone_into(&mut stored.categories);
+                    stored.link = news.link().cloned();
+                    stored.language = news.language().map(str::to_string);
+                    stored.article.summary = news.summary().map(str::to_string);
+                    stored.article.headline = news.headline().map(str::to_string);
+                    stored.replace(news, now).cloned()
+                }
+                None => {
+                    let stored = StoredArticle::new(id, news, now);
+                    let article = stored.article.clone();
+                    data.articles.insert(id, stored);
+                    Some(article)
+                }
+            };
+
+            if let Some(article) = article {
+                *modified.entry(article.parent_id).or_default() += 1;
+                let marks = data
+                    .articles
+                    .get(&id)
+                    .map(StoredArticle::marks)
+                    .unwrap_or_default();
+                changes.push(ArticleChange {
+                    kind,
+                    article,
+                    marks,
+                });
+            }
+        }
+
+        for (uuid, modified) in &modified {
+            info!("[news_service={}] {} articles modified", uuid, modified);
+        }
+
+        Ok(changes)
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Article>, GetAllError> {
+        let data = self.store.lock();
+        let articles = data.articles.values().map(|stored| stored.article.clone());
+
+        Ok(newest_first(articles))
+    }
+
+    async fn get_created_after(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<impl IntoIterator<Item = Article>, GetAllError> {
+        let data = self.store.lock();
+        let articles = data
+            .articles
+            .values()
+            .filter(|stored| stored.article.created_at > after)
+            .map(|stored| stored.article.clone());
+
+        Ok(newest_first(articles))
+    }
+
+    async fn revisions(&self, id: Uuid) -> Result<Vec<ArticleRevision>, RevisionsError> {
+        let data = self.store.lock();
+        let stored = data.articles.get(&id).ok_or(RevisionsError::NotFound(id))?;
+        let current = &stored.article;
+
+        let mut revisions = stored.revisions.clone();
+        revisions.push(ArticleRevision {
+            article_id: current.id,
+            created_at: current.updated_at,
+            replaced_at: None,
+            title: current.title.clone(),
+            description: current.description.clone(),
+            content: current.content.clone(),
+        });
+
+        Ok(revisions)
+    }
+
+    async fn set_starred(&self, id: Uuid, starred: bool) -> Result<(), MarkError> {
+        let mut data = self.store.lock();
+        let stored = data.articles.get_mut(&id).ok_or(MarkError::NotFound(id))?;
+
+        stored.starred_at = match starred {
+            true => Some(stored.starred_at.unwrap_or_else(Utc::now)),
+            false => None,
+        };
+
+        Ok(())
+    }
+
+    async fn set_read(&self, id: Uuid, read: bool) -> Result<(), MarkError> {
+        let mut data = self.store.lock();
+        let stored = data.articles.get_mut(&id).ok_or(MarkError::NotFound(id))?;
+
+        stored.read_at = match read {
+            true => Some(stored.read_at.unwrap_or_else(Utc::now)),
+            false => None,
+        };
+
+        Ok(())
+    }
+
+    async fn add_tag(&self, id: Uuid, tag: &str) -> Result<(), MarkError> {
+        let mut data = self.store.lock();
+        let stored = data.articles.get_mut(&id).ok_or(MarkError::NotFound(id))?;
+
+        if let Err(index) = stored.tags.binary_search_by(|t| t.as_str().cmp(tag)) {
+            stored.tags.insert(index, tag.to_string());
+        }
+
+        Ok(())
+    }
+
+    async fn contents(&self, source_keys: &[&str]) -> Result<HashMap<String, String>, GetAllError> {
+        let data = self.store.lock();
+
+        Ok(source_keys
+            .iter()
+            .filter_map(|key| {
+                let id = data.find_mapping(UuidGroup::News, key)?;
+                let content = data.articles.get(&id)?.article.content.clone()?;
+                Some(((*key).to_string(), content))
+            })
+            .collect())
+    }
+
+    async fn attachments(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Attachment>>, GetAllError> {
+        let data = self.store.lock();
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| {
+                let stored = data.articles.get(id)?;
+                (!stored.attachments.is_empty()).then(|| (*id, stored.attachments.clone()))
+            })
+            .collect())
+    }
+
+    async fn details(&self, ids: &[Uuid]) -> Result<HashMap<Uuid, ArticleDetails>, GetAllError> {
+        let data = self.store.lock();
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| {
+                let stored = data.articles.get(id)?;
+                let details = ArticleDetails {
+                    authors: stored.authors.clone(),
+                    categories: stored.categories.clone(),
+                    tags: stored.tags.clone(),
+                    read: stored.read_at.is_some(),
+                    starred: stored.starred_at.is_some(),
+                    language: stored.language.clone(),
+                };
+                Some((*id, details))
+            })
+            .collect())
+    }
+
+    async fn fingerprints(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<ArticleFingerprint>, GetAllError> {
+        let data = self.store.lock();
+
+        let mut stored: Vec<_> = data
+            .articles
+            .values()
+            .filter(|stored| stored.article.created_at > after)
+            .collect();
+        stored.sort_by_key(|stored| (stored.article.created_at, stored.article.id));
+
+        Ok(stored
+            .into_iter()
+            .map(|stored| ArticleFingerprint {
+                article_id: stored.article.id,
+                parent_id: stored.article.parent_id,
+                link: stored.link.clone(),
+                simhash: stored.simhash,
+                story_id: stored.story_id,
+            })
+            .collect())
+    }
+
+    async fn set_story(
+        &self,
+        id: Uuid,
+        story_id: Uuid,
+        simhash: Option<u64>,
+    ) -> Result<(), MarkError> {
+        let mut data = self.store.lock();
+        let stored = data.articles.get_mut(&id).ok_or(MarkError::NotFound(id))?;
+
+        stored.story_id = Some(story_id);
+        stored.simhash = simhash;
+        Ok(())
+    }
+
+    async fn stories(&self) -> Result<Vec<Story>, GetAllError> {
+        let data = self.store.lock();
+
+        let mut members: Vec<_> = data
+            .articles
+            .values()
+            .filter_map(|stored| {
+                let article = &stored.article;
+                Some((article.created_at, article.id, stored.story_id?))
+            })
+            .collect();
+        members.sort();
+
+        Ok(Story::group(
+            members
+                .into_iter()
+                .map(|(_, article_id, story_id)| (story_id, article_id)),
+        ))
+    }
+}
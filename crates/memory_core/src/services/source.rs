@@ -0,0 +1,129 @@
+use crate::store::{MemoryStore, SourceDetails, StoredSource, UpsertMapping, UuidGroup};
+use async_trait::async_trait;
+use chrono::Utc;
+use news_core::models::ExternalEntity;
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::AtomSource;
+use news_core::models::source::telegram::TelegramSource;
+use news_core::services::source::{
+    AddError, DeleteCriteria, DropError, GetAllError, GetError, MoveError, SourceDraft,
+    SourceService,
+};
+use url::Url;
+use uuid::Uuid;
+
+pub struct MemorySourceService {
+    store: MemoryStore,
+}
+
+impl MemorySourceService {
+    #[must_use]
+    pub const fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+fn key_of(draft: &SourceDraft) -> (UuidGroup, &str) {
+    match draft {
+        SourceDraft::Atom(draft) => (UuidGroup::SourceAtom, ExternalEntity::source_key(draft)),
+        SourceDraft::Telegram(draft) => {
+            (UuidGroup::SourceTelegram, ExternalEntity::source_key(draft))
+        }
+    }
+}
+
+fn to_source(stored: &StoredSource) -> Result<SourceEnum, GetError> {
+    Ok(match &stored.details {
+        SourceDetails::Atom(url, request) => SourceEnum::Atom(
+            AtomSource::new(stored.id, stored.created_at, url.clone())
+                .with_request(request.clone()),
+        ),
+        SourceDetails::Telegram(username) => SourceEnum::Telegram(
+            TelegramSource::new(stored.id, username.clone(), stored.created_at)
+                .map_err(|e| GetError::Internal(Box::new(e)))?,
+        ),
+    })
+}
+
+#[async_trait]
+impl SourceService for MemorySourceService {
+    async fn add(&self, draft: SourceDraft) -> Result<(), AddError> {
+        let (group, source_key) = key_of(&draft);
+        let mut data = self.store.lock();
+
+        let id = match data.upsert_mapping(group, source_key) {
+            UpsertMapping::Existing(_) => {
+                return Err(AddError::AlreadyExists(source_key.to_string()));
+            }
+            UpsertMapping::New(id) => id,
+        };
+
+        let details = match draft {
+            SourceDraft::Atom(draft) => SourceDetails::Atom(draft.url, draft.request),
+            SourceDraft::Telegram(draft) => SourceDetails::Telegram(draft.username),
+        };
+
+        data.sources.insert(
+            id,
+            StoredSource {
+                id,
+                created_at: Utc::now(),
+                details,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<SourceEnum, GetError> {
+        match self.store.lock().sources.get(&id) {
+            Some(stored) => to_source(stored),
+            None => Err(GetError::NotFound {
+                id: id.to_string(),
+                entity: String::from("source"),
+            }),
+        }
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = SourceEnum>, GetAllError> {
+        self.store
+            .lock()
+            .sources
+            .values()
+            .map(|stored| to_source(stored).map_err(|error| GetAllError(Box::new(error))))
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn drop_by(&self, criteria: DeleteCriteria) -> Result<(), DropError> {
+        let (group, source_key) = key_of(&criteria);
+        let mut data = self.store.lock();
+
+        if let Some(id) = data.remove_mapping(group, source_key) {
+            data.remove_source(id);
+        }
+
+        Ok(())
+    }
+
+    async fn move_atom(&self, id: Uuid, url: Url) -> Result<(), MoveError> {
+        let mut data = self.store.lock();
+
+        let old_url = match data.sources.get(&id).map(|stored| &stored.details) {
+            Some(SourceDetails::Atom(old_url, _)) => old_url.clone(),
+            _ => return Err(MoveError::NotFound(id)),
+        };
+
+        data.move_mapping(UuidGroup::SourceAtom, old_url.as_str(), url.as_str())
+            .map_err(|_| MoveError::AlreadyExists(url.to_string()))?;
+
+        if let Some(StoredSource {
+            details: SourceDetails::Atom(stored_url, _),
+            ..
+        }) = data.sources.get_mut(&id)
+        {
+            *stored_url = url;
+        }
+
+        Ok(())
+    }
+}
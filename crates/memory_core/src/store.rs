@@ -0,0 +1,165 @@
+use crate::services::news::MemoryNewsService;
+use crate::services::source::MemorySourceService;
+use chrono::{DateTime, Utc};
+use news_core::models::news::{Article, ArticleRevision, Attachment};
+use news_core::models::source::request::RequestSettings;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum UuidGroup {
+    SourceTelegram,
+    SourceAtom,
+    News,
+}
+
+pub(crate) enum UpsertMapping {
+    New(Uuid),
+    Existing(Uuid),
+}
+
+pub(crate) enum SourceDetails {
+    Atom(Url, RequestSettings),
+    Telegram(String),
+}
+
+pub(crate) struct StoredSource {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub details: SourceDetails,
+}
+
+pub(crate) struct StoredArticle {
+    pub article: Article,
+    pub starred_at: Option<DateTime<Utc>>,
+    pub read_at: Option<DateTime<Utc>>,
+    /// Replaced versions, oldest first.
+    pub revisions: Vec<ArticleRevision>,
+    pub attachments: Vec<Attachment>,
+    pub authors: Vec<String>,
+    pub categories: Vec<String>,
+    /// Sorted and without repeats.
+    pub tags: Vec<String>,
+    pub link: Option<Url>,
+    pub language: Option<String>,
+    pub simhash: Option<u64>,
+    pub story_id: Option<Uuid>,
+}
+
+/// Mirrors the tables of the SQL backends. Internal ids are `UUIDv7`, so the
+/// maps iterate in insertion order.
+#[derive(Default)]
+pub(crate) struct Data {
+    namespaces: HashMap<UuidGroup, Uuid>,
+    mappings: HashMap<Uuid, Uuid>,
+    pub sources: BTreeMap<Uuid, StoredSource>,
+    pub articles: BTreeMap<Uuid, StoredArticle>,
+}
+
+impl Data {
+    fn consistent_id(&mut self, group: UuidGroup, key: &str) -> Uuid {
+        let namespace = *self.namespaces.entry(group).or_insert_with(Uuid::now_v7);
+        Uuid::new_v5(&namespace, key.as_bytes())
+    }
+
+    pub fn upsert_mapping(&mut self, group: UuidGroup, key: &str) -> UpsertMapping {
+        let external_id = self.consistent_id(group, key);
+
+        match self.mappings.get(&external_id) {
+            Some(id) => UpsertMapping::Existing(*id),
+            None => {
+                let id = Uuid::now_v7();
+                self.mappings.insert(external_id, id);
+                UpsertMapping::New(id)
+            }
+        }
+    }
+
+    /// Internal id mapped to `key`, without creating the namespace of `group`.
+    pub fn find_mapping(&self, group: UuidGroup, key: &str) -> Option<Uuid> {
+        let namespace = self.namespaces.get(&group)?;
+        self.mappings
+            .get(&Uuid::new_v5(namespace, key.as_bytes()))
+            .copied()
+    }
+
+    pub fn remove_mapping(&mut self, group: UuidGroup, key: &str) -> Option<Uuid> {
+        let external_id = self.consistent_id(group, key);
+        self.mappings.remove(&external_id)
+    }
+
+    /// Re-keys the mapping of `old_key` to `new_key`, failing if `new_key` is
+    /// mapped to another entity already.
+    pub fn move_mapping(
+        &mut self,
+        group: UuidGroup,
+        old_key: &str,
+        new_key: &str,
+    ) -> Result<(), Uuid> {
+        let old_id = self.consistent_id(group, old_key);
+        let new_id = self.consistent_id(group, new_key);
+
+        if let Some(taken_by) = self.mappings.get(&new_id) {
+            return match self.mappings.get(&old_id) == Some(taken_by) {
+                true => Ok(()),
+                false => Err(*taken_by),
+            };
+        }
+
+        if let Some(internal_id) = self.mappings.remove(&old_id) {
+            self.mappings.insert(new_id, internal_id);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the source together with its articles, as the foreign keys of the
+    /// SQL backends do.
+    pub fn remove_source(&mut self, id: Uuid) {
+        if self.sources.remove(&id).is_none() {
+            return;
+        }
+
+        self.articles
+            .retain(|_, stored| stored.article.parent_id != id);
+
+        let articles = &self.articles;
+        let sources = &self.sources;
+        self.mappings.retain(|_, internal_id| {
+            articles.contains_key(internal_id) || sources.contains_key(internal_id)
+        });
+    }
+}
+
+/// Sources and articles kept in process memory for tests and runs that should
+/// leave nothing behind. Services built from one store, or from its clones, see
+/// the same data; it is gone once the last of them is dropped.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    data: Arc<Mutex<Data>>,
+}
+
+impl MemoryStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn sources(&self) -> MemorySourceService {
+        MemorySourceService::new(self.clone())
+    }
+
+    #[must_use]
+    pub fn news(&self) -> MemoryNewsService {
+        MemoryNewsService::new(self.clone())
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Data> {
+        // Updates check their input before touching the data, so a panic
+        // elsewhere cannot leave it half-written.
+        self.data.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
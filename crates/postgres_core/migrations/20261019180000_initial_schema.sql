@@ -0,0 +1,79 @@
+-- Create a table with persistence uuid groups
+CREATE TABLE IF NOT EXISTS persistence_uuid
+(
+    internal_id UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    group_type  TEXT             NOT NULL UNIQUE,
+    CHECK (group_type IN ('source_telegram', 'source_atom', 'news'))
+);
+
+-- Create table for mapping UUIDv7 <-> UUIDv5
+CREATE TABLE IF NOT EXISTS uuid_mappings
+(
+    internal_id UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    external_id UUID UNIQUE      NOT NULL, -- UUIDv5
+    created_at  TIMESTAMPTZ      NOT NULL DEFAULT now()
+);
+
+-- Create a table for data sources
+CREATE TABLE IF NOT EXISTS sources
+(
+    id         UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    source     TEXT             NOT NULL CHECK (source IN ('atom', 'telegram')),
+    created_at TIMESTAMPTZ      NOT NULL DEFAULT now(),
+    -- metadata
+    CONSTRAINT fk_uuid_mappings_id FOREIGN KEY (id) REFERENCES uuid_mappings (internal_id) ON DELETE CASCADE
+);
+
+-- Create a table for atom-feed additional data
+CREATE TABLE IF NOT EXISTS source_atom_details
+(
+    atom_details_id UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    url             TEXT UNIQUE      NOT NULL,
+    -- metadata
+    CONSTRAINT fk_sources_id FOREIGN KEY (atom_details_id) REFERENCES sources (id) ON DELETE CASCADE
+);
+
+-- Create a table for telegram-channel additional data
+CREATE TABLE IF NOT EXISTS source_telegram_details
+(
+    telegram_details_id UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    username            TEXT UNIQUE      NOT NULL,
+    -- metadata
+    CONSTRAINT fk_sources_id FOREIGN KEY (telegram_details_id) REFERENCES sources (id) ON DELETE CASCADE
+);
+
+-- Create an article table
+CREATE TABLE IF NOT EXISTS articles
+(
+    id          UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    parent_id   UUID             NOT NULL, -- UUIDv7
+    created_at  TIMESTAMPTZ      NOT NULL DEFAULT now(),
+    updated_at  TIMESTAMPTZ      NOT NULL DEFAULT now(),
+    starred_at  TIMESTAMPTZ,
+    -- article data
+    title       TEXT NOT NULL,
+    description TEXT NOT NULL,
+    content     TEXT,
+    -- metadata
+    CONSTRAINT fk_uuid_mappings_id FOREIGN KEY (id) REFERENCES uuid_mappings (internal_id) ON DELETE CASCADE,
+    CONSTRAINT fk_sources_id FOREIGN KEY (parent_id) REFERENCES sources (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_articles_parent_id_created_at ON articles (parent_id, created_at);
+
+-- Create a table with previous versions of articles edited upstream
+CREATE TABLE IF NOT EXISTS article_revisions
+(
+    id          UUID PRIMARY KEY NOT NULL, -- UUIDv7
+    article_id  UUID             NOT NULL, -- UUIDv7
+    created_at  TIMESTAMPTZ      NOT NULL, -- when this version was stored
+    replaced_at TIMESTAMPTZ      NOT NULL DEFAULT now(),
+    -- article data
+    title       TEXT NOT NULL,
+    description TEXT NOT NULL,
+    content     TEXT,
+    -- metadata
+    CONSTRAINT fk_articles_id FOREIGN KEY (article_id) REFERENCES articles (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_revisions_article_id ON article_revisions (article_id, created_at);
@@ -0,0 +1,14 @@
+-- Create a table with the files published with articles, such as podcast audio
+CREATE TABLE IF NOT EXISTS article_attachments
+(
+    article_id   UUID    NOT NULL, -- UUIDv7
+    position     INTEGER NOT NULL, -- order in the feed entry
+    url          TEXT    NOT NULL,
+    content_type TEXT,
+    length       BIGINT,           -- bytes
+    duration     BIGINT,           -- seconds
+    thumbnail    TEXT,
+    -- metadata
+    PRIMARY KEY (article_id, position),
+    CONSTRAINT fk_articles_id FOREIGN KEY (article_id) REFERENCES articles (id) ON DELETE CASCADE
+);
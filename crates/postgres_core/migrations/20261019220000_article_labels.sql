@@ -0,0 +1,15 @@
+-- Rules can mark articles as read when they are stored
+ALTER TABLE articles ADD COLUMN read_at TIMESTAMPTZ;
+
+-- Create a table with the authors and categories announced by feeds, and the
+-- tags added by rules
+CREATE TABLE IF NOT EXISTS article_labels
+(
+    article_id UUID    NOT NULL, -- UUIDv7
+    kind       TEXT    NOT NULL CHECK (kind IN ('author', 'category', 'tag')),
+    position   INTEGER NOT NULL, -- order in the feed entry, 0 for tags
+    value      TEXT    NOT NULL,
+    -- metadata
+    PRIMARY KEY (article_id, kind, value),
+    CONSTRAINT fk_articles_id FOREIGN KEY (article_id) REFERENCES articles (id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Keep the page of each article and group duplicates of several sources into
+-- stories
+ALTER TABLE articles ADD COLUMN link TEXT;
+ALTER TABLE articles ADD COLUMN simhash BIGINT; -- 64-bit SimHash of the text, as signed
+ALTER TABLE articles ADD COLUMN story_id UUID;  -- UUIDv7 of the first article of the story
+
+CREATE INDEX IF NOT EXISTS idx_articles_story_id ON articles (story_id);
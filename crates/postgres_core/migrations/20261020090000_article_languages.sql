@@ -0,0 +1,2 @@
+-- Keep the language detected in the text of each article
+ALTER TABLE articles ADD COLUMN language TEXT; -- ISO 639-1 code, NULL when not detected
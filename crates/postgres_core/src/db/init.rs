@@ -0,0 +1,35 @@
+use sqlx::PgPool;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Schema of the Postgres backend, kept apart from the `SQLite` migrations.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+#[derive(Error, Debug)]
+pub enum DBInitError {
+    #[error("Failed to connect to database")]
+    Connect(#[from] sqlx::Error),
+
+    #[error("Failed to apply database migrations")]
+    Migrate(#[from] MigrateError),
+}
+
+/// Opens a pool to `url` and brings the schema up to date.
+///
+/// # Errors
+/// Returns [`DBInitError`] if the server cannot be reached or a migration fails.
+pub async fn connect_db_pool(url: &str, max_connections: u32) -> Result<PgPool, DBInitError> {
+    // Keeps notices like "relation already exists, skipping" out of the logs.
+    let options = PgConnectOptions::from_str(url)?.options([("client_min_messages", "warning")]);
+
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect_with(options)
+        .await?;
+
+    MIGRATOR.run(&pool).await?;
+
+    Ok(pool)
+}
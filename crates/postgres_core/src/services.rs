@@ -0,0 +1,3 @@
+pub mod news;
+pub mod source;
+pub mod uuid;
@@ -0,0 +1,790 @@
+use super::uuid::{PgUuidService, UpsertMapping, UuidGroup};
+use crate::db::errors::PgServiceError;
+use crate::db::errors::PgServiceError::{Execute, Transaction};
+use async_trait::async_trait;
+use news_core::models::news::{
+    Article, ArticleChange, ArticleDetails, ArticleFingerprint, ArticleMarks, ArticleRevision,
+    Attachment, ChangeKind, News, Story,
+};
+use news_core::services::news::UpdateError::{Internal, UpdateItem};
+use news_core::services::news::{GetAllError, MarkError, NewsService, RevisionsError, UpdateError};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool, query, query_as, query_scalar};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+use url::Url;
+use uuid::Uuid;
+
+struct AttachmentRow {
+    url: String,
+    content_type: Option<String>,
+    length: Option<i64>,
+    duration: Option<i64>,
+    thumbnail: Option<String>,
+}
+
+impl AttachmentRow {
+    fn into_attachment(self) -> Option<Attachment> {
+        Some(Attachment {
+            url: Url::parse(&self.url).ok()?,
+            content_type: self.content_type,
+            length: self.length.and_then(|length| u64::try_from(length).ok()),
+            duration: self
+                .duration
+                .and_then(|secs| u64::try_from(secs).ok())
+                .map(Duration::from_secs),
+            thumbnail: self.thumbnail.and_then(|url| Url::parse(&url).ok()),
+        })
+    }
+}
+
+async fn stored_attachments(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> Result<Vec<Attachment>, sqlx::Error> {
+    let rows = query_as!(
+        AttachmentRow,
+        r#"
+        SELECT a.url, a.content_type, a.length, a.duration, a.thumbnail
+        FROM article_attachments a
+        WHERE a.article_id = $1
+        ORDER BY a.position
+        "#,
+        id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(AttachmentRow::into_attachment)
+        .collect())
+}
+
+/// Replaces the stored attachments of the article `id` when they differ from
+/// `attachments`.
+async fn sync_attachments(
+    conn: &mut PgConnection,
+    id: Uuid,
+    attachments: &[Attachment],
+) -> Result<(), sqlx::Error> {
+    if stored_attachments(conn, id).await? == attachments {
+        return Ok(());
+    }
+
+    query!(
+        "DELETE FROM article_attachments a WHERE a.article_id = $1",
+        id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for (position, attachment) in attachments.iter().enumerate() {
+        let position = i32::try_from(position).unwrap_or(i32::MAX);
+        let url = attachment.url.as_str();
+        let length = attachment
+            .length
+            .and_then(|length| i64::try_from(length).ok());
+        let duration = attachment
+            .duration
+            .and_then(|duration| i64::try_from(duration.as_secs()).ok());
+        let thumbnail = attachment.thumbnail.as_ref().map(Url::as_str);
+
+        query!(
+            r#"
+            INSERT INTO article_attachments AS a (article_id, position, url, content_type, length, duration, thumbnail)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            id,
+            position,
+            url,
+            attachment.content_type,
+            length,
+            duration,
+            thumbnail,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Authors or categories of the article `id`, in the order of the feed.
+async fn stored_labels(
+    conn: &mut PgConnection,
+    id: Uuid,
+    kind: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    query_scalar!(
+        r#"
+        SELECT l.value
+        FROM article_labels l
+        WHERE l.article_id = $1 AND l.kind = $2
+        ORDER BY l.position, l.value COLLATE "C"
+        "#,
+        id,
+        kind
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Replaces the stored `kind` labels of the article `id` when they differ
+/// from `values`.
+async fn sync_labels(
+    conn: &mut PgConnection,
+    id: Uuid,
+    kind: &str,
+    values: &[String],
+) -> Result<(), sqlx::Error> {
+    if stored_labels(conn, id, kind).await? == values {
+        return Ok(());
+    }
+
+    query!(
+        "DELETE FROM article_labels l WHERE l.article_id = $1 AND l.kind = $2",
+        id,
+        kind
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for (position, value) in values.iter().enumerate() {
+        let position = i32::try_from(position).unwrap_or(i32::MAX);
+
+        query!(
+            r#"
+            INSERT INTO article_labels AS l (article_id, kind, position, value)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            kind,
+            position,
+            value,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub struct PgNewsService {
+    db_pool: PgPool,
+    uuid_repo: PgUuidService,
+}
+
+/// Stores the marks `news` was given along with the new article `id`.
+async fn store_marks(
+    conn: &mut PgConnection,
+    id: Uuid,
+    marks: &ArticleMarks,
+) -> Result<(), sqlx::Error> {
+    if marks.read || marks.starred {
+        query!(
+            r#"
+            UPDATE articles AS a SET
+                read_at = CASE WHEN $2 THEN now() END,
+                starred_at = CASE WHEN $3 THEN now() END
+            WHERE a.id = $1
+            "#,
+            id,
+            marks.read,
+            marks.starred,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for tag in &marks.tags {
+        query!(
+            r#"
+            INSERT INTO article_labels AS l (article_id, kind, position, value)
+            VALUES ($1, 'tag', 0, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            tag,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn stored_marks(conn: &mut PgConnection, id: Uuid) -> Result<ArticleMarks, sqlx::Error> {
+    let marks = query!(
+        r#"
+        SELECT
+            a.read_at IS NOT NULL as "read!",
+            a.starred_at IS NOT NULL as "starred!"
+        FROM articles a
+        WHERE a.id = $1
+        "#,
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(ArticleMarks {
+        read: marks.read,
+        starred: marks.starred,
+        tags: stored_labels(conn, id, "tag").await?,
+    })
+}
+
+impl PgNewsService {
+    #[must_use]
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            uuid_repo: PgUuidService::new(db_pool.clone()),
+            db_pool,
+        }
+    }
+
+    async fn update(&self, news: &[Arc<impl News>]) -> Result<Vec<ArticleChange>, PgServiceError> {
+        let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
+
+        let mut modified: HashMap<Uuid, usize> = HashMap::new();
+        let mut changes = Vec::new();
+
+        for news in news {
+            let (id, kind) = match self
+                .uuid_repo
+                .upsert_uuid_mapping(&mut tx, UuidGroup::News, news.source_key())
+                .await?
+            {
+                UpsertMapping::New(id) => (id, ChangeKind::Created),
+                UpsertMapping::Existing(id) => (id, ChangeKind::Updated),
+            };
+
+            let parent_id = news.parent_id();
+            let title = news.title();
+            let description = news.description();
+            let content = news.content().as_deref();
+
+            if kind == ChangeKind::Updated {
+                let revision_id = Uuid::now_v7();
+
+                // Keeps the stored text when the upsert below is about to replace it.
+                query!(
+                    r#"
+                    INSERT INTO article_revisions (id, article_id, created_at, title, description, content)
+                    SELECT $1, id, updated_at, title, description, content
+                    FROM articles
+                    WHERE id = $2 AND (
+                        title IS DISTINCT FROM $3 OR
+                        description IS DISTINCT FROM $4 OR
+                        content IS DISTINCT FROM $5
+                    )
+                    "#,
+                    revision_id,
+                    id,
+                    title,
+                    description,
+                    content,
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(|error| Execute {
+                    id: Some(id),
+                    identifier: Some(title.to_string()),
+                    error,
+                })?;
+            }
+
+            let result = query_as!(
+                Article,
+                r#"
+                INSERT INTO articles (id, parent_id, title, description, content)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (id) DO UPDATE SET
+                    parent_id = excluded.parent_id,
+                    title = excluded.title,
+                    description = excluded.description,
+                    content = excluded.content,
+                    updated_at = clock_timestamp()
+                WHERE
+                    articles.parent_id IS DISTINCT FROM excluded.parent_id OR
+                    articles.title IS DISTINCT FROM excluded.title OR
+                    articles.description IS DISTINCT FROM excluded.description OR
+                    articles.content IS DISTINCT FROM excluded.content
+                RETURNING
+                    id,
+                    parent_id,
+                    created_at as "created_at: DateTime<Utc>",
+                    updated_at as "updated_at: DateTime<Utc>",
+                    title,
+                    description,
+                    content,
+                    summary,
+                    headline
+                "#,
+                id,
+                parent_id,
+                title,
+                description,
+                content,
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|error| Execute {
+                id: Some(id),
+                identifier: Some(title.to_string()),
+                error,
+            })?;
+
+            let link = news.link().map(Url::as_str);
+            let language = news.language();
+            let summary = news.summary();
+            let headline = news.headline();
+            let synced = async {
+                query!(
+                    r#"
+                    UPDATE articles AS a
+                    SET link = $2, language = $3, summary = $4, headline = $5
+                    WHERE a.id = $1 AND (
+                        a.link IS DISTINCT FROM $2 OR a.language IS DISTINCT FROM $3 OR
+                        a.summary IS DISTINCT FROM $4 OR a.headline IS DISTINCT FROM $5
+                    )
+                    "#,
+                    id,
+                    link,
+                    language,
+                    summary,
+                    headline
+                )
+                .execute(&mut *tx)
+                .await?;
+                sync_attachments(&mut tx, id, news.attachments()).await?;
+                sync_labels(&mut tx, id, "author", news.authors()).await?;
+                sync_labels(&mut tx, id, "category", news.categories()).await
+            };
+            synced.await.map_err(|error| Execute {
+                id: Some(id),
+                identifier: Some(title.to_string()),
+                error,
+            })?;
+
+            // Returned before the extracted text was synced.
+            if let Some(article) = result.map(|article| Article {
+                summary: summary.map(str::to_string),
+                headline: headline.map(str::to_string),
+                ..article
+            }) {
+                let marks = match kind {
+                    ChangeKind::Created => store_marks(&mut tx, id, news.marks())
+                        .await
+                        .map(|()| news.marks().clone()),
+                    ChangeKind::Updated => stored_marks(&mut tx, id).await,
+                }
+                .map_err(|error| Execute {
+                    id: Some(id),
+                    identifier: Some(title.to_string()),
+                    error,
+                })?;
+
+                *modified.entry(article.parent_id).or_default() += 1;
+                changes.push(ArticleChange {
+                    kind,
+                    article,
+                    marks,
+                });
+            }
+        }
+
+        tx.commit().await.map_err(Transaction)?;
+
+        for (uuid, modified) in &modified {
+            info!("[news_service={}] {} articles modified", uuid, modified);
+        }
+
+        Ok(changes)
+    }
+}
+
+#[async_trait]
+impl NewsService for PgNewsService {
+    async fn update(&self, news: &[Arc<impl News>]) -> Result<Vec<ArticleChange>, UpdateError> {
+        self.update(news).await.map_err(|e| match e {
+            Execute {
+                id,
+                identifier,
+                error,
+            } => UpdateItem {
+                id: id.unwrap_or_else(Uuid::nil),
+                title: identifier.unwrap_or_else(|| "Unknown title".to_string()),
+                error: Box::new(error),
+            },
+            Transaction(e) => Internal(Box::new(e)),
+        })
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Article>, GetAllError> {
+        query_as!(
+            Article,
+            r#"
+            SELECT
+                id,
+                parent_id,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                title,
+                description,
+                content,
+                summary,
+                headline
+            FROM articles
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))
+    }
+
+    async fn get_created_after(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<impl IntoIterator<Item = Article>, GetAllError> {
+        query_as!(
+            Article,
+            r#"
+            SELECT
+                id,
+                parent_id,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                title,
+                description,
+                content,
+                summary,
+                headline
+            FROM articles
+            WHERE created_at > $1
+            ORDER BY created_at DESC
+            "#,
+            after as DateTime<Utc>
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))
+    }
+
+    async fn revisions(&self, id: Uuid) -> Result<Vec<ArticleRevision>, RevisionsError> {
+        let internal = |error: sqlx::Error| RevisionsError::Internal(Box::new(error));
+
+        let current = query_as!(
+            Article,
+            r#"
+            SELECT
+                id,
+                parent_id,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                title,
+                description,
+                content,
+                summary,
+                headline
+            FROM articles
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .ok_or(RevisionsError::NotFound(id))?;
+
+        let mut revisions = query_as!(
+            ArticleRevision,
+            r#"
+            SELECT
+                article_id,
+                created_at as "created_at: DateTime<Utc>",
+                replaced_at as "replaced_at?: DateTime<Utc>",
+                title,
+                description,
+                content
+            FROM article_revisions
+            WHERE article_id = $1
+            ORDER BY created_at, id
+            "#,
+            id
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        revisions.push(ArticleRevision {
+            article_id: current.id,
+            created_at: current.updated_at,
+            replaced_at: None,
+            title: current.title,
+            description: current.description,
+            content: current.content,
+        });
+
+        Ok(revisions)
+    }
+
+    async fn set_starred(&self, id: Uuid, starred: bool) -> Result<(), MarkError> {
+        let result = query!(
+            r#"
+            UPDATE articles
+            SET starred_at = CASE WHEN $2 THEN COALESCE(starred_at, now()) END
+            WHERE id = $1
+            "#,
+            id,
+            starred
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| MarkError::Internal(Box::new(error)))?;
+
+        match result.rows_affected() {
+            0 => Err(MarkError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn set_read(&self, id: Uuid, read: bool) -> Result<(), MarkError> {
+        let result = query!(
+            r#"
+            UPDATE articles
+            SET read_at = CASE WHEN $2 THEN COALESCE(read_at, now()) END
+            WHERE id = $1
+            "#,
+            id,
+            read
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| MarkError::Internal(Box::new(error)))?;
+
+        match result.rows_affected() {
+            0 => Err(MarkError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn add_tag(&self, id: Uuid, tag: &str) -> Result<(), MarkError> {
+        let internal = |error| MarkError::Internal(Box::new(error));
+
+        let result = query!(
+            r#"
+            INSERT INTO article_labels AS l (article_id, kind, position, value)
+            SELECT a.id, 'tag', 0, $2
+            FROM articles a
+            WHERE a.id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            tag
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        query_scalar!("SELECT a.id FROM articles a WHERE a.id = $1", id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(internal)?
+            .map(|_| ())
+            .ok_or(MarkError::NotFound(id))
+    }
+
+    async fn contents(&self, source_keys: &[&str]) -> Result<HashMap<String, String>, GetAllError> {
+        let internal = |error| GetAllError(Box::new(error));
+
+        let Some(external_ids) = self
+            .uuid_repo
+            .find_by_source_keys(&UuidGroup::News, source_keys)
+            .await
+            .map_err(internal)?
+        else {
+            return Ok(HashMap::new());
+        };
+
+        let mut contents = HashMap::new();
+        for (source_key, external_id) in source_keys.iter().zip(external_ids) {
+            let content = query!(
+                r#"
+                SELECT a.content
+                FROM articles a
+                JOIN uuid_mappings m ON m.internal_id = a.id
+                WHERE m.external_id = $1
+                "#,
+                external_id
+            )
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(|error| GetAllError(Box::new(error)))?
+            .and_then(|row| row.content);
+
+            if let Some(content) = content {
+                contents.insert((*source_key).to_string(), content);
+            }
+        }
+
+        Ok(contents)
+    }
+
+    async fn attachments(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Attachment>>, GetAllError> {
+        let mut conn = self
+            .db_pool
+            .acquire()
+            .await
+            .map_err(|error| GetAllError(Box::new(error)))?;
+
+        let mut attachments = HashMap::new();
+        for id in ids {
+            let stored = stored_attachments(&mut conn, *id)
+                .await
+                .map_err(|error| GetAllError(Box::new(error)))?;
+
+            if !stored.is_empty() {
+                attachments.insert(*id, stored);
+            }
+        }
+
+        Ok(attachments)
+    }
+
+    async fn details(&self, ids: &[Uuid]) -> Result<HashMap<Uuid, ArticleDetails>, GetAllError> {
+        let internal = |error| GetAllError(Box::new(error));
+        let mut conn = self.db_pool.acquire().await.map_err(internal)?;
+
+        let mut details = HashMap::new();
+        for id in ids {
+            let Some(marks) = query!(
+                r#"
+                SELECT
+                    a.read_at IS NOT NULL as "read!",
+                    a.starred_at IS NOT NULL as "starred!",
+                    a.language
+                FROM articles a
+                WHERE a.id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(internal)?
+            else {
+                continue;
+            };
+
+            details.insert(
+                *id,
+                ArticleDetails {
+                    authors: stored_labels(&mut conn, *id, "author")
+                        .await
+                        .map_err(internal)?,
+                    categories: stored_labels(&mut conn, *id, "category")
+                        .await
+                        .map_err(internal)?,
+                    // Tags all have position 0, so they come sorted by value.
+                    tags: stored_labels(&mut conn, *id, "tag")
+                        .await
+                        .map_err(internal)?,
+                    read: marks.read,
+                    starred: marks.starred,
+                    language: marks.language,
+                },
+            );
+        }
+
+        Ok(details)
+    }
+
+    async fn fingerprints(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<ArticleFingerprint>, GetAllError> {
+        let rows = query!(
+            r#"
+            SELECT a.id, a.parent_id, a.link, a.simhash, a.story_id
+            FROM articles a
+            WHERE a.created_at > $1
+            ORDER BY a.created_at, a.id
+            "#,
+            after as DateTime<Utc>
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ArticleFingerprint {
+                article_id: row.id,
+                parent_id: row.parent_id,
+                link: row.link.and_then(|link| Url::parse(&link).ok()),
+                simhash: row.simhash.map(i64::cast_unsigned),
+                story_id: row.story_id,
+            })
+            .collect())
+    }
+
+    async fn set_story(
+        &self,
+        id: Uuid,
+        story_id: Uuid,
+        simhash: Option<u64>,
+    ) -> Result<(), MarkError> {
+        let simhash = simhash.map(u64::cast_signed);
+
+        let result = query!(
+            "UPDATE articles AS a SET story_id = $2, simhash = $3 WHERE a.id = $1",
+            id,
+            story_id,
+            simhash
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| MarkError::Internal(Box::new(error)))?;
+
+        match result.rows_affected() {
+            0 => Err(MarkError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn stories(&self) -> Result<Vec<Story>, GetAllError> {
+        let rows = query!(
+            r#"
+            SELECT a.story_id as "story_id!", a.id
+            FROM articles a
+            WHERE a.story_id IN (
+                SELECT s.story_id
+                FROM articles s
+                WHERE s.story_id IS NOT NULL
+                GROUP BY s.story_id
+                HAVING COUNT(*) > 1
+            )
+            ORDER BY a.created_at, a.id
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))?;
+
+        Ok(Story::group(
+            rows.into_iter().map(|row| (row.story_id, row.id)),
+        ))
+    }
+}
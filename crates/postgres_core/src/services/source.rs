@@ -0,0 +1,387 @@
+use super::uuid::UuidGroup::{SourceAtom, SourceTelegram};
+use super::uuid::{PgUuidService, UpsertMapping};
+use crate::db::errors::PgServiceError;
+use crate::db::errors::PgServiceError::{Execute, Transaction};
+use async_trait::async_trait;
+use news_core::errors::InvalidArgument;
+use news_core::models::ExternalEntity;
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::{AtomDraft, AtomSource};
+use news_core::models::source::request::RequestSettings;
+use news_core::models::source::telegram::{TelegramDraft, TelegramSource};
+use news_core::services::source::{
+    AddError, DeleteCriteria, DropError, GetAllError, GetError, MoveError, SourceDraft,
+    SourceService,
+};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{PgPool, Type, query, query_as};
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+enum SourceType {
+    Atom,
+    Telegram,
+}
+
+struct SourceQueryResult {
+    id: Uuid,
+    source: SourceType,
+    created_at: DateTime<Utc>,
+
+    atom_url: Option<String>,
+    atom_request: Option<String>,
+
+    telegram_username: Option<String>,
+}
+
+pub struct PgSourceService {
+    db_pool: PgPool,
+    uuid_repo: PgUuidService,
+}
+
+impl From<PgServiceError> for AddError {
+    fn from(e: PgServiceError) -> Self {
+        AddError::Internal(Box::new(e))
+    }
+}
+
+impl From<PgServiceError> for MoveError {
+    fn from(e: PgServiceError) -> Self {
+        MoveError::Internal(Box::new(e))
+    }
+}
+
+impl PgSourceService {
+    #[must_use]
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            uuid_repo: PgUuidService::new(db_pool.clone()),
+            db_pool,
+        }
+    }
+
+    async fn get_data_by_criteria(
+        &self,
+        draft: SourceDraft,
+    ) -> Result<(Uuid, SourceType), PgServiceError> {
+        let (source_key, uuid_group, source_type) = match &draft {
+            SourceDraft::Atom(draft) => (
+                ExternalEntity::source_key(draft),
+                SourceAtom,
+                SourceType::Atom,
+            ),
+            SourceDraft::Telegram(draft) => (
+                ExternalEntity::source_key(draft),
+                SourceTelegram,
+                SourceType::Telegram,
+            ),
+        };
+
+        Ok((
+            self.uuid_repo
+                .get_by_source_key(&uuid_group, source_key)
+                .await?,
+            source_type,
+        ))
+    }
+
+    async fn insert_atom(&self, draft: AtomDraft) -> Result<(), AddError> {
+        let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
+
+        let url = draft.url.as_str();
+        let request = request_to_json(&draft.request)?;
+        let source_key = ExternalEntity::source_key(&draft);
+        let upsert_id = self
+            .uuid_repo
+            .upsert_uuid_mapping(&mut tx, SourceAtom, source_key)
+            .await?;
+
+        let UpsertMapping::New(id) = upsert_id else {
+            return Err(AddError::AlreadyExists(source_key.to_string()));
+        };
+
+        query!(
+            "INSERT INTO sources (id, source) VALUES ($1, $2)",
+            id,
+            SourceType::Atom as SourceType,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        query!(
+            r#"
+            INSERT INTO source_atom_details (atom_details_id, url, request)
+            VALUES ($1, $2, $3) ON CONFLICT (url) DO NOTHING
+            "#,
+            id,
+            url,
+            request,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        tx.commit().await.map_err(Transaction)?;
+
+        Ok(())
+    }
+
+    async fn insert_telegram_channel(&self, draft: TelegramDraft) -> Result<(), AddError> {
+        let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
+
+        let source_key = ExternalEntity::source_key(&draft);
+        let upsert_id = self
+            .uuid_repo
+            .upsert_uuid_mapping(&mut tx, SourceTelegram, source_key)
+            .await?;
+
+        let UpsertMapping::New(id) = upsert_id else {
+            return Err(AddError::AlreadyExists(source_key.to_string()));
+        };
+
+        let username = draft.username;
+
+        query!(
+            "INSERT INTO sources (id, source) VALUES ($1, $2)",
+            id,
+            SourceType::Telegram as SourceType,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(username.clone()),
+            error,
+        })?;
+
+        query!(
+            r#"
+            INSERT INTO source_telegram_details (telegram_details_id, username)
+            VALUES ($1, $2)
+            "#,
+            id,
+            username,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(username.clone()),
+            error,
+        })?;
+
+        tx.commit().await.map_err(Transaction)?;
+
+        Ok(())
+    }
+}
+
+/// Stored as `NULL` when there is nothing to send.
+fn request_to_json(request: &RequestSettings) -> Result<Option<String>, AddError> {
+    match request.is_empty() {
+        true => Ok(None),
+        false => serde_json::to_string(request)
+            .map(Some)
+            .map_err(|e| AddError::Internal(Box::new(e))),
+    }
+}
+
+fn query_reply_to(query_result: SourceQueryResult) -> Result<SourceEnum, InvalidArgument> {
+    Ok(match query_result.source {
+        SourceType::Atom => {
+            let value = query_result.atom_url.unwrap_or_default();
+            let url = Url::parse(&value).map_err(|e| InvalidArgument {
+                name: "atom_url".to_string(),
+                value: value.clone(),
+                reason: format!("Invalid URL for Atom source {}: {e}", query_result.id),
+            })?;
+
+            let request = match &query_result.atom_request {
+                Some(request) => serde_json::from_str(request).map_err(|e| InvalidArgument {
+                    name: "atom_request".to_string(),
+                    value: String::new(),
+                    reason: format!(
+                        "Invalid request settings of Atom source {}: {e}",
+                        query_result.id
+                    ),
+                })?,
+                None => RequestSettings::default(),
+            };
+
+            SourceEnum::Atom(
+                AtomSource::new(query_result.id, query_result.created_at, url)
+                    .with_request(request),
+            )
+        }
+        SourceType::Telegram => SourceEnum::Telegram(TelegramSource::new(
+            query_result.id,
+            query_result.telegram_username.unwrap_or_default(),
+            query_result.created_at,
+        )?),
+    })
+}
+
+#[async_trait]
+impl SourceService for PgSourceService {
+    async fn add(&self, draft: SourceDraft) -> Result<(), AddError> {
+        match draft {
+            SourceDraft::Atom(draft) => self.insert_atom(draft).await,
+            SourceDraft::Telegram(draft) => self.insert_telegram_channel(draft).await,
+        }
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<SourceEnum, GetError> {
+        let result = query_as!(
+            SourceQueryResult,
+            r#"
+            SELECT
+                src.id as "id!",
+                src.source as "source!: SourceType",
+                src.created_at as "created_at!: DateTime<Utc>",
+                -- atom details
+                atom.url as "atom_url?",
+                atom.request as "atom_request?",
+                -- telegram details
+                tg.username as "telegram_username?"
+            FROM sources src
+            LEFT JOIN
+                source_atom_details atom ON src.id = atom.atom_details_id
+            LEFT JOIN
+                source_telegram_details tg ON src.id = tg.telegram_details_id
+            WHERE
+                src.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| GetError::Internal(Box::new(e)))?;
+
+        match result {
+            Some(source) => query_reply_to(source).map_err(|e| GetError::Internal(Box::new(e))),
+            None => Err(GetError::NotFound {
+                id: id.to_string(),
+                entity: String::from("source"),
+            }),
+        }
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = SourceEnum>, GetAllError> {
+        query_as!(
+            SourceQueryResult,
+            r#"
+            SELECT
+                src.id as "id!",
+                src.source as "source!: SourceType",
+                src.created_at as "created_at!: DateTime<Utc>",
+                -- atom details
+                atom.url as "atom_url?",
+                atom.request as "atom_request?",
+                -- telegram details
+                tg.username as "telegram_username?"
+            FROM sources src
+            LEFT JOIN
+                source_atom_details atom ON src.id = atom.atom_details_id
+            LEFT JOIN
+                source_telegram_details tg ON src.id = tg.telegram_details_id
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))?
+        .into_iter()
+        .map(|row| query_reply_to(row).map_err(|error| GetAllError(Box::new(error))))
+        .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn drop_by(&self, criteria: DeleteCriteria) -> Result<(), DropError> {
+        let (id, source_type) = self
+            .get_data_by_criteria(criteria)
+            .await
+            .map_err(|error| DropError(Box::new(error)))?;
+
+        query!(
+            r#"
+            DELETE FROM uuid_mappings m
+            USING sources s
+            WHERE m.external_id = $1 AND s.id = m.internal_id AND s.source = $2
+            "#,
+            id,
+            source_type as SourceType,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| DropError(Box::new(error)))?;
+
+        Ok(())
+    }
+
+    async fn move_atom(&self, id: Uuid, url: Url) -> Result<(), MoveError> {
+        let url = url.as_str();
+        let external_id = self.uuid_repo.get_by_source_key(&SourceAtom, url).await?;
+        let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
+
+        let taken_by = query!(
+            "SELECT internal_id FROM uuid_mappings WHERE external_id = $1",
+            external_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        match taken_by {
+            Some(row) if row.internal_id == id => return Ok(()),
+            Some(_) => return Err(MoveError::AlreadyExists(url.to_string())),
+            None => {}
+        }
+
+        let moved = query!(
+            "UPDATE source_atom_details SET url = $1 WHERE atom_details_id = $2",
+            url,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        if moved.rows_affected() == 0 {
+            return Err(MoveError::NotFound(id));
+        }
+
+        query!(
+            "UPDATE uuid_mappings SET external_id = $1 WHERE internal_id = $2",
+            external_id,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        tx.commit().await.map_err(Transaction)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::db::errors::PgServiceError;
+use sqlx::{PgPool, Postgres, Transaction, Type, query};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum UuidGroup {
+    SourceTelegram,
+    SourceAtom,
+    News,
+}
+
+fn gen_consistent_id(parent_id: &Uuid, key: &str) -> Uuid {
+    Uuid::new_v5(parent_id, key.as_bytes())
+}
+
+pub struct PgUuidService {
+    db_pool: PgPool,
+}
+
+pub enum UpsertMapping {
+    New(Uuid),
+    Existing(Uuid),
+}
+
+impl PgUuidService {
+    #[must_use]
+    pub const fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    pub async fn get_by_source_key(
+        &self,
+        group: &UuidGroup,
+        key: &str,
+    ) -> Result<Uuid, PgServiceError> {
+        let result = query!(
+            r#"
+            SELECT internal_id as uuid
+            FROM persistence_uuid
+            WHERE group_type = $1
+            "#,
+            group as &UuidGroup,
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| PgServiceError::Execute {
+            id: None,
+            identifier: Some(format!("{group:?}")),
+            error: e,
+        })?;
+
+        Ok(gen_consistent_id(&result.uuid, key))
+    }
+
+    /// External ids of `keys`, in order, or `None` if no key of `group` was
+    /// ever stored.
+    pub async fn find_by_source_keys(
+        &self,
+        group: &UuidGroup,
+        keys: &[&str],
+    ) -> Result<Option<Vec<Uuid>>, PgServiceError> {
+        let result = query!(
+            r#"
+            SELECT internal_id as uuid
+            FROM persistence_uuid
+            WHERE group_type = $1
+            "#,
+            group as &UuidGroup,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| PgServiceError::Execute {
+            id: None,
+            identifier: Some(format!("{group:?}")),
+            error: e,
+        })?;
+
+        Ok(result.map(|result| {
+            keys.iter()
+                .map(|key| gen_consistent_id(&result.uuid, key))
+                .collect()
+        }))
+    }
+
+    pub async fn upsert_uuid_mapping(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        group: UuidGroup,
+        key: &str,
+    ) -> Result<UpsertMapping, PgServiceError> {
+        let parent_id = Uuid::now_v7();
+
+        let uuid = query!(
+            r#"
+            INSERT INTO persistence_uuid (internal_id, group_type)
+            VALUES ($1, $2)
+            ON CONFLICT (group_type) DO UPDATE SET internal_id = persistence_uuid.internal_id
+            RETURNING internal_id as uuid
+            "#,
+            parent_id,
+            group as UuidGroup,
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| PgServiceError::Execute {
+            id: None,
+            identifier: Some(format!("{group:?}")),
+            error: e,
+        })?
+        .uuid;
+
+        let consistent_id = gen_consistent_id(&uuid, key);
+        let internal_id = Uuid::now_v7();
+
+        let id = query!(
+            r#"
+            INSERT INTO uuid_mappings (internal_id, external_id)
+            VALUES ($1, $2)
+            ON CONFLICT (external_id) DO UPDATE SET internal_id = uuid_mappings.internal_id
+            RETURNING internal_id
+            "#,
+            internal_id,
+            consistent_id,
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|error| PgServiceError::Execute {
+            id: Some(internal_id),
+            identifier: Some(consistent_id.to_string()),
+            error,
+        })?
+        .internal_id;
+
+        match id == internal_id {
+            true => Ok(UpsertMapping::New(id)),
+            false => Ok(UpsertMapping::Existing(id)),
+        }
+    }
+}
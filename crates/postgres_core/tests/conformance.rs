@@ -0,0 +1,707 @@
+//! Behaviour every storage backend must share. Every test runs against a
+//! temporary `SQLite` database, a private in-memory `SQLite` database, the
+//! in-memory store and the Postgres server named by `UNINEWS_TEST_POSTGRES_URL`.
+//! The Postgres variants are ignored unless asked for with `--ignored`, and
+//! fail without the variable.
+//!
+//! Tests of a backend may share one database, so each works on its own sources.
+
+use chrono::{TimeDelta, Utc};
+use news_core::config::DatabaseSettings;
+use news_core::models::news::{ArticleMarks, Attachment, ChangeKind, News};
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::AtomDraft;
+use news_core::models::source::request::{Header, RequestSettings};
+use news_core::models::source::telegram::TelegramDraft;
+use news_core::services::news::{MarkError, NewsService, RevisionsError};
+use news_core::services::source::{AddError, GetError, MoveError, SourceDraft, SourceService};
+use news_memory_core::services::news::MemoryNewsService;
+use news_memory_core::services::source::MemorySourceService;
+use news_memory_core::store::MemoryStore;
+use news_postgres_core::db::init::connect_db_pool;
+use news_postgres_core::services::news::PgNewsService;
+use news_postgres_core::services::source::PgSourceService;
+use news_sqlite_core::db::init::{connect_memory_pool, open_db_pool};
+use news_sqlite_core::services::news::SqliteNewsService;
+use news_sqlite_core::services::source::SqliteSourceService;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
+
+const POSTGRES_URL_ENV: &str = "UNINEWS_TEST_POSTGRES_URL";
+
+static SQLITE_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    let dir = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temporary directory must be writable");
+    dir
+});
+
+struct Item {
+    key: String,
+    parent_id: Uuid,
+    title: String,
+    description: String,
+    content: Option<String>,
+    attachments: Vec<Attachment>,
+    authors: Vec<String>,
+    categories: Vec<String>,
+    link: Option<Url>,
+    language: Option<String>,
+    summary: Option<String>,
+    headline: Option<String>,
+    marks: ArticleMarks,
+}
+
+impl Item {
+    fn new(parent_id: Uuid, key: &str, title: &str) -> Arc<Self> {
+        Arc::new(Self {
+            key: format!("{parent_id}/{key}"),
+            parent_id,
+            title: title.to_string(),
+            description: format!("<p>{title}</p>"),
+            content: None,
+            attachments: Vec::new(),
+            authors: Vec::new(),
+            categories: Vec::new(),
+            link: None,
+            language: None,
+            summary: None,
+            headline: None,
+            marks: ArticleMarks::default(),
+        })
+    }
+}
+
+impl News for Item {
+    fn source_key(&self) -> &str {
+        &self.key
+    }
+
+    fn parent_id(&self) -> Uuid {
+        self.parent_id
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn content(&self) -> &Option<String> {
+        &self.content
+    }
+
+    fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    fn authors(&self) -> &[String] {
+        &self.authors
+    }
+
+    fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    fn link(&self) -> Option<&Url> {
+        self.link.as_ref()
+    }
+
+    fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    fn headline(&self) -> Option<&str> {
+        self.headline.as_deref()
+    }
+
+    fn marks(&self) -> &ArticleMarks {
+        &self.marks
+    }
+}
+
+fn unique_feed() -> Url {
+    Url::parse(&format!("https://example.com/{}.xml", Uuid::now_v7())).expect("valid URL")
+}
+
+fn unique_channel() -> String {
+    format!("channel_{}", Uuid::now_v7().simple())
+}
+
+async fn find(sources: &impl SourceService, key: &str) -> Option<SourceEnum> {
+    sources
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .find(|source| source.matches_key(key))
+}
+
+async fn add_feed(sources: &impl SourceService) -> (Url, Uuid) {
+    let url = unique_feed();
+    sources
+        .add(SourceDraft::Atom(AtomDraft::new(url.clone())))
+        .await
+        .expect("add feed");
+
+    let id = find(sources, url.as_str())
+        .await
+        .expect("feed is listed")
+        .id();
+    (url, id)
+}
+
+async fn adds_and_lists_sources(sources: &impl SourceService, _news: &impl NewsService) {
+    let (url, feed_id) = add_feed(sources).await;
+
+    let channel = unique_channel();
+    sources
+        .add(SourceDraft::Telegram(TelegramDraft::new(channel.clone())))
+        .await
+        .expect("add channel");
+
+    let channel_source = find(sources, &channel).await.expect("channel is listed");
+    assert_eq!(channel_source.kind(), "telegram");
+
+    match sources.get_by_id(feed_id).await.expect("get feed") {
+        SourceEnum::Atom(feed) => assert_eq!(feed.url, url),
+        SourceEnum::Telegram(_) => panic!("feed came back as a Telegram channel"),
+    }
+
+    let duplicate = sources.add(SourceDraft::Atom(AtomDraft::new(url))).await;
+    assert!(matches!(duplicate, Err(AddError::AlreadyExists(_))));
+}
+
+async fn drops_sources_with_their_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (url, feed_id) = add_feed(sources).await;
+
+    let changes = news
+        .update(&[Item::new(feed_id, "1", "First")])
+        .await
+        .expect("store article");
+    let article_id = changes[0].article.id;
+
+    sources
+        .drop_by(SourceDraft::Atom(AtomDraft::new(url.clone())))
+        .await
+        .expect("drop feed");
+
+    assert!(find(sources, url.as_str()).await.is_none());
+    assert!(matches!(
+        sources.get_by_id(feed_id).await,
+        Err(GetError::NotFound { .. })
+    ));
+    assert!(matches!(
+        news.revisions(article_id).await,
+        Err(RevisionsError::NotFound(_))
+    ));
+}
+
+async fn stores_articles_once_and_reports_changes(
+    sources: &impl SourceService,
+    news: &impl NewsService,
+) {
+    let (_, feed_id) = add_feed(sources).await;
+    let started_at = Utc::now() - TimeDelta::seconds(1);
+
+    let items = [
+        Item::new(feed_id, "1", "First"),
+        Item::new(feed_id, "2", "Second"),
+    ];
+    let created = news.update(&items).await.expect("store articles");
+    assert_eq!(created.len(), 2);
+    assert!(created.iter().all(|c| c.kind == ChangeKind::Created));
+
+    let unchanged = news.update(&items).await.expect("store same articles");
+    assert!(unchanged.is_empty(), "unchanged items must not be reported");
+
+    let edited = news
+        .update(&[Item::new(feed_id, "1", "First, edited")])
+        .await
+        .expect("store edited article");
+    assert_eq!(edited.len(), 1);
+    assert_eq!(edited[0].kind, ChangeKind::Updated);
+    assert_eq!(edited[0].article.id, created[0].article.id);
+    assert_eq!(edited[0].article.title, "First, edited");
+
+    let recent: Vec<_> = news
+        .get_created_after(started_at)
+        .await
+        .expect("recent articles")
+        .into_iter()
+        .filter(|article| article.parent_id == feed_id)
+        .collect();
+    assert_eq!(recent.len(), 2);
+}
+
+async fn keeps_revisions_of_edited_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+
+    let mut article_id = Uuid::nil();
+    for title in ["Draft", "Draft, fixed", "Final"] {
+        let changes = news
+            .update(&[Item::new(feed_id, "1", title)])
+            .await
+            .expect("store article");
+        article_id = changes[0].article.id;
+    }
+
+    let revisions = news.revisions(article_id).await.expect("revisions");
+    let titles: Vec<_> = revisions.iter().map(|r| r.title.as_str()).collect();
+    assert_eq!(titles, ["Draft", "Draft, fixed", "Final"]);
+
+    let replaced: Vec<_> = revisions.iter().map(|r| r.replaced_at.is_some()).collect();
+    assert_eq!(replaced, [true, true, false]);
+}
+
+async fn stars_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+
+    let changes = news
+        .update(&[Item::new(feed_id, "1", "Keep me")])
+        .await
+        .expect("store article");
+    let article_id = changes[0].article.id;
+
+    news.set_starred(article_id, true).await.expect("star");
+    news.set_starred(article_id, true)
+        .await
+        .expect("star twice");
+    news.set_starred(article_id, false).await.expect("unstar");
+
+    assert!(matches!(
+        news.set_starred(Uuid::now_v7(), true).await,
+        Err(MarkError::NotFound(_))
+    ));
+}
+
+async fn moves_feeds_keeping_their_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (old_url, feed_id) = add_feed(sources).await;
+    let changes = news
+        .update(&[Item::new(feed_id, "1", "Before the move")])
+        .await
+        .expect("store article");
+
+    let new_url = unique_feed();
+    sources
+        .move_atom(feed_id, new_url.clone())
+        .await
+        .expect("move feed");
+    sources
+        .move_atom(feed_id, new_url.clone())
+        .await
+        .expect("move feed again");
+
+    assert!(find(sources, old_url.as_str()).await.is_none());
+    let moved = find(sources, new_url.as_str())
+        .await
+        .expect("feed is listed");
+    assert_eq!(moved.id(), feed_id);
+
+    let unchanged = news
+        .update(&[Item::new(feed_id, "1", "Before the move")])
+        .await
+        .expect("store same article");
+    assert!(unchanged.is_empty());
+    news.revisions(changes[0].article.id)
+        .await
+        .expect("article kept");
+
+    let (taken_url, _) = add_feed(sources).await;
+    assert!(matches!(
+        sources.move_atom(feed_id, taken_url).await,
+        Err(MoveError::AlreadyExists(_))
+    ));
+    assert!(matches!(
+        sources.move_atom(Uuid::now_v7(), unique_feed()).await,
+        Err(MoveError::NotFound(_))
+    ));
+}
+
+async fn keeps_request_settings_of_feeds(sources: &impl SourceService, _news: &impl NewsService) {
+    let url = unique_feed();
+    let request = RequestSettings {
+        headers: vec![Header {
+            name: "X-Feed-Key".to_string(),
+            value: "${FEED_KEY}".to_string(),
+        }],
+        bearer_token: Some("plain-token".to_string()),
+        cookies: vec!["session=abc".to_string()],
+        ..RequestSettings::default()
+    };
+    sources
+        .add(SourceDraft::Atom(
+            AtomDraft::new(url.clone()).with_request(request.clone()),
+        ))
+        .await
+        .expect("add feed");
+
+    match find(sources, url.as_str()).await.expect("feed is listed") {
+        SourceEnum::Atom(feed) => assert_eq!(feed.request, request),
+        SourceEnum::Telegram(_) => panic!("feed came back as a Telegram channel"),
+    }
+
+    let (_, plain_id) = add_feed(sources).await;
+    match sources.get_by_id(plain_id).await.expect("get feed") {
+        SourceEnum::Atom(feed) => assert!(feed.request.is_empty()),
+        SourceEnum::Telegram(_) => panic!("feed came back as a Telegram channel"),
+    }
+}
+
+async fn looks_up_stored_content(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+    let summary = Item::new(feed_id, "summary", "Summary only");
+    let mut full = Item::new(feed_id, "full", "Full article");
+    Arc::get_mut(&mut full).expect("not shared").content = Some("Full text".to_string());
+
+    news.update(&[summary.clone(), full.clone()])
+        .await
+        .expect("store articles");
+
+    let unknown = format!("{feed_id}/unknown");
+    let contents = news
+        .contents(&[&summary.key, &full.key, &unknown])
+        .await
+        .expect("stored content");
+
+    assert_eq!(contents.len(), 1);
+    assert_eq!(contents[&full.key], "Full text");
+}
+
+fn episode(number: u64) -> Attachment {
+    Attachment {
+        url: Url::parse(&format!("https://cdn.example.com/episode-{number}.mp3"))
+            .expect("valid URL"),
+        content_type: Some("audio/mpeg".to_string()),
+        length: Some(number * 1_000_000),
+        duration: Some(Duration::from_secs(number * 60)),
+        thumbnail: Url::parse("https://cdn.example.com/cover.jpg").ok(),
+    }
+}
+
+async fn keeps_attachments_of_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+    let text = Item::new(feed_id, "text", "Show notes");
+    let mut podcast = Item::new(feed_id, "podcast", "Episode 1");
+    Arc::get_mut(&mut podcast).expect("not shared").attachments = vec![episode(1), episode(2)];
+
+    let changes = news
+        .update(&[text.clone(), podcast.clone()])
+        .await
+        .expect("store articles");
+    let ids: Vec<_> = changes.iter().map(|change| change.article.id).collect();
+
+    let attachments = news.attachments(&ids).await.expect("stored attachments");
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[&ids[1]], [episode(1), episode(2)]);
+
+    // A changed enclosure replaces the stored ones without editing the text.
+    let mut podcast = Item::new(feed_id, "podcast", "Episode 1");
+    Arc::get_mut(&mut podcast).expect("not shared").attachments = vec![episode(3)];
+    let changes = news.update(&[podcast]).await.expect("store article again");
+    assert!(changes.is_empty());
+
+    let attachments = news.attachments(&ids).await.expect("stored attachments");
+    assert_eq!(attachments[&ids[1]], [episode(3)]);
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+}
+
+async fn keeps_labels_and_marks_of_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+    let mut item = Item::new(feed_id, "labelled", "Release notes");
+    let labels = Arc::get_mut(&mut item).expect("not shared");
+    labels.authors = strings(&["Ferris", "Corro"]);
+    labels.categories = strings(&["release", "compiler"]);
+    labels.language = Some("en".to_string());
+
+    let changes = news.update(&[item]).await.expect("store article");
+    let id = changes[0].article.id;
+
+    news.add_tag(id, "rust").await.expect("tag article");
+    news.add_tag(id, "important").await.expect("tag article");
+    news.add_tag(id, "rust").await.expect("tag article twice");
+    news.set_read(id, true).await.expect("mark read");
+
+    let details = news.details(&[id, Uuid::now_v7()]).await.expect("details");
+    assert_eq!(details.len(), 1);
+    assert_eq!(details[&id].authors, ["Ferris", "Corro"]);
+    assert_eq!(details[&id].categories, ["release", "compiler"]);
+    assert_eq!(details[&id].tags, ["important", "rust"]);
+    assert!(details[&id].read);
+    assert!(!details[&id].starred);
+    assert_eq!(details[&id].language.as_deref(), Some("en"));
+
+    // New labels from the feed replace the old ones, tags stay.
+    let mut item = Item::new(feed_id, "labelled", "Release notes");
+    Arc::get_mut(&mut item).expect("not shared").authors = strings(&["Ferris"]);
+    let changes = news.update(&[item]).await.expect("store article again");
+    assert!(changes.is_empty());
+
+    news.set_read(id, false).await.expect("mark unread");
+    let details = news.details(&[id]).await.expect("details");
+    assert_eq!(details[&id].authors, ["Ferris"]);
+    assert!(details[&id].categories.is_empty());
+    assert_eq!(details[&id].tags, ["important", "rust"]);
+    assert!(!details[&id].read);
+    assert_eq!(details[&id].language, None);
+
+    let missing = Uuid::now_v7();
+    assert!(matches!(
+        news.add_tag(missing, "rust").await,
+        Err(MarkError::NotFound(_))
+    ));
+    assert!(matches!(
+        news.set_read(missing, true).await,
+        Err(MarkError::NotFound(_))
+    ));
+}
+
+async fn stores_marks_of_new_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+    let marks = ArticleMarks {
+        read: true,
+        starred: false,
+        tags: strings(&["release", "rust"]),
+    };
+    let mut item = Item::new(feed_id, "marked", "Release notes");
+    Arc::get_mut(&mut item).expect("not shared").marks = marks.clone();
+
+    let changes = news.update(&[item]).await.expect("store article");
+    let id = changes[0].article.id;
+    assert_eq!(changes[0].marks, marks);
+
+    let details = news.details(&[id]).await.expect("details");
+    assert_eq!(details[&id].tags, ["release", "rust"]);
+    assert!(details[&id].read);
+    assert!(!details[&id].starred);
+
+    // Marks of an edited article are those stored, not given again.
+    news.set_read(id, false).await.expect("mark unread");
+    let mut item = Item::new(feed_id, "marked", "Release notes, edited");
+    Arc::get_mut(&mut item).expect("not shared").marks = ArticleMarks {
+        starred: true,
+        ..marks.clone()
+    };
+    let changes = news.update(&[item]).await.expect("store article again");
+    assert_eq!(changes[0].kind, ChangeKind::Updated);
+    assert_eq!(
+        changes[0].marks,
+        ArticleMarks {
+            read: false,
+            ..marks
+        }
+    );
+}
+
+async fn keeps_extracted_text_of_articles(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+    let mut item = Item::new(feed_id, "long", "Long read");
+    let extracted = Arc::get_mut(&mut item).expect("not shared");
+    extracted.summary = Some("First sentence.".to_string());
+    extracted.headline = Some("Long read, explained".to_string());
+
+    let changes = news.update(&[item]).await.expect("store article");
+    let id = changes[0].article.id;
+    assert_eq!(
+        changes[0].article.summary.as_deref(),
+        Some("First sentence.")
+    );
+    assert_eq!(changes[0].article.display_title(), "Long read, explained");
+
+    // A new extraction of the same text is kept without reporting a change.
+    let mut item = Item::new(feed_id, "long", "Long read");
+    Arc::get_mut(&mut item).expect("not shared").summary = Some("Other sentence.".to_string());
+    let changes = news.update(&[item]).await.expect("store article again");
+    assert!(changes.is_empty());
+
+    let stored = news
+        .get_all()
+        .await
+        .expect("list articles")
+        .into_iter()
+        .find(|article| article.id == id)
+        .expect("stored article");
+    assert_eq!(stored.summary.as_deref(), Some("Other sentence."));
+    assert_eq!(stored.headline, None);
+    assert_eq!(stored.display_title(), "Long read");
+}
+
+async fn groups_articles_into_stories(sources: &impl SourceService, news: &impl NewsService) {
+    let (_, feed_id) = add_feed(sources).await;
+    let (_, other_feed_id) = add_feed(sources).await;
+    let before = Utc::now() - TimeDelta::seconds(1);
+
+    let link = Url::parse("https://example.org/press/launch").expect("valid URL");
+    let mut first = Item::new(feed_id, "launch", "Launch");
+    Arc::get_mut(&mut first).expect("not shared").link = Some(link.clone());
+    let first_id = news.update(&[first]).await.expect("store article")[0]
+        .article
+        .id;
+    let second_id = news
+        .update(&[Item::new(other_feed_id, "launch", "Launch, again")])
+        .await
+        .expect("store duplicate")[0]
+        .article
+        .id;
+    let other_id = news
+        .update(&[Item::new(feed_id, "other", "Something else")])
+        .await
+        .expect("store other article")[0]
+        .article
+        .id;
+
+    let fingerprints: Vec<_> = news
+        .fingerprints(before)
+        .await
+        .expect("fingerprints")
+        .into_iter()
+        .filter(|fingerprint| [first_id, second_id, other_id].contains(&fingerprint.article_id))
+        .collect();
+    let ids: Vec<_> = fingerprints.iter().map(|f| f.article_id).collect();
+    assert_eq!(ids, [first_id, second_id, other_id]);
+    assert_eq!(fingerprints[0].parent_id, feed_id);
+    assert_eq!(fingerprints[0].link, Some(link));
+    assert_eq!(fingerprints[1].link, None);
+    assert!(fingerprints.iter().all(|f| f.story_id.is_none()));
+
+    news.set_story(first_id, first_id, Some(u64::MAX))
+        .await
+        .expect("start story");
+    news.set_story(second_id, first_id, Some(7))
+        .await
+        .expect("join story");
+    news.set_story(other_id, other_id, None)
+        .await
+        .expect("start another story");
+
+    let fingerprints = news.fingerprints(before).await.expect("fingerprints");
+    let first = fingerprints
+        .iter()
+        .find(|f| f.article_id == first_id)
+        .expect("first article");
+    assert_eq!(first.simhash, Some(u64::MAX));
+    assert_eq!(first.story_id, Some(first_id));
+
+    let stories: Vec<_> = news
+        .stories()
+        .await
+        .expect("stories")
+        .into_iter()
+        .filter(|story| story.id == first_id || story.id == other_id)
+        .collect();
+    assert_eq!(stories.len(), 1);
+    assert_eq!(stories[0].primary, first_id);
+    assert_eq!(stories[0].articles, [first_id, second_id]);
+
+    assert!(matches!(
+        news.set_story(Uuid::now_v7(), first_id, None).await,
+        Err(MarkError::NotFound(_))
+    ));
+}
+
+async fn sqlite() -> (SqliteSourceService, SqliteNewsService) {
+    let pool = open_db_pool(&DatabaseSettings {
+        path: SQLITE_DIR.join("app.sqlite"),
+        ..DatabaseSettings::default()
+    })
+    .await
+    .expect("SQLite database");
+
+    (
+        SqliteSourceService::from_pool(pool.clone()),
+        SqliteNewsService::from_pool(pool),
+    )
+}
+
+async fn sqlite_memory() -> (SqliteSourceService, SqliteNewsService) {
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    (
+        SqliteSourceService::from_pool(pool.clone()),
+        SqliteNewsService::from_pool(pool),
+    )
+}
+
+async fn memory() -> (MemorySourceService, MemoryNewsService) {
+    let store = MemoryStore::new();
+    (store.sources(), store.news())
+}
+
+async fn postgres() -> (PgSourceService, PgNewsService) {
+    let url = std::env::var(POSTGRES_URL_ENV)
+        .unwrap_or_else(|_| panic!("{POSTGRES_URL_ENV} must name a test database"));
+
+    let pool = connect_db_pool(&url, 2).await.expect("Postgres pool");
+    (PgSourceService::new(pool.clone()), PgNewsService::new(pool))
+}
+
+macro_rules! conformance {
+    ($($test:ident),* $(,)?) => {
+        mod sqlite {
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    let (sources, news) = super::sqlite().await;
+                    super::$test(&sources, &news).await;
+                }
+            )*
+        }
+
+        mod sqlite_memory {
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    let (sources, news) = super::sqlite_memory().await;
+                    super::$test(&sources, &news).await;
+                }
+            )*
+        }
+
+        mod memory {
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    let (sources, news) = super::memory().await;
+                    super::$test(&sources, &news).await;
+                }
+            )*
+        }
+
+        mod postgres {
+            $(
+                #[tokio::test]
+                #[ignore = "needs a Postgres database in UNINEWS_TEST_POSTGRES_URL"]
+                async fn $test() {
+                    let (sources, news) = super::postgres().await;
+                    super::$test(&sources, &news).await;
+                }
+            )*
+        }
+    };
+}
+
+conformance!(
+    adds_and_lists_sources,
+    drops_sources_with_their_articles,
+    stores_articles_once_and_reports_changes,
+    keeps_revisions_of_edited_articles,
+    stars_articles,
+    moves_feeds_keeping_their_articles,
+    keeps_request_settings_of_feeds,
+    looks_up_stored_content,
+    keeps_attachments_of_articles,
+    keeps_labels_and_marks_of_articles,
+    stores_marks_of_new_articles,
+    keeps_extracted_text_of_articles,
+    groups_articles_into_stories,
+);
@@ -1,3 +1,5 @@
 pub mod codecs;
 pub mod errors;
 pub mod init;
+pub mod maintenance;
+pub mod migrate;
@@ -1,17 +1,177 @@
-use crate::utils::fs::get_db_uri;
+use crate::db::migrate::{MIGRATOR, MigrationError, migrate};
+use news_core::config::{DatabaseSettings, JournalMode, Synchronous};
 use sqlx::SqlitePool;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use std::str::FromStr;
+use thiserror::Error;
 use tokio::sync::OnceCell;
-static DB_POOL: OnceCell<SqlitePool> = OnceCell::const_new();
+use tracing::info;
 
-pub type DBInitError = sqlx::Error;
+#[derive(Error, Debug)]
+pub enum DBInitError {
+    #[error("Failed to connect to database")]
+    Connect(#[from] sqlx::Error),
 
-pub async fn init_db_pool() -> Result<SqlitePool, DBInitError> {
-    DB_POOL
-        .get_or_try_init(|| async {
-            let uri = get_db_uri();
-            let pool = SqlitePool::connect(&uri).await?;
-            Ok(pool)
-        })
+    #[error(transparent)]
+    Migrate(#[from] MigrationError),
+}
+
+const fn journal_mode(mode: JournalMode) -> SqliteJournalMode {
+    match mode {
+        JournalMode::Delete => SqliteJournalMode::Delete,
+        JournalMode::Truncate => SqliteJournalMode::Truncate,
+        JournalMode::Persist => SqliteJournalMode::Persist,
+        JournalMode::Memory => SqliteJournalMode::Memory,
+        JournalMode::Wal => SqliteJournalMode::Wal,
+        JournalMode::Off => SqliteJournalMode::Off,
+    }
+}
+
+const fn synchronous(mode: Synchronous) -> SqliteSynchronous {
+    match mode {
+        Synchronous::Off => SqliteSynchronous::Off,
+        Synchronous::Normal => SqliteSynchronous::Normal,
+        Synchronous::Full => SqliteSynchronous::Full,
+        Synchronous::Extra => SqliteSynchronous::Extra,
+    }
+}
+
+fn base_options(settings: &DatabaseSettings) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(settings.sqlite_path())
+        .busy_timeout(settings.busy_timeout)
+        .foreign_keys(settings.foreign_keys)
+}
+
+/// Connection options of the read-write pool, creating the file if needed.
+#[must_use]
+pub fn connect_options(settings: &DatabaseSettings) -> SqliteConnectOptions {
+    base_options(settings)
+        .create_if_missing(true)
+        .journal_mode(journal_mode(settings.journal_mode))
+        .synchronous(synchronous(settings.synchronous))
+}
+
+/// Opens a read-write pool without touching the schema.
+///
+/// # Errors
+/// Returns [`sqlx::Error`] if the database cannot be opened.
+pub async fn connect_db_pool(settings: &DatabaseSettings) -> Result<SqlitePool, sqlx::Error> {
+    SqlitePoolOptions::new()
+        .max_connections(settings.max_connections)
+        .connect_with(connect_options(settings))
         .await
-        .cloned()
+}
+
+/// Opens a read-write pool and brings the schema up to date.
+///
+/// # Errors
+/// Returns [`DBInitError`] if the database cannot be opened or migrated.
+pub async fn open_db_pool(settings: &DatabaseSettings) -> Result<SqlitePool, DBInitError> {
+    let db_path = settings.sqlite_path();
+    let pool = connect_db_pool(settings).await?;
+
+    let outcome = migrate(&pool, db_path).await?;
+
+    if !outcome.applied.is_empty() {
+        let backup = outcome.backup.map_or_else(
+            || "no backup, database was empty".to_string(),
+            |path| format!("backup at {}", path.display()),
+        );
+        info!(
+            "[db=\"{}\"] applied {} pending migrations ({backup})",
+            db_path.display(),
+            outcome.applied.len()
+        );
+    }
+
+    Ok(pool)
+}
+
+/// Opens a read-only pool for commands that only query. In WAL mode its readers
+/// neither wait for nor block a concurrent writer. Read-only connections cannot
+/// migrate, so the schema must be settled by [`open_db_pool`] first.
+///
+/// # Errors
+/// Returns [`sqlx::Error`] if the database cannot be opened.
+pub async fn open_db_read_pool(settings: &DatabaseSettings) -> Result<SqlitePool, sqlx::Error> {
+    SqlitePoolOptions::new()
+        .max_connections(settings.max_read_connections)
+        .connect_with(base_options(settings).read_only(true))
+        .await
+}
+
+/// Opens a private in-memory database with the current schema, independent of
+/// the configured one. Everything stored in it is lost once the pool is dropped.
+///
+/// # Errors
+/// Returns [`DBInitError`] if the database cannot be created or migrated.
+pub async fn connect_memory_pool() -> Result<SqlitePool, DBInitError> {
+    // Every connection to `sqlite::memory:` opens a database of its own, so the
+    // pool keeps exactly one for its whole lifetime.
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(SqliteConnectOptions::from_str("sqlite::memory:")?)
+        .await?;
+
+    MIGRATOR.run(&pool).await.map_err(MigrationError::Apply)?;
+
+    Ok(pool)
+}
+
+/// One `SQLite` database and its pools, opened on first use. Each value owns its
+/// pools, so a process can work with several databases side by side.
+pub struct SqliteDatabase {
+    settings: DatabaseSettings,
+    pool: OnceCell<SqlitePool>,
+    read_pool: OnceCell<SqlitePool>,
+}
+
+impl SqliteDatabase {
+    #[must_use]
+    pub fn new(settings: DatabaseSettings) -> Self {
+        Self {
+            settings,
+            pool: OnceCell::new(),
+            read_pool: OnceCell::new(),
+        }
+    }
+
+    /// Database behind an already open pool, e.g. one from [`connect_memory_pool`].
+    /// Reads go through the same pool.
+    #[must_use]
+    pub fn from_pool(pool: SqlitePool) -> Self {
+        Self {
+            settings: DatabaseSettings::default(),
+            pool: OnceCell::from(pool.clone()),
+            read_pool: OnceCell::from(pool),
+        }
+    }
+
+    /// Returns the read-write pool, bringing the schema up to date on first use.
+    ///
+    /// # Errors
+    /// Returns [`DBInitError`] if the database cannot be opened or migrated.
+    pub async fn pool(&self) -> Result<SqlitePool, DBInitError> {
+        self.pool
+            .get_or_try_init(|| open_db_pool(&self.settings))
+            .await
+            .cloned()
+    }
+
+    /// Returns the read-only pool, see [`open_db_read_pool`].
+    ///
+    /// # Errors
+    /// Returns [`DBInitError`] if the database cannot be opened or migrated.
+    pub async fn read_pool(&self) -> Result<SqlitePool, DBInitError> {
+        self.read_pool
+            .get_or_try_init(|| async {
+                self.pool().await?;
+                Ok(open_db_read_pool(&self.settings).await?)
+            })
+            .await
+            .cloned()
+    }
 }
@@ -0,0 +1,397 @@
+use libsqlite3_sys::{
+    SQLITE_BUSY, SQLITE_DONE, SQLITE_LOCKED, SQLITE_OK, sqlite3, sqlite3_backup_finish,
+    sqlite3_backup_init, sqlite3_backup_step, sqlite3_errmsg, sqlite3_errstr,
+};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{ConnectOptions, Connection, SqlitePool, query, query_as, query_scalar};
+use std::ffi::CStr;
+use std::path::Path;
+use std::ptr::NonNull;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::runtime::Handle;
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+const BACKUP_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+#[derive(Error, Debug)]
+pub enum MaintenanceError {
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("SQLite backup failed: {0}")]
+    Backup(String),
+}
+
+#[derive(Debug)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    pub rowid: Option<i64>,
+    pub parent: String,
+}
+
+#[derive(Debug)]
+pub struct Orphans {
+    pub description: &'static str,
+    pub count: i64,
+}
+
+#[derive(Debug)]
+pub struct CheckReport {
+    /// Problems reported by `PRAGMA integrity_check`, empty when it says `ok`.
+    pub integrity: Vec<String>,
+    pub foreign_keys: Vec<ForeignKeyViolation>,
+    pub orphans: Vec<Orphans>,
+}
+
+impl CheckReport {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.integrity.is_empty()
+            && self.foreign_keys.is_empty()
+            && self.orphans.iter().all(|orphans| orphans.count == 0)
+    }
+}
+
+#[derive(Debug)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: i64,
+    /// Bytes used by the table and its indexes.
+    pub bytes: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct SourceStats {
+    pub id: Uuid,
+    pub kind: String,
+    pub name: String,
+    pub articles: i64,
+    pub latest: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct DbStats {
+    pub bytes: i64,
+    pub tables: Vec<TableStats>,
+    pub sources: Vec<SourceStats>,
+}
+
+fn last_error(db: NonNull<sqlite3>, code: i32) -> String {
+    // SAFETY: `db` is a valid open connection; SQLite returns static or
+    // connection-owned NUL-terminated strings that are copied right away.
+    unsafe {
+        let message = match code {
+            SQLITE_OK => sqlite3_errmsg(db.as_ptr()),
+            _ => sqlite3_errstr(code),
+        };
+        CStr::from_ptr(message).to_string_lossy().into_owned()
+    }
+}
+
+fn run_backup(source: NonNull<sqlite3>, target: NonNull<sqlite3>) -> Result<(), MaintenanceError> {
+    // SAFETY: both connections are locked by the caller for the whole call and
+    // outlive the backup object, which is always finished before returning.
+    unsafe {
+        let backup = sqlite3_backup_init(
+            target.as_ptr(),
+            c"main".as_ptr(),
+            source.as_ptr(),
+            c"main".as_ptr(),
+        );
+
+        let Some(backup) = NonNull::new(backup) else {
+            return Err(MaintenanceError::Backup(last_error(target, SQLITE_OK)));
+        };
+
+        // Copying everything in one step keeps a single read transaction, which
+        // in WAL mode does not block writers.
+        let step = loop {
+            match sqlite3_backup_step(backup.as_ptr(), -1) {
+                SQLITE_BUSY | SQLITE_LOCKED => std::thread::sleep(BACKUP_RETRY_DELAY),
+                code => break code,
+            }
+        };
+
+        let finish = sqlite3_backup_finish(backup.as_ptr());
+
+        match (step, finish) {
+            (SQLITE_DONE, SQLITE_OK) => Ok(()),
+            (SQLITE_DONE, code) | (code, _) => {
+                Err(MaintenanceError::Backup(last_error(target, code)))
+            }
+        }
+    }
+}
+
+/// Copies the live database into `destination` with SQLite's online backup
+/// API. Safe to run while other processes keep writing, and does not hold up
+/// the tasks of the runtime while it copies.
+///
+/// # Errors
+/// Returns [`MaintenanceError`] if either database cannot be opened or the copy fails.
+pub async fn backup(pool: &SqlitePool, destination: &Path) -> Result<(), MaintenanceError> {
+    let mut source = pool.acquire().await?;
+    let mut target = SqliteConnectOptions::new()
+        .filename(destination)
+        .create_if_missing(true)
+        .connect()
+        .await?;
+
+    // The copy blocks on file I/O and sleeps while writers hold locks, so it
+    // runs on a blocking thread that takes both connections along.
+    let runtime = Handle::current();
+    let (result, source, target) = spawn_blocking(move || {
+        let result = runtime.block_on(async {
+            let mut source_handle = source.lock_handle().await?;
+            let mut target_handle = target.lock_handle().await?;
+            run_backup(source_handle.as_raw_handle(), target_handle.as_raw_handle())
+        });
+        (result, source, target)
+    })
+    .await
+    .map_err(|e| MaintenanceError::Backup(e.to_string()))?;
+
+    // Returned to the pool from the runtime rather than the blocking thread.
+    drop(source);
+    result?;
+
+    target.close().await?;
+    Ok(())
+}
+
+async fn size_in_bytes(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    let page_count: i64 = query_scalar("PRAGMA page_count").fetch_one(pool).await?;
+    let page_size: i64 = query_scalar("PRAGMA page_size").fetch_one(pool).await?;
+    Ok(page_count * page_size)
+}
+
+/// Rebuilds the database file to reclaim free pages and truncates the WAL.
+/// Returns the size in bytes before and after.
+///
+/// # Errors
+/// Returns [`MaintenanceError`] if `VACUUM` fails, e.g. while another process writes.
+pub async fn vacuum(pool: &SqlitePool) -> Result<(i64, i64), MaintenanceError> {
+    let before = size_in_bytes(pool).await?;
+
+    query("VACUUM").execute(pool).await?;
+    query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await?;
+
+    let after = size_in_bytes(pool).await?;
+    Ok((before, after))
+}
+
+/// Deletes the webhooks, notification routes and rules scoped to the source
+/// `source_id` once it is removed. They have no foreign key to `sources`, which
+/// may be kept in Postgres instead. Returns how many were deleted.
+///
+/// # Errors
+/// Returns [`MaintenanceError`] if a delete fails; then none of them is deleted.
+pub async fn forget_source(pool: &SqlitePool, source_id: Uuid) -> Result<u64, MaintenanceError> {
+    let mut tx = pool.begin().await?;
+
+    let webhooks = query!("DELETE FROM webhooks WHERE source_id = $1", source_id)
+        .execute(&mut *tx)
+        .await?;
+    let routes = query!(
+        "DELETE FROM notification_routes WHERE source_id = $1",
+        source_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    let rules = query!("DELETE FROM rules WHERE source_id = $1", source_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(webhooks.rows_affected() + routes.rows_affected() + rules.rows_affected())
+}
+
+async fn count_orphans(pool: &SqlitePool) -> Result<Vec<Orphans>, sqlx::Error> {
+    let mappings = query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM uuid_mappings m
+        WHERE NOT EXISTS (SELECT 1 FROM sources s WHERE s.id = m.internal_id)
+          AND NOT EXISTS (SELECT 1 FROM articles a WHERE a.id = m.internal_id)
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let sources = query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM sources s
+        WHERE NOT EXISTS (SELECT 1 FROM uuid_mappings m WHERE m.internal_id = s.id)
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let details = query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM sources s
+        WHERE (s.source = 'atom' AND NOT EXISTS (
+                SELECT 1 FROM source_atom_details d WHERE d.atom_details_id = s.id))
+           OR (s.source = 'telegram' AND NOT EXISTS (
+                SELECT 1 FROM source_telegram_details d WHERE d.telegram_details_id = s.id))
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let articles = query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM articles a
+        WHERE NOT EXISTS (SELECT 1 FROM uuid_mappings m WHERE m.internal_id = a.id)
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let parentless = query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM articles a
+        WHERE NOT EXISTS (SELECT 1 FROM sources s WHERE s.id = a.parent_id)
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(vec![
+        Orphans {
+            description: "uuid_mappings without a source or article",
+            count: mappings,
+        },
+        Orphans {
+            description: "sources without uuid_mappings",
+            count: sources,
+        },
+        Orphans {
+            description: "sources without Atom or Telegram details",
+            count: details,
+        },
+        Orphans {
+            description: "articles without uuid_mappings",
+            count: articles,
+        },
+        Orphans {
+            description: "articles without a source",
+            count: parentless,
+        },
+    ])
+}
+
+/// Runs `integrity_check`, `foreign_key_check` and looks for rows that lost
+/// their identity mapping or parent.
+///
+/// # Errors
+/// Returns [`MaintenanceError`] if a check cannot be executed.
+pub async fn check(pool: &SqlitePool) -> Result<CheckReport, MaintenanceError> {
+    let integrity: Vec<String> = query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .filter(|message: &String| message != "ok")
+        .collect();
+
+    let foreign_keys =
+        query_as::<_, (String, Option<i64>, String, i64)>("PRAGMA foreign_key_check")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|(table, rowid, parent, _)| ForeignKeyViolation {
+                table,
+                rowid,
+                parent,
+            })
+            .collect();
+
+    Ok(CheckReport {
+        integrity,
+        foreign_keys,
+        orphans: count_orphans(pool).await?,
+    })
+}
+
+async fn table_stats(pool: &SqlitePool) -> Result<Vec<TableStats>, sqlx::Error> {
+    let names: Vec<String> = query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // `dbstat` is optional in SQLite builds; sizes are simply omitted without it.
+    let sizes: Vec<(String, i64)> = query_as(
+        r"
+        SELECT m.tbl_name, SUM(d.pgsize)
+        FROM dbstat d
+        JOIN sqlite_master m ON m.name = d.name
+        GROUP BY m.tbl_name
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    let mut tables = Vec::with_capacity(names.len());
+
+    for name in names {
+        let rows: i64 = query_scalar(&format!(
+            "SELECT COUNT(*) FROM \"{}\"",
+            name.replace('"', "\"\"")
+        ))
+        .fetch_one(pool)
+        .await?;
+
+        let bytes = sizes
+            .iter()
+            .find(|(table, _)| *table == name)
+            .map(|(_, bytes)| *bytes);
+
+        tables.push(TableStats { name, rows, bytes });
+    }
+
+    Ok(tables)
+}
+
+/// Collects the database size, rows and bytes per table and articles per source.
+///
+/// # Errors
+/// Returns [`MaintenanceError`] if a statistics query fails.
+pub async fn stats(pool: &SqlitePool) -> Result<DbStats, MaintenanceError> {
+    let sources = query_as!(
+        SourceStats,
+        r#"
+        SELECT
+            s.id as "id!: Uuid",
+            s.source as "kind!: String",
+            COALESCE(a.url, t.username, '') as "name!: String",
+            COUNT(ar.id) as "articles!: i64",
+            MAX(ar.created_at) as "latest: DateTime<Utc>"
+        FROM sources s
+        LEFT JOIN source_atom_details a ON a.atom_details_id = s.id
+        LEFT JOIN source_telegram_details t ON t.telegram_details_id = s.id
+        LEFT JOIN articles ar ON ar.parent_id = s.id
+        GROUP BY s.id
+        ORDER BY COUNT(ar.id) DESC, s.created_at
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(DbStats {
+        bytes: size_in_bytes(pool).await?,
+        tables: table_stats(pool).await?,
+        sources,
+    })
+}
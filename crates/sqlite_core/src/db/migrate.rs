@@ -0,0 +1,203 @@
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{SqlitePool, query, query_as, query_scalar};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::fs::{remove_file, try_exists};
+
+/// Migrations embedded into the binary from the workspace `migrations/` folder.
+pub static MIGRATOR: Migrator = sqlx::migrate!("../../migrations");
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error(
+        "Database schema version {0} is newer than this build of uninews supports, upgrade uninews to use it"
+    )]
+    NewerDatabase(i64),
+
+    #[error("Failed to read applied migrations")]
+    Status(#[source] sqlx::Error),
+
+    #[error("Failed to back up the database to {0}")]
+    Backup(PathBuf, #[source] sqlx::Error),
+
+    #[error("Failed to apply database migrations")]
+    Apply(#[source] MigrateError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStatus {
+    Applied(DateTime<Utc>),
+    Pending,
+    /// Started but did not finish; `sqlx` refuses to continue until it is fixed by hand.
+    Failed(DateTime<Utc>),
+    /// Applied by a newer build that knows migrations this one does not.
+    Unknown(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationState {
+    pub version: i64,
+    pub description: String,
+    pub status: MigrationStatus,
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationOutcome {
+    pub applied: Vec<i64>,
+    /// Copy of the database taken before migrating, `None` for a fresh database.
+    pub backup: Option<PathBuf>,
+}
+
+struct AppliedRow {
+    version: i64,
+    description: String,
+    installed_on: DateTime<Utc>,
+    success: bool,
+}
+
+async fn applied_migrations(pool: &SqlitePool) -> Result<Vec<AppliedRow>, MigrationError> {
+    let has_table: bool = query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(MigrationError::Status)?;
+
+    if !has_table {
+        return Ok(Vec::new());
+    }
+
+    // `_sqlx_migrations` is created at runtime, so it is not known to the offline query cache.
+    let rows: Vec<(i64, String, DateTime<Utc>, bool)> = query_as(
+        "SELECT version, description, installed_on, success FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(MigrationError::Status)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(version, description, installed_on, success)| AppliedRow {
+            version,
+            description,
+            installed_on,
+            success,
+        })
+        .collect())
+}
+
+/// Lists every migration known to the binary or recorded in the database.
+///
+/// # Errors
+/// Returns [`MigrationError::Status`] if the migrations table cannot be read.
+pub async fn migration_states(pool: &SqlitePool) -> Result<Vec<MigrationState>, MigrationError> {
+    let mut states: BTreeMap<i64, MigrationState> = MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| {
+            let state = MigrationState {
+                version: migration.version,
+                description: migration.description.to_string(),
+                status: MigrationStatus::Pending,
+            };
+            (migration.version, state)
+        })
+        .collect();
+
+    for row in applied_migrations(pool).await? {
+        let status = match (row.success, states.contains_key(&row.version)) {
+            (_, false) => MigrationStatus::Unknown(row.installed_on),
+            (false, true) => MigrationStatus::Failed(row.installed_on),
+            (true, true) => MigrationStatus::Applied(row.installed_on),
+        };
+
+        states.insert(
+            row.version,
+            MigrationState {
+                version: row.version,
+                description: row.description,
+                status,
+            },
+        );
+    }
+
+    Ok(states.into_values().collect())
+}
+
+async fn backup(
+    pool: &SqlitePool,
+    db_path: &Path,
+    version: i64,
+) -> Result<PathBuf, MigrationError> {
+    let mut file_name = db_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".v{version}.bak"));
+    let backup_path = db_path.with_file_name(file_name);
+
+    let backup_error = |e| MigrationError::Backup(backup_path.clone(), e);
+
+    // `VACUUM INTO` refuses to overwrite, and a leftover copy of the same version is stale.
+    if try_exists(&backup_path).await.unwrap_or(false) {
+        remove_file(&backup_path)
+            .await
+            .map_err(|e| backup_error(sqlx::Error::Io(e)))?;
+    }
+
+    query("VACUUM INTO $1")
+        .bind(backup_path.display().to_string())
+        .execute(pool)
+        .await
+        .map_err(backup_error)?;
+
+    Ok(backup_path)
+}
+
+/// Applies pending migrations, backing the database up next to `db_path`
+/// first unless it is empty.
+///
+/// # Errors
+/// Returns [`MigrationError::NewerDatabase`] without touching the database
+/// when it was migrated by a newer build, or another [`MigrationError`] if
+/// the backup or a migration fails.
+pub async fn migrate(
+    pool: &SqlitePool,
+    db_path: &Path,
+) -> Result<MigrationOutcome, MigrationError> {
+    let states = migration_states(pool).await?;
+
+    if let Some(state) = states
+        .iter()
+        .rfind(|state| matches!(state.status, MigrationStatus::Unknown(_)))
+    {
+        return Err(MigrationError::NewerDatabase(state.version));
+    }
+
+    let pending: Vec<i64> = states
+        .iter()
+        .filter(|state| state.status == MigrationStatus::Pending)
+        .map(|state| state.version)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(MigrationOutcome::default());
+    }
+
+    let current = states
+        .iter()
+        .filter(|state| matches!(state.status, MigrationStatus::Applied(_)))
+        .map(|state| state.version)
+        .max();
+
+    let backup = match current {
+        Some(version) => Some(backup(pool, db_path, version).await?),
+        None => None,
+    };
+
+    MIGRATOR.run(pool).await.map_err(MigrationError::Apply)?;
+
+    Ok(MigrationOutcome {
+        applied: pending,
+        backup,
+    })
+}
@@ -1,4 +1,10 @@
+pub mod digest;
 pub mod http;
+pub mod media;
 pub mod news;
+pub mod notifier;
+pub mod retention;
+pub mod rule;
 pub mod source;
 pub mod uuid;
+pub mod webhook;
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use news_core::services::digest::{DigestService, WatermarkError};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{SqlitePool, query};
+
+pub struct SqliteDigestService {
+    db_pool: SqlitePool,
+}
+
+impl SqliteDigestService {
+    /// Service over `db_pool`, whose schema must be up to date.
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl DigestService for SqliteDigestService {
+    async fn get_watermark(
+        &self,
+        recipient: &str,
+    ) -> Result<Option<DateTime<Utc>>, WatermarkError> {
+        let result = query!(
+            r#"
+            SELECT
+                sent_until as "sent_until: DateTime<Utc>"
+            FROM digest_watermarks
+            WHERE recipient = $1
+            "#,
+            recipient,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|error| WatermarkError(Box::new(error)))?;
+
+        Ok(result.map(|record| record.sent_until))
+    }
+
+    async fn set_watermark(
+        &self,
+        recipient: &str,
+        sent_until: DateTime<Utc>,
+    ) -> Result<(), WatermarkError> {
+        query!(
+            r#"
+            INSERT INTO digest_watermarks (recipient, sent_until)
+            VALUES ($1, strftime('%Y-%m-%dT%H:%M:%fZ', $2))
+            ON CONFLICT(recipient) DO UPDATE SET
+                sent_until = excluded.sent_until,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            "#,
+            recipient,
+            sent_until,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| WatermarkError(Box::new(error)))?;
+
+        Ok(())
+    }
+}
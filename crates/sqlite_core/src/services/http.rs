@@ -1,90 +1,695 @@
+pub mod fixture;
+mod politeness;
+pub mod robots;
+
+use crate::utils::secrets::{Secrets, SecretsError};
 use async_trait::async_trait;
-use news_core::errors::ExternalServiceError;
-use news_core::services::{HttpService, HttpUpdateHandle, WatchError};
-use reqwest::Client;
+use news_core::config::{HttpSettings, SourceHttpSettings, WatchSettings, names_source};
+use news_core::errors::{ExternalServiceError, Internal};
+use news_core::models::source::request::RequestSettings;
+use news_core::services::{FetchError, HttpResponse, HttpService, HttpUpdateHandle, WatchError};
+use politeness::Hosts;
+use reqwest::cookie::Jar;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Client, Proxy, RequestBuilder, Response, StatusCode};
+use robots::Robots;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
-use tracing::error;
+use thiserror::Error;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::time::{sleep, sleep_until};
+use tracing::{debug, error, info};
+use url::{Origin, Url};
+
+#[derive(Error, Debug)]
+pub enum HttpClientError {
+    #[error("Failed to read CA certificates {path}: {source}")]
+    ReadCertificates {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid CA certificates in {path}: {source}")]
+    ParseCertificates {
+        path: PathBuf,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Invalid proxy {url}: {source}")]
+    Proxy {
+        url: Url,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Failed to build HTTP client: {0}")]
+    Build(#[source] reqwest::Error),
+
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+
+    #[error(
+        "Secret {name} of source {source_key} is neither an environment variable nor in the secrets file"
+    )]
+    UnknownSecret { source_key: String, name: String },
+
+    #[error("Invalid request settings of source {source_key}: {reason}")]
+    Request { source_key: String, reason: String },
+}
 
 pub struct LiveHttpService {
     client: Client,
+    /// Clients of sources with a proxy of their own, by the `source` of their
+    /// entry in `http.sources`.
+    source_clients: HashMap<String, Client>,
+    hosts: Hosts,
+    secrets: Secrets,
+    certificates: Vec<Certificate>,
+    http: HttpSettings,
+    max_body_size: u64,
+    max_redirects: usize,
+    poll_interval: Duration,
+    max_backoff: Duration,
+}
+
+fn http_error(message: String) -> ExternalServiceError {
+    ExternalServiceError {
+        service: "http".to_string(),
+        message,
+    }
+}
+
+fn load_certificates(paths: &[PathBuf]) -> Result<Vec<Certificate>, HttpClientError> {
+    let mut certificates = Vec::new();
+
+    for path in paths {
+        let pem = std::fs::read(path).map_err(|source| HttpClientError::ReadCertificates {
+            path: path.clone(),
+            source,
+        })?;
+        let bundle = Certificate::from_pem_bundle(&pem).map_err(|source| {
+            HttpClientError::ParseCertificates {
+                path: path.clone(),
+                source,
+            }
+        })?;
+        certificates.extend(bundle);
+    }
+
+    Ok(certificates)
+}
+
+/// Request settings of a source with its secrets resolved. Headers and
+/// credentials only go to the origin of the source, not to redirect targets
+/// elsewhere; cookies follow the rules of the client's jar.
+struct SourceRequest {
+    origin: Origin,
+    headers: HeaderMap,
+    basic_auth: Option<(String, Option<String>)>,
+    bearer_token: Option<String>,
+    /// Client with a cookie jar of its own, for sources with cookies.
+    client: Option<Client>,
+}
+
+impl SourceRequest {
+    fn apply(&self, url: &Url, builder: RequestBuilder) -> RequestBuilder {
+        if url.origin() != self.origin {
+            return builder;
+        }
+
+        let builder = builder.headers(self.headers.clone());
+        let builder = match &self.basic_auth {
+            Some((username, password)) => builder.basic_auth(username, password.as_ref()),
+            None => builder,
+        };
+        match &self.bearer_token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+}
+
+fn build_client(
+    http: &HttpSettings,
+    certificates: &[Certificate],
+    proxy: Option<&Url>,
+    cookies: Option<Arc<Jar>>,
+) -> Result<Client, HttpClientError> {
+    let mut builder = Client::builder()
+        .user_agent(&http.user_agent)
+        .timeout(http.timeout)
+        .connect_timeout(http.connect_timeout)
+        .read_timeout(http.read_timeout)
+        .gzip(http.gzip)
+        .brotli(http.brotli)
+        // `fetch` follows redirects itself to notice permanent moves.
+        .redirect(Policy::none());
+
+    for certificate in certificates {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+
+    if let Some(jar) = cookies {
+        builder = builder.cookie_provider(jar);
+    }
+
+    if let Some(url) = proxy {
+        let proxy = Proxy::all(url.as_str()).map_err(|source| HttpClientError::Proxy {
+            url: url.clone(),
+            source,
+        })?;
+        builder = builder.proxy(proxy);
+    }
+
+    builder.build().map_err(HttpClientError::Build)
+}
+
+/// A response whose body is not read yet.
+struct Sent {
+    response: Response,
+    /// Address of the response, after redirects.
+    url: Url,
+    /// Set to `url` when every redirect on the way was permanent.
+    moved_to: Option<Url>,
+}
+
+/// Next chunk of the body of `response` from `url`, failing once the body
+/// would take more than `max_size` bytes with the `read` ones before it.
+async fn next_chunk(
+    response: &mut Response,
+    url: &Url,
+    max_size: u64,
+    read: usize,
+) -> Result<Option<impl Deref<Target = [u8]> + use<>>, ExternalServiceError> {
+    let too_large = || http_error(format!("Response of {url} is larger than {max_size} bytes"));
+
+    if read == 0
+        && response
+            .content_length()
+            .is_some_and(|length| length > max_size)
+    {
+        return Err(too_large());
+    }
+
+    let chunk = response
+        .chunk()
+        .await
+        .map_err(|e| http_error(format!("Failed to read HTTP response body: {e}")))?;
+
+    match chunk {
+        Some(chunk) if u64::try_from(read + chunk.len()).unwrap_or(u64::MAX) > max_size => {
+            Err(too_large())
+        }
+        chunk => Ok(chunk),
+    }
 }
 
 impl LiveHttpService {
-    async fn get_and_update(
+    /// Builds the clients described by `http`, one per source with its own proxy.
+    ///
+    /// # Errors
+    /// Returns [`HttpClientError`] if a CA bundle cannot be read or a proxy is invalid.
+    pub fn try_new(http: &HttpSettings, watch: &WatchSettings) -> Result<Self, HttpClientError> {
+        let certificates = load_certificates(&http.ca_certificates)?;
+        let client = build_client(http, &certificates, http.proxy.as_ref(), None)?;
+
+        let source_clients = http
+            .sources
+            .iter()
+            .filter_map(|source| Some((source.source.clone(), source.proxy.as_ref()?)))
+            .map(|(key, proxy)| Ok((key, build_client(http, &certificates, Some(proxy), None)?)))
+            .collect::<Result<_, HttpClientError>>()?;
+
+        Ok(Self {
+            client,
+            source_clients,
+            hosts: Hosts::new(http.robots_ttl),
+            secrets: Secrets::load(http.secrets_file.as_deref())?,
+            certificates,
+            http: http.clone(),
+            max_body_size: http.max_body_size,
+            max_redirects: http.max_redirects,
+            poll_interval: watch.poll_interval,
+            max_backoff: watch.max_backoff,
+        })
+    }
+
+    /// Resolves the secrets of `request` and builds a client with a cookie jar
+    /// if the source has cookies.
+    fn source_request(
         &self,
-        update_handler: &Arc<dyn HttpUpdateHandle>,
-    ) -> Result<(), ExternalServiceError> {
-        let url_str = update_handler.url().as_str();
+        source_key: &str,
+        url: &Url,
+        request: &RequestSettings,
+    ) -> Result<SourceRequest, HttpClientError> {
+        let resolve = |value: &str| {
+            self.secrets
+                .resolve(value)
+                .map_err(|name| HttpClientError::UnknownSecret {
+                    source_key: source_key.to_string(),
+                    name,
+                })
+        };
+        let invalid = |reason: String| HttpClientError::Request {
+            source_key: source_key.to_string(),
+            reason,
+        };
 
-        let response = self
-            .client
-            .get(url_str)
-            .send()
-            .await
-            .map_err(|e| ExternalServiceError {
-                service: "http".to_string(),
-                message: format!("Failed to send HTTP request: {e}"),
-            })?;
-
-        if !response.status().is_success() {
-            return Err(ExternalServiceError {
-                service: "http".to_string(),
-                message: format!(
-                    "HTTP request failed with status[{}] for {url_str}",
-                    response.status(),
-                ),
+        let mut headers = HeaderMap::new();
+        for header in &request.headers {
+            let name = HeaderName::from_bytes(header.name.as_bytes())
+                .map_err(|e| invalid(format!("header {}: {e}", header.name)))?;
+            let mut value = HeaderValue::from_str(&resolve(&header.value)?)
+                .map_err(|e| invalid(format!("header {}: {e}", header.name)))?;
+            value.set_sensitive(true);
+            headers.append(name, value);
+        }
+
+        let basic_auth = match &request.basic_auth {
+            Some(auth) => Some((
+                resolve(&auth.username)?,
+                auth.password.as_deref().map(resolve).transpose()?,
+            )),
+            None => None,
+        };
+        let bearer_token = request.bearer_token.as_deref().map(resolve).transpose()?;
+
+        let client = match request.cookies.is_empty() {
+            true => None,
+            false => {
+                let jar = Jar::default();
+                for cookie in &request.cookies {
+                    jar.add_cookie_str(&resolve(cookie)?, url);
+                }
+                let proxy = self
+                    .http
+                    .sources
+                    .iter()
+                    .find(|source| source.source == source_key)
+                    .and_then(|source| source.proxy.as_ref())
+                    .or(self.http.proxy.as_ref());
+                Some(build_client(
+                    &self.http,
+                    &self.certificates,
+                    proxy,
+                    Some(Arc::new(jar)),
+                )?)
+            }
+        };
+
+        Ok(SourceRequest {
+            origin: url.origin(),
+            headers,
+            basic_auth,
+            bearer_token,
+            client,
+        })
+    }
+
+    /// Requests `url`, following up to `max_redirects` redirects, and returns
+    /// the final response, whatever its status, before reading its body.
+    async fn send(
+        &self,
+        client: &Client,
+        url: &Url,
+        request: Option<&SourceRequest>,
+    ) -> Result<Sent, ExternalServiceError> {
+        let mut url = url.clone();
+        let mut redirects = 0;
+        let mut permanent = true;
+
+        let response = loop {
+            let builder = client.get(url.clone());
+            let builder = match request {
+                Some(request) => request.apply(&url, builder),
+                None => builder,
+            };
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| http_error(format!("Failed to send HTTP request: {e}")))?;
+
+            let location = match response.status().is_redirection() {
+                true => response.headers().get(LOCATION),
+                false => None,
+            };
+            let Some(location) = location else {
+                break response;
+            };
+
+            if redirects == self.max_redirects {
+                return Err(http_error(format!("Too many redirects, gave up at {url}")));
+            }
+
+            let location = location
+                .to_str()
+                .ok()
+                .and_then(|location| url.join(location).ok())
+                .ok_or_else(|| http_error(format!("Invalid redirect location from {url}")))?;
+
+            redirects += 1;
+            permanent &= matches!(
+                response.status(),
+                StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+            );
+            url = location;
+        };
+
+        Ok(Sent {
+            response,
+            moved_to: (permanent && redirects > 0).then(|| url.clone()),
+            url,
+        })
+    }
+
+    /// Reads the body of `sent` into memory, up to `max_body_size` bytes.
+    async fn read_body(&self, sent: Sent) -> Result<HttpResponse, ExternalServiceError> {
+        let Sent {
+            mut response,
+            url,
+            moved_to,
+        } = sent;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let mut body = Vec::new();
+
+        while let Some(chunk) =
+            next_chunk(&mut response, &url, self.max_body_size, body.len()).await?
+        {
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            url,
+            moved_to,
+            body,
+        })
+    }
+
+    /// Requests `url` like [`Self::send`] and reads the body of the final
+    /// response, whatever its status.
+    async fn fetch(
+        &self,
+        client: &Client,
+        url: &Url,
+        request: Option<&SourceRequest>,
+    ) -> Result<HttpResponse, ExternalServiceError> {
+        let sent = self.send(client, url, request).await?;
+        self.read_body(sent).await
+    }
+
+    /// Rules of the host of `url`, fetched once per `robots_ttl`. A missing
+    /// file allows everything; an unreachable one disallows everything until
+    /// the next poll tries again.
+    async fn robots(&self, client: &Client, url: &Url) -> Robots {
+        if let Some(robots) = self.hosts.robots(url) {
+            return robots;
+        }
+        let Ok(robots_url) = url.join("/robots.txt") else {
+            return Robots::allow_all();
+        };
+
+        let robots = match self.fetch(client, &robots_url, None).await {
+            Ok(response) if response.status.is_success() => {
+                Robots::parse(&response.text(), &self.http.user_agent)
+            }
+            Ok(response) if response.status.is_client_error() => Robots::allow_all(),
+            Ok(response) => {
+                debug!("[http_service=\"{robots_url}\"] status {}", response.status);
+                return Robots::disallow_all();
+            }
+            Err(e) => {
+                debug!("[http_service=\"{robots_url}\"] {e}");
+                return Robots::disallow_all();
+            }
+        };
+
+        self.hosts.set_robots(url, robots.clone());
+        robots
+    }
+
+    /// Requests `url` once its host allows it and fails unless the response
+    /// is successful; the body is left to read.
+    async fn send_politely(
+        &self,
+        client: &Client,
+        url: &Url,
+        request: Option<&SourceRequest>,
+        respect_robots: bool,
+    ) -> Result<Sent, ExternalServiceError> {
+        let robots = match respect_robots {
+            true => self.robots(client, url).await,
+            false => Robots::allow_all(),
+        };
+        if !robots.is_allowed(url) {
+            return Err(http_error(format!("{url} is disallowed by robots.txt")));
+        }
+
+        sleep_until(self.hosts.reserve(url, robots.crawl_delay())).await;
+
+        let sent = self.send(client, url, request).await?;
+        let status = sent.response.status();
+        let pause = self
+            .hosts
+            .note_response(&sent.url, status, sent.response.headers());
+
+        if !status.is_success() {
+            let retry = pause.map_or_else(String::new, |pause| {
+                format!(", retrying after {}s", pause.as_secs())
             });
+            return Err(http_error(format!(
+                "HTTP request failed with status[{status}] for {}{retry}",
+                sent.url,
+            )));
+        }
+
+        Ok(sent)
+    }
+
+    /// Fetches `url` once its host allows it and fails unless the response is
+    /// successful.
+    async fn get_politely(
+        &self,
+        client: &Client,
+        url: &Url,
+        request: Option<&SourceRequest>,
+        respect_robots: bool,
+    ) -> Result<HttpResponse, ExternalServiceError> {
+        let sent = self
+            .send_politely(client, url, request, respect_robots)
+            .await?;
+        self.read_body(sent).await
+    }
+
+    /// Fetches `url` once its host allows it into the file `path`, up to
+    /// `max_size` bytes, and returns the size of the body.
+    async fn download_politely(
+        &self,
+        client: &Client,
+        url: &Url,
+        request: Option<&SourceRequest>,
+        respect_robots: bool,
+        path: &Path,
+        max_size: u64,
+    ) -> Result<u64, ExternalServiceError> {
+        let Sent {
+            mut response, url, ..
+        } = self
+            .send_politely(client, url, request, respect_robots)
+            .await?;
+
+        let write_error =
+            |e: std::io::Error| http_error(format!("Failed to write {}: {e}", path.display()));
+        let mut file = File::create(path).await.map_err(write_error)?;
+        let mut size = 0;
+
+        while let Some(chunk) = next_chunk(&mut response, &url, max_size, size).await? {
+            file.write_all(&chunk).await.map_err(write_error)?;
+            size += chunk.len();
         }
+        file.flush().await.map_err(write_error)?;
+
+        Ok(u64::try_from(size).unwrap_or(u64::MAX))
+    }
+
+    /// Polls `url` once, when its host allows it, and hands the response
+    /// over; returns where the source moved if it answered with a permanent
+    /// redirect.
+    async fn get_and_update(
+        &self,
+        client: &Client,
+        url: &Url,
+        request: Option<&SourceRequest>,
+        respect_robots: bool,
+        update_handler: &Arc<dyn HttpUpdateHandle>,
+    ) -> Result<Option<Url>, ExternalServiceError> {
+        let response = self
+            .get_politely(client, url, request, respect_robots)
+            .await?;
+        let moved_to = response.moved_to.clone();
 
         update_handler
             .handle(response)
             .await
-            .map_err(|e| ExternalServiceError {
-                service: "http".to_string(),
-                message: format!("Failed to handle response: {e}"),
-            })?;
+            .map_err(|e| http_error(format!("Failed to handle response: {e}")))?;
 
-        Ok(())
+        Ok(moved_to)
     }
-}
 
-impl LiveHttpService {
-    #[must_use]
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+    /// Entries of `http.sources` naming the source polled by `handler`, by its
+    /// key or its id.
+    fn source_settings<'a>(
+        &'a self,
+        handler: &'a dyn HttpUpdateHandle,
+    ) -> impl Iterator<Item = &'a SourceHttpSettings> {
+        let key = handler.source_key();
+        self.http.sources.iter().filter(move |settings| {
+            handler.source_id().map_or(settings.source == key, |id| {
+                names_source(&settings.source, id, key)
+            })
+        })
+    }
+
+    /// Whether the source polled by `handler` is polled regardless of
+    /// `robots.txt`.
+    fn respects_robots(&self, handler: &dyn HttpUpdateHandle) -> bool {
+        !self
+            .source_settings(handler)
+            .any(|settings| settings.ignore_robots)
+    }
+
+    /// Request settings of the source polled by `handler`, with the client
+    /// its requests go through.
+    fn source_client(
+        &self,
+        handler: &dyn HttpUpdateHandle,
+    ) -> Result<(Client, Option<SourceRequest>), HttpClientError> {
+        let source_key = handler.source_key();
+        let request = handler
+            .request()
+            .map(|request| self.source_request(source_key, handler.url(), request))
+            .transpose()?;
+        let client = request
+            .as_ref()
+            .and_then(|request| request.client.as_ref())
+            .or_else(|| {
+                self.source_settings(handler)
+                    .find_map(|settings| self.source_clients.get(&settings.source))
+            })
+            .unwrap_or(&self.client)
+            .clone();
+
+        Ok((client, request))
     }
 }
 
 impl Default for LiveHttpService {
     fn default() -> Self {
-        Self::new()
+        let http = HttpSettings::default();
+        let watch = WatchSettings::default();
+
+        Self {
+            client: build_client(&http, &[], None, None).unwrap_or_else(|_| Client::new()),
+            source_clients: HashMap::new(),
+            hosts: Hosts::new(http.robots_ttl),
+            secrets: Secrets::default(),
+            certificates: Vec::new(),
+            max_body_size: http.max_body_size,
+            max_redirects: http.max_redirects,
+            poll_interval: watch.poll_interval,
+            max_backoff: watch.max_backoff,
+            http,
+        }
     }
 }
 
-const POLL_INTERVAL_SECS: usize = 60;
-const MAX_BACKOFF_SECS: usize = 10 * POLL_INTERVAL_SECS;
-
 #[async_trait]
 impl HttpService for LiveHttpService {
     async fn watch_changes(
         &self,
         update_handler: Arc<dyn HttpUpdateHandle>,
     ) -> Result<(), WatchError> {
-        let mut backoff_secs = POLL_INTERVAL_SECS;
+        let (client, request) = self
+            .source_client(update_handler.as_ref())
+            .map_err(|e| WatchError::from(Box::new(e) as Internal))?;
+        let respect_robots = self.respects_robots(update_handler.as_ref());
+        let mut url = update_handler.url().clone();
+        let mut backoff = self.poll_interval;
+
         loop {
-            backoff_secs = match self.get_and_update(&update_handler).await {
-                Ok(()) => POLL_INTERVAL_SECS,
+            backoff = match self
+                .get_and_update(
+                    &client,
+                    &url,
+                    request.as_ref(),
+                    respect_robots,
+                    &update_handler,
+                )
+                .await
+            {
+                Ok(moved_to) => {
+                    if let Some(moved_to) = moved_to {
+                        info!("[http_service=\"{url}\"] moved permanently to {moved_to}");
+                        url = moved_to;
+                    }
+                    self.poll_interval
+                }
                 Err(e) => {
-                    error!("[http_service=\"{0}\"] {e}", update_handler.url());
-                    (backoff_secs * 2).min(MAX_BACKOFF_SECS)
+                    error!("[http_service=\"{url}\"] {e}");
+                    backoff.saturating_mul(2).min(self.max_backoff)
                 }
             };
 
-            sleep(Duration::from_secs(backoff_secs as u64)).await;
+            sleep(backoff).await;
         }
     }
+
+    async fn get(
+        &self,
+        source: &dyn HttpUpdateHandle,
+        url: &Url,
+    ) -> Result<HttpResponse, FetchError> {
+        let (client, request) = self
+            .source_client(source)
+            .map_err(|e| FetchError::from(Box::new(e) as Internal))?;
+        let respect_robots = self.respects_robots(source);
+
+        self.get_politely(&client, url, request.as_ref(), respect_robots)
+            .await
+            .map_err(|e| FetchError::from(Box::new(e) as Internal))
+    }
+
+    async fn download(
+        &self,
+        source: &dyn HttpUpdateHandle,
+        url: &Url,
+        path: &Path,
+        max_size: u64,
+    ) -> Result<u64, FetchError> {
+        let (client, request) = self
+            .source_client(source)
+            .map_err(|e| FetchError::from(Box::new(e) as Internal))?;
+        let respect_robots = self.respects_robots(source);
+
+        self.download_politely(
+            &client,
+            url,
+            request.as_ref(),
+            respect_robots,
+            path,
+            max_size,
+        )
+        .await
+        .map_err(|e| FetchError::from(Box::new(e) as Internal))
+    }
 }
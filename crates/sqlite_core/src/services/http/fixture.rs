@@ -0,0 +1,268 @@
+use async_trait::async_trait;
+use http::header::{HeaderName, HeaderValue, LOCATION};
+use http::{HeaderMap, StatusCode};
+use news_core::errors::Internal;
+use news_core::services::{
+    FetchError, HandleError, HttpResponse, HttpService, HttpUpdateHandle, WatchError,
+};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum FixtureError {
+    #[error("Failed to read fixture {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Fixture {path} is not a recorded HTTP response: {reason}")]
+    Malformed { path: PathBuf, reason: String },
+
+    #[error("No response recorded for {0}")]
+    Missing(Url),
+
+    #[error("Recorded response for {url} has status {status}")]
+    Status { url: Url, status: StatusCode },
+
+    #[error("Recorded response for {url} is larger than {max_size} bytes")]
+    TooLarge { url: Url, max_size: u64 },
+
+    #[error("Failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to handle recorded response for {url}: {source}")]
+    Handle {
+        url: Url,
+        #[source]
+        source: HandleError,
+    },
+}
+
+/// Replays recorded responses instead of polling the network.
+///
+/// Responses are queued per URL and handed to the handler in order, one per
+/// poll; `watch_changes` returns once the queue of its URL runs out, and `get`
+/// and `download` take the next response of their URL. Like the live service, responses
+/// without a success status are not handed over.
+#[derive(Default)]
+pub struct FixtureHttpService {
+    responses: Mutex<HashMap<Url, VecDeque<HttpResponse>>>,
+}
+
+impl FixtureHttpService {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues `response` for polls of `response.url`.
+    #[must_use]
+    pub fn with_response(self, response: HttpResponse) -> Self {
+        let url = response.url.clone();
+        self.queue(url, response)
+    }
+
+    /// Queues the response recorded in `path` for polls of `url`.
+    ///
+    /// A recording is the output of `curl --include` (or `curl --include
+    /// --location`): status line, headers, a blank line and the body. With
+    /// redirects, the last response is replayed, its URL follows `Location` and
+    /// it is marked as moved if every hop was permanent.
+    ///
+    /// # Errors
+    /// Returns [`FixtureError`] if the file cannot be read or parsed.
+    pub fn with_recorded(self, url: Url, path: impl AsRef<Path>) -> Result<Self, FixtureError> {
+        let path = path.as_ref();
+        let recorded = std::fs::read(path).map_err(|source| FixtureError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let url_polled = url.clone();
+        let response =
+            parse_recorded(url, &recorded).map_err(|reason| FixtureError::Malformed {
+                path: path.to_path_buf(),
+                reason,
+            })?;
+
+        Ok(self.queue(url_polled, response))
+    }
+
+    fn queue(self, url: Url, response: HttpResponse) -> Self {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(url)
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    fn next_response(&self, url: &Url) -> Option<HttpResponse> {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mut(url)?
+            .pop_front()
+    }
+}
+
+fn split_head(message: &[u8]) -> (&[u8], &[u8]) {
+    for (separator, len) in [(&b"\r\n\r\n"[..], 4), (&b"\n\n"[..], 2)] {
+        if let Some(at) = message.windows(len).position(|w| w == separator) {
+            return (&message[..at], &message[at + len..]);
+        }
+    }
+
+    (message, &[])
+}
+
+fn parse_head(head: &str) -> Result<(StatusCode, HeaderMap), String> {
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .ok_or_else(|| format!("invalid status line \"{status_line}\""))?;
+
+    let mut headers = HeaderMap::new();
+    for line in lines {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("invalid header line \"{line}\""))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|e| e.to_string())?;
+        let value = HeaderValue::from_str(value.trim()).map_err(|e| e.to_string())?;
+        headers.append(name, value);
+    }
+
+    Ok((status, headers))
+}
+
+fn parse_recorded(mut url: Url, mut message: &[u8]) -> Result<HttpResponse, String> {
+    let mut redirects = 0;
+    let mut permanent = true;
+
+    loop {
+        let (head, body) = split_head(message);
+        let head = std::str::from_utf8(head).map_err(|e| e.to_string())?;
+        let (status, headers) = parse_head(head)?;
+
+        // `curl --location` prints every hop; only the last one has the body.
+        let followed =
+            (status.is_informational() || status.is_redirection()) && body.starts_with(b"HTTP/");
+        if !followed {
+            return Ok(HttpResponse {
+                status,
+                headers,
+                moved_to: (permanent && redirects > 0).then(|| url.clone()),
+                url,
+                body: body.to_vec(),
+            });
+        }
+
+        if let Some(location) = headers.get(LOCATION).and_then(|l| l.to_str().ok()) {
+            url = url.join(location).map_err(|e| e.to_string())?;
+            redirects += 1;
+            permanent &= matches!(
+                status,
+                StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+            );
+        }
+        message = body;
+    }
+}
+
+#[async_trait]
+impl HttpService for FixtureHttpService {
+    async fn watch_changes(&self, handler: Arc<dyn HttpUpdateHandle>) -> Result<(), WatchError> {
+        let url = handler.url().clone();
+        let mut replayed = false;
+
+        while let Some(response) = self.next_response(&url) {
+            replayed = true;
+
+            if !response.status.is_success() {
+                debug!(
+                    "[http_fixture=\"{url}\"] skipped status {}",
+                    response.status
+                );
+                continue;
+            }
+
+            handler.handle(response).await.map_err(|source| {
+                let error = FixtureError::Handle {
+                    url: url.clone(),
+                    source,
+                };
+                WatchError::from(Box::new(error) as Internal)
+            })?;
+        }
+
+        match replayed {
+            true => Ok(()),
+            false => Err(WatchError::from(
+                Box::new(FixtureError::Missing(url)) as Internal
+            )),
+        }
+    }
+
+    async fn get(
+        &self,
+        _source: &dyn HttpUpdateHandle,
+        url: &Url,
+    ) -> Result<HttpResponse, FetchError> {
+        let response = self
+            .next_response(url)
+            .ok_or_else(|| FixtureError::Missing(url.clone()))
+            .map_err(|e| FetchError::from(Box::new(e) as Internal))?;
+
+        match response.status.is_success() {
+            true => Ok(response),
+            false => Err(FetchError::from(Box::new(FixtureError::Status {
+                url: url.clone(),
+                status: response.status,
+            }) as Internal)),
+        }
+    }
+
+    async fn download(
+        &self,
+        source: &dyn HttpUpdateHandle,
+        url: &Url,
+        path: &Path,
+        max_size: u64,
+    ) -> Result<u64, FetchError> {
+        let response = self.get(source, url).await?;
+        let size = u64::try_from(response.body.len()).unwrap_or(u64::MAX);
+
+        let written = match size > max_size {
+            true => Err(FixtureError::TooLarge {
+                url: url.clone(),
+                max_size,
+            }),
+            false => tokio::fs::write(path, &response.body)
+                .await
+                .map_err(|source| FixtureError::Write {
+                    path: path.to_path_buf(),
+                    source,
+                }),
+        };
+
+        written
+            .map(|()| size)
+            .map_err(|e| FetchError::from(Box::new(e) as Internal))
+    }
+}
@@ -0,0 +1,115 @@
+use super::robots::Robots;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tokio::time::Instant;
+use url::Url;
+
+/// Longest `Retry-After` honoured, so a bogus date cannot stop a source for good.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What is known about one host, shared by every source it serves.
+#[derive(Default)]
+struct Host {
+    robots: Option<(Robots, Instant)>,
+    /// Earliest time of the next request, spaced by `Crawl-delay`.
+    next_request: Option<Instant>,
+    /// Set by `Retry-After` on a 429 or 503 answer.
+    paused_until: Option<Instant>,
+}
+
+/// Per-host `robots.txt` cache and request schedule.
+pub(super) struct Hosts {
+    robots_ttl: Duration,
+    hosts: Mutex<HashMap<String, Host>>,
+}
+
+fn host_key(url: &Url) -> String {
+    url.origin().ascii_serialization()
+}
+
+impl Hosts {
+    pub(super) fn new(robots_ttl: Duration) -> Self {
+        Self {
+            robots_ttl,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn with_host<T>(&self, url: &Url, f: impl FnOnce(&mut Host) -> T) -> T {
+        let mut hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+        f(hosts.entry(host_key(url)).or_default())
+    }
+
+    /// Cached rules of the host of `url`, if fetched less than `robots_ttl` ago.
+    pub(super) fn robots(&self, url: &Url) -> Option<Robots> {
+        self.with_host(url, |host| {
+            host.robots
+                .as_ref()
+                .filter(|(_, fetched_at)| fetched_at.elapsed() < self.robots_ttl)
+                .map(|(robots, _)| robots.clone())
+        })
+    }
+
+    pub(super) fn set_robots(&self, url: &Url, robots: Robots) {
+        self.with_host(url, |host| host.robots = Some((robots, Instant::now())));
+    }
+
+    /// Books the next request to the host of `url` and returns when it may be
+    /// sent: after any pause and `crawl_delay` after the previous booking.
+    pub(super) fn reserve(&self, url: &Url, crawl_delay: Option<Duration>) -> Instant {
+        self.with_host(url, |host| {
+            let at = [host.next_request, host.paused_until]
+                .into_iter()
+                .flatten()
+                .fold(Instant::now(), Instant::max);
+
+            if let Some(delay) = crawl_delay {
+                host.next_request = Some(at + delay);
+            }
+            at
+        })
+    }
+
+    /// Pauses every request to the host of `url` when it answered 429 or 503
+    /// with a `Retry-After`; returns the pause.
+    pub(super) fn note_response(
+        &self,
+        url: &Url,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Option<Duration> {
+        if !matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        ) {
+            return None;
+        }
+
+        let pause = retry_after(headers, Utc::now())?.min(MAX_RETRY_AFTER);
+        let until = Instant::now() + pause;
+        self.with_host(url, |host| {
+            host.paused_until = host.paused_until.max(Some(until));
+        });
+        Some(pause)
+    }
+}
+
+/// Reads `Retry-After` as seconds or as an HTTP date relative to `now`.
+fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
@@ -0,0 +1,184 @@
+use std::time::Duration;
+use url::Url;
+
+/// Longest `Crawl-delay` honoured, so a bogus value cannot stop a host for good.
+const MAX_CRAWL_DELAY: Duration = Duration::from_hours(1);
+
+/// Rules of a `robots.txt` file that apply to one user agent.
+///
+/// Follows RFC 9309: the group naming the agent's product token wins over
+/// the `*` group, the longest matching rule decides and `Allow` wins ties.
+/// `Crawl-delay`, which the RFC leaves out, is read from the same group.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Robots {
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+    /// Rules allowing everything, used when a host has no `robots.txt`.
+    #[must_use]
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Rules allowing nothing, used while a host's `robots.txt` is unreachable.
+    #[must_use]
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![Rule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+            crawl_delay: None,
+        }
+    }
+
+    /// Reads the rules of `robots_txt` for `user_agent`. Only the product
+    /// token of the agent counts: `uninews` for `uninews/1.0 (+https://...)`.
+    #[must_use]
+    pub fn parse(robots_txt: &str, user_agent: &str) -> Self {
+        let token = product_token(user_agent);
+        let groups = parse_groups(robots_txt);
+
+        let group = groups
+            .iter()
+            .find(|group| group.agents.contains(&token))
+            .or_else(|| {
+                groups
+                    .iter()
+                    .find(|group| group.agents.contains(&"*".to_string()))
+            });
+
+        group.map_or_else(Self::allow_all, |group| Self {
+            rules: group.rules.clone(),
+            crawl_delay: group.crawl_delay,
+        })
+    }
+
+    /// Whether the path and query of `url` may be requested.
+    #[must_use]
+    pub fn is_allowed(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+
+        self.rules
+            .iter()
+            .filter(|rule| matches(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    /// Delay the host asks for between two requests.
+    #[must_use]
+    pub const fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+fn product_token(user_agent: &str) -> String {
+    user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+fn parse_groups(robots_txt: &str) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    // Consecutive `User-agent` lines share the group that follows them.
+    let mut in_agents = false;
+
+    for line in robots_txt.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "user-agent" => {
+                if !in_agents {
+                    groups.push(Group::default());
+                    in_agents = true;
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.agents.push(product_token(value));
+                }
+            }
+            key @ ("allow" | "disallow") => {
+                in_agents = false;
+                // An empty `Disallow` allows everything, like no rule at all.
+                if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                    group.rules.push(Rule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                    });
+                }
+            }
+            "crawl-delay" => {
+                in_agents = false;
+                let delay = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|d| !d.is_nan() && *d >= 0.0)
+                    .map(|d| {
+                        Duration::try_from_secs_f64(d)
+                            .map_or(MAX_CRAWL_DELAY, |delay| delay.min(MAX_CRAWL_DELAY))
+                    });
+                if let (Some(group), Some(delay)) = (groups.last_mut(), delay) {
+                    group.crawl_delay = Some(delay);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    groups
+}
+
+/// Matches `path` against a rule pattern, where `*` stands for any characters
+/// and a trailing `$` anchors the end.
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let Some(prefix) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = path.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let parts: Vec<_> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use news_core::models::media::{CachedMedia, MediaFile};
+use news_core::services::media::{MediaIndexError, MediaService};
+use sqlx::{SqlitePool, query, query_scalar};
+use url::Url;
+
+pub struct SqliteMediaService {
+    db_pool: SqlitePool,
+}
+
+fn internal(error: impl std::error::Error + Send + Sync + 'static) -> MediaIndexError {
+    MediaIndexError(Box::new(error))
+}
+
+impl SqliteMediaService {
+    /// Service over `db_pool`, whose schema must be up to date.
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl MediaService for SqliteMediaService {
+    async fn find(&self, url: &Url) -> Result<Option<CachedMedia>, MediaIndexError> {
+        let url = url.as_str();
+        let record = query!(
+            r#"
+            SELECT name, content_type, size
+            FROM media_files
+            WHERE url = $1
+            "#,
+            url,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        Ok(record.map(
+            |record| match (record.name, record.content_type, record.size) {
+                (Some(name), Some(content_type), Some(size)) => CachedMedia::File(MediaFile {
+                    name,
+                    content_type,
+                    size: u64::try_from(size).unwrap_or_default(),
+                }),
+                _ => CachedMedia::Failed,
+            },
+        ))
+    }
+
+    async fn insert(&self, url: &Url, file: &MediaFile) -> Result<(), MediaIndexError> {
+        let url = url.as_str();
+        let size = i64::try_from(file.size).map_err(internal)?;
+
+        query!(
+            r#"
+            INSERT INTO media_files (url, name, content_type, size)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT(url) DO UPDATE SET
+                name = excluded.name,
+                content_type = excluded.content_type,
+                size = excluded.size,
+                created_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            "#,
+            url,
+            file.name,
+            file.content_type,
+            size,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        Ok(())
+    }
+
+    async fn insert_failure(&self, url: &Url) -> Result<(), MediaIndexError> {
+        let url = url.as_str();
+
+        query!(
+            r#"
+            INSERT INTO media_files (url)
+            VALUES ($1)
+            ON CONFLICT(url) DO UPDATE SET
+                name = NULL,
+                content_type = NULL,
+                size = NULL,
+                created_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            "#,
+            url,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        Ok(())
+    }
+
+    async fn total_size(&self) -> Result<u64, MediaIndexError> {
+        let total = query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(size), 0) as "total!: i64"
+            FROM (SELECT DISTINCT name, size FROM media_files WHERE name IS NOT NULL)
+            "#
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        Ok(u64::try_from(total).unwrap_or_default())
+    }
+
+    async fn forget(&self, names: &[String]) -> Result<(), MediaIndexError> {
+        let mut tx = self.db_pool.begin().await.map_err(internal)?;
+
+        for name in names {
+            query!("DELETE FROM media_files WHERE name = $1", name)
+                .execute(&mut *tx)
+                .await
+                .map_err(internal)?;
+        }
+
+        tx.commit().await.map_err(internal)
+    }
+
+    async fn forget_failures(&self) -> Result<u64, MediaIndexError> {
+        let result = query!("DELETE FROM media_files WHERE name IS NULL")
+            .execute(&self.db_pool)
+            .await
+            .map_err(internal)?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,48 +1,282 @@
 use super::uuid::{SqliteUuidService, UpsertMapping, UuidGroup};
+use super::webhook::SqliteWebhookService;
 use crate::db::errors::SqlxServiceError;
 use crate::db::errors::SqlxServiceError::{DBInit, Execute, Transaction};
-use crate::db::init::{DBInitError, init_db_pool};
 use async_trait::async_trait;
-use news_core::models::news::News;
-use news_core::services::news::NewsService;
-use news_core::services::news::UpdateError;
+use news_core::models::news::{
+    Article, ArticleChange, ArticleDetails, ArticleFingerprint, ArticleMarks, ArticleRevision,
+    Attachment, ChangeKind, News, Story,
+};
 use news_core::services::news::UpdateError::{Internal, UpdateItem};
-use sqlx::{SqlitePool, query};
+use news_core::services::news::{GetAllError, MarkError, NewsService, RevisionsError, UpdateError};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool, query, query_as, query_scalar};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::try_join;
+use std::time::Duration;
 use tracing::info;
+use url::Url;
 use uuid::Uuid;
 
+struct AttachmentRow {
+    url: String,
+    content_type: Option<String>,
+    length: Option<i64>,
+    duration: Option<i64>,
+    thumbnail: Option<String>,
+}
+
+impl AttachmentRow {
+    fn into_attachment(self) -> Option<Attachment> {
+        Some(Attachment {
+            url: Url::parse(&self.url).ok()?,
+            content_type: self.content_type,
+            length: self.length.and_then(|length| u64::try_from(length).ok()),
+            duration: self
+                .duration
+                .and_then(|secs| u64::try_from(secs).ok())
+                .map(Duration::from_secs),
+            thumbnail: self.thumbnail.and_then(|url| Url::parse(&url).ok()),
+        })
+    }
+}
+
+async fn stored_attachments(
+    conn: &mut SqliteConnection,
+    id: Uuid,
+) -> Result<Vec<Attachment>, sqlx::Error> {
+    let rows = query_as!(
+        AttachmentRow,
+        r#"
+        SELECT url, content_type, length, duration, thumbnail
+        FROM article_attachments
+        WHERE article_id = $1
+        ORDER BY position
+        "#,
+        id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(AttachmentRow::into_attachment)
+        .collect())
+}
+
+/// Replaces the stored attachments of the article `id` when they differ from
+/// `attachments`.
+async fn sync_attachments(
+    conn: &mut SqliteConnection,
+    id: Uuid,
+    attachments: &[Attachment],
+) -> Result<(), sqlx::Error> {
+    if stored_attachments(conn, id).await? == attachments {
+        return Ok(());
+    }
+
+    query!("DELETE FROM article_attachments WHERE article_id = $1", id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (position, attachment) in attachments.iter().enumerate() {
+        let position = i64::try_from(position).unwrap_or(i64::MAX);
+        let url = attachment.url.as_str();
+        let length = attachment
+            .length
+            .and_then(|length| i64::try_from(length).ok());
+        let duration = attachment
+            .duration
+            .and_then(|duration| i64::try_from(duration.as_secs()).ok());
+        let thumbnail = attachment.thumbnail.as_ref().map(Url::as_str);
+
+        query!(
+            r#"
+            INSERT INTO article_attachments (article_id, position, url, content_type, length, duration, thumbnail)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            id,
+            position,
+            url,
+            attachment.content_type,
+            length,
+            duration,
+            thumbnail,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Authors or categories of the article `id`, in the order of the feed.
+async fn stored_labels(
+    conn: &mut SqliteConnection,
+    id: Uuid,
+    kind: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    query_scalar!(
+        r#"
+        SELECT value
+        FROM article_labels
+        WHERE article_id = $1 AND kind = $2
+        ORDER BY position, value
+        "#,
+        id,
+        kind
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Replaces the stored `kind` labels of the article `id` when they differ
+/// from `values`.
+async fn sync_labels(
+    conn: &mut SqliteConnection,
+    id: Uuid,
+    kind: &str,
+    values: &[String],
+) -> Result<(), sqlx::Error> {
+    if stored_labels(conn, id, kind).await? == values {
+        return Ok(());
+    }
+
+    query!(
+        "DELETE FROM article_labels WHERE article_id = $1 AND kind = $2",
+        id,
+        kind
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for (position, value) in values.iter().enumerate() {
+        let position = i64::try_from(position).unwrap_or(i64::MAX);
+
+        query!(
+            r#"
+            INSERT INTO article_labels (article_id, kind, position, value)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            kind,
+            position,
+            value,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Stores the marks `news` was given along with the new article `id`.
+async fn store_marks(
+    conn: &mut SqliteConnection,
+    id: Uuid,
+    marks: &ArticleMarks,
+) -> Result<(), sqlx::Error> {
+    if marks.read || marks.starred {
+        query!(
+            r#"
+            UPDATE articles SET
+                read_at = CASE WHEN $2 THEN strftime('%Y-%m-%dT%H:%M:%fZ', 'now') END,
+                starred_at = CASE WHEN $3 THEN strftime('%Y-%m-%dT%H:%M:%fZ', 'now') END
+            WHERE id = $1
+            "#,
+            id,
+            marks.read,
+            marks.starred,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for tag in &marks.tags {
+        query!(
+            r#"
+            INSERT INTO article_labels (article_id, kind, position, value)
+            VALUES ($1, 'tag', 0, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            tag,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn stored_marks(conn: &mut SqliteConnection, id: Uuid) -> Result<ArticleMarks, sqlx::Error> {
+    let marks = query!(
+        r#"
+        SELECT
+            read_at IS NOT NULL as "read!: bool",
+            starred_at IS NOT NULL as "starred!: bool"
+        FROM articles
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(ArticleMarks {
+        read: marks.read,
+        starred: marks.starred,
+        // Tags all have position 0, so they come sorted by value.
+        tags: stored_labels(conn, id, "tag").await?,
+    })
+}
+
 pub struct SqliteNewsService {
     db_pool: SqlitePool,
     uuid_repo: SqliteUuidService,
+    webhooks: SqliteWebhookService,
 }
 
 impl SqliteNewsService {
-    pub async fn try_new() -> Result<Self, DBInitError> {
-        let (db_pool, uuid_repo) = try_join!(init_db_pool(), SqliteUuidService::init_lazy())?;
-
-        Ok(Self { db_pool, uuid_repo })
+    /// Service over `db_pool`, whose schema must be up to date, e.g. one from
+    /// [`crate::db::init::SqliteDatabase`]. A read-only pool makes
+    /// [`NewsService::update`] fail.
+    #[must_use]
+    pub fn from_pool(db_pool: SqlitePool) -> Self {
+        Self {
+            uuid_repo: SqliteUuidService::from_pool(db_pool.clone()),
+            webhooks: SqliteWebhookService::from_pool(db_pool.clone()),
+            db_pool,
+        }
     }
 
-    async fn update(&self, news: &[Arc<impl News>]) -> Result<(), SqlxServiceError> {
+    async fn update(
+        &self,
+        news: &[Arc<impl News>],
+    ) -> Result<Vec<ArticleChange>, SqlxServiceError> {
         let uuid_repo = &self.uuid_repo;
         let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
 
         let mut modified: HashMap<Uuid, usize> = HashMap::new();
+        let mut changes = Vec::new();
 
         for news in news {
             let source_key = news.source_key();
 
-            let id = {
-                let upsert_id = uuid_repo
-                    .upsert_uuid_mapping(&mut tx, UuidGroup::News, source_key)
-                    .await?;
+            if uuid_repo
+                .is_pruned(&mut tx, &UuidGroup::News, source_key)
+                .await?
+            {
+                continue;
+            }
 
-                match upsert_id {
-                    UpsertMapping::Existing(id) | UpsertMapping::New(id) => id,
-                }
+            let (id, kind) = match uuid_repo
+                .upsert_uuid_mapping(&mut tx, UuidGroup::News, source_key)
+                .await?
+            {
+                UpsertMapping::New(id) => (id, ChangeKind::Created),
+                UpsertMapping::Existing(id) => (id, ChangeKind::Updated),
             };
 
             let parent_id = news.parent_id();
@@ -50,7 +284,34 @@ async fn update(&self, news: &[Arc<impl News>]) -> Result<(), SqlxServiceError>
             let description = news.description();
             let content = news.content();
 
-            let result = query!(
+            if kind == ChangeKind::Updated {
+                let revision_id = Uuid::now_v7();
+
+                // Keeps the stored text when the upsert below is about to replace it.
+                query!(
+                    r#"
+                    INSERT INTO article_revisions (id, article_id, created_at, title, description, content)
+                    SELECT $1, id, updated_at, title, description, content
+                    FROM articles
+                    WHERE id = $2 AND (title != $3 OR description != $4 OR content IS NOT $5)
+                    "#,
+                    revision_id,
+                    id,
+                    title,
+                    description,
+                    content,
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(|error| Execute {
+                    id: Some(id),
+                    identifier: Some(title.to_string()),
+                    error,
+                })?;
+            }
+
+            let result = query_as!(
+                Article,
                 r#"
                 INSERT INTO articles (id, parent_id, title, description, content)
                 VALUES ($1, $2, $3, $4, $5)
@@ -68,7 +329,15 @@ async fn update(&self, news: &[Arc<impl News>]) -> Result<(), SqlxServiceError>
                     (articles.content IS NULL AND excluded.content IS NOT NULL) OR
                     (articles.content IS NOT NULL AND excluded.content IS NULL)
                 RETURNING
-                    parent_id as "parent_id: Uuid"
+                    id as "id: Uuid",
+                    parent_id as "parent_id: Uuid",
+                    created_at as "created_at: DateTime<Utc>",
+                    updated_at as "updated_at: DateTime<Utc>",
+                    title,
+                    description,
+                    content,
+                    summary,
+                    headline
                 "#,
                 id,
                 parent_id,
@@ -84,10 +353,67 @@ async fn update(&self, news: &[Arc<impl News>]) -> Result<(), SqlxServiceError>
                 error,
             })?;
 
-            if let Some(record) = result {
-                let parent_id = record.parent_id;
+            let link = news.link().map(Url::as_str);
+            let language = news.language();
+            let summary = news.summary();
+            let headline = news.headline();
+            let synced = async {
+                query!(
+                    r#"
+                    UPDATE articles SET link = $2, language = $3, summary = $4, headline = $5
+                    WHERE id = $1 AND (
+                        link IS NOT $2 OR language IS NOT $3 OR
+                        summary IS NOT $4 OR headline IS NOT $5
+                    )
+                    "#,
+                    id,
+                    link,
+                    language,
+                    summary,
+                    headline
+                )
+                .execute(&mut *tx)
+                .await?;
+                sync_attachments(&mut tx, id, news.attachments()).await?;
+                sync_labels(&mut tx, id, "author", news.authors()).await?;
+                sync_labels(&mut tx, id, "category", news.categories()).await
+            };
+            synced.await.map_err(|error| Execute {
+                id: Some(id),
+                identifier: Some(title.to_string()),
+                error,
+            })?;
+
+            // Returned before the extracted text was synced.
+            if let Some(article) = result.map(|article| Article {
+                summary: summary.map(str::to_string),
+                headline: headline.map(str::to_string),
+                ..article
+            }) {
+                let marks = match kind {
+                    ChangeKind::Created => store_marks(&mut tx, id, news.marks())
+                        .await
+                        .map(|()| news.marks().clone()),
+                    ChangeKind::Updated => stored_marks(&mut tx, id).await,
+                }
+                .map_err(|error| Execute {
+                    id: Some(id),
+                    identifier: Some(title.to_string()),
+                    error,
+                })?;
+
+                let change = ArticleChange {
+                    kind,
+                    article,
+                    marks,
+                };
+                self.webhooks.enqueue(&mut tx, &change).await?;
+
+                let parent_id = change.article.parent_id;
                 let numbers = modified.get(&parent_id).unwrap_or(&0) + 1;
                 modified.insert(parent_id, numbers);
+
+                changes.push(change);
             }
         }
 
@@ -99,13 +425,13 @@ async fn update(&self, news: &[Arc<impl News>]) -> Result<(), SqlxServiceError>
             }
         }
 
-        Ok(())
+        Ok(changes)
     }
 }
 
 #[async_trait]
 impl NewsService for SqliteNewsService {
-    async fn update(&self, news: &[Arc<impl News>]) -> Result<(), UpdateError> {
+    async fn update(&self, news: &[Arc<impl News>]) -> Result<Vec<ArticleChange>, UpdateError> {
         self.update(news).await.map_err(|e| match e {
             Execute {
                 id,
@@ -116,7 +442,382 @@ async fn update(&self, news: &[Arc<impl News>]) -> Result<(), UpdateError> {
                 title: identifier.unwrap_or_else(|| "Unknown title".to_string()),
                 error: Box::new(error),
             },
-            DBInit(e) | Transaction(e) => Internal(Box::new(e)),
+            DBInit(e) => Internal(Box::new(e)),
+            Transaction(e) => Internal(Box::new(e)),
         })
     }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Article>, GetAllError> {
+        query_as!(
+            Article,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                parent_id as "parent_id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                title,
+                description,
+                content,
+                summary,
+                headline
+            FROM articles
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))
+    }
+
+    async fn get_created_after(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<impl IntoIterator<Item = Article>, GetAllError> {
+        query_as!(
+            Article,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                parent_id as "parent_id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                title,
+                description,
+                content,
+                summary,
+                headline
+            FROM articles
+            WHERE created_at > strftime('%Y-%m-%dT%H:%M:%fZ', $1)
+            ORDER BY created_at DESC
+            "#,
+            after
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))
+    }
+
+    async fn revisions(&self, id: Uuid) -> Result<Vec<ArticleRevision>, RevisionsError> {
+        let internal = |error: sqlx::Error| RevisionsError::Internal(Box::new(error));
+
+        let current = query_as!(
+            Article,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                parent_id as "parent_id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                title,
+                description,
+                content,
+                summary,
+                headline
+            FROM articles
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .ok_or(RevisionsError::NotFound(id))?;
+
+        let mut revisions = query_as!(
+            ArticleRevision,
+            r#"
+            SELECT
+                article_id as "article_id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                replaced_at as "replaced_at?: DateTime<Utc>",
+                title,
+                description,
+                content
+            FROM article_revisions
+            WHERE article_id = $1
+            ORDER BY created_at, id
+            "#,
+            id
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        revisions.push(ArticleRevision {
+            article_id: current.id,
+            created_at: current.updated_at,
+            replaced_at: None,
+            title: current.title,
+            description: current.description,
+            content: current.content,
+        });
+
+        Ok(revisions)
+    }
+
+    async fn set_starred(&self, id: Uuid, starred: bool) -> Result<(), MarkError> {
+        let result = query!(
+            r#"
+            UPDATE articles
+            SET starred_at = CASE
+                WHEN $2 THEN COALESCE(starred_at, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+            END
+            WHERE id = $1
+            "#,
+            id,
+            starred
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| MarkError::Internal(Box::new(error)))?;
+
+        match result.rows_affected() {
+            0 => Err(MarkError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn set_read(&self, id: Uuid, read: bool) -> Result<(), MarkError> {
+        let result = query!(
+            r#"
+            UPDATE articles
+            SET read_at = CASE
+                WHEN $2 THEN COALESCE(read_at, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+            END
+            WHERE id = $1
+            "#,
+            id,
+            read
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| MarkError::Internal(Box::new(error)))?;
+
+        match result.rows_affected() {
+            0 => Err(MarkError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn add_tag(&self, id: Uuid, tag: &str) -> Result<(), MarkError> {
+        let internal = |error| MarkError::Internal(Box::new(error));
+
+        let result = query!(
+            r#"
+            INSERT INTO article_labels (article_id, kind, position, value)
+            SELECT id, 'tag', 0, $2
+            FROM articles
+            WHERE id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            tag
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        query_scalar!(r#"SELECT id as "id: Uuid" FROM articles WHERE id = $1"#, id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(internal)?
+            .map(|_| ())
+            .ok_or(MarkError::NotFound(id))
+    }
+
+    async fn contents(&self, source_keys: &[&str]) -> Result<HashMap<String, String>, GetAllError> {
+        let internal = |error| GetAllError(Box::new(error));
+
+        let Some(external_ids) = self
+            .uuid_repo
+            .find_by_source_keys(&UuidGroup::News, source_keys)
+            .await
+            .map_err(internal)?
+        else {
+            return Ok(HashMap::new());
+        };
+
+        let mut contents = HashMap::new();
+        for (source_key, external_id) in source_keys.iter().zip(external_ids) {
+            let content = query!(
+                r#"
+                SELECT articles.content
+                FROM articles
+                JOIN uuid_mappings ON uuid_mappings.internal_id = articles.id
+                WHERE uuid_mappings.external_id = $1
+                "#,
+                external_id
+            )
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(|error| GetAllError(Box::new(error)))?
+            .and_then(|row| row.content);
+
+            if let Some(content) = content {
+                contents.insert((*source_key).to_string(), content);
+            }
+        }
+
+        Ok(contents)
+    }
+
+    async fn attachments(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Attachment>>, GetAllError> {
+        let mut conn = self
+            .db_pool
+            .acquire()
+            .await
+            .map_err(|error| GetAllError(Box::new(error)))?;
+
+        let mut attachments = HashMap::new();
+        for id in ids {
+            let stored = stored_attachments(&mut conn, *id)
+                .await
+                .map_err(|error| GetAllError(Box::new(error)))?;
+
+            if !stored.is_empty() {
+                attachments.insert(*id, stored);
+            }
+        }
+
+        Ok(attachments)
+    }
+
+    async fn details(&self, ids: &[Uuid]) -> Result<HashMap<Uuid, ArticleDetails>, GetAllError> {
+        let internal = |error| GetAllError(Box::new(error));
+        let mut conn = self.db_pool.acquire().await.map_err(internal)?;
+
+        let mut details = HashMap::new();
+        for id in ids {
+            let Some(marks) = query!(
+                r#"
+                SELECT
+                    read_at IS NOT NULL as "read!: bool",
+                    starred_at IS NOT NULL as "starred!: bool",
+                    language
+                FROM articles
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(internal)?
+            else {
+                continue;
+            };
+
+            details.insert(
+                *id,
+                ArticleDetails {
+                    authors: stored_labels(&mut conn, *id, "author")
+                        .await
+                        .map_err(internal)?,
+                    categories: stored_labels(&mut conn, *id, "category")
+                        .await
+                        .map_err(internal)?,
+                    // Tags all have position 0, so they come sorted by value.
+                    tags: stored_labels(&mut conn, *id, "tag")
+                        .await
+                        .map_err(internal)?,
+                    read: marks.read,
+                    starred: marks.starred,
+                    language: marks.language,
+                },
+            );
+        }
+
+        Ok(details)
+    }
+
+    async fn fingerprints(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<ArticleFingerprint>, GetAllError> {
+        let rows = query!(
+            r#"
+            SELECT
+                id as "id: Uuid",
+                parent_id as "parent_id: Uuid",
+                link,
+                simhash,
+                story_id as "story_id: Uuid"
+            FROM articles
+            WHERE created_at > strftime('%Y-%m-%dT%H:%M:%fZ', $1)
+            ORDER BY created_at, id
+            "#,
+            after
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ArticleFingerprint {
+                article_id: row.id,
+                parent_id: row.parent_id,
+                link: row.link.and_then(|link| Url::parse(&link).ok()),
+                simhash: row.simhash.map(i64::cast_unsigned),
+                story_id: row.story_id,
+            })
+            .collect())
+    }
+
+    async fn set_story(
+        &self,
+        id: Uuid,
+        story_id: Uuid,
+        simhash: Option<u64>,
+    ) -> Result<(), MarkError> {
+        let simhash = simhash.map(u64::cast_signed);
+
+        let result = query!(
+            "UPDATE articles SET story_id = $2, simhash = $3 WHERE id = $1",
+            id,
+            story_id,
+            simhash
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| MarkError::Internal(Box::new(error)))?;
+
+        match result.rows_affected() {
+            0 => Err(MarkError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn stories(&self) -> Result<Vec<Story>, GetAllError> {
+        let rows = query!(
+            r#"
+            SELECT
+                story_id as "story_id!: Uuid",
+                id as "id: Uuid"
+            FROM articles
+            WHERE story_id IN (
+                SELECT story_id
+                FROM articles
+                WHERE story_id IS NOT NULL
+                GROUP BY story_id
+                HAVING COUNT(*) > 1
+            )
+            ORDER BY created_at, id
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| GetAllError(Box::new(error)))?;
+
+        Ok(Story::group(
+            rows.into_iter().map(|row| (row.story_id, row.id)),
+        ))
+    }
 }
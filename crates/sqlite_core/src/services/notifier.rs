@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use news_core::models::notifier::{
+    Notification, NotificationRoute, NotificationRouteDraft, NotifierConfig, QueuedNotification,
+    RouteFilter,
+};
+use news_core::services::notifier::{NotificationRouteService, RouteError};
+use news_core::services::webhook::QueueError;
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{SqlitePool, query, query_as};
+use uuid::Uuid;
+
+pub struct SqliteNotificationRouteService {
+    db_pool: SqlitePool,
+}
+
+struct RouteRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    config: String,
+    source_id: Option<Uuid>,
+    keywords: String,
+    tag: Option<String>,
+    template: Option<String>,
+}
+
+struct QueuedRow {
+    id: Uuid,
+    route_id: Uuid,
+    config: String,
+    title: String,
+    message: String,
+    attempts: i64,
+}
+
+fn internal(error: impl std::error::Error + Send + Sync + 'static) -> RouteError {
+    RouteError::Internal(Box::new(error))
+}
+
+fn queue_error(error: impl std::error::Error + Send + Sync + 'static) -> QueueError {
+    QueueError(Box::new(error))
+}
+
+impl TryFrom<RouteRow> for NotificationRoute {
+    type Error = RouteError;
+
+    fn try_from(row: RouteRow) -> Result<Self, Self::Error> {
+        let config: NotifierConfig = serde_json::from_str(&row.config).map_err(internal)?;
+        let keywords: Vec<String> = serde_json::from_str(&row.keywords).map_err(internal)?;
+
+        Ok(Self {
+            id: row.id,
+            created_at: row.created_at,
+            config,
+            filter: RouteFilter {
+                source_id: row.source_id,
+                keywords,
+                tag: row.tag,
+            },
+            template: row.template,
+        })
+    }
+}
+
+impl TryFrom<QueuedRow> for QueuedNotification {
+    type Error = QueueError;
+
+    fn try_from(row: QueuedRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            route_id: row.route_id,
+            config: serde_json::from_str(&row.config).map_err(queue_error)?,
+            notification: Notification {
+                title: row.title,
+                message: row.message,
+            },
+            attempts: row.attempts,
+        })
+    }
+}
+
+impl SqliteNotificationRouteService {
+    /// Service over `db_pool`, whose schema must be up to date.
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl NotificationRouteService for SqliteNotificationRouteService {
+    async fn add(&self, draft: NotificationRouteDraft) -> Result<NotificationRoute, RouteError> {
+        let id = Uuid::now_v7();
+        let kind = draft.config.kind();
+        let config = serde_json::to_string(&draft.config).map_err(internal)?;
+        let keywords = serde_json::to_string(&draft.filter.keywords).map_err(internal)?;
+
+        query!(
+            r#"
+            INSERT INTO notification_routes (id, kind, config, source_id, keywords, tag, template)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            id,
+            kind,
+            config,
+            draft.filter.source_id,
+            keywords,
+            draft.filter.tag,
+            draft.template,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        self.get_by_id(id).await
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<NotificationRoute, RouteError> {
+        query_as!(
+            RouteRow,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                config,
+                source_id as "source_id: Uuid",
+                keywords,
+                tag,
+                template
+            FROM notification_routes
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .ok_or(RouteError::NotFound(id))?
+        .try_into()
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = NotificationRoute>, RouteError> {
+        query_as!(
+            RouteRow,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                config,
+                source_id as "source_id: Uuid",
+                keywords,
+                tag,
+                template
+            FROM notification_routes
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(NotificationRoute::try_from)
+        .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn drop_by_id(&self, id: Uuid) -> Result<(), RouteError> {
+        let result = query!("DELETE FROM notification_routes WHERE id = $1", id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(internal)?;
+
+        match result.rows_affected() {
+            0 => Err(RouteError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn enqueue(&self, notifications: &[(Uuid, Notification)]) -> Result<(), QueueError> {
+        let mut tx = self.db_pool.begin().await.map_err(queue_error)?;
+
+        for (route_id, notification) in notifications {
+            let id = Uuid::now_v7();
+
+            query!(
+                r#"
+                INSERT INTO notification_queue (id, route_id, title, message)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                id,
+                route_id,
+                notification.title,
+                notification.message,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(queue_error)?;
+        }
+
+        tx.commit().await.map_err(queue_error)
+    }
+
+    async fn get_due(&self, limit: u32) -> Result<Vec<QueuedNotification>, QueueError> {
+        query_as!(
+            QueuedRow,
+            r#"
+            SELECT
+                q.id as "id: Uuid",
+                q.route_id as "route_id: Uuid",
+                r.config,
+                q.title,
+                q.message,
+                q.attempts
+            FROM notification_queue q
+            JOIN
+                notification_routes r ON r.id = q.route_id
+            WHERE
+                q.next_attempt_at <= strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            ORDER BY q.next_attempt_at, q.id
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(queue_error)?
+        .into_iter()
+        .map(QueuedNotification::try_from)
+        .collect()
+    }
+
+    async fn mark_sent(&self, id: Uuid) -> Result<(), QueueError> {
+        query!("DELETE FROM notification_queue WHERE id = $1", id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(queue_error)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), QueueError> {
+        match retry_at {
+            Some(retry_at) => {
+                query!(
+                    r#"
+                UPDATE notification_queue SET
+                    attempts = attempts + 1,
+                    next_attempt_at = strftime('%Y-%m-%dT%H:%M:%fZ', $2)
+                WHERE id = $1
+                "#,
+                    id,
+                    retry_at,
+                )
+                .execute(&self.db_pool)
+                .await
+            }
+            None => {
+                query!("DELETE FROM notification_queue WHERE id = $1", id)
+                    .execute(&self.db_pool)
+                    .await
+            }
+        }
+        .map_err(queue_error)?;
+
+        Ok(())
+    }
+}
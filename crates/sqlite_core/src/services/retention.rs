@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use news_core::models::retention::{PruneReport, PrunedSource, RetentionLimits, RetentionPolicy};
+use news_core::services::retention::{PruneError, RetentionService};
+use sqlx::{SqlitePool, query, query_scalar};
+use tracing::info;
+use uuid::Uuid;
+
+pub struct SqliteRetentionService {
+    db_pool: SqlitePool,
+}
+
+fn internal(error: impl std::error::Error + Send + Sync + 'static) -> PruneError {
+    PruneError(Box::new(error))
+}
+
+impl SqliteRetentionService {
+    /// Service over `db_pool`, whose schema must be up to date.
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Articles of the source outside `limits`, newest first.
+    async fn expired(
+        &self,
+        source_id: Uuid,
+        limits: RetentionLimits,
+        keep_starred: bool,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let cutoff: Option<DateTime<Utc>> = limits
+            .max_age
+            .and_then(|age| TimeDelta::from_std(age).ok())
+            .and_then(|age| Utc::now().checked_sub_signed(age));
+        let max_count = limits.max_count.map(i64::from);
+
+        query_scalar!(
+            r#"
+            SELECT id as "id!: Uuid"
+            FROM (
+                SELECT
+                    id,
+                    created_at,
+                    ROW_NUMBER() OVER (ORDER BY created_at DESC, id DESC) AS position
+                FROM articles
+                WHERE parent_id = $1 AND ($2 = FALSE OR starred_at IS NULL)
+            )
+            WHERE created_at < strftime('%Y-%m-%dT%H:%M:%fZ', $3) OR position > $4
+            ORDER BY position
+            "#,
+            source_id,
+            keep_starred,
+            cutoff,
+            max_count,
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    async fn delete(&self, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        // Both rows go, and the item key is kept instead: an article without its
+        // mapping is an orphan, and an item still in its feed must not come back
+        // as a new article.
+        for id in ids {
+            query!(
+                r#"
+                INSERT OR IGNORE INTO pruned_articles (external_id, source_id)
+                SELECT m.external_id, a.parent_id
+                FROM uuid_mappings m
+                JOIN
+                    articles a ON a.id = m.internal_id
+                WHERE m.internal_id = $1
+                "#,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+            query!("DELETE FROM article_revisions WHERE article_id = $1", id)
+                .execute(&mut *tx)
+                .await?;
+            query!("DELETE FROM article_attachments WHERE article_id = $1", id)
+                .execute(&mut *tx)
+                .await?;
+            query!("DELETE FROM article_labels WHERE article_id = $1", id)
+                .execute(&mut *tx)
+                .await?;
+            query!("DELETE FROM articles WHERE id = $1", id)
+                .execute(&mut *tx)
+                .await?;
+            query!("DELETE FROM uuid_mappings WHERE internal_id = $1", id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await
+    }
+}
+
+#[async_trait]
+impl RetentionService for SqliteRetentionService {
+    async fn prune(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<PruneReport, PruneError> {
+        let source_ids = query_scalar!(r#"SELECT id as "id: Uuid" FROM sources ORDER BY id"#)
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(internal)?;
+
+        let mut report = PruneReport::default();
+
+        for source_id in source_ids {
+            let limits = policy.limits_for(source_id);
+            if limits.is_unlimited() {
+                continue;
+            }
+
+            let expired = self
+                .expired(source_id, limits, policy.keep_starred)
+                .await
+                .map_err(internal)?;
+
+            if expired.is_empty() {
+                continue;
+            }
+
+            if !dry_run {
+                self.delete(&expired).await.map_err(internal)?;
+                info!(
+                    "[retention_service={source_id}] {} articles pruned",
+                    expired.len()
+                );
+            }
+
+            report.sources.push(PrunedSource {
+                source_id,
+                articles: expired.len() as u64,
+            });
+        }
+
+        Ok(report)
+    }
+}
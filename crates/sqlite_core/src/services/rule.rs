@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use news_core::models::rule::{Rule, RuleAction, RuleDraft, RuleFilter};
+use news_core::services::rule::{RuleError, RuleService};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{SqlitePool, query, query_as};
+use uuid::Uuid;
+
+pub struct SqliteRuleService {
+    db_pool: SqlitePool,
+}
+
+struct RuleRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    action: String,
+    tag: Option<String>,
+    source_id: Option<Uuid>,
+    scope_tag: Option<String>,
+    fields: String,
+    include: String,
+    exclude: String,
+}
+
+fn internal(error: impl std::error::Error + Send + Sync + 'static) -> RuleError {
+    RuleError::Internal(Box::new(error))
+}
+
+impl TryFrom<RuleRow> for Rule {
+    type Error = RuleError;
+
+    fn try_from(row: RuleRow) -> Result<Self, Self::Error> {
+        let action = match (row.action.as_str(), row.tag) {
+            ("drop", _) => RuleAction::Drop,
+            ("read", _) => RuleAction::MarkRead,
+            ("star", _) => RuleAction::Star,
+            ("tag", Some(tag)) => RuleAction::Tag(tag),
+            (action, _) => {
+                let message = format!("rule {} has an invalid action: {action}", row.id);
+                return Err(RuleError::Internal(message.into()));
+            }
+        };
+
+        Ok(Self {
+            id: row.id,
+            created_at: row.created_at,
+            action,
+            filter: RuleFilter {
+                source_id: row.source_id,
+                tag: row.scope_tag,
+                fields: serde_json::from_str(&row.fields).map_err(internal)?,
+                include: serde_json::from_str(&row.include).map_err(internal)?,
+                exclude: serde_json::from_str(&row.exclude).map_err(internal)?,
+            },
+        })
+    }
+}
+
+impl SqliteRuleService {
+    /// Service over `db_pool`, whose schema must be up to date.
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl RuleService for SqliteRuleService {
+    async fn add(&self, draft: RuleDraft) -> Result<Rule, RuleError> {
+        let id = Uuid::now_v7();
+        let action = draft.action.kind();
+        let tag = match &draft.action {
+            RuleAction::Tag(tag) => Some(tag.as_str()),
+            _ => None,
+        };
+        let filter = &draft.filter;
+        let fields = serde_json::to_string(&filter.fields).map_err(internal)?;
+        let include = serde_json::to_string(&filter.include).map_err(internal)?;
+        let exclude = serde_json::to_string(&filter.exclude).map_err(internal)?;
+
+        query!(
+            r#"
+            INSERT INTO rules (id, action, tag, source_id, scope_tag, fields, include, exclude)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            id,
+            action,
+            tag,
+            filter.source_id,
+            filter.tag,
+            fields,
+            include,
+            exclude,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        self.get_by_id(id).await
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Rule, RuleError> {
+        query_as!(
+            RuleRow,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                action,
+                tag,
+                source_id as "source_id: Uuid",
+                scope_tag,
+                fields,
+                include,
+                exclude
+            FROM rules
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .ok_or(RuleError::NotFound(id))?
+        .try_into()
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Rule>, RuleError> {
+        query_as!(
+            RuleRow,
+            r#"
+            SELECT
+                id as "id: Uuid",
+                created_at as "created_at: DateTime<Utc>",
+                action,
+                tag,
+                source_id as "source_id: Uuid",
+                scope_tag,
+                fields,
+                include,
+                exclude
+            FROM rules
+            ORDER BY created_at, id
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(Rule::try_from)
+        .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn drop_by_id(&self, id: Uuid) -> Result<(), RuleError> {
+        let result = query!("DELETE FROM rules WHERE id = $1", id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(internal)?;
+
+        match result.rows_affected() {
+            0 => Err(RuleError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+}
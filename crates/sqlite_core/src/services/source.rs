@@ -3,19 +3,19 @@
 use crate::db::codecs::Url;
 use crate::db::errors::SqlxServiceError;
 use crate::db::errors::SqlxServiceError::{Execute, Transaction};
-use crate::db::init::{DBInitError, init_db_pool};
 use async_trait::async_trait;
 use news_core::errors::InvalidArgument;
 use news_core::models::ExternalEntity;
 use news_core::models::source::SourceEnum;
 use news_core::models::source::atom::{AtomDraft, AtomSource};
+use news_core::models::source::request::RequestSettings;
 use news_core::models::source::telegram::{TelegramDraft, TelegramSource};
 use news_core::services::source::{
-    AddError, DeleteCriteria, DropError, GetAllError, GetError, SourceDraft, SourceService,
+    AddError, DeleteCriteria, DropError, GetAllError, GetError, MoveError, SourceDraft,
+    SourceService,
 };
 use sqlx::types::chrono::{DateTime, Utc};
 use sqlx::{FromRow, SqlitePool, Type, query, query_as};
-use tokio::try_join;
 use uuid::Uuid;
 
 #[derive(Debug, Type)]
@@ -32,6 +32,7 @@ struct SourceQueryResult {
     created_at: DateTime<Utc>,
 
     atom_url: Option<Url>,
+    atom_request: Option<String>,
 
     telegram_username: Option<String>,
 }
@@ -47,10 +48,21 @@ fn from(e: SqlxServiceError) -> Self {
     }
 }
 
+impl From<SqlxServiceError> for MoveError {
+    fn from(e: SqlxServiceError) -> Self {
+        MoveError::Internal(Box::new(e))
+    }
+}
+
 impl SqliteSourceService {
-    pub async fn try_new() -> Result<Self, DBInitError> {
-        let (db_pool, uuid_repo) = try_join!(init_db_pool(), SqliteUuidService::init_lazy())?;
-        Ok(Self { db_pool, uuid_repo })
+    /// Service over `db_pool`, whose schema must be up to date, e.g. one from
+    /// [`crate::db::init::SqliteDatabase`].
+    #[must_use]
+    pub fn from_pool(db_pool: SqlitePool) -> Self {
+        Self {
+            uuid_repo: SqliteUuidService::from_pool(db_pool.clone()),
+            db_pool,
+        }
     }
 
     async fn get_data_by_criteria(
@@ -83,6 +95,7 @@ async fn insert_atom(&self, draft: AtomDraft) -> Result<(), AddError> {
         let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
 
         let url = draft.url.as_str();
+        let request = request_to_json(&draft.request)?;
         let source_key = ExternalEntity::source_key(&draft);
         let upsert_id = uuid_repo
             .upsert_uuid_mapping(&mut tx, SourceAtom, source_key)
@@ -111,11 +124,12 @@ async fn insert_atom(&self, draft: AtomDraft) -> Result<(), AddError> {
 
                 query!(
                     r#"
-                    INSERT INTO source_atom_details (atom_details_id, url)
-                    VALUES ($1, $2) ON CONFLICT(url) DO NOTHING
+                    INSERT INTO source_atom_details (atom_details_id, url, request)
+                    VALUES ($1, $2, $3) ON CONFLICT(url) DO NOTHING
                     "#,
                     id,
                     url,
+                    request,
                 )
                 .execute(&mut *tx)
                 .await
@@ -191,6 +205,16 @@ async fn insert_telegram_channel(&self, draft: TelegramDraft) -> Result<(), AddE
     }
 }
 
+/// Stored as `NULL` when there is nothing to send.
+fn request_to_json(request: &RequestSettings) -> Result<Option<String>, AddError> {
+    match request.is_empty() {
+        true => Ok(None),
+        false => serde_json::to_string(request)
+            .map(Some)
+            .map_err(|e| AddError::Internal(Box::new(e))),
+    }
+}
+
 fn query_reply_to_atom(query_result: SourceQueryResult) -> Result<AtomSource, InvalidArgument> {
     let atom_url = match query_result.atom_url {
         None => {
@@ -203,11 +227,19 @@ fn query_reply_to_atom(query_result: SourceQueryResult) -> Result<AtomSource, In
         Some(url) => url.0,
     };
 
-    Ok(AtomSource::new(
-        query_result.id,
-        query_result.created_at,
-        atom_url,
-    ))
+    let request = match query_result.atom_request {
+        Some(request) => serde_json::from_str(&request).map_err(|e| InvalidArgument {
+            name: "atom_request".to_string(),
+            value: String::new(),
+            reason: format!(
+                "Invalid request settings of Atom source {}: {e}",
+                query_result.id
+            ),
+        })?,
+        None => RequestSettings::default(),
+    };
+
+    Ok(AtomSource::new(query_result.id, query_result.created_at, atom_url).with_request(request))
 }
 
 fn query_reply_to_telegram(
@@ -248,6 +280,7 @@ async fn get_by_id(&self, id: Uuid) -> Result<SourceEnum, GetError> {
                 src.created_at as "created_at: DateTime<Utc>",
                 -- atom details
                 atom.url as "atom_url: Url",
+                atom.request as "atom_request: String",
                 -- telegram details
                 tg.username as "telegram_username: String"
             FROM sources src
@@ -286,6 +319,7 @@ async fn get_all(&self) -> Result<impl IntoIterator<Item = SourceEnum>, GetAllEr
                 src.created_at as "created_at: DateTime<Utc>",
                 -- atom details
                 atom.url as "atom_url: Url",
+                atom.request as "atom_request: String",
                 -- telegram details
                 tg.username as "telegram_username: String"
             FROM sources src
@@ -328,4 +362,74 @@ async fn drop_by(&self, criteria: DeleteCriteria) -> Result<(), DropError> {
 
         Ok(())
     }
+
+    async fn move_atom(&self, id: Uuid, url: url::Url) -> Result<(), MoveError> {
+        let url = url.as_str();
+        let external_id = self.uuid_repo.get_by_source_key(&SourceAtom, url).await?;
+        let mut tx = self.db_pool.begin().await.map_err(Transaction)?;
+
+        let taken_by = query!(
+            r#"
+            SELECT internal_id as "internal_id: Uuid"
+            FROM uuid_mappings
+            WHERE external_id = $1
+            "#,
+            external_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        match taken_by {
+            Some(row) if row.internal_id == id => return Ok(()),
+            Some(_) => return Err(MoveError::AlreadyExists(url.to_string())),
+            None => {}
+        }
+
+        let moved = query!(
+            r#"
+            UPDATE source_atom_details
+            SET url = $1
+            WHERE atom_details_id = $2
+            "#,
+            url,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        if moved.rows_affected() == 0 {
+            return Err(MoveError::NotFound(id));
+        }
+
+        query!(
+            r#"
+            UPDATE uuid_mappings
+            SET external_id = $1
+            WHERE internal_id = $2
+            "#,
+            external_id,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|error| Execute {
+            id: Some(id),
+            identifier: Some(url.to_string()),
+            error,
+        })?;
+
+        tx.commit().await.map_err(Transaction)?;
+
+        Ok(())
+    }
 }
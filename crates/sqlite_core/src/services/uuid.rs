@@ -1,5 +1,4 @@
 use crate::db::errors::SqlxServiceError;
-use crate::db::init::{DBInitError, init_db_pool};
 use sqlx::{Sqlite, SqlitePool, Transaction, Type, query};
 use uuid::Uuid;
 
@@ -25,10 +24,9 @@ pub enum UpsertMapping {
 }
 
 impl SqliteUuidService {
-    pub async fn init_lazy() -> Result<Self, DBInitError> {
-        Ok(Self {
-            db_pool: init_db_pool().await?,
-        })
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
     }
 
     pub async fn get_by_source_key(
@@ -56,6 +54,81 @@ pub async fn get_by_source_key(
         Ok(gen_consistent_id(&result.uuid, key))
     }
 
+    /// External ids of `keys`, in order, or `None` if no key of `group` was
+    /// ever stored.
+    pub async fn find_by_source_keys(
+        &self,
+        group: &UuidGroup,
+        keys: &[&str],
+    ) -> Result<Option<Vec<Uuid>>, SqlxServiceError> {
+        let result = query!(
+            r#"
+            SELECT internal_id as "uuid: Uuid"
+            FROM persistence_uuid
+            WHERE group_type = ?1
+            "#,
+            group,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| SqlxServiceError::Execute {
+            id: None,
+            identifier: Some(format!("{group:?}")),
+            error: e,
+        })?;
+
+        Ok(result.map(|result| {
+            keys.iter()
+                .map(|key| gen_consistent_id(&result.uuid, key))
+                .collect()
+        }))
+    }
+
+    /// Whether retention pruned the item `key` of `group`, which must then not
+    /// be stored again.
+    pub async fn is_pruned(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        group: &UuidGroup,
+        key: &str,
+    ) -> Result<bool, SqlxServiceError> {
+        let error = |error| SqlxServiceError::Execute {
+            id: None,
+            identifier: Some(key.to_string()),
+            error,
+        };
+
+        let Some(group_id) = query!(
+            r#"
+            SELECT internal_id as "uuid: Uuid"
+            FROM persistence_uuid
+            WHERE group_type = ?1
+            "#,
+            group,
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(error)?
+        else {
+            return Ok(false);
+        };
+
+        let external_id = gen_consistent_id(&group_id.uuid, key);
+
+        query!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM pruned_articles WHERE external_id = ?1
+            ) as "is_pruned!: bool"
+            "#,
+            external_id,
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map(|row| row.is_pruned)
+        .map_err(error)
+    }
+
     pub async fn upsert_uuid_mapping(
         &self,
         tx: &mut Transaction<'_, Sqlite>,
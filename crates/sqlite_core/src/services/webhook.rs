@@ -0,0 +1,423 @@
+use crate::db::codecs::Url;
+use crate::db::errors::SqlxServiceError;
+use async_trait::async_trait;
+use news_core::models::news::ArticleChange;
+use news_core::models::webhook::{
+    ArticlePayload, Delivery, DeliveryStats, Webhook, WebhookDraft, WebhookEvent, WebhookPayload,
+};
+use news_core::services::webhook::{QueueError, ReplayCriteria, WebhookError, WebhookService};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{Sqlite, SqlitePool, Transaction, query, query_as};
+use uuid::Uuid;
+
+pub struct SqliteWebhookService {
+    db_pool: SqlitePool,
+}
+
+struct WebhookRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    url: Url,
+    secret: String,
+    events: String,
+    source_id: Option<Uuid>,
+    tag: Option<String>,
+    pending: i64,
+    delivered: i64,
+    failed: i64,
+}
+
+impl WebhookRow {
+    fn into_webhook(self) -> (Webhook, DeliveryStats) {
+        let events: Vec<String> = serde_json::from_str(&self.events).unwrap_or_default();
+
+        let webhook = Webhook {
+            id: self.id,
+            created_at: self.created_at,
+            url: self.url.0,
+            secret: self.secret,
+            events: events.iter().filter_map(|e| e.parse().ok()).collect(),
+            source_id: self.source_id,
+            tag: self.tag,
+        };
+
+        let stats = DeliveryStats {
+            pending: self.pending,
+            delivered: self.delivered,
+            failed: self.failed,
+        };
+
+        (webhook, stats)
+    }
+}
+
+struct DeliveryRow {
+    id: Uuid,
+    webhook_id: Uuid,
+    url: Url,
+    secret: String,
+    event: String,
+    payload: String,
+    attempts: i64,
+}
+
+fn internal(error: impl std::error::Error + Send + Sync + 'static) -> WebhookError {
+    WebhookError::Internal(Box::new(error))
+}
+
+impl SqliteWebhookService {
+    /// Service over `db_pool`, whose schema must be up to date.
+    #[must_use]
+    pub const fn from_pool(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Queues the change for every webhook subscribed to its event, source and
+    /// tag.
+    /// Runs inside the transaction that stores the article, so a change is either
+    /// persisted together with its deliveries or not at all.
+    pub async fn enqueue(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        change: &ArticleChange,
+    ) -> Result<(), SqlxServiceError> {
+        let article = &change.article;
+        let article_id = article.id;
+        let source_id = article.parent_id;
+        let event_name = WebhookEvent::from(change.kind).as_str();
+        let tags =
+            serde_json::to_string(&change.marks.tags).map_err(|e| SqlxServiceError::Execute {
+                id: Some(article_id),
+                identifier: Some(event_name.to_string()),
+                error: sqlx::Error::Encode(Box::new(e)),
+            })?;
+
+        let webhooks = query!(
+            r#"
+            SELECT
+                id as "id: Uuid"
+            FROM webhooks
+            WHERE
+                (source_id IS NULL OR source_id = $1) AND
+                (tag IS NULL OR tag IN (SELECT value FROM json_each($3))) AND
+                EXISTS (SELECT 1 FROM json_each(webhooks.events) WHERE json_each.value = $2)
+            "#,
+            source_id,
+            event_name,
+            tags,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|error| SqlxServiceError::Execute {
+            id: Some(article_id),
+            identifier: Some(event_name.to_string()),
+            error,
+        })?;
+
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_string(&WebhookPayload {
+            event: event_name,
+            occurred_at: Utc::now(),
+            article: ArticlePayload {
+                id: article_id,
+                source_id,
+                title: &article.title,
+                description: &article.description,
+                content: article.content.as_deref(),
+                read: change.marks.read,
+                starred: change.marks.starred,
+                tags: &change.marks.tags,
+            },
+        })
+        .map_err(|e| SqlxServiceError::Execute {
+            id: Some(article_id),
+            identifier: Some(event_name.to_string()),
+            error: sqlx::Error::Encode(Box::new(e)),
+        })?;
+
+        for webhook in webhooks {
+            let delivery_id = Uuid::now_v7();
+
+            query!(
+                r#"
+                INSERT INTO webhook_deliveries (id, webhook_id, event, payload)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                delivery_id,
+                webhook.id,
+                event_name,
+                payload,
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(|error| SqlxServiceError::Execute {
+                id: Some(webhook.id),
+                identifier: Some(event_name.to_string()),
+                error,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Queues changes already committed to another database, such as
+    /// Postgres, in a transaction of their own.
+    pub async fn enqueue_all(&self, changes: &[ArticleChange]) -> Result<(), SqlxServiceError> {
+        let mut tx = self.db_pool.begin().await?;
+        for change in changes {
+            self.enqueue(&mut tx, change).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebhookService for SqliteWebhookService {
+    async fn add(&self, draft: WebhookDraft) -> Result<Webhook, WebhookError> {
+        let id = Uuid::now_v7();
+        let url = draft.url.as_str();
+        let events: Vec<&str> = draft.events.iter().map(WebhookEvent::as_str).collect();
+        let events = serde_json::to_string(&events).map_err(internal)?;
+
+        query!(
+            r#"
+            INSERT INTO webhooks (id, url, secret, events, source_id, tag)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            id,
+            url,
+            draft.secret,
+            events,
+            draft.source_id,
+            draft.tag,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(internal)?;
+
+        self.get_by_id(id).await
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Webhook, WebhookError> {
+        query_as!(
+            WebhookRow,
+            r#"
+            SELECT
+                w.id as "id: Uuid",
+                w.created_at as "created_at: DateTime<Utc>",
+                w.url as "url: Url",
+                w.secret,
+                w.events,
+                w.source_id as "source_id: Uuid",
+                w.tag,
+                COALESCE(SUM(d.status = 'pending'), 0) as "pending!: i64",
+                COALESCE(SUM(d.status = 'delivered'), 0) as "delivered!: i64",
+                COALESCE(SUM(d.status = 'failed'), 0) as "failed!: i64"
+            FROM webhooks w
+            LEFT JOIN
+                webhook_deliveries d ON d.webhook_id = w.id
+            WHERE
+                w.id = $1
+            GROUP BY w.id
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .map(|row| row.into_webhook().0)
+        .ok_or(WebhookError::NotFound(id))
+    }
+
+    async fn get_all(
+        &self,
+    ) -> Result<impl IntoIterator<Item = (Webhook, DeliveryStats)>, WebhookError> {
+        Ok(query_as!(
+            WebhookRow,
+            r#"
+            SELECT
+                w.id as "id: Uuid",
+                w.created_at as "created_at: DateTime<Utc>",
+                w.url as "url: Url",
+                w.secret,
+                w.events,
+                w.source_id as "source_id: Uuid",
+                w.tag,
+                COALESCE(SUM(d.status = 'pending'), 0) as "pending!: i64",
+                COALESCE(SUM(d.status = 'delivered'), 0) as "delivered!: i64",
+                COALESCE(SUM(d.status = 'failed'), 0) as "failed!: i64"
+            FROM webhooks w
+            LEFT JOIN
+                webhook_deliveries d ON d.webhook_id = w.id
+            GROUP BY w.id
+            ORDER BY w.created_at
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(WebhookRow::into_webhook))
+    }
+
+    async fn drop_by_id(&self, id: Uuid) -> Result<(), WebhookError> {
+        let result = query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(internal)?;
+
+        match result.rows_affected() {
+            0 => Err(WebhookError::NotFound(id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn get_due(&self, limit: u32) -> Result<Vec<Delivery>, QueueError> {
+        let rows = query_as!(
+            DeliveryRow,
+            r#"
+            SELECT
+                d.id as "id: Uuid",
+                d.webhook_id as "webhook_id: Uuid",
+                w.url as "url: Url",
+                w.secret,
+                d.event,
+                d.payload,
+                d.attempts
+            FROM webhook_deliveries d
+            JOIN
+                webhooks w ON w.id = d.webhook_id
+            WHERE
+                d.status = 'pending' AND
+                d.next_attempt_at <= strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            ORDER BY d.next_attempt_at
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|error| QueueError(Box::new(error)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Delivery {
+                id: row.id,
+                webhook_id: row.webhook_id,
+                url: row.url.0,
+                secret: row.secret,
+                event: row.event,
+                payload: row.payload,
+                attempts: row.attempts,
+            })
+            .collect())
+    }
+
+    async fn mark_delivered(&self, id: Uuid) -> Result<(), QueueError> {
+        query!(
+            r#"
+            UPDATE webhook_deliveries SET
+                status = 'delivered',
+                attempts = attempts + 1,
+                last_error = NULL,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| QueueError(Box::new(error)))?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), QueueError> {
+        query!(
+            r#"
+            UPDATE webhook_deliveries SET
+                status = CASE WHEN $3 IS NULL THEN 'failed' ELSE 'pending' END,
+                attempts = attempts + 1,
+                last_error = $2,
+                next_attempt_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', $3), next_attempt_at),
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            WHERE id = $1
+            "#,
+            id,
+            error,
+            retry_at,
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| QueueError(Box::new(error)))?;
+
+        Ok(())
+    }
+
+    async fn replay(&self, criteria: ReplayCriteria) -> Result<u64, QueueError> {
+        let result = match criteria {
+            ReplayCriteria::Delivery(id) => {
+                query!(
+                    r#"
+                    UPDATE webhook_deliveries SET
+                        status = 'pending',
+                        attempts = 0,
+                        last_error = NULL,
+                        next_attempt_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+                        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = $1
+                    "#,
+                    id
+                )
+                .execute(&self.db_pool)
+                .await
+            }
+            ReplayCriteria::Failed(webhook_id) => {
+                query!(
+                    r#"
+                    UPDATE webhook_deliveries SET
+                        status = 'pending',
+                        attempts = 0,
+                        last_error = NULL,
+                        next_attempt_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+                        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE status = 'failed' AND ($1 IS NULL OR webhook_id = $1)
+                    "#,
+                    webhook_id
+                )
+                .execute(&self.db_pool)
+                .await
+            }
+        }
+        .map_err(|error| QueueError(Box::new(error)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn prune_delivered(&self, before: DateTime<Utc>) -> Result<u64, QueueError> {
+        let result = query!(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE
+                status = 'delivered' AND
+                updated_at < strftime('%Y-%m-%dT%H:%M:%fZ', $1)
+            "#,
+            before
+        )
+        .execute(&self.db_pool)
+        .await
+        .map_err(|error| QueueError(Box::new(error)))?;
+
+        Ok(result.rows_affected())
+    }
+}
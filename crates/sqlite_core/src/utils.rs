@@ -1,3 +1,5 @@
 pub mod fs;
+pub mod html;
 pub mod parse;
+pub mod secrets;
 pub mod text;
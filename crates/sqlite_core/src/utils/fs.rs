@@ -1,6 +1,5 @@
 use fs::{create_dir_all, write};
-use std::env::var;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use thiserror::Error;
 use tokio::fs;
 
@@ -47,17 +46,3 @@ pub async fn create_parent_dirs(path: &Path) -> Result<&Path, CreateParentDirsEr
 
     Ok(parent)
 }
-
-pub fn get_db_path() -> PathBuf {
-    PathBuf::from(var("UNINEWS_DB_PATH").unwrap_or_else(|_| String::from("data/app.sqlite")))
-}
-
-#[must_use]
-pub fn to_db_uri(db_file: &Path) -> String {
-    format!("sqlite:{0}?mode=rwc", db_file.display())
-}
-
-pub fn get_db_uri() -> String {
-    let db_file = get_db_path();
-    to_db_uri(&db_file)
-}
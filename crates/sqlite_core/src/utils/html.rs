@@ -0,0 +1,82 @@
+use ego_tree::NodeRef;
+use scraper::{Html, Node};
+use thiserror::Error;
+use tokio::task::spawn_blocking;
+
+/// Elements whose text does not run on into the text around them.
+const BLOCK_TAGS: [&str; 30] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "td",
+    "tr",
+    "ul",
+];
+
+/// Elements whose content is not text to read.
+const HIDDEN_TAGS: [&str; 4] = ["noscript", "script", "style", "template"];
+
+#[derive(Error, Debug)]
+#[error("Failed to convert HTML to text: {0}")]
+pub struct ConvertError(String);
+
+fn push_text(node: NodeRef<'_, Node>, text: &mut String) {
+    match node.value() {
+        Node::Text(content) => text.push_str(content),
+        Node::Element(element) if !HIDDEN_TAGS.contains(&element.name()) => {
+            let is_block = BLOCK_TAGS.contains(&element.name());
+            if is_block {
+                text.push('\n');
+            }
+            for child in node.children() {
+                push_text(child, text);
+            }
+            if is_block {
+                text.push('\n');
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Strips all tags from HTML, keeping only its text, with entities decoded
+/// and a line break around each block and `<br>`, without scripts and styles.
+///
+/// # Errors
+/// Returns [`ConvertError`] if the blocking conversion task fails.
+pub async fn html_to_text(html_dirty: &str) -> Result<String, ConvertError> {
+    let html_owned = html_dirty.to_string();
+
+    spawn_blocking(move || {
+        let fragment = Html::parse_fragment(&html_owned);
+        let mut text = String::new();
+        push_text(*fragment.root_element(), &mut text);
+        text
+    })
+    .await
+    .map_err(|e| ConvertError(e.to_string()))
+}
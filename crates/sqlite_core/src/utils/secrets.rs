@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("Invalid secrets file {path}: {reason}")]
+pub struct SecretsError {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Resolves `${NAME}` references in stored settings: from the environment
+/// variable `NAME` first, then from the secrets file.
+#[derive(Default)]
+pub struct Secrets {
+    file: HashMap<String, String>,
+}
+
+impl Secrets {
+    /// Reads `path`, a TOML file of `NAME = "value"` lines.
+    ///
+    /// # Errors
+    /// Returns [`SecretsError`] if the file is unreadable or malformed.
+    pub fn load(path: Option<&Path>) -> Result<Self, SecretsError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let invalid = |reason: String| SecretsError {
+            path: path.to_path_buf(),
+            reason,
+        };
+        let content = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let file = toml::from_str(&content).map_err(|e| invalid(e.message().to_string()))?;
+
+        Ok(Self { file })
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        std::env::var(name)
+            .ok()
+            .or_else(|| self.file.get(name).cloned())
+    }
+
+    /// Replaces every `${NAME}` in `value`.
+    ///
+    /// # Errors
+    /// Returns the first name that is neither an environment variable nor in
+    /// the secrets file.
+    pub fn resolve(&self, value: &str) -> Result<String, String> {
+        let mut resolved = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find("${") {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 2..start + end];
+
+            resolved.push_str(&rest[..start]);
+            resolved.push_str(&self.get(name).ok_or_else(|| name.to_string())?);
+            rest = &rest[start + end + 1..];
+        }
+
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+}
@@ -0,0 +1,102 @@
+//! Every test in this file runs against the same temporary database, each
+//! through pools of its own.
+
+use news_core::config::DatabaseSettings;
+use news_core::models::source::atom::AtomDraft;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_sqlite_core::db::init::SqliteDatabase;
+use news_sqlite_core::services::source::SqliteSourceService;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use tokio::task::JoinSet;
+use url::Url;
+use uuid::Uuid;
+
+const WRITERS: usize = 4;
+const READERS: usize = 8;
+const WRITES_PER_WRITER: usize = 25;
+
+static DB_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    let dir = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temporary directory must be writable");
+    dir
+});
+
+fn database() -> SqliteDatabase {
+    SqliteDatabase::new(DatabaseSettings {
+        path: DB_DIR.join("app.sqlite"),
+        ..DatabaseSettings::default()
+    })
+}
+
+fn feed_url(writer: usize, item: usize) -> Url {
+    Url::parse(&format!("https://example.com/{writer}/{item}.xml")).expect("valid URL")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_writers_and_readers_do_not_lock() {
+    let database = database();
+    let writer = Arc::new(SqliteSourceService::from_pool(
+        database.pool().await.expect("write pool"),
+    ));
+    let reader = Arc::new(SqliteSourceService::from_pool(
+        database.read_pool().await.expect("read pool"),
+    ));
+
+    let mut tasks = JoinSet::new();
+
+    for id in 0..WRITERS {
+        let writer = writer.clone();
+        tasks.spawn(async move {
+            for item in 0..WRITES_PER_WRITER {
+                let draft = SourceDraft::Atom(AtomDraft::new(feed_url(id, item)));
+                writer.add(draft).await.map_err(|e| e.to_string())?;
+            }
+            Ok::<_, String>(())
+        });
+    }
+
+    for _ in 0..READERS {
+        let reader = reader.clone();
+        tasks.spawn(async move {
+            for _ in 0..WRITES_PER_WRITER {
+                reader.get_all().await.map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        });
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        result
+            .expect("task must not panic")
+            .expect("no lock errors");
+    }
+
+    let count = reader.get_all().await.expect("read").into_iter().count();
+    assert!(count >= WRITERS * WRITES_PER_WRITER);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn read_pool_rejects_writes() {
+    let pool = database().read_pool().await.expect("read pool");
+
+    let result = sqlx::query("DELETE FROM sources").execute(&pool).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn write_pool_enforces_foreign_keys() {
+    let pool = database().pool().await.expect("write pool");
+
+    let enabled: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(&pool)
+        .await
+        .expect("pragma");
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&pool)
+        .await
+        .expect("pragma");
+
+    assert!(enabled);
+    assert_eq!(journal_mode, "wal");
+}
@@ -0,0 +1,36 @@
+//! Extracts the text of HTML descriptions.
+
+use news_sqlite_core::utils::html::html_to_text;
+
+async fn lines(html: &str) -> Vec<String> {
+    html_to_text(html)
+        .await
+        .expect("text")
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn blocks_and_line_breaks_end_lines() {
+    assert_eq!(
+        lines("<p>One &amp; <b>two</b></p><p>three<br>four</p>").await,
+        ["One & two", "three", "four"]
+    );
+    assert_eq!(
+        lines("<b>Gifts 2.0</b><br/><br/>Collectible gifts").await,
+        ["Gifts 2.0", "Collectible gifts"]
+    );
+}
+
+#[tokio::test]
+async fn scripts_styles_and_comments_are_not_text() {
+    assert_eq!(
+        lines(
+            "<style>p { color: red }</style><p>Kept<!-- dropped --></p><script>alert(1)</script>"
+        )
+        .await,
+        ["Kept"]
+    );
+}
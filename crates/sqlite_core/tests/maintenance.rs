@@ -0,0 +1,58 @@
+//! Backs up a database on disk while it is in use.
+
+use news_core::config::DatabaseSettings;
+use news_core::models::source::atom::AtomDraft;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_sqlite_core::db::init::SqliteDatabase;
+use news_sqlite_core::db::maintenance::{backup, check};
+use news_sqlite_core::services::source::SqliteSourceService;
+use std::path::Path;
+use url::Url;
+use uuid::Uuid;
+
+fn database(path: &Path) -> SqliteDatabase {
+    SqliteDatabase::new(DatabaseSettings {
+        path: path.to_path_buf(),
+        ..DatabaseSettings::default()
+    })
+}
+
+fn feed(n: usize) -> SourceDraft {
+    let url = format!("https://example.com/feeds/{n}.xml");
+    SourceDraft::Atom(AtomDraft::new(Url::parse(&url).expect("valid URL")))
+}
+
+#[tokio::test]
+async fn backup_copies_the_live_database() {
+    let dir = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temporary directory must be writable");
+
+    let pool = database(&dir.join("app.sqlite"))
+        .pool()
+        .await
+        .expect("open database");
+    let sources = SqliteSourceService::from_pool(pool.clone());
+    for n in 0..3 {
+        sources.add(feed(n)).await.expect("add source");
+    }
+
+    // A writer keeps going on another connection while the copy runs.
+    let destination = dir.join("backup.sqlite");
+    let (copied, ()) = tokio::join!(backup(&pool, &destination), async {
+        for n in 3..10 {
+            sources.add(feed(n)).await.expect("add source");
+        }
+    });
+    copied.expect("back up database");
+
+    let copy = database(&destination).pool().await.expect("open backup");
+    assert!(check(&copy).await.expect("check backup").is_ok());
+
+    let count = SqliteSourceService::from_pool(copy)
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .count();
+    assert!((3..=10).contains(&count), "{count} sources copied");
+}
@@ -0,0 +1,101 @@
+//! Reads `robots.txt` files the way `LiveHttpService` does before polling.
+
+use news_sqlite_core::services::http::robots::Robots;
+use std::time::Duration;
+use url::Url;
+
+const USER_AGENT: &str = "uninews/0.1.0 (+https://example.com/uninews)";
+
+const ROBOTS_TXT: &str = "\
+# Everyone else stays out of the admin pages.
+User-agent: *
+Disallow: /admin/
+Crawl-delay: 10
+
+User-agent: Googlebot
+User-agent: UniNews
+Disallow: /private/
+Allow: /private/feed.xml
+Disallow: /*.json$
+Crawl-delay: 2.5
+";
+
+fn allowed(robots: &Robots, path: &str) -> bool {
+    let url = Url::parse("https://example.com")
+        .and_then(|base| base.join(path))
+        .expect("valid URL");
+    robots.is_allowed(&url)
+}
+
+#[test]
+fn group_of_the_product_token_wins_over_the_wildcard() {
+    let robots = Robots::parse(ROBOTS_TXT, USER_AGENT);
+
+    assert!(allowed(&robots, "/admin/feed.xml"));
+    assert!(!allowed(&robots, "/private/notes.xml"));
+    assert_eq!(robots.crawl_delay(), Some(Duration::from_millis(2500)));
+}
+
+#[test]
+fn other_agents_follow_the_wildcard_group() {
+    let robots = Robots::parse(ROBOTS_TXT, "curl/8.5.0");
+
+    assert!(!allowed(&robots, "/admin/feed.xml"));
+    assert!(allowed(&robots, "/private/notes.xml"));
+    assert_eq!(robots.crawl_delay(), Some(Duration::from_secs(10)));
+}
+
+#[test]
+fn longest_rule_decides() {
+    let robots = Robots::parse(ROBOTS_TXT, USER_AGENT);
+
+    assert!(allowed(&robots, "/private/feed.xml"));
+    assert!(!allowed(&robots, "/private/feed"));
+}
+
+#[test]
+fn wildcards_and_end_anchors_match() {
+    let robots = Robots::parse(ROBOTS_TXT, USER_AGENT);
+
+    assert!(!allowed(&robots, "/api/v1/posts.json"));
+    assert!(allowed(&robots, "/api/v1/posts.json?page=2"));
+    assert!(allowed(&robots, "/feed.xml"));
+}
+
+#[test]
+fn empty_disallow_allows_everything() {
+    let robots = Robots::parse("User-agent: *\nDisallow:\n", USER_AGENT);
+
+    assert!(allowed(&robots, "/"));
+    assert_eq!(robots.crawl_delay(), None);
+}
+
+#[test]
+fn crawl_delays_are_capped() {
+    for value in ["7200", "1e300", "inf"] {
+        let robots = Robots::parse(
+            &format!("User-agent: *\nCrawl-delay: {value}\n"),
+            USER_AGENT,
+        );
+        assert_eq!(
+            robots.crawl_delay(),
+            Some(Duration::from_hours(1)),
+            "{value}"
+        );
+    }
+
+    for value in ["-1", "NaN", "soon"] {
+        let robots = Robots::parse(
+            &format!("User-agent: *\nCrawl-delay: {value}\n"),
+            USER_AGENT,
+        );
+        assert_eq!(robots.crawl_delay(), None, "{value}");
+    }
+}
+
+#[test]
+fn missing_and_unreachable_files() {
+    assert!(allowed(&Robots::allow_all(), "/feed.xml"));
+    assert!(!allowed(&Robots::disallow_all(), "/feed.xml"));
+    assert_eq!(Robots::parse("", USER_AGENT), Robots::allow_all());
+}
@@ -1,20 +1,12 @@
 use crate::source::atom::watch_atom_feed;
 use crate::source::telegram::watch_telegram_channel;
-use crate::state::LiveAppState;
+use crate::state::AppState;
 use news_core::models::source::SourceEnum;
 use news_core::services::WatchError;
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::OnceCell;
-
-static APP_STATE: OnceCell<Arc<LiveAppState>> = OnceCell::const_new();
-
-pub async fn app_state() -> Arc<LiveAppState> {
-    APP_STATE
-        .get_or_init(async || Arc::new(LiveAppState::new()))
-        .await
-        .clone()
-}
 
 #[derive(Error, Debug)]
 pub enum SourceWatchError {
@@ -22,9 +14,11 @@ pub enum SourceWatchError {
     Internal(#[from] WatchError),
 }
 
-/// Watches news updates from the provided source (Atom feed or Telegram channel).
+/// Watches news updates from the provided source (Atom feed or Telegram channel),
+/// storing them through the services of `app_state`.
 ///
 /// # Arguments
+/// * `app_state` - The services to fetch, store and announce updates with.
 /// * `source_type` - The source to watch, either an Atom feed or a Telegram channel.
 ///
 /// # Errors
@@ -33,9 +27,10 @@ pub enum SourceWatchError {
 /// * There is a connection error while watching the source
 /// * There is an error parsing updates from the source
 /// * The source service is not accessible
-pub async fn source_watch(source_type: SourceEnum) -> Result<(), SourceWatchError> {
-    let app_state = app_state().await;
-
+pub async fn watch_source<S: SourceService + 'static, N: NewsService + 'static>(
+    app_state: Arc<AppState<S, N>>,
+    source_type: SourceEnum,
+) -> Result<(), SourceWatchError> {
     match source_type {
         SourceEnum::Atom(src) => watch_atom_feed(app_state, src).await?,
         SourceEnum::Telegram(src) => watch_telegram_channel(app_state, src).await?,
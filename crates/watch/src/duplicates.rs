@@ -0,0 +1,192 @@
+//! Groups the articles several sources publish about the same story.
+//!
+//! A new article joins the story of a stored one when both link to the same
+//! page, once tracking parameters are removed, or when the `SimHash` of their
+//! texts differ in at most `duplicates.max_distance` bits. Only articles stored
+//! within `duplicates.window` are compared. An article that matches none
+//! starts its own story.
+
+use crate::state::AppState;
+use chrono::{DateTime, TimeDelta, Utc};
+use news_core::models::news::{ArticleChange, ArticleFingerprint, ChangeKind};
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use news_sqlite_core::utils::html::html_to_text;
+use tracing::{debug, warn};
+use url::Url;
+
+/// Consecutive words hashed together.
+const SHINGLE_WORDS: usize = 3;
+
+/// Shorter texts, such as a photo caption, say too little to be compared.
+const MIN_WORDS: usize = 8;
+
+/// Query parameters added by newsletters and social networks to follow
+/// clicks, besides the `utm_` ones.
+const TRACKING_PARAMETERS: [&str; 8] = [
+    "fbclid", "gclid", "dclid", "yclid", "mc_cid", "mc_eid", "ref", "ref_src",
+];
+
+/// `url` without what differs between links to the same page: scheme, `www.`,
+/// port, fragment, trailing slash and tracking parameters.
+#[must_use]
+pub fn canonical_link(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let path = url.path().trim_end_matches('/');
+
+    let query: Vec<String> = url
+        .query_pairs()
+        .filter(|(name, _)| {
+            !name.starts_with("utm_") && !TRACKING_PARAMETERS.contains(&name.as_ref())
+        })
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+
+    if query.is_empty() {
+        format!("{host}{path}")
+    } else {
+        format!("{host}{path}?{}", query.join("&"))
+    }
+}
+
+/// 64-bit FNV-1a of the words, finished with the `SplitMix64` mixer so that
+/// every bit depends on every byte.
+fn shingle_hash(words: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in words.iter().flat_map(|word| word.bytes().chain([b' '])) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// `SimHash` of the lowercase words of `text`, hashed by three, or `None` when
+/// it has fewer than eight words.
+///
+/// The hashes are stable across releases, as they are stored.
+#[must_use]
+pub fn simhash(text: &str) -> Option<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if words.len() < MIN_WORDS {
+        return None;
+    }
+
+    let mut weights = [0i64; 64];
+    for shingle in words.windows(SHINGLE_WORDS) {
+        let hash = shingle_hash(shingle);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            match hash >> bit & 1 {
+                1 => *weight += 1,
+                _ => *weight -= 1,
+            }
+        }
+    }
+
+    Some(
+        weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0)
+            .fold(0, |simhash, (bit, _)| simhash | 1 << bit),
+    )
+}
+
+/// Number of differing bits of two `SimHash`.
+#[must_use]
+pub const fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Puts the articles created by `changes` in the story of a stored duplicate,
+/// or in a story of their own. Failures are logged and skipped.
+pub async fn assign_stories<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    changes: &[ArticleChange],
+) {
+    let created: Vec<_> = changes
+        .iter()
+        .filter(|change| change.kind == ChangeKind::Created)
+        .map(|change| &change.article)
+        .collect();
+
+    if created.is_empty() {
+        return;
+    }
+
+    let settings = app_state.duplicate_settings();
+    let news = app_state.news();
+    // The new articles are candidates too, however short the window.
+    let oldest = created
+        .iter()
+        .map(|article| article.created_at - TimeDelta::microseconds(1))
+        .min()
+        .unwrap_or_else(Utc::now);
+    let after = TimeDelta::from_std(settings.window)
+        .ok()
+        .and_then(|window| Utc::now().checked_sub_signed(window))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        .min(oldest);
+    let mut candidates = match news.fingerprints(after).await {
+        Ok(fingerprints) => fingerprints,
+        Err(e) => {
+            warn!("[duplicates] {e}");
+            return;
+        }
+    };
+
+    for article in created {
+        let Some(position) = candidates
+            .iter()
+            .position(|candidate| candidate.article_id == article.id)
+        else {
+            continue;
+        };
+        let mut fingerprint = candidates.remove(position);
+
+        let text = html_to_text(&article.description).await.unwrap_or_default();
+        fingerprint.simhash = simhash(&[article.title.as_str(), &text].join("\n"));
+
+        let link = fingerprint.link.as_ref().map(canonical_link);
+        let duplicate = candidates.iter().find(|candidate| {
+            let same_link = link.is_some() && candidate.link.as_ref().map(canonical_link) == link;
+            let similar = matches!(
+                (fingerprint.simhash, candidate.simhash),
+                (Some(a), Some(b)) if distance(a, b) <= settings.max_distance
+            );
+
+            candidate.story_id.is_some() && (same_link || similar)
+        });
+
+        let story_id = match duplicate {
+            Some(ArticleFingerprint {
+                story_id: Some(story_id),
+                article_id,
+                ..
+            }) => {
+                debug!("[duplicates] \"{}\" duplicates {article_id}", article.title);
+                *story_id
+            }
+            _ => article.id,
+        };
+
+        if let Err(e) = news
+            .set_story(article.id, story_id, fingerprint.simhash)
+            .await
+        {
+            warn!("[duplicates] {e}");
+            continue;
+        }
+
+        fingerprint.story_id = Some(story_id);
+        candidates.push(fingerprint);
+    }
+}
@@ -0,0 +1,48 @@
+//! Language of collected items, detected offline from their title and the
+//! plain text of their description.
+
+use news_core::models::news::News;
+use news_core::utils::language::code;
+use tracing::debug;
+
+/// ISO 639-1 code of the language of `text`, or `None` when it cannot be told
+/// reliably, as for most texts of a few words.
+#[must_use]
+pub fn detect(text: &str) -> Option<&'static str> {
+    whatlang::detect(text)
+        .filter(whatlang::Info::is_reliable)
+        .map(|info| code(info.lang()))
+}
+
+/// Language of an item with `title` and the plain `text` of its description.
+#[must_use]
+pub fn detect_item(title: &str, text: &str) -> Option<&'static str> {
+    detect(&[title, text].join("\n"))
+}
+
+/// Whether an item in `language` is kept by a source restricted to
+/// `languages`: always when it keeps every language or the item's language
+/// is unknown.
+#[must_use]
+pub fn is_kept(languages: &[String], language: Option<&str>) -> bool {
+    languages.is_empty() || language.is_none_or(|language| languages.iter().any(|l| l == language))
+}
+
+/// `items` without those detected in a language outside `languages`.
+#[must_use]
+pub fn without_other_languages<T: News>(items: Vec<T>, languages: &[String]) -> Vec<T> {
+    items
+        .into_iter()
+        .filter(|item| {
+            let kept = is_kept(languages, item.language());
+            if !kept {
+                debug!(
+                    "[language] dropped \"{}\" in {}",
+                    item.title(),
+                    item.language().unwrap_or_default()
+                );
+            }
+            kept
+        })
+        .collect()
+}
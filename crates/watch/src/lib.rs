@@ -1,6 +1,15 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
 pub mod cli;
+pub mod duplicates;
+pub mod language;
+pub mod media;
+pub mod notify;
+pub mod retention;
+pub mod rules;
 mod source;
-mod state;
+pub mod state;
+pub mod storage;
+pub mod summary;
 mod utils;
+pub mod webhook;
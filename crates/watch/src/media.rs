@@ -0,0 +1,689 @@
+//! Local copies of the images of stored articles.
+//!
+//! Files are named by the SHA-256 of their content, so an image shared by
+//! many articles is stored once. Articles are stored with the remote URLs of
+//! their images, in the HTML of their description and the Markdown of their
+//! full content, which [`localize_images`] points under [`MEDIA_PATH`] when
+//! rendering them. Files no stored image maps to any more are removed by
+//! [`collect_garbage`].
+//!
+//! Attachments, such as podcast episodes, are not cached; they are fetched to
+//! a directory on request by [`download_attachments`].
+
+use crate::state::{AppState, StateError};
+use async_trait::async_trait;
+use news_core::models::media::{
+    CachedMedia, MEDIA_PATH, MediaFile, is_media_name, media_references,
+};
+use news_core::models::source::SourceEnum;
+use news_core::models::source::request::RequestSettings;
+use news_core::services::media::{MediaIndexError, MediaService};
+use news_core::services::news::{GetAllError, NewsService};
+use news_core::services::source::{GetAllError as GetAllSourcesError, SourceService};
+use news_core::services::{FetchError, HandleError, HttpResponse, HttpUpdateHandle};
+use reqwest::header::CONTENT_TYPE;
+use scraper::Html;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+use tokio::fs::{create_dir_all, metadata, read_dir, remove_file, rename, write};
+use tokio::time::sleep;
+use tracing::{debug, error, info, warn};
+use url::Url;
+use uuid::Uuid;
+
+/// Files younger than this are kept by [`collect_garbage`], as the article
+/// referencing them may not be stored yet.
+const GC_GRACE: Duration = Duration::from_hours(1);
+
+#[derive(Error, Debug)]
+pub enum MediaError {
+    #[error(transparent)]
+    State(#[from] StateError),
+
+    #[error("Failed to access the media index: {0}")]
+    Index(#[from] MediaIndexError),
+
+    #[error("Failed to fetch {0}: {1}")]
+    Fetch(Url, #[source] FetchError),
+
+    #[error("{0} is not an image of an allowed type (served as {1})")]
+    ContentType(Url, String),
+
+    #[error("{0} is larger than {1} bytes")]
+    TooLarge(Url, u64),
+
+    #[error("The media cache is full, {0} is not stored")]
+    Full(Url),
+
+    #[error("Failed to write {0}: {1}")]
+    Write(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to read {0}: {1}")]
+    Read(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to load articles: {0}")]
+    Articles(#[source] GetAllError),
+
+    #[error("Failed to load sources: {0}")]
+    Sources(#[source] GetAllSourcesError),
+}
+
+/// Files removed by a garbage collection run (or that would be, for a dry run).
+#[derive(Debug, Clone, Default)]
+pub struct GcReport {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Attachments fetched by [`download_attachments`].
+#[derive(Debug, Clone, Default)]
+pub struct DownloadReport {
+    pub files: u64,
+    pub bytes: u64,
+    /// Attachments already in the directory.
+    pub skipped: u64,
+    /// Attachments that could not be fetched or written; they are logged.
+    pub failed: u64,
+}
+
+/// Fetches attachments on behalf of the source of their article.
+struct SourceHandle {
+    id: Uuid,
+    url: Url,
+    key: String,
+    request: Option<RequestSettings>,
+}
+
+impl SourceHandle {
+    fn new(source: &SourceEnum) -> Self {
+        match source {
+            SourceEnum::Atom(atom) => Self {
+                id: atom.id,
+                url: atom.url.clone(),
+                key: atom.url.to_string(),
+                request: Some(atom.request.clone()).filter(|request| !request.is_empty()),
+            },
+            SourceEnum::Telegram(telegram) => Self {
+                id: telegram.id,
+                url: telegram.public_url.clone(),
+                key: telegram.username.clone(),
+                request: None,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl HttpUpdateHandle for SourceHandle {
+    fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn source_key(&self) -> &str {
+        &self.key
+    }
+
+    fn source_id(&self) -> Option<Uuid> {
+        Some(self.id)
+    }
+
+    fn request(&self) -> Option<&RequestSettings> {
+        self.request.as_ref()
+    }
+
+    async fn handle(&self, _response: HttpResponse) -> Result<(), HandleError> {
+        Ok(())
+    }
+}
+
+/// Type and extension of an image, read from its first bytes.
+fn sniff(body: &[u8]) -> Option<(&'static str, &'static str)> {
+    match body {
+        [0xFF, 0xD8, 0xFF, ..] => Some(("image/jpeg", "jpg")),
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(("image/png", "png")),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(("image/gif", "gif")),
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'E',
+            b'B',
+            b'P',
+            ..,
+        ] => Some(("image/webp", "webp")),
+        [
+            _,
+            _,
+            _,
+            _,
+            b'f',
+            b't',
+            b'y',
+            b'p',
+            b'a',
+            b'v',
+            b'i',
+            b'f' | b's',
+            ..,
+        ] => Some(("image/avif", "avif")),
+        _ => None,
+    }
+}
+
+fn size_of(body: &[u8]) -> u64 {
+    u64::try_from(body.len()).unwrap_or(u64::MAX)
+}
+
+/// Destinations of the Markdown images of `text`, as in `![alt](url "title")`.
+fn markdown_image_sources(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices("![").filter_map(|(start, _)| {
+        let (_, rest) = text[start..].split_once("](")?;
+        let end = rest.find([')', ' ', '\n'])?;
+        Some(rest[..end].trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+/// Distinct absolute `http` and `https` URLs of the images of `text`, the
+/// HTML of a description or the Markdown of full content.
+fn image_urls(text: &str) -> Vec<Url> {
+    let mut urls: Vec<Url> = Vec::new();
+    let mut push = |src: &str| {
+        if let Ok(url) = Url::parse(src)
+            && matches!(url.scheme(), "http" | "https")
+            && !urls.contains(&url)
+        {
+            urls.push(url);
+        }
+    };
+
+    if text.contains("<img") {
+        let fragment = Html::parse_fragment(text);
+        for image in fragment.root_element().descendent_elements() {
+            if image.value().name() == "img"
+                && let Some(src) = image.attr("src")
+            {
+                push(src);
+            }
+        }
+    }
+    for src in markdown_image_sources(text) {
+        push(src);
+    }
+
+    urls
+}
+
+/// References to the image at `url` in sanitized HTML and in Markdown.
+fn image_references(url: &str) -> [String; 2] {
+    let value = url.replace('&', "&amp;").replace('"', "&quot;");
+    [["src=\"", &value, "\""].concat(), ["](", url].concat()]
+}
+
+/// Writes `body` to `path` through a temporary file, so a file with a cached
+/// name is always complete.
+async fn write_file(dir: &Path, name: &str, body: &[u8]) -> Result<(), MediaError> {
+    let path = dir.join(name);
+    let partial = dir.join([".", name, ".part"].concat());
+
+    create_dir_all(dir)
+        .await
+        .map_err(|e| MediaError::Write(dir.to_path_buf(), e))?;
+    write(&partial, body)
+        .await
+        .map_err(|e| MediaError::Write(partial.clone(), e))?;
+    rename(&partial, &path)
+        .await
+        .map_err(|e| MediaError::Write(path, e))
+}
+
+async fn download<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    media: &impl MediaService,
+    source: &dyn HttpUpdateHandle,
+    url: &Url,
+) -> Result<MediaFile, MediaError> {
+    let settings = app_state.media_settings();
+    let response = app_state
+        .http()
+        .get(source, url)
+        .await
+        .map_err(|e| MediaError::Fetch(url.clone(), e))?;
+
+    let size = size_of(&response.body);
+    if size > settings.max_file_size {
+        return Err(MediaError::TooLarge(url.clone(), settings.max_file_size));
+    }
+
+    // The type is read from the content; the declared one only has to agree
+    // that this is an image.
+    let declared = response
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase());
+    let is_declared_image = declared
+        .as_deref()
+        .is_none_or(|value| value.starts_with("image/") || value == "application/octet-stream");
+    let sniffed = sniff(&response.body)
+        .filter(|(content_type, _)| settings.content_types.iter().any(|t| t == content_type));
+
+    let Some((content_type, extension)) = sniffed.filter(|_| is_declared_image) else {
+        let served = declared.unwrap_or_else(|| "unknown".to_string());
+        return Err(MediaError::ContentType(url.clone(), served));
+    };
+
+    let name = format!("{:x}.{extension}", Sha256::digest(&response.body));
+    let path = settings.dir.join(&name);
+
+    if !metadata(&path).await.is_ok_and(|meta| meta.is_file()) {
+        if media.total_size().await?.saturating_add(size) > settings.max_total_size {
+            return Err(MediaError::Full(url.clone()));
+        }
+        write_file(&settings.dir, &name, &response.body).await?;
+        debug!("[media] {url} stored as {name}");
+    }
+
+    Ok(MediaFile {
+        name,
+        content_type: content_type.to_string(),
+        size,
+    })
+}
+
+/// Cached file of `url`, downloading it when it is not cached yet. A URL that
+/// cannot be cached is recorded, and only tried again after the next garbage
+/// collection.
+async fn cached<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    media: &impl MediaService,
+    source: &dyn HttpUpdateHandle,
+    url: &Url,
+) -> Result<Option<MediaFile>, MediaError> {
+    match media.find(url).await? {
+        Some(CachedMedia::Failed) => return Ok(None),
+        Some(CachedMedia::File(file))
+            if metadata(app_state.media_settings().dir.join(&file.name))
+                .await
+                .is_ok_and(|meta| meta.is_file()) =>
+        {
+            return Ok(Some(file));
+        }
+        _ => {}
+    }
+
+    match download(app_state, media, source, url).await {
+        Ok(file) => {
+            media.insert(url, &file).await?;
+            Ok(Some(file))
+        }
+        Err(e @ MediaError::Index(_)) => Err(e),
+        Err(e) => {
+            warn!("[media] {e}");
+            media.insert_failure(url).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Downloads the images of `text`, sanitized HTML or Markdown, that are not
+/// cached yet, with the politeness and credentials of `source`. Does nothing
+/// when caching is off.
+///
+/// `text` itself is stored unchanged, so whether its images are cached never
+/// makes an article look edited; [`localize_images`] points them at their
+/// copies when it is rendered.
+pub async fn cache_images<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    source: &dyn HttpUpdateHandle,
+    text: &str,
+) {
+    if !app_state.media_settings().enabled {
+        return;
+    }
+
+    let urls = image_urls(text);
+    if urls.is_empty() {
+        return;
+    }
+
+    let media = match app_state.media().await {
+        Ok(media) => media,
+        Err(e) => {
+            warn!("[media] {e}");
+            return;
+        }
+    };
+
+    for url in urls {
+        if let Err(e) = cached(app_state, media.as_ref(), source, &url).await {
+            warn!("[media] {e}");
+        }
+    }
+}
+
+/// Cached files of the images of `text`, with the URL each replaces. Files
+/// missing from the media directory are left out.
+async fn cached_images(
+    dir: &Path,
+    media: &impl MediaService,
+    text: &str,
+) -> Result<Vec<(Url, MediaFile)>, MediaIndexError> {
+    let mut files = Vec::new();
+
+    for url in image_urls(text) {
+        if let Some(CachedMedia::File(file)) = media.find(&url).await?
+            && metadata(dir.join(&file.name))
+                .await
+                .is_ok_and(|meta| meta.is_file())
+        {
+            files.push((url, file));
+        }
+    }
+
+    Ok(files)
+}
+
+/// `text` with its cached images pointing under [`MEDIA_PATH`], for
+/// rendering it where the media directory is served. `text` is either the
+/// HTML of a description or the Markdown of full content.
+///
+/// # Errors
+/// Returns [`MediaError`] if the media index cannot be read.
+pub async fn localize_images<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    text: &str,
+) -> Result<String, MediaError> {
+    if !text.contains("<img") && !text.contains("![") {
+        return Ok(text.to_string());
+    }
+
+    let media = app_state.media().await?;
+    let dir = &app_state.media_settings().dir;
+
+    let mut text = text.to_string();
+    for (url, file) in cached_images(dir, media.as_ref(), &text).await? {
+        let local = [MEDIA_PATH, &file.name].concat();
+        for (remote, local) in image_references(url.as_str())
+            .into_iter()
+            .zip(image_references(&local))
+        {
+            text = text.replace(&remote, &local);
+        }
+    }
+
+    Ok(text)
+}
+
+/// Removes the cached files no stored article references, and forgets
+/// recorded failures so their URLs are tried again.
+///
+/// # Errors
+/// Returns [`MediaError`] if the articles, the index or the media directory
+/// cannot be read, or a file cannot be removed.
+pub async fn collect_garbage<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    dry_run: bool,
+) -> Result<GcReport, MediaError> {
+    let dir = &app_state.media_settings().dir;
+
+    let media = app_state.media().await?;
+
+    // Articles stored by earlier versions reference their files in the HTML.
+    let articles: Vec<_> = app_state
+        .news()
+        .get_all()
+        .await
+        .map_err(MediaError::Articles)?
+        .into_iter()
+        .collect();
+    let mut referenced: HashSet<String> = HashSet::new();
+    for article in &articles {
+        for text in std::iter::once(&article.description).chain(&article.content) {
+            referenced.extend(media_references(text).map(String::from));
+            for (_, file) in cached_images(dir, media.as_ref(), text).await? {
+                referenced.insert(file.name);
+            }
+        }
+    }
+
+    let mut entries = match read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(GcReport::default()),
+        Err(e) => return Err(MediaError::Read(dir.clone(), e)),
+    };
+
+    let mut report = GcReport::default();
+    let mut removed = Vec::new();
+    let now = SystemTime::now();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| MediaError::Read(dir.clone(), e))?
+    {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !is_media_name(&name) || referenced.contains(&name) {
+            continue;
+        }
+
+        let path = entry.path();
+        let meta = entry
+            .metadata()
+            .await
+            .map_err(|e| MediaError::Read(path.clone(), e))?;
+        let is_recent = meta
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age < GC_GRACE);
+        if !meta.is_file() || is_recent {
+            continue;
+        }
+
+        if !dry_run {
+            remove_file(&path)
+                .await
+                .map_err(|e| MediaError::Write(path.clone(), e))?;
+        }
+        report.files += 1;
+        report.bytes += meta.len();
+        removed.push(name);
+    }
+
+    if !dry_run {
+        media.forget(&removed).await?;
+        media.forget_failures().await?;
+    }
+
+    Ok(report)
+}
+
+/// Name of the `position`-th attachment of `article` in the download
+/// directory: the article ID followed by the last segment of the URL, so
+/// episodes keep a recognizable name but never collide.
+fn attachment_name(article: Uuid, position: usize, url: &Url) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|segment| !segment.is_empty())
+        .unwrap_or("attachment");
+    let segment: String = segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    match position {
+        0 => format!("{article}-{segment}"),
+        _ => format!("{article}-{position}-{segment}"),
+    }
+}
+
+/// Streams the attachment at `url` to `name` in `dir` through a temporary
+/// file, so a file with that name is always complete, and returns its size.
+async fn download_attachment<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    source: &dyn HttpUpdateHandle,
+    url: &Url,
+    dir: &Path,
+    name: &str,
+) -> Result<u64, MediaError> {
+    let path = dir.join(name);
+    let partial = dir.join([".", name, ".part"].concat());
+    let max_size = app_state.media_settings().max_attachment_size;
+
+    let downloaded = app_state
+        .http()
+        .download(source, url, &partial, max_size)
+        .await;
+    let size = match downloaded {
+        Ok(size) => size,
+        Err(e) => {
+            if let Err(e) = remove_file(&partial).await
+                && e.kind() != ErrorKind::NotFound
+            {
+                warn!("[media] Failed to remove {}: {e}", partial.display());
+            }
+            return Err(MediaError::Fetch(url.clone(), e));
+        }
+    };
+
+    rename(&partial, &path)
+        .await
+        .map_err(|e| MediaError::Write(path, e))?;
+
+    Ok(size)
+}
+
+/// Fetches the attachments of the stored articles into `out_dir`, or only
+/// those of the articles of `source`.
+///
+/// Requests are made with the politeness and credentials of the source of
+/// each article, and bodies are streamed to disk; attachments over
+/// `media.max_attachment_size` fail. Files already in `out_dir` are skipped,
+/// so running it again only fetches new attachments.
+///
+/// # Errors
+/// Returns [`MediaError`] if the sources or articles cannot be loaded, or
+/// `out_dir` cannot be created. Attachments that cannot be fetched or written
+/// are logged and counted as failed.
+pub async fn download_attachments<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    out_dir: &Path,
+    source: Option<Uuid>,
+) -> Result<DownloadReport, MediaError> {
+    let handles: Vec<(Uuid, SourceHandle)> = app_state
+        .sources()
+        .get_all()
+        .await
+        .map_err(MediaError::Sources)?
+        .into_iter()
+        .filter(|item| source.is_none_or(|id| item.id() == id))
+        .map(|item| (item.id(), SourceHandle::new(&item)))
+        .collect();
+
+    let articles: Vec<_> = app_state
+        .news()
+        .get_all()
+        .await
+        .map_err(MediaError::Articles)?
+        .into_iter()
+        .filter(|article| handles.iter().any(|(id, _)| *id == article.parent_id))
+        .collect();
+    let ids: Vec<Uuid> = articles.iter().map(|article| article.id).collect();
+    let attachments = app_state
+        .news()
+        .attachments(&ids)
+        .await
+        .map_err(MediaError::Articles)?;
+
+    create_dir_all(out_dir)
+        .await
+        .map_err(|e| MediaError::Write(out_dir.to_path_buf(), e))?;
+
+    let mut report = DownloadReport::default();
+
+    for article in &articles {
+        let Some((_, handle)) = handles.iter().find(|(id, _)| *id == article.parent_id) else {
+            continue;
+        };
+
+        for (position, attachment) in attachments
+            .get(&article.id)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let name = attachment_name(article.id, position, &attachment.url);
+            if metadata(out_dir.join(&name)).await.is_ok() {
+                report.skipped += 1;
+                continue;
+            }
+
+            let written =
+                download_attachment(app_state, handle, &attachment.url, out_dir, &name).await;
+
+            match written {
+                Ok(size) => {
+                    debug!("[media] {} stored as {name}", attachment.url);
+                    report.files += 1;
+                    report.bytes += size;
+                }
+                Err(e) => {
+                    warn!("[media] {e}");
+                    report.failed += 1;
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Collects garbage every `media.gc_interval` while `collect` runs. A failed
+/// run is logged and retried on the next tick. Returns right away when
+/// caching is off.
+///
+/// # Errors
+/// Never at the moment; the signature matches the other `collect` tasks.
+pub async fn watch_media<S: SourceService, N: NewsService>(
+    app_state: Arc<AppState<S, N>>,
+) -> Result<(), MediaError> {
+    let settings = app_state.media_settings();
+
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    loop {
+        match collect_garbage(&app_state, false).await {
+            Ok(report) if report.files > 0 => {
+                info!(
+                    "[media] {} unreferenced files removed, {} bytes",
+                    report.files, report.bytes
+                );
+            }
+            Ok(_) => {}
+            Err(e) => error!("[media] {e}"),
+        }
+
+        sleep(settings.gc_interval).await;
+    }
+}
@@ -0,0 +1,346 @@
+mod gotify;
+mod matrix;
+mod ntfy;
+mod slack;
+mod telegram;
+
+use crate::state::{AppState, StateError};
+use crate::webhook::next_attempt_delay;
+use chrono::Utc;
+use gotify::GotifyNotifier;
+use matrix::MatrixNotifier;
+use minijinja::Environment;
+use news_core::models::news::{ArticleChange, ChangeKind};
+use news_core::models::notifier::{Notification, NotifierConfig, QueuedNotification, RouteFilter};
+use news_core::services::news::NewsService;
+use news_core::services::notifier::{NotificationRouteService, Notifier, NotifyError, RouteError};
+use news_core::services::source::SourceService;
+use news_core::services::webhook::QueueError;
+use news_sqlite_core::utils::html::html_to_text;
+use news_sqlite_core::utils::secrets::Secrets;
+use ntfy::NtfyNotifier;
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use slack::SlackNotifier;
+use std::sync::Arc;
+use std::time::Duration;
+use telegram::TelegramNotifier;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{debug, error, warn};
+use url::Url;
+use uuid::Uuid;
+
+pub const DEFAULT_TEMPLATE: &str = "{{ title }}\n{{ source }}";
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const POLL_INTERVAL_SECS: u64 = 10;
+const SEND_BATCH_SIZE: u32 = 50;
+
+#[derive(Error, Debug)]
+pub enum DispatchError {
+    #[error(transparent)]
+    State(#[from] StateError),
+
+    #[error(transparent)]
+    Route(#[from] RouteError),
+
+    #[error(transparent)]
+    Queue(#[from] QueueError),
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid notification template: {0}")]
+pub struct TemplateError(#[from] minijinja::Error);
+
+#[derive(Serialize)]
+struct MessageContext<'a> {
+    id: Uuid,
+    title: &'a str,
+    description: &'a str,
+    text: &'a str,
+    source: &'a str,
+}
+
+fn client() -> Result<Client, reqwest::Error> {
+    Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+}
+
+async fn send(notifier: &'static str, request: RequestBuilder) -> Result<(), NotifyError> {
+    let error = |message: String| NotifyError { notifier, message };
+
+    let response = request.send().await.map_err(|e| error(e.to_string()))?;
+
+    match response.status() {
+        status if status.is_success() => Ok(()),
+        status => Err(error(format!("responded with status {status}"))),
+    }
+}
+
+/// Builds the notifier described by `config`, with its credentials resolved
+/// from `secrets`.
+///
+/// # Errors
+/// Returns [`NotifyError`] if a secret is unknown, the resolved Slack webhook
+/// URL is invalid or the HTTP client cannot be created.
+pub fn notifier_for(
+    config: &NotifierConfig,
+    secrets: &Secrets,
+) -> Result<Box<dyn Notifier>, NotifyError> {
+    let error = |message: String| NotifyError {
+        notifier: config.kind(),
+        message,
+    };
+    let resolve = |value: String| {
+        secrets.resolve(&value).map_err(|name| {
+            error(format!(
+                "secret {name} is neither an environment variable nor in the secrets file"
+            ))
+        })
+    };
+    let client = client().map_err(|e| error(e.to_string()))?;
+
+    Ok(match config.clone() {
+        NotifierConfig::Telegram {
+            api_url,
+            bot_token,
+            chat_id,
+        } => Box::new(TelegramNotifier {
+            client,
+            api_url,
+            bot_token: resolve(bot_token)?,
+            chat_id,
+        }),
+        NotifierConfig::Slack { webhook_url } => Box::new(SlackNotifier {
+            client,
+            // The URL is a credential, so it is left out of the error.
+            webhook_url: Url::parse(&resolve(webhook_url)?)
+                .map_err(|e| error(format!("invalid webhook URL: {e}")))?,
+        }),
+        NotifierConfig::Matrix {
+            homeserver,
+            access_token,
+            room_id,
+        } => Box::new(MatrixNotifier {
+            client,
+            homeserver,
+            access_token: resolve(access_token)?,
+            room_id,
+        }),
+        NotifierConfig::Ntfy {
+            server,
+            topic,
+            token,
+        } => Box::new(NtfyNotifier {
+            client,
+            server,
+            topic,
+            token: token.map(resolve).transpose()?,
+        }),
+        NotifierConfig::Gotify {
+            server,
+            token,
+            priority,
+        } => Box::new(GotifyNotifier {
+            client,
+            server,
+            token: resolve(token)?,
+            priority,
+        }),
+    })
+}
+
+/// Checks that a route template compiles before it gets stored.
+///
+/// # Errors
+/// Returns [`TemplateError`] describing the syntax problem.
+pub fn validate_template(template: &str) -> Result<(), TemplateError> {
+    Environment::new().template_from_str(template)?;
+    Ok(())
+}
+
+fn render(template: Option<&str>, context: &MessageContext) -> Result<String, TemplateError> {
+    let env = Environment::new();
+    let template = env.template_from_str(template.unwrap_or(DEFAULT_TEMPLATE))?;
+    Ok(template.render(context)?)
+}
+
+fn is_match(filter: &RouteFilter, change: &ArticleChange, title: &str, text: &str) -> bool {
+    if filter
+        .source_id
+        .is_some_and(|id| id != change.article.parent_id)
+    {
+        return false;
+    }
+
+    if filter
+        .tag
+        .as_ref()
+        .is_some_and(|tag| !change.marks.tags.contains(tag))
+    {
+        return false;
+    }
+
+    if filter.keywords.is_empty() {
+        return true;
+    }
+
+    let title = title.to_lowercase();
+    let text = text.to_lowercase();
+
+    filter.keywords.iter().any(|keyword| {
+        let keyword = keyword.to_lowercase();
+        title.contains(&keyword) || text.contains(&keyword)
+    })
+}
+
+async fn send_queued(
+    routes: &impl NotificationRouteService,
+    queued: QueuedNotification,
+    secrets: &Secrets,
+) -> Result<(), QueueError> {
+    let result = match notifier_for(&queued.config, secrets) {
+        Ok(notifier) => notifier.notify(&queued.notification).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => {
+            debug!(
+                "[notify=\"{}\"] sent \"{}\"",
+                queued.route_id, queued.notification.title
+            );
+            routes.mark_sent(queued.id).await
+        }
+        Err(e) => {
+            let retry_at = next_attempt_delay(queued.attempts + 1).map(|delay| Utc::now() + delay);
+
+            if let Some(at) = retry_at {
+                warn!("[notify=\"{}\"] {e}, retrying at {at}", queued.route_id);
+            } else {
+                error!("[notify=\"{}\"] {e}, giving up", queued.route_id);
+            }
+
+            routes.mark_failed(queued.id, retry_at).await
+        }
+    }
+}
+
+/// Queues newly created articles for every notification route whose filter
+/// matches them, except those a rule marked as read. Returns once they are
+/// queued: [`watch_notifications`] sends them.
+///
+/// # Errors
+/// Returns [`DispatchError`] if the routes cannot be loaded or the queue
+/// cannot be written.
+pub async fn dispatch<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    source: &str,
+    changes: &[ArticleChange],
+) -> Result<(), DispatchError> {
+    let created: Vec<_> = changes
+        .iter()
+        .filter(|change| change.kind == ChangeKind::Created && !change.marks.read)
+        .collect();
+
+    if created.is_empty() {
+        return Ok(());
+    }
+
+    let notifications = app_state.notifications().await?;
+    let routes: Vec<_> = notifications.get_all().await?.into_iter().collect();
+
+    if routes.is_empty() {
+        return Ok(());
+    }
+
+    let mut queued = Vec::new();
+
+    for change in created {
+        let article = &change.article;
+        let title = article.display_title();
+        let text = html_to_text(&article.description).await.unwrap_or_default();
+
+        let context = MessageContext {
+            id: article.id,
+            title,
+            description: &article.description,
+            text: &text,
+            source,
+        };
+
+        for route in &routes {
+            if !is_match(&route.filter, change, title, &text) {
+                continue;
+            }
+
+            match render(route.template.as_deref(), &context) {
+                Ok(message) => queued.push((
+                    route.id,
+                    Notification {
+                        title: title.to_string(),
+                        message,
+                    },
+                )),
+                Err(e) => warn!("[notify=\"{}\"] {e}", route.id),
+            }
+        }
+    }
+
+    if !queued.is_empty() {
+        notifications.enqueue(&queued).await?;
+    }
+
+    Ok(())
+}
+
+/// Sends a batch of the queued notifications that are due, one at a time in
+/// the order they were queued, and records each outcome. Returns how many
+/// were attempted.
+///
+/// # Errors
+/// Returns [`QueueError`] if the notification queue cannot be accessed.
+pub async fn send_due(
+    routes: &impl NotificationRouteService,
+    secrets: &Secrets,
+) -> Result<usize, QueueError> {
+    let due = routes.get_due(SEND_BATCH_SIZE).await?;
+    let count = due.len();
+
+    for queued in due {
+        send_queued(routes, queued, secrets).await?;
+    }
+
+    Ok(count)
+}
+
+/// Continuously sends queued notifications, apart from polling so a slow
+/// endpoint never delays it.
+///
+/// Failed ones are retried with the backoff of webhook deliveries until they
+/// are sent or run out of attempts. Errors of the queue are logged and the
+/// next round tries again.
+///
+/// # Errors
+/// Returns [`StateError`] if the notification queue cannot be opened.
+pub async fn watch_notifications<S: SourceService, N: NewsService>(
+    app_state: Arc<AppState<S, N>>,
+) -> Result<(), StateError> {
+    let routes = app_state.notifications().await?;
+
+    loop {
+        let is_batch_full = match send_due(routes.as_ref(), app_state.secrets()).await {
+            Ok(count) => count == SEND_BATCH_SIZE as usize,
+            Err(e) => {
+                error!("[notifications] {e}");
+                false
+            }
+        };
+
+        if !is_batch_full {
+            sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use news_core::models::notifier::Notification;
+use news_core::services::notifier::{Notifier, NotifyError};
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+
+const TOKEN_HEADER: &str = "X-Gotify-Key";
+
+pub struct GotifyNotifier {
+    pub client: Client,
+    pub server: Url,
+    pub token: String,
+    pub priority: Option<i64>,
+}
+
+#[async_trait]
+impl Notifier for GotifyNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let url = format!("{}/message", self.server.as_str().trim_end_matches('/'));
+
+        let mut body = json!({
+            "title": notification.title,
+            "message": notification.message,
+        });
+
+        // Without an explicit priority Gotify falls back to the application default.
+        if let Some(priority) = self.priority {
+            body["priority"] = json!(priority);
+        }
+
+        let request = self
+            .client
+            .post(url)
+            .header(TOKEN_HEADER, &self.token)
+            .json(&body);
+
+        super::send("gotify", request).await
+    }
+}
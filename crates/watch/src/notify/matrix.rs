@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use news_core::models::notifier::Notification;
+use news_core::services::notifier::{Notifier, NotifyError};
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+use uuid::Uuid;
+
+pub struct MatrixNotifier {
+    pub client: Client,
+    pub homeserver: Url,
+    pub access_token: String,
+    pub room_id: String,
+}
+
+impl MatrixNotifier {
+    fn message_url(&self) -> Result<Url, NotifyError> {
+        let mut url = self.homeserver.clone();
+        let txn_id = Uuid::now_v7().to_string();
+
+        url.path_segments_mut()
+            .map_err(|()| NotifyError {
+                notifier: "matrix",
+                message: format!("{} cannot be a homeserver base URL", self.homeserver),
+            })?
+            .pop_if_empty()
+            .extend([
+                "_matrix",
+                "client",
+                "v3",
+                "rooms",
+                &self.room_id,
+                "send",
+                "m.room.message",
+                &txn_id,
+            ]);
+
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let request = self
+            .client
+            .put(self.message_url()?)
+            .bearer_auth(&self.access_token)
+            .json(&json!({
+                "msgtype": "m.text",
+                "body": notification.message,
+            }));
+
+        super::send("matrix", request).await
+    }
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use news_core::models::notifier::Notification;
+use news_core::services::notifier::{Notifier, NotifyError};
+use reqwest::Client;
+use url::Url;
+
+pub struct NtfyNotifier {
+    pub client: Client,
+    pub server: Url,
+    pub topic: String,
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl Notifier for NtfyNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let url = format!(
+            "{}/{}",
+            self.server.as_str().trim_end_matches('/'),
+            self.topic
+        );
+
+        // Header values must be plain ASCII, so non-ASCII titles go RFC 2047 encoded.
+        let mut request = self
+            .client
+            .post(url)
+            .header("Title", encode_header(&notification.title))
+            .body(notification.message.clone());
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        super::send("ntfy", request).await
+    }
+}
+
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() && !value.contains(['\r', '\n']) {
+        return value.to_string();
+    }
+
+    let hex: String = value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => char::from(byte).to_string(),
+            b' ' => "_".to_string(),
+            _ => format!("={byte:02X}"),
+        })
+        .collect();
+
+    format!("=?UTF-8?Q?{hex}?=")
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use news_core::models::notifier::Notification;
+use news_core::services::notifier::{Notifier, NotifyError};
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+
+pub struct SlackNotifier {
+    pub client: Client,
+    pub webhook_url: Url,
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let request = self
+            .client
+            .post(self.webhook_url.as_str())
+            .json(&json!({ "text": notification.message }));
+
+        super::send("slack", request).await
+    }
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use news_core::models::notifier::Notification;
+use news_core::services::notifier::{Notifier, NotifyError};
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+
+pub struct TelegramNotifier {
+    pub client: Client,
+    pub api_url: Url,
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let url = format!(
+            "{}/bot{}/sendMessage",
+            self.api_url.as_str().trim_end_matches('/'),
+            self.bot_token
+        );
+
+        let request = self.client.post(url).json(&json!({
+            "chat_id": self.chat_id,
+            "text": notification.message,
+        }));
+
+        super::send("telegram", request).await
+    }
+}
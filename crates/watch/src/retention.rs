@@ -0,0 +1,126 @@
+use crate::state::{AppState, StateError};
+use news_core::config::{RetentionSettings, names_source};
+use news_core::models::retention::{PruneReport, RetentionLimits, RetentionPolicy};
+use news_core::models::source::SourceEnum;
+use news_core::services::news::NewsService;
+use news_core::services::retention::{PruneError, RetentionService};
+use news_core::services::source::{GetAllError, SourceService};
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+#[derive(Error, Debug)]
+pub enum RetentionError {
+    #[error(transparent)]
+    State(#[from] StateError),
+
+    #[error("Failed to load content sources list: {0}")]
+    Sources(#[source] GetAllError),
+
+    #[error("Failed to prune articles: {0}")]
+    Prune(#[from] PruneError),
+
+    #[error("Retention policies are not supported with a Postgres database yet")]
+    Unsupported,
+}
+
+/// Resolves the per-source settings against the configured sources. Entries
+/// naming an unknown source are skipped with a warning.
+#[must_use]
+pub fn policy(settings: &RetentionSettings, sources: &[SourceEnum]) -> RetentionPolicy {
+    let overrides = settings
+        .sources
+        .iter()
+        .filter_map(|entry| {
+            let Some(source) = sources
+                .iter()
+                .find(|source| names_source(&entry.source, source.id(), source.key()))
+            else {
+                warn!(
+                    "[retention] source {} is not configured, its limits are ignored",
+                    entry.source
+                );
+                return None;
+            };
+
+            let limits = RetentionLimits {
+                max_age: entry.max_age,
+                max_count: entry.max_per_source,
+            };
+            Some((source.id(), limits))
+        })
+        .collect::<HashMap<_, _>>();
+
+    RetentionPolicy {
+        default: RetentionLimits {
+            max_age: settings.max_age,
+            max_count: settings.max_per_source,
+        },
+        sources: overrides,
+        keep_starred: settings.keep_starred,
+    }
+}
+
+/// Applies the retention policy of `app_state` once.
+///
+/// # Errors
+/// Returns [`RetentionError`] if the sources cannot be loaded, articles
+/// cannot be deleted or they are stored in Postgres.
+pub async fn prune<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+    dry_run: bool,
+) -> Result<PruneReport, RetentionError> {
+    if app_state.storage().is_postgres() {
+        return Err(RetentionError::Unsupported);
+    }
+
+    let sources = app_state
+        .sources()
+        .get_all()
+        .await
+        .map_err(RetentionError::Sources)?
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let policy = policy(app_state.retention_settings(), &sources);
+    if policy.is_unlimited() {
+        return Ok(PruneReport::default());
+    }
+
+    Ok(app_state.retention().await?.prune(&policy, dry_run).await?)
+}
+
+/// Prunes articles every `retention.interval` while `collect` runs. A failed run
+/// is logged and retried on the next tick. Returns right away when the
+/// configuration sets no limits.
+///
+/// # Errors
+/// Never at the moment; the signature matches the other `collect` tasks.
+pub async fn watch_retention<S: SourceService, N: NewsService>(
+    app_state: Arc<AppState<S, N>>,
+) -> Result<(), RetentionError> {
+    let settings = app_state.retention_settings();
+
+    if settings.is_unlimited() {
+        return Ok(());
+    }
+
+    if app_state.storage().is_postgres() {
+        warn!("[retention] {}", RetentionError::Unsupported);
+        return Ok(());
+    }
+
+    loop {
+        match prune(&app_state, false).await {
+            Ok(report) if report.total() > 0 => {
+                info!("[retention] {} articles pruned", report.total());
+            }
+            Ok(_) => {}
+            Err(e) => error!("[retention] {e}"),
+        }
+
+        sleep(settings.interval).await;
+    }
+}
@@ -0,0 +1,278 @@
+//! Rules applied to collected items before they are stored.
+//!
+//! Rules are evaluated in the order they were added, before items are stored.
+//! A `drop` rule keeps the item from being stored at all; the other actions
+//! mark, star or tag the article in the transaction that first stores it, so
+//! edits by the publisher do not undo what the user changed since. A rule
+//! scoped to a tag sees the tags added by the rules before it.
+
+use crate::state::{AppState, StateError};
+use news_core::models::news::{ArticleMarks, News};
+use news_core::models::rule::{Rule, RuleAction, RuleField, RuleFilter, RulePattern};
+use news_core::services::news::NewsService;
+use news_core::services::rule::{RuleError, RuleService};
+use news_core::services::source::SourceService;
+use news_sqlite_core::utils::html::html_to_text;
+use regex::Regex;
+use thiserror::Error;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+const ALL_FIELDS: [RuleField; 4] = [
+    RuleField::Title,
+    RuleField::Description,
+    RuleField::Author,
+    RuleField::Category,
+];
+
+#[derive(Error, Debug)]
+#[error("Invalid regular expression {0}: {1}")]
+pub struct PatternError(String, #[source] regex::Error);
+
+#[derive(Error, Debug)]
+pub enum RulesError {
+    #[error(transparent)]
+    State(#[from] StateError),
+
+    #[error(transparent)]
+    Rule(#[from] RuleError),
+}
+
+/// What rules are matched against: an item about to be stored, or a stored
+/// article.
+pub struct Subject<'a> {
+    pub source_id: Uuid,
+    pub title: &'a str,
+    /// Text of the description, without its markup.
+    pub text: &'a str,
+    pub authors: &'a [String],
+    pub categories: &'a [String],
+    /// Tags the article already has.
+    pub tags: &'a [String],
+}
+
+impl Subject<'_> {
+    const fn values(&self, field: RuleField) -> &[String] {
+        match field {
+            RuleField::Title | RuleField::Description => &[],
+            RuleField::Author => self.authors,
+            RuleField::Category => self.categories,
+        }
+    }
+
+    const fn text_of(&self, field: RuleField) -> Option<&str> {
+        match field {
+            RuleField::Title => Some(self.title),
+            RuleField::Description => Some(self.text),
+            RuleField::Author | RuleField::Category => None,
+        }
+    }
+}
+
+/// What the matching rules do to a subject.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// Rules that matched, in order. Evaluation stops at a `drop` rule.
+    pub matched: Vec<Uuid>,
+    pub drop: bool,
+    pub read: bool,
+    pub star: bool,
+    /// Tags to add, without those the subject already has.
+    pub tags: Vec<String>,
+}
+
+enum Matcher {
+    /// Lowercase keyword.
+    Keyword(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(pattern: &RulePattern) -> Result<Self, PatternError> {
+        match pattern {
+            RulePattern::Keyword(keyword) => Ok(Self::Keyword(keyword.to_lowercase())),
+            RulePattern::Regex(regex) => Regex::new(regex)
+                .map(Self::Regex)
+                .map_err(|e| PatternError(regex.clone(), e)),
+        }
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Self::Keyword(keyword) => value.to_lowercase().contains(keyword),
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+struct CompiledRule {
+    rule: Rule,
+    include: Vec<Matcher>,
+    exclude: Vec<Matcher>,
+}
+
+impl CompiledRule {
+    fn new(rule: Rule) -> Result<Self, PatternError> {
+        let compile = |patterns: &[RulePattern]| {
+            patterns
+                .iter()
+                .map(Matcher::new)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            include: compile(&rule.filter.include)?,
+            exclude: compile(&rule.filter.exclude)?,
+            rule,
+        })
+    }
+
+    fn fields(&self) -> &[RuleField] {
+        match self.rule.filter.fields.as_slice() {
+            [] => &ALL_FIELDS,
+            fields => fields,
+        }
+    }
+
+    /// Whether one of `matchers` is found in one of the fields of `subject`.
+    fn finds(&self, matchers: &[Matcher], subject: &Subject) -> bool {
+        self.fields().iter().any(|field| {
+            let text = subject.text_of(*field).into_iter();
+            let values = subject.values(*field).iter().map(String::as_str);
+
+            text.chain(values)
+                .any(|value| matchers.iter().any(|matcher| matcher.is_match(value)))
+        })
+    }
+
+    fn is_match(&self, subject: &Subject, tags: &[String]) -> bool {
+        let RuleFilter { source_id, tag, .. } = &self.rule.filter;
+
+        source_id.is_none_or(|id| id == subject.source_id)
+            && tag.as_ref().is_none_or(|tag| tags.contains(tag))
+            && (self.include.is_empty() || self.finds(&self.include, subject))
+            && !self.finds(&self.exclude, subject)
+    }
+}
+
+/// Checks that a pattern compiles before it gets stored.
+///
+/// # Errors
+/// Returns [`PatternError`] describing the syntax problem of a regular
+/// expression.
+pub fn validate_pattern(pattern: &RulePattern) -> Result<(), PatternError> {
+    Matcher::new(pattern).map(|_| ())
+}
+
+/// Compiled rules, ready to be evaluated.
+#[derive(Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    /// Compiles `rules`, in their order. A rule whose pattern does not compile
+    /// is logged and left out.
+    #[must_use]
+    pub fn new(rules: impl IntoIterator<Item = Rule>) -> Self {
+        let rules = rules
+            .into_iter()
+            .filter_map(|rule| {
+                let id = rule.id;
+                CompiledRule::new(rule)
+                    .inspect_err(|e| warn!("[rules] rule {id} is skipped: {e}"))
+                    .ok()
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Runs the rules over `subject`.
+    #[must_use]
+    pub fn evaluate(&self, subject: &Subject) -> Outcome {
+        let mut outcome = Outcome::default();
+        let mut tags = subject.tags.to_vec();
+
+        for compiled in &self.rules {
+            if !compiled.is_match(subject, &tags) {
+                continue;
+            }
+            outcome.matched.push(compiled.rule.id);
+
+            match &compiled.rule.action {
+                RuleAction::Drop => {
+                    outcome.drop = true;
+                    break;
+                }
+                RuleAction::MarkRead => outcome.read = true,
+                RuleAction::Star => outcome.star = true,
+                RuleAction::Tag(tag) => {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                        outcome.tags.push(tag.clone());
+                    }
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// `items` without those a `drop` rule matches, each with the marks the
+    /// other rules give it. The marks are stored only when the item creates
+    /// its article.
+    pub async fn triage<T: News>(&self, items: Vec<T>) -> Vec<(T, ArticleMarks)> {
+        if self.is_empty() {
+            return items
+                .into_iter()
+                .map(|item| (item, ArticleMarks::default()))
+                .collect();
+        }
+
+        let mut kept = Vec::with_capacity(items.len());
+        for item in items {
+            let text = html_to_text(item.description()).await.unwrap_or_default();
+            let outcome = self.evaluate(&Subject {
+                source_id: item.parent_id(),
+                title: item.title(),
+                text: &text,
+                authors: item.authors(),
+                categories: item.categories(),
+                tags: &[],
+            });
+
+            if outcome.drop {
+                debug!("[rules] dropped \"{}\"", item.title());
+                continue;
+            }
+
+            let mut tags = outcome.tags;
+            tags.sort();
+            let marks = ArticleMarks {
+                read: outcome.read,
+                starred: outcome.star,
+                tags,
+            };
+            kept.push((item, marks));
+        }
+
+        kept
+    }
+}
+
+/// Rules of `app_state`.
+///
+/// # Errors
+/// Returns [`RulesError`] if the rules cannot be loaded.
+pub async fn load_rules<S: SourceService, N: NewsService>(
+    app_state: &AppState<S, N>,
+) -> Result<RuleSet, RulesError> {
+    let service = app_state.rules().await?;
+    Ok(RuleSet::new(service.get_all().await?))
+}
@@ -1,24 +1,26 @@
-use news_core::services::HttpService;
 mod feed;
 mod feed_item;
 mod update_handle;
 
-use crate::state::LiveAppState;
+use crate::state::AppState;
 use news_core::models::source::atom::AtomSource;
 use news_core::services::WatchError;
-use std::sync::Arc;
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use std::sync::{Arc, Mutex};
 use tracing::info;
 use update_handle::AtomUpdateHandle;
 
-pub async fn watch_atom_feed(
-    app_state: Arc<LiveAppState>,
+pub async fn watch_atom_feed<S: SourceService + 'static, N: NewsService + 'static>(
+    app_state: Arc<AppState<S, N>>,
     source: AtomSource,
 ) -> Result<(), WatchError> {
-    let http = app_state.http().await;
+    let http = app_state.http();
 
     let update_handler = Arc::new(AtomUpdateHandle {
         app_state: app_state.clone(),
         source,
+        failed_pages: Mutex::default(),
     });
 
     info!("[atom_feed=\"{0}\"] watch news", update_handler.source.url);
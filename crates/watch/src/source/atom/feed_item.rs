@@ -1,12 +1,16 @@
+use crate::language::detect_item;
+use crate::summary::{headline, summarize};
 use crate::utils::html::sanitize_html;
 use async_trait::async_trait;
-use feed_rs::model::{Entry, Feed};
+use feed_rs::model::{Entry, Feed, Link, MediaObject};
 use futures::future::try_join_all;
 use futures::{TryFutureExt, try_join};
-use news_core::models::news::News;
+use news_core::models::news::{ArticleMarks, Attachment, News};
 use news_core::models::source::atom::AtomSource;
-use std::sync::Arc;
+use news_sqlite_core::utils::html::html_to_text;
+use std::time::Duration;
 use thiserror::Error;
+use url::Url;
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -14,13 +18,21 @@ pub struct AtomItem {
     parent_id: Uuid,
     source_key: String,
 
-    // link: Option<String>,
+    link: Option<Url>,
     // guid: String,
     title: String,
     description: String,
     // image: Option<Url>,
     // published_at: Option<String>,
     content: Option<String>,
+    attachments: Vec<Attachment>,
+    authors: Vec<String>,
+    categories: Vec<String>,
+    language: Option<&'static str>,
+    summary: Option<String>,
+    /// Only for entries whose title is blank.
+    headline: Option<String>,
+    marks: ArticleMarks,
 }
 
 #[derive(Error, Debug)]
@@ -32,27 +44,104 @@ pub enum AtomItemFromEntryError {
     TitleEmpty(String),
     #[error("Atom title is not sanitized {0}")]
     TitleSanitize(String),
+    #[error("Atom title is not converted to text {0}")]
+    TitleText(String),
 
     #[error("Atom description is empty {0}")]
     DescriptionEmpty(String),
     #[error("Atom description is not sanitized {0}")]
     DescriptionSanitize(String),
+    #[error("Atom description is not converted to text {0}")]
+    DescriptionText(String),
 }
 
+impl AtomItem {
+    pub fn set_content(&mut self, content: String) {
+        self.content = Some(content);
+    }
+
+    pub fn set_marks(&mut self, marks: ArticleMarks) {
+        self.marks = marks;
+    }
+}
+
+fn is_web_url(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+/// Whole seconds of `duration`, as stored.
+const fn whole_seconds(duration: Duration) -> Duration {
+    Duration::from_secs(duration.as_secs())
+}
+
+/// Files of a Media RSS group, an RSS `<enclosure>` or the iTunes tags of an
+/// item, which share the group's duration and first thumbnail.
+fn media_attachments(media: &MediaObject) -> impl Iterator<Item = Attachment> + '_ {
+    let thumbnail = media
+        .thumbnails
+        .first()
+        .and_then(|thumbnail| Url::parse(&thumbnail.image.uri).ok())
+        .filter(is_web_url);
+
+    media.content.iter().filter_map(move |content| {
+        let url = content.url.clone().filter(is_web_url)?;
+
+        Some(Attachment {
+            url,
+            content_type: content.content_type.as_ref().map(ToString::to_string),
+            length: content.size,
+            duration: content.duration.or(media.duration).map(whole_seconds),
+            thumbnail: thumbnail.clone(),
+        })
+    })
+}
+
+/// An Atom `<link rel="enclosure">`.
+fn link_attachment(link: &Link, base: &Url) -> Option<Attachment> {
+    if link.rel.as_deref() != Some("enclosure") {
+        return None;
+    }
+
+    Some(Attachment {
+        url: base.join(&link.href).ok().filter(is_web_url)?,
+        content_type: link.media_type.clone(),
+        length: link.length,
+        duration: None,
+        thumbnail: None,
+    })
+}
+
+/// Trimmed `values` without empty ones and repeats, in their order.
+fn distinct(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut distinct: Vec<String> = Vec::new();
+
+    for value in values {
+        let value = value.trim();
+        if !value.is_empty() && !distinct.iter().any(|known| known == value) {
+            distinct.push(value.to_string());
+        }
+    }
+
+    distinct
+}
+
+/// Items of the feed served from `feed_url`.
 pub async fn atom_items_parse(
     src: &AtomSource,
+    feed_url: &Url,
     data: Feed,
-) -> Result<Vec<Arc<AtomItem>>, AtomItemFromEntryError> {
-    let news_futures = data.entries.into_iter().map(async |item| {
-        let news_item = try_atom_item_from_entry(src, item).await?;
-        Ok(Arc::new(news_item))
-    });
+) -> Result<Vec<AtomItem>, AtomItemFromEntryError> {
+    let news_futures = data
+        .entries
+        .into_iter()
+        .map(async |item| try_atom_item_from_entry(src, feed_url, item).await);
 
     try_join_all(news_futures).await
 }
 
 pub async fn try_atom_item_from_entry(
     source: &AtomSource,
+    feed_url: &Url,
     item: Entry,
 ) -> Result<AtomItem, AtomItemFromEntryError> {
     let mut links = item.links;
@@ -64,6 +153,43 @@ pub async fn try_atom_item_from_entry(
         .find(|link| !link.href.is_empty())
         .map(|link| link.href.clone());
 
+    // Enclosures and related links are not the page of the entry.
+    let page = links
+        .iter()
+        .filter(|link| !link.href.is_empty())
+        .find(|link| link.rel.as_deref().is_none_or(|rel| rel == "alternate"))
+        .map_or_else(|| link.clone(), |link| Some(link.href.clone()))
+        .and_then(|page| feed_url.join(&page).ok());
+
+    // Relative references in the text resolve against the `xml:base` of the
+    // content, else the page of the entry, else the feed.
+    let base = item
+        .base
+        .as_deref()
+        .and_then(|base| feed_url.join(base).ok())
+        .or_else(|| page.clone())
+        .unwrap_or_else(|| feed_url.clone());
+
+    let mut attachments: Vec<Attachment> = Vec::new();
+    let found = item.media.iter().flat_map(media_attachments).chain(
+        links
+            .iter()
+            .filter_map(|link| link_attachment(link, feed_url)),
+    );
+    for attachment in found {
+        if !attachments.iter().any(|known| known.url == attachment.url) {
+            attachments.push(attachment);
+        }
+    }
+
+    let authors = distinct(item.authors.into_iter().map(|person| person.name));
+    // The label is meant for display; the term may be a URI or a slug.
+    let categories = distinct(
+        item.categories
+            .into_iter()
+            .map(|category| category.label.unwrap_or(category.term)),
+    );
+
     let id = match (item.id.is_empty(), &link) {
         (false, _) => &item.id,
         (true, Some(url)) => url,
@@ -92,13 +218,24 @@ pub async fn try_atom_item_from_entry(
     let description =
         description.ok_or_else(|| AtomItemFromEntryError::DescriptionEmpty(id.clone()))?;
 
-    let future_title =
-        sanitize_html(&title).map_err(|e| AtomItemFromEntryError::TitleSanitize(e.to_string()));
+    let future_title = sanitize_html(&title, &base)
+        .map_err(|e| AtomItemFromEntryError::TitleSanitize(e.to_string()));
 
-    let future_description = sanitize_html(&description)
+    let future_description = sanitize_html(&description, &base)
         .map_err(|e| AtomItemFromEntryError::DescriptionSanitize(e.to_string()));
 
     let (title, description) = try_join!(future_title, future_description)?;
+    let (title_text, text) = try_join!(
+        html_to_text(&title).map_err(|e| AtomItemFromEntryError::TitleText(e.to_string())),
+        html_to_text(&description)
+            .map_err(|e| AtomItemFromEntryError::DescriptionText(e.to_string())),
+    )?;
+    let language = detect_item(&title_text, &text);
+    let headline = match title_text.trim() {
+        "" => headline(&text),
+        _ => None,
+    };
+    let summary = summarize(&text);
 
     // let published_at = item.published.map(|dt| dt.to_string());
 
@@ -106,11 +243,18 @@ pub async fn try_atom_item_from_entry(
         parent_id,
         source_key: id.clone(),
 
+        link: page,
         title,
         description,
         content: None,
+        attachments,
+        authors,
+        categories,
+        language,
+        summary,
+        headline,
+        marks: ArticleMarks::default(),
         // guid: item.id,
-        // link,
         // image: None,
         // published_at,
     })
@@ -133,4 +277,29 @@ fn description(&self) -> &str {
     fn content(&self) -> &Option<String> {
         &self.content
     }
+    fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+    fn authors(&self) -> &[String] {
+        &self.authors
+    }
+    fn categories(&self) -> &[String] {
+        &self.categories
+    }
+    /// Page of the entry, as an absolute URL.
+    fn link(&self) -> Option<&Url> {
+        self.link.as_ref()
+    }
+    fn language(&self) -> Option<&str> {
+        self.language
+    }
+    fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+    fn headline(&self) -> Option<&str> {
+        self.headline.as_deref()
+    }
+    fn marks(&self) -> &ArticleMarks {
+        &self.marks
+    }
 }
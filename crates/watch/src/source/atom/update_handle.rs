@@ -1,79 +1,242 @@
 use super::feed::{AtomFeedParseError, atom_feed_parse};
-use super::feed_item::{AtomItemFromEntryError, atom_items_parse};
-use crate::state::{LiveAppState, StateError};
+use super::feed_item::{AtomItem, AtomItemFromEntryError, atom_items_parse};
+use crate::duplicates::assign_stories;
+use crate::language::without_other_languages;
+use crate::media::cache_images;
+use crate::notify::dispatch;
+use crate::rules::load_rules;
+use crate::state::AppState;
+use crate::utils::readability::page_to_content;
 use async_trait::async_trait;
+use news_core::models::news::News;
 use news_core::models::source::atom::AtomSource;
+use news_core::models::source::request::RequestSettings;
 use news_core::services::news::NewsService;
 use news_core::services::news::UpdateError;
+use news_core::services::source::SourceService;
 use news_core::services::{HandleError, HttpResponse, HttpUpdateHandle};
-use std::sync::Arc;
-use tracing::debug;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+use tracing::{debug, info, warn};
 use url::Url;
+use uuid::Uuid;
 
-pub struct AtomUpdateHandle {
-    pub app_state: Arc<LiveAppState>,
+pub struct AtomUpdateHandle<S, N> {
+    pub app_state: Arc<AppState<S, N>>,
     pub source: AtomSource,
+    /// Items whose page failed, by source key, with when it may be fetched
+    /// again.
+    pub failed_pages: Mutex<HashMap<String, Instant>>,
 }
 
 // ========== Error conversions ==========
 
 impl From<AtomFeedParseError> for HandleError {
     fn from(e: AtomFeedParseError) -> Self {
-        match e {
-            AtomFeedParseError::GetTextError(_e) => todo!(),
-            AtomFeedParseError::ParseError(_e) => todo!(),
-        }
+        Self(Box::new(e))
     }
 }
 
 impl From<AtomItemFromEntryError> for HandleError {
     fn from(e: AtomItemFromEntryError) -> Self {
-        match e {
-            AtomItemFromEntryError::IdEmpty => todo!(),
-            AtomItemFromEntryError::TitleEmpty(_e) => todo!(),
-            AtomItemFromEntryError::TitleSanitize(_e) => todo!(),
-            AtomItemFromEntryError::DescriptionEmpty(_e) => todo!(),
-            AtomItemFromEntryError::DescriptionSanitize(_e) => todo!(),
-        }
+        Self(Box::new(e))
     }
 }
 
-impl From<StateError> for HandleError {
-    fn from(_e: StateError) -> Self {
-        todo!()
+fn map_news_update_error(e: UpdateError) -> HandleError {
+    HandleError(Box::new(e))
+}
+
+impl<S: SourceService, N: NewsService> AtomUpdateHandle<S, N> {
+    /// Stores the new address of a permanently moved feed; failing to do so
+    /// only costs a redirect on every poll. A feed with request settings is
+    /// kept at its address when it moves to another origin, so its headers and
+    /// credentials are not sent there on later polls.
+    async fn record_move(&self, url: &Url) {
+        if *url == self.source.url {
+            return;
+        }
+        if url.origin() != self.source.url.origin() && !self.source.request.is_empty() {
+            warn!(
+                "[atom_feed=\"{0}\"] Moved permanently to {url}, not recorded because it has request settings for another origin; update the source to poll the new address",
+                self.source.url
+            );
+            return;
+        }
+
+        match self
+            .app_state
+            .sources()
+            .move_atom(self.source.id, url.clone())
+            .await
+        {
+            Ok(()) => info!(
+                "[atom_feed=\"{0}\"] Moved permanently to {url}; per-source settings naming the old URL stop applying on restart, name the source by its id {1} instead",
+                self.source.url, self.source.id
+            ),
+            Err(e) => warn!(
+                "[atom_feed=\"{0}\"] Failed to record move to {url}: {e}",
+                self.source.url
+            ),
+        }
     }
 }
 
-fn map_news_update_error(e: UpdateError) -> HandleError {
-    match e {
-        UpdateError::UpdateItem {
-            id: _id,
-            title: _title,
-            error: _error,
-        } => todo!(),
-        UpdateError::Internal(_e) => todo!(),
+impl<S: SourceService + 'static, N: NewsService + 'static> AtomUpdateHandle<S, N> {
+    /// Completes `items` with the main text of their pages. Text stored
+    /// before is reused, so each page is fetched once; items whose page fails
+    /// keep their summary and are tried again after `full_content_retry`.
+    ///
+    /// Fails when the stored text cannot be read, rather than storing the
+    /// items without it.
+    async fn fill_content(&self, items: &mut [AtomItem]) -> Result<(), HandleError> {
+        let source_keys: Vec<_> = items.iter().map(News::source_key).collect();
+        let mut stored = self
+            .app_state
+            .news()
+            .contents(&source_keys)
+            .await
+            .map_err(|e| HandleError(Box::new(e)))?;
+
+        let now = Instant::now();
+        self.failed_pages
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, retry_at| *retry_at > now);
+
+        for item in items {
+            if let Some(content) = stored.remove(item.source_key()) {
+                item.set_content(content);
+                continue;
+            }
+            let Some(link) = item.link().cloned() else {
+                continue;
+            };
+            if self
+                .failed_pages
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains_key(item.source_key())
+            {
+                continue;
+            }
+
+            match self.page_content(&link).await {
+                Ok(Some(content)) => {
+                    item.set_content(content);
+                    continue;
+                }
+                Ok(None) => debug!(
+                    "[atom_feed=\"{0}\"] No article text found in {link}",
+                    self.source.url
+                ),
+                Err(e) => warn!(
+                    "[atom_feed=\"{0}\"] Failed to fetch full content of {link}: {e}",
+                    self.source.url
+                ),
+            }
+
+            let retry = self.app_state.watch_settings().full_content_retry;
+            self.failed_pages
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(item.source_key().to_string(), Instant::now() + retry);
+        }
+
+        Ok(())
+    }
+
+    async fn page_content(&self, link: &Url) -> Result<Option<String>, HandleError> {
+        let response = self
+            .app_state
+            .http()
+            .get(self, link)
+            .await
+            .map_err(|e| HandleError(Box::new(e)))?;
+
+        page_to_content(response.text(), &response.url)
+            .await
+            .map_err(|e| HandleError(Box::new(e)))
     }
 }
 
 // ========== HttpUpdateHandle ==========
 
 #[async_trait]
-impl HttpUpdateHandle for AtomUpdateHandle {
+impl<S: SourceService + 'static, N: NewsService + 'static> HttpUpdateHandle
+    for AtomUpdateHandle<S, N>
+{
     fn url(&self) -> &Url {
         &self.source.url
     }
 
+    fn source_id(&self) -> Option<Uuid> {
+        Some(self.source.id)
+    }
+
+    fn request(&self) -> Option<&RequestSettings> {
+        Some(&self.source.request).filter(|request| !request.is_empty())
+    }
+
     async fn handle(&self, response: HttpResponse) -> Result<(), HandleError> {
-        let atom_channel = atom_feed_parse(response).await?;
-        let update = atom_items_parse(&self.source, atom_channel).await?;
+        if let Some(url) = &response.moved_to {
+            self.record_move(url).await;
+        }
+
+        let atom_channel = atom_feed_parse(&response)?;
+        let items = atom_items_parse(&self.source, &response.url, atom_channel).await?;
+        let items = without_other_languages(
+            items,
+            self.app_state
+                .watch_settings()
+                .languages(self.source.id, self.source.url.as_str()),
+        );
+
+        // Dropped items are not worth fetching pages and images for. Without
+        // the rules, the poll fails and is retried rather than storing items
+        // the user filters out.
+        let rules = load_rules(&self.app_state)
+            .await
+            .map_err(|e| HandleError(Box::new(e)))?;
+        let mut items: Vec<_> = rules
+            .triage(items)
+            .await
+            .into_iter()
+            .map(|(mut item, marks)| {
+                item.set_marks(marks);
+                item
+            })
+            .collect();
+
+        if self
+            .app_state
+            .watch_settings()
+            .full_content(self.source.id, self.source.url.as_str())
+        {
+            self.fill_content(&mut items).await?;
+        }
+        for item in &items {
+            cache_images(&self.app_state, self, item.description()).await;
+            if let Some(content) = item.content() {
+                cache_images(&self.app_state, self, content).await;
+            }
+        }
+        let update: Vec<_> = items.into_iter().map(Arc::new).collect();
 
-        self.app_state
+        let changes = self
+            .app_state
             .news()
-            .await?
             .update(&update)
             .await
             .map_err(map_news_update_error)?;
 
+        assign_stories(&self.app_state, &changes).await;
+
+        if let Err(e) = dispatch(&self.app_state, self.source.url.as_str(), &changes).await {
+            warn!("[atom_feed=\"{0}\"] {e}", self.source.url);
+        }
+
         debug!(
             "[atom_feed=\"{0}\"] Updated {1} news items",
             self.source.url,
@@ -3,18 +3,19 @@
 mod update_handle;
 
 use self::update_handle::TelegramWebUpdateHandle;
-use crate::state::LiveAppState;
+use crate::state::AppState;
 use news_core::models::source::telegram::TelegramSource;
-use news_core::services::HttpService;
 use news_core::services::WatchError;
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
 use std::sync::Arc;
 use tracing::info;
 
-pub async fn watch_telegram_channel(
-    app_state: Arc<LiveAppState>,
+pub async fn watch_telegram_channel<S: SourceService + 'static, N: NewsService + 'static>(
+    app_state: Arc<AppState<S, N>>,
     source: TelegramSource,
 ) -> Result<(), WatchError> {
-    let schedule = app_state.http().await;
+    let schedule = app_state.http();
     let update_handler = Arc::new(TelegramWebUpdateHandle {
         app_state: app_state.clone(),
         url: source.public_url.clone(),
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use news_core::models::news::News;
+use news_core::models::news::{ArticleMarks, Attachment, News};
+use url::Url;
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -9,6 +10,13 @@ pub struct TelegramItem {
 
     pub title: String,
     pub description: String,
+    /// First page outside Telegram the message links to.
+    pub link: Option<Url>,
+    pub language: Option<&'static str>,
+    pub summary: Option<String>,
+    /// First line or sentence of the text, where `title` is cut at a length.
+    pub headline: Option<String>,
+    pub marks: ArticleMarks,
 }
 
 #[async_trait]
@@ -28,4 +36,28 @@ fn description(&self) -> &str {
     fn content(&self) -> &Option<String> {
         &None
     }
+    fn attachments(&self) -> &[Attachment] {
+        &[]
+    }
+    fn authors(&self) -> &[String] {
+        &[]
+    }
+    fn categories(&self) -> &[String] {
+        &[]
+    }
+    fn link(&self) -> Option<&Url> {
+        self.link.as_ref()
+    }
+    fn language(&self) -> Option<&str> {
+        self.language
+    }
+    fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+    fn headline(&self) -> Option<&str> {
+        self.headline.as_deref()
+    }
+    fn marks(&self) -> &ArticleMarks {
+        &self.marks
+    }
 }
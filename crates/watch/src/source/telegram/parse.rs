@@ -1,9 +1,11 @@
-use crate::utils::html::{ConvertError, SanitizeError, html_to_text, sanitize_html};
+use crate::utils::html::{SanitizeError, sanitize_html};
 use futures::TryFutureExt;
-use futures::future::{try_join, try_join_all};
+use futures::future::{try_join_all, try_join3};
+use news_sqlite_core::utils::html::{ConvertError, html_to_text};
 use news_sqlite_core::utils::text::truncate_with_dots;
 use scraper::{Html, Selector};
 use thiserror::Error;
+use url::Url;
 
 const TITLE_MAX_LENGTH: usize = 100;
 
@@ -21,15 +23,80 @@ pub enum ParseHtmlError {
 
     #[error("Failed to construct message selector: {0}")]
     MessageSelector(String),
+
+    #[error("Failed to construct photo selector: {0}")]
+    PhotoSelector(String),
+    #[error("Failed to construct link selector: {0}")]
+    LinkSelector(String),
+    #[error("Failed to sanitize photos: {0}")]
+    PhotoConvert(#[source] SanitizeError),
+}
+
+/// A message of a channel page.
+pub struct TelegramMessage {
+    pub title: String,
+    /// Sanitized text of the message.
+    pub body: String,
+    /// Sanitized `<img>` elements of the photos of the message, empty if it
+    /// has none.
+    pub photos: String,
+    /// First page outside Telegram the text links to.
+    pub link: Option<Url>,
+}
+
+/// The text of a message on one line, with its blocks and line breaks kept
+/// apart by a space.
+fn one_line_title(text: &str) -> String {
+    let title = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_with_dots(&title, TITLE_MAX_LENGTH)
+}
+
+/// URL in the `background-image` of a photo wrapper's inline style.
+fn background_image(style: &str) -> Option<&str> {
+    const PREFIX: &str = "background-image:url(";
+    let start = style.find(PREFIX)? + PREFIX.len();
+    let len = style[start..].find(')')?;
+
+    Some(style[start..start + len].trim_matches(|c| c == '\'' || c == '"'))
+}
+
+/// `href` resolved against the page `url`, unless it points back to Telegram
+/// or is not a web page.
+fn external_link(href: &str, url: &Url) -> Option<Url> {
+    let link = url.join(href).ok()?;
+    let host = link.host_str()?;
+
+    let telegram = ["t.me", "telegram.me", "telegram.org"]
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{domain}")));
+
+    (matches!(link.scheme(), "http" | "https") && !telegram).then_some(link)
+}
+
+fn photos_html(urls: &[&str]) -> String {
+    urls.iter()
+        .map(|url| {
+            let url = url.replace('&', "&amp;").replace('"', "&quot;");
+            ["<p><img src=\"", &url, "\"></p>"].concat()
+        })
+        .collect()
 }
 
-pub async fn parse_html(html_content: &str) -> Result<Vec<(String, String)>, ParseHtmlError> {
+/// Messages of the channel page served from `url`.
+pub async fn parse_html(
+    html_content: &str,
+    url: &Url,
+) -> Result<Vec<TelegramMessage>, ParseHtmlError> {
     let message_selector = Selector::parse(".tgme_widget_message_wrap")
         .map_err(|e| ParseHtmlError::MessageSelector(e.to_string()))?;
     let title_selector = Selector::parse(".tgme_widget_message_text")
         .map_err(|e| ParseHtmlError::TitleSelector(e.to_string()))?;
     let body_selector = Selector::parse(".tgme_widget_message_text")
         .map_err(|e| ParseHtmlError::BodySelector(e.to_string()))?;
+    let photo_selector = Selector::parse(".tgme_widget_message_photo_wrap")
+        .map_err(|e| ParseHtmlError::PhotoSelector(e.to_string()))?;
+    let link_selector = Selector::parse(".tgme_widget_message_text a[href]")
+        .map_err(|e| ParseHtmlError::LinkSelector(e.to_string()))?;
 
     let result: Vec<_> = {
         let document = Html::parse_document(html_content);
@@ -39,23 +106,38 @@ pub async fn parse_html(html_content: &str) -> Result<Vec<(String, String)>, Par
             .filter_map(|element| {
                 let title = element.select(&title_selector).next()?.html();
                 let body = element.select(&body_selector).next()?.html();
-                Some((title, body))
+                let photos: Vec<_> = element
+                    .select(&photo_selector)
+                    .filter_map(|photo| photo.attr("style").and_then(background_image))
+                    .collect();
+                let link = element
+                    .select(&link_selector)
+                    .filter_map(|link| link.attr("href"))
+                    .find_map(|href| external_link(href, url));
+                Some((title, body, photos_html(&photos), link))
             })
             .collect()
     };
 
-    let html_futures = result
-        .into_iter()
-        .map(|(title_html, body_html)| async move {
-            let title_text = html_to_text(&title_html)
-                .map_ok(|text| truncate_with_dots(&text, TITLE_MAX_LENGTH))
-                .map_err(ParseHtmlError::TitleConvert);
+    let html_futures =
+        result
+            .into_iter()
+            .map(|(title_html, body_html, photos_html, link)| async move {
+                let title_text = html_to_text(&title_html)
+                    .map_ok(|text| one_line_title(&text))
+                    .map_err(ParseHtmlError::TitleConvert);
 
-            let body_text = sanitize_html(&body_html).map_err(ParseHtmlError::BodyConvert);
-            let result = try_join(title_text, body_text).await?;
+                let body_text = sanitize_html(&body_html, url).map_err(ParseHtmlError::BodyConvert);
+                let photos = sanitize_html(&photos_html, url).map_err(ParseHtmlError::PhotoConvert);
+                let (title, body, photos) = try_join3(title_text, body_text, photos).await?;
 
-            Ok(result)
-        });
+                Ok(TelegramMessage {
+                    title,
+                    body,
+                    photos,
+                    link,
+                })
+            });
 
     let result = try_join_all(html_futures).await?;
 
@@ -1,15 +1,26 @@
 use super::item::TelegramItem;
 use super::parse::{ParseHtmlError, parse_html};
-use crate::state::LiveAppState;
+use crate::duplicates::assign_stories;
+use crate::language::{detect_item, without_other_languages};
+use crate::media::cache_images;
+use crate::notify::dispatch;
+use crate::rules::load_rules;
+use crate::state::AppState;
+use crate::summary::{headline, summarize};
 use async_trait::async_trait;
+use news_core::models::news::ArticleMarks;
 use news_core::models::source::telegram::TelegramSource;
 use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
 use news_core::services::{HandleError, HttpResponse, HttpUpdateHandle};
+use news_sqlite_core::utils::html::html_to_text;
 use std::sync::Arc;
+use tracing::warn;
 use url::Url;
+use uuid::Uuid;
 
-pub struct TelegramWebUpdateHandle {
-    pub app_state: Arc<LiveAppState>,
+pub struct TelegramWebUpdateHandle<S, N> {
+    pub app_state: Arc<AppState<S, N>>,
     pub source: TelegramSource,
     pub url: Url,
 }
@@ -18,50 +29,89 @@ pub struct TelegramWebUpdateHandle {
 
 impl From<ParseHtmlError> for HandleError {
     fn from(err: ParseHtmlError) -> Self {
-        match err {
-            ParseHtmlError::TitleSelector(_e) => todo!(),
-            ParseHtmlError::TitleConvert(_e) => todo!(),
-            ParseHtmlError::BodySelector(_e) => todo!(),
-            ParseHtmlError::BodyConvert(_e) => todo!(),
-            ParseHtmlError::MessageSelector(_e) => todo!(),
-        }
+        Self(Box::new(err))
     }
 }
 
 // ========== HttpUpdateHandle ==========
 
 #[async_trait]
-impl HttpUpdateHandle for TelegramWebUpdateHandle {
+impl<S: SourceService + 'static, N: NewsService + 'static> HttpUpdateHandle
+    for TelegramWebUpdateHandle<S, N>
+{
     fn url(&self) -> &Url {
         &self.url
     }
 
+    fn source_key(&self) -> &str {
+        &self.source.username
+    }
+
+    fn source_id(&self) -> Option<Uuid> {
+        Some(self.source.id)
+    }
+
     async fn handle(&self, response: HttpResponse) -> Result<(), HandleError> {
-        let html_content = response
-            .text()
-            .await
-            .map_err(|e| HandleError(Box::new(e)))?;
+        let html_content = response.text();
 
-        let result = parse_html(&html_content).await?;
+        let result = parse_html(&html_content, &self.source.public_url).await?;
+
+        // Photos are not part of the key, which stays the text the message
+        // was stored under before they were kept.
+        let mut items: Vec<TelegramItem> = Vec::with_capacity(result.len());
+        for message in result {
+            let text = html_to_text(&message.body)
+                .await
+                .map_err(|e| HandleError(Box::new(e)))?;
+            items.push(TelegramItem {
+                parent_id: self.source.id,
+                language: detect_item(&message.title, &text),
+                summary: summarize(&text),
+                headline: headline(&text),
+                description: message.body.clone() + &message.photos,
+                source_key: message.body,
+                title: message.title,
+                link: message.link,
+                marks: ArticleMarks::default(),
+            });
+        }
+        let items = without_other_languages(
+            items,
+            self.app_state
+                .watch_settings()
+                .languages(self.source.id, &self.source.username),
+        );
 
-        let update: Vec<Arc<TelegramItem>> = result
+        // Without the rules, the poll fails and is retried rather than storing
+        // items the user filters out.
+        let rules = load_rules(&self.app_state)
+            .await
+            .map_err(|e| HandleError(Box::new(e)))?;
+        let items: Vec<_> = rules
+            .triage(items)
+            .await
             .into_iter()
-            .map(|(title, description)| {
-                Arc::new(TelegramItem {
-                    parent_id: self.source.id,
-                    source_key: description.clone(),
-                    title,
-                    description,
-                })
-            })
+            .map(|(item, marks)| TelegramItem { marks, ..item })
             .collect();
 
-        let news = self.app_state.news().await?;
+        for item in &items {
+            cache_images(&self.app_state, self, &item.description).await;
+        }
+        let update: Vec<Arc<TelegramItem>> = items.into_iter().map(Arc::new).collect();
+
+        let news = self.app_state.news();
 
-        news.update(&update)
+        let changes = news
+            .update(&update)
             .await
             .map_err(|e| HandleError(Box::new(e)))?;
 
+        assign_stories(&self.app_state, &changes).await;
+
+        if let Err(e) = dispatch(&self.app_state, &self.source.username, &changes).await {
+            warn!("[telegram_channel=\"{0}\"] {e}", self.source.username);
+        }
+
         Ok(())
     }
 }
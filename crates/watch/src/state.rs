@@ -1,68 +1,322 @@
+use crate::storage::{Access, AnyNewsService, AnySourceService, Storage, StorageError};
+use news_core::config::{
+    DuplicateSettings, MediaSettings, RetentionSettings, Settings, WatchSettings,
+};
 use news_core::services::HttpService;
 use news_core::services::news::NewsService;
 use news_core::services::source::SourceService;
-use news_sqlite_core::db::init::DBInitError;
-use news_sqlite_core::services::http::LiveHttpService;
-use news_sqlite_core::services::news::SqliteNewsService;
-use news_sqlite_core::services::source::SqliteSourceService;
+use news_sqlite_core::services::digest::SqliteDigestService;
+use news_sqlite_core::services::http::{HttpClientError, LiveHttpService};
+use news_sqlite_core::services::media::SqliteMediaService;
+use news_sqlite_core::services::notifier::SqliteNotificationRouteService;
+use news_sqlite_core::services::retention::SqliteRetentionService;
+use news_sqlite_core::services::rule::SqliteRuleService;
+use news_sqlite_core::services::webhook::SqliteWebhookService;
+use news_sqlite_core::utils::secrets::{Secrets, SecretsError};
+use sqlx::SqlitePool;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::OnceCell;
 
-pub struct LiveAppState {
-    sources: OnceCell<Arc<SqliteSourceService>>,
-    news: OnceCell<Arc<SqliteNewsService>>,
-    http: OnceCell<Arc<LiveHttpService>>,
+/// Services shared by the watchers and commands of one application instance.
+///
+/// Nothing in it is global: a process may hold several states over different
+/// databases, and tests build one over in-memory services with
+/// [`AppState::builder`].
+pub struct AppState<S = AnySourceService, N = AnyNewsService> {
+    storage: Storage,
+    retention_settings: RetentionSettings,
+    watch_settings: WatchSettings,
+    media_settings: MediaSettings,
+    duplicate_settings: DuplicateSettings,
+    sources: Arc<S>,
+    news: Arc<N>,
+    http: Arc<dyn HttpService>,
+    secrets: Secrets,
+    webhooks: OnceCell<Arc<SqliteWebhookService>>,
+    notifications: OnceCell<Arc<SqliteNotificationRouteService>>,
+    retention: OnceCell<Arc<SqliteRetentionService>>,
+    digests: OnceCell<Arc<SqliteDigestService>>,
+    media: OnceCell<Arc<SqliteMediaService>>,
+    rules: OnceCell<Arc<SqliteRuleService>>,
 }
 
 #[derive(Error, Debug)]
-#[error("failed to initialize {0}")]
-pub struct StateError(#[source] DBInitError);
+pub enum StateError {
+    #[error("failed to initialize {0}")]
+    Storage(#[source] StorageError),
 
-impl Default for LiveAppState {
-    fn default() -> Self {
-        Self::new()
+    #[error("failed to initialize {0}")]
+    Http(#[source] HttpClientError),
+
+    #[error("failed to initialize {0}")]
+    Secrets(#[source] SecretsError),
+}
+
+impl AppState {
+    /// Opens the storage named by `settings` and wires the live services to it.
+    ///
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated, or
+    /// the HTTP client or secrets cannot be loaded from `settings.http`.
+    pub async fn open(settings: &Settings, access: Access) -> Result<Self, StateError> {
+        let http =
+            LiveHttpService::try_new(&settings.http, &settings.watch).map_err(StateError::Http)?;
+        let secrets =
+            Secrets::load(settings.http.secrets_file.as_deref()).map_err(StateError::Secrets)?;
+        let storage = Storage::new(&settings.database);
+        let sources = storage.sources(access).await.map_err(StateError::Storage)?;
+        let news = storage.news(access).await.map_err(StateError::Storage)?;
+
+        Ok(Self::builder(storage)
+            .sources(sources)
+            .news(news)
+            .http(Arc::new(http))
+            .secrets(secrets)
+            .retention(settings.retention.clone())
+            .watch(settings.watch.clone())
+            .media(settings.media.clone())
+            .duplicates(settings.duplicates.clone())
+            .build())
+    }
+
+    /// Starts a state over `storage`, which keeps the local services such as
+    /// webhooks and notification routes. Sources and articles must be given.
+    #[must_use]
+    pub fn builder(storage: Storage) -> AppStateBuilder<(), ()> {
+        AppStateBuilder {
+            storage,
+            retention_settings: RetentionSettings::default(),
+            watch_settings: WatchSettings::default(),
+            media_settings: MediaSettings::default(),
+            duplicate_settings: DuplicateSettings::default(),
+            sources: (),
+            news: (),
+            http: None,
+            secrets: Secrets::default(),
+        }
+    }
+}
+
+pub struct AppStateBuilder<S, N> {
+    storage: Storage,
+    retention_settings: RetentionSettings,
+    watch_settings: WatchSettings,
+    media_settings: MediaSettings,
+    duplicate_settings: DuplicateSettings,
+    sources: S,
+    news: N,
+    http: Option<Arc<dyn HttpService>>,
+    secrets: Secrets,
+}
+
+impl<S, N> AppStateBuilder<S, N> {
+    #[must_use]
+    pub fn sources<T: SourceService>(self, sources: T) -> AppStateBuilder<T, N> {
+        AppStateBuilder {
+            storage: self.storage,
+            retention_settings: self.retention_settings,
+            watch_settings: self.watch_settings,
+            media_settings: self.media_settings,
+            duplicate_settings: self.duplicate_settings,
+            sources,
+            news: self.news,
+            http: self.http,
+            secrets: self.secrets,
+        }
+    }
+
+    #[must_use]
+    pub fn news<T: NewsService>(self, news: T) -> AppStateBuilder<S, T> {
+        AppStateBuilder {
+            storage: self.storage,
+            retention_settings: self.retention_settings,
+            watch_settings: self.watch_settings,
+            media_settings: self.media_settings,
+            duplicate_settings: self.duplicate_settings,
+            sources: self.sources,
+            news,
+            http: self.http,
+            secrets: self.secrets,
+        }
+    }
+
+    /// Client that polls sources; defaults to a live one with default settings.
+    #[must_use]
+    pub fn http(mut self, http: Arc<dyn HttpService>) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Resolves the `${NAME}` credentials of notification routes; only the
+    /// environment by default.
+    #[must_use]
+    pub fn secrets(mut self, secrets: Secrets) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Limits applied by [`crate::retention::prune`]; unlimited by default.
+    #[must_use]
+    pub fn retention(mut self, settings: RetentionSettings) -> Self {
+        self.retention_settings = settings;
+        self
+    }
+
+    /// Per-source watch settings, such as which feeds get their full content.
+    #[must_use]
+    pub fn watch(mut self, settings: WatchSettings) -> Self {
+        self.watch_settings = settings;
+        self
+    }
+
+    /// Where and which images are cached; caching is off by default.
+    #[must_use]
+    pub fn media(mut self, settings: MediaSettings) -> Self {
+        self.media_settings = settings;
+        self
+    }
+
+    /// How new articles are matched with the stories of stored ones.
+    #[must_use]
+    pub const fn duplicates(mut self, settings: DuplicateSettings) -> Self {
+        self.duplicate_settings = settings;
+        self
     }
 }
 
-impl LiveAppState {
+impl<S: SourceService, N: NewsService> AppStateBuilder<S, N> {
     #[must_use]
-    pub fn new() -> Self {
-        Self {
-            sources: OnceCell::new(),
-            news: OnceCell::new(),
-            http: OnceCell::new(),
+    pub fn build(self) -> AppState<S, N> {
+        let http = self
+            .http
+            .unwrap_or_else(|| Arc::new(LiveHttpService::default()));
+
+        AppState {
+            storage: self.storage,
+            retention_settings: self.retention_settings,
+            watch_settings: self.watch_settings,
+            media_settings: self.media_settings,
+            duplicate_settings: self.duplicate_settings,
+            sources: Arc::new(self.sources),
+            news: Arc::new(self.news),
+            http,
+            secrets: self.secrets,
+            webhooks: OnceCell::new(),
+            notifications: OnceCell::new(),
+            retention: OnceCell::new(),
+            digests: OnceCell::new(),
+            media: OnceCell::new(),
+            rules: OnceCell::new(),
         }
     }
+}
 
-    pub async fn sources(&self) -> Result<Arc<impl SourceService + 'static>, StateError> {
-        Ok(self
-            .sources
+impl<S: SourceService, N: NewsService> AppState<S, N> {
+    #[must_use]
+    pub const fn storage(&self) -> &Storage {
+        &self.storage
+    }
+
+    #[must_use]
+    pub const fn retention_settings(&self) -> &RetentionSettings {
+        &self.retention_settings
+    }
+
+    #[must_use]
+    pub const fn watch_settings(&self) -> &WatchSettings {
+        &self.watch_settings
+    }
+
+    #[must_use]
+    pub const fn media_settings(&self) -> &MediaSettings {
+        &self.media_settings
+    }
+
+    #[must_use]
+    pub const fn duplicate_settings(&self) -> &DuplicateSettings {
+        &self.duplicate_settings
+    }
+
+    #[must_use]
+    pub fn sources(&self) -> Arc<S> {
+        self.sources.clone()
+    }
+
+    #[must_use]
+    pub fn news(&self) -> Arc<N> {
+        self.news.clone()
+    }
+
+    #[must_use]
+    pub fn http(&self) -> Arc<dyn HttpService> {
+        self.http.clone()
+    }
+
+    #[must_use]
+    pub const fn secrets(&self) -> &Secrets {
+        &self.secrets
+    }
+
+    async fn local<T: Send + Sync>(
+        &self,
+        cell: &OnceCell<Arc<T>>,
+        from_pool: impl FnOnce(SqlitePool) -> T + Send,
+    ) -> Result<Arc<T>, StateError> {
+        Ok(cell
             .get_or_try_init(async || {
-                let sources = Arc::new(SqliteSourceService::try_new().await?);
-                Ok(sources)
+                let pool = self
+                    .storage
+                    .sqlite()
+                    .pool()
+                    .await
+                    .map_err(|e| StateError::Storage(e.into()))?;
+                Ok(Arc::new(from_pool(pool)))
             })
-            .await
-            .map_err(StateError)?
+            .await?
             .clone())
     }
 
-    pub async fn news(&self) -> Result<Arc<impl NewsService + 'static>, StateError> {
-        Ok(self
-            .news
-            .get_or_try_init(async || {
-                let news = Arc::new(SqliteNewsService::try_new().await?);
-                Ok(news)
-            })
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated.
+    pub async fn webhooks(&self) -> Result<Arc<SqliteWebhookService>, StateError> {
+        self.local(&self.webhooks, SqliteWebhookService::from_pool)
             .await
-            .map_err(StateError)?
-            .clone())
     }
 
-    pub async fn http(&self) -> Arc<impl HttpService + 'static> {
-        self.http
-            .get_or_init(async || Arc::new(LiveHttpService::new()))
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated.
+    pub async fn notifications(&self) -> Result<Arc<SqliteNotificationRouteService>, StateError> {
+        self.local(
+            &self.notifications,
+            SqliteNotificationRouteService::from_pool,
+        )
+        .await
+    }
+
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated.
+    pub async fn retention(&self) -> Result<Arc<SqliteRetentionService>, StateError> {
+        self.local(&self.retention, SqliteRetentionService::from_pool)
+            .await
+    }
+
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated.
+    pub async fn digests(&self) -> Result<Arc<SqliteDigestService>, StateError> {
+        self.local(&self.digests, SqliteDigestService::from_pool)
             .await
-            .clone()
+    }
+
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated.
+    pub async fn media(&self) -> Result<Arc<SqliteMediaService>, StateError> {
+        self.local(&self.media, SqliteMediaService::from_pool).await
+    }
+
+    /// # Errors
+    /// Returns [`StateError`] if the database cannot be opened or migrated.
+    pub async fn rules(&self) -> Result<Arc<SqliteRuleService>, StateError> {
+        self.local(&self.rules, SqliteRuleService::from_pool).await
     }
 }
@@ -0,0 +1,360 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use news_core::config::DatabaseSettings;
+use news_core::models::news::{
+    Article, ArticleChange, ArticleDetails, ArticleFingerprint, ArticleRevision, Attachment, News,
+    Story,
+};
+use news_core::models::source::SourceEnum;
+use news_core::services::news::{
+    GetAllError as NewsGetAllError, MarkError, NewsService, RevisionsError, UpdateError,
+};
+use news_core::services::source::{
+    AddError, DeleteCriteria, DropError, GetAllError, GetError, MoveError, SourceDraft,
+    SourceService,
+};
+use news_memory_core::services::news::MemoryNewsService;
+use news_memory_core::services::source::MemorySourceService;
+use news_postgres_core::db::init::{DBInitError as PgInitError, connect_db_pool};
+use news_postgres_core::services::news::PgNewsService;
+use news_postgres_core::services::source::PgSourceService;
+use news_sqlite_core::db::init::{DBInitError as SqliteInitError, SqliteDatabase};
+use news_sqlite_core::services::news::SqliteNewsService;
+use news_sqlite_core::services::source::SqliteSourceService;
+use news_sqlite_core::services::webhook::SqliteWebhookService;
+use sqlx::{PgPool, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use tracing::warn;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("SQLite database: {0}")]
+    Sqlite(#[from] SqliteInitError),
+
+    #[error("Postgres database: {0}")]
+    Postgres(#[from] PgInitError),
+}
+
+/// Whether storage is opened for writing or only for queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    ReadWrite,
+    /// Queries go through the read-only `SQLite` pool; Postgres has no such pool.
+    ReadOnly,
+}
+
+/// The databases named by one configuration, each opened on first use.
+///
+/// Sources and articles live in the backend selected by `database.url`;
+/// webhooks, notification routes and the other local state always stay in `SQLite`.
+pub struct Storage {
+    postgres_url: Option<String>,
+    max_connections: u32,
+    sqlite: SqliteDatabase,
+    postgres: OnceCell<PgPool>,
+}
+
+impl Storage {
+    #[must_use]
+    pub fn new(settings: &DatabaseSettings) -> Self {
+        Self {
+            postgres_url: settings.postgres_url().map(str::to_string),
+            max_connections: settings.max_connections,
+            sqlite: SqliteDatabase::new(settings.clone()),
+            postgres: OnceCell::new(),
+        }
+    }
+
+    /// Storage kept entirely in `db_pool`, e.g. one from
+    /// [`news_sqlite_core::db::init::connect_memory_pool`].
+    #[must_use]
+    pub fn from_pool(db_pool: SqlitePool) -> Self {
+        Self {
+            postgres_url: None,
+            max_connections: 1,
+            sqlite: SqliteDatabase::from_pool(db_pool),
+            postgres: OnceCell::new(),
+        }
+    }
+
+    /// Whether sources and articles are kept in Postgres.
+    #[must_use]
+    pub const fn is_postgres(&self) -> bool {
+        self.postgres_url.is_some()
+    }
+
+    #[must_use]
+    pub const fn sqlite(&self) -> &SqliteDatabase {
+        &self.sqlite
+    }
+
+    async fn postgres_pool(&self, url: &str) -> Result<PgPool, PgInitError> {
+        self.postgres
+            .get_or_try_init(|| connect_db_pool(url, self.max_connections))
+            .await
+            .cloned()
+    }
+
+    async fn sqlite_pool(&self, access: Access) -> Result<SqlitePool, SqliteInitError> {
+        match access {
+            Access::ReadWrite => self.sqlite.pool().await,
+            Access::ReadOnly => self.sqlite.read_pool().await,
+        }
+    }
+
+    /// # Errors
+    /// Returns [`StorageError`] if the database cannot be opened or migrated.
+    pub async fn sources(&self, access: Access) -> Result<AnySourceService, StorageError> {
+        Ok(match &self.postgres_url {
+            Some(url) => PgSourceService::new(self.postgres_pool(url).await?).into(),
+            None => SqliteSourceService::from_pool(self.sqlite_pool(access).await?).into(),
+        })
+    }
+
+    /// # Errors
+    /// Returns [`StorageError`] if the database cannot be opened or migrated.
+    pub async fn news(&self, access: Access) -> Result<AnyNewsService, StorageError> {
+        Ok(match &self.postgres_url {
+            Some(url) => AnyNewsService::Postgres(
+                PgNewsService::new(self.postgres_pool(url).await?),
+                SqliteWebhookService::from_pool(self.sqlite_pool(access).await?),
+            ),
+            None => SqliteNewsService::from_pool(self.sqlite_pool(access).await?).into(),
+        })
+    }
+}
+
+/// Source storage of the backend selected by `database.url`, or the in-memory
+/// one injected by embedders and tests.
+pub enum AnySourceService {
+    Sqlite(SqliteSourceService),
+    Postgres(PgSourceService),
+    Memory(MemorySourceService),
+}
+
+macro_rules! impl_from {
+    ($any:ident { $($variant:ident($service:ty)),* $(,)? }) => {
+        $(
+            impl From<$service> for $any {
+                fn from(service: $service) -> Self {
+                    Self::$variant(service)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(AnySourceService {
+    Sqlite(SqliteSourceService),
+    Postgres(PgSourceService),
+    Memory(MemorySourceService),
+});
+
+#[async_trait]
+impl SourceService for AnySourceService {
+    async fn add(&self, draft: SourceDraft) -> Result<(), AddError> {
+        match self {
+            Self::Sqlite(service) => service.add(draft).await,
+            Self::Postgres(service) => service.add(draft).await,
+            Self::Memory(service) => service.add(draft).await,
+        }
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<SourceEnum, GetError> {
+        match self {
+            Self::Sqlite(service) => service.get_by_id(id).await,
+            Self::Postgres(service) => service.get_by_id(id).await,
+            Self::Memory(service) => service.get_by_id(id).await,
+        }
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = SourceEnum>, GetAllError> {
+        Ok(match self {
+            Self::Sqlite(service) => service.get_all().await?.into_iter().collect::<Vec<_>>(),
+            Self::Postgres(service) => service.get_all().await?.into_iter().collect(),
+            Self::Memory(service) => service.get_all().await?.into_iter().collect(),
+        })
+    }
+
+    async fn drop_by(&self, criteria: DeleteCriteria) -> Result<(), DropError> {
+        match self {
+            Self::Sqlite(service) => service.drop_by(criteria).await,
+            Self::Postgres(service) => service.drop_by(criteria).await,
+            Self::Memory(service) => service.drop_by(criteria).await,
+        }
+    }
+
+    async fn move_atom(&self, id: Uuid, url: Url) -> Result<(), MoveError> {
+        match self {
+            Self::Sqlite(service) => service.move_atom(id, url).await,
+            Self::Postgres(service) => service.move_atom(id, url).await,
+            Self::Memory(service) => service.move_atom(id, url).await,
+        }
+    }
+}
+
+/// Article storage of the backend selected by `database.url`, or the in-memory
+/// one injected by embedders and tests.
+pub enum AnyNewsService {
+    Sqlite(SqliteNewsService),
+    /// Changes are queued for webhooks in `SQLite` once Postgres committed them.
+    Postgres(PgNewsService, SqliteWebhookService),
+    Memory(MemoryNewsService),
+}
+
+impl_from!(AnyNewsService {
+    Sqlite(SqliteNewsService),
+    Memory(MemoryNewsService),
+});
+
+#[async_trait]
+impl NewsService for AnyNewsService {
+    async fn update(&self, news: &[Arc<impl News>]) -> Result<Vec<ArticleChange>, UpdateError> {
+        match self {
+            Self::Sqlite(service) => service.update(news).await,
+            Self::Postgres(service, webhooks) => {
+                let changes = service.update(news).await?;
+                // The articles are stored: failing the update would not bring
+                // the changes back on the next poll.
+                if let Err(e) = webhooks.enqueue_all(&changes).await {
+                    warn!("[webhooks] Failed to queue deliveries of stored articles: {e}");
+                }
+                Ok(changes)
+            }
+            Self::Memory(service) => service.update(news).await,
+        }
+    }
+
+    async fn get_all(&self) -> Result<impl IntoIterator<Item = Article>, NewsGetAllError> {
+        Ok(match self {
+            Self::Sqlite(service) => service.get_all().await?.into_iter().collect::<Vec<_>>(),
+            Self::Postgres(service, _) => service.get_all().await?.into_iter().collect(),
+            Self::Memory(service) => service.get_all().await?.into_iter().collect(),
+        })
+    }
+
+    async fn get_created_after(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<impl IntoIterator<Item = Article>, NewsGetAllError> {
+        Ok(match self {
+            Self::Sqlite(service) => service
+                .get_created_after(after)
+                .await?
+                .into_iter()
+                .collect::<Vec<_>>(),
+            Self::Postgres(service, _) => service
+                .get_created_after(after)
+                .await?
+                .into_iter()
+                .collect(),
+            Self::Memory(service) => service
+                .get_created_after(after)
+                .await?
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    async fn revisions(&self, id: Uuid) -> Result<Vec<ArticleRevision>, RevisionsError> {
+        match self {
+            Self::Sqlite(service) => service.revisions(id).await,
+            Self::Postgres(service, _) => service.revisions(id).await,
+            Self::Memory(service) => service.revisions(id).await,
+        }
+    }
+
+    async fn set_starred(&self, id: Uuid, starred: bool) -> Result<(), MarkError> {
+        match self {
+            Self::Sqlite(service) => service.set_starred(id, starred).await,
+            Self::Postgres(service, _) => service.set_starred(id, starred).await,
+            Self::Memory(service) => service.set_starred(id, starred).await,
+        }
+    }
+
+    async fn set_read(&self, id: Uuid, read: bool) -> Result<(), MarkError> {
+        match self {
+            Self::Sqlite(service) => service.set_read(id, read).await,
+            Self::Postgres(service, _) => service.set_read(id, read).await,
+            Self::Memory(service) => service.set_read(id, read).await,
+        }
+    }
+
+    async fn add_tag(&self, id: Uuid, tag: &str) -> Result<(), MarkError> {
+        match self {
+            Self::Sqlite(service) => service.add_tag(id, tag).await,
+            Self::Postgres(service, _) => service.add_tag(id, tag).await,
+            Self::Memory(service) => service.add_tag(id, tag).await,
+        }
+    }
+
+    async fn contents(
+        &self,
+        source_keys: &[&str],
+    ) -> Result<HashMap<String, String>, NewsGetAllError> {
+        match self {
+            Self::Sqlite(service) => service.contents(source_keys).await,
+            Self::Postgres(service, _) => service.contents(source_keys).await,
+            Self::Memory(service) => service.contents(source_keys).await,
+        }
+    }
+
+    async fn attachments(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Attachment>>, NewsGetAllError> {
+        match self {
+            Self::Sqlite(service) => service.attachments(ids).await,
+            Self::Postgres(service, _) => service.attachments(ids).await,
+            Self::Memory(service) => service.attachments(ids).await,
+        }
+    }
+
+    async fn details(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ArticleDetails>, NewsGetAllError> {
+        match self {
+            Self::Sqlite(service) => service.details(ids).await,
+            Self::Postgres(service, _) => service.details(ids).await,
+            Self::Memory(service) => service.details(ids).await,
+        }
+    }
+
+    async fn fingerprints(
+        &self,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<ArticleFingerprint>, NewsGetAllError> {
+        match self {
+            Self::Sqlite(service) => service.fingerprints(after).await,
+            Self::Postgres(service, _) => service.fingerprints(after).await,
+            Self::Memory(service) => service.fingerprints(after).await,
+        }
+    }
+
+    async fn set_story(
+        &self,
+        id: Uuid,
+        story_id: Uuid,
+        simhash: Option<u64>,
+    ) -> Result<(), MarkError> {
+        match self {
+            Self::Sqlite(service) => service.set_story(id, story_id, simhash).await,
+            Self::Postgres(service, _) => service.set_story(id, story_id, simhash).await,
+            Self::Memory(service) => service.set_story(id, story_id, simhash).await,
+        }
+    }
+
+    async fn stories(&self) -> Result<Vec<Story>, NewsGetAllError> {
+        match self {
+            Self::Sqlite(service) => service.stories().await,
+            Self::Postgres(service, _) => service.stories().await,
+            Self::Memory(service) => service.stories().await,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Short summaries and titles of collected items, extracted offline.
+//!
+//! Sentences are scored by how often their words appear in the whole text,
+//! the lead sentence counting double, and the best ones are kept in the order
+//! of the text. Words shorter than four letters, mostly articles and
+//! prepositions in any language, are not counted, and headings are not picked.
+
+use news_sqlite_core::utils::text::truncate_with_dots;
+use std::collections::HashMap;
+
+/// Longest generated title, in characters.
+pub const HEADLINE_MAX_LENGTH: usize = 100;
+
+/// Sentences kept in a summary.
+const SUMMARY_SENTENCES: usize = 3;
+
+/// Longest summary, in characters, unless its best sentence alone is longer.
+const SUMMARY_MAX_LENGTH: usize = 400;
+
+/// Texts up to this length, in characters, are read whole.
+const MIN_TEXT_LENGTH: usize = 500;
+
+const MIN_WORD_LENGTH: usize = 4;
+
+/// Sentences with fewer counted words, such as a sign-off, are not picked.
+const MIN_SENTENCE_WORDS: usize = 4;
+
+const SENTENCE_ENDS: [char; 4] = ['.', '!', '?', '…'];
+
+/// Closing quotes and brackets after the end of a sentence.
+const CLOSING: [char; 6] = ['"', '\'', '»', '”', '’', ')'];
+
+/// Sentences of `text`, with their spaces collapsed. A sentence ends with a
+/// line, or after `.`, `!`, `?` or `…` followed by a space and a word that
+/// does not start with a lowercase letter.
+#[must_use]
+pub fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+
+    for line in text.lines() {
+        let chars: Vec<(usize, char)> = line.char_indices().collect();
+        let mut start = 0;
+
+        for (i, (at, c)) in chars.iter().enumerate() {
+            if !SENTENCE_ENDS.contains(c) {
+                continue;
+            }
+            let next = chars.get(i + 1).map(|(_, c)| *c);
+            let after = chars.get(i + 2).map(|(_, c)| *c);
+            if next.is_some_and(char::is_whitespace) && after.is_some_and(|c| !c.is_lowercase()) {
+                let end = at + c.len_utf8();
+                sentences.push(&line[start..end]);
+                start = end;
+            }
+        }
+        sentences.push(&line[start..]);
+    }
+
+    sentences
+        .into_iter()
+        .map(|sentence| sentence.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
+
+/// Whether `sentence` ends like one, unlike a heading or a list item.
+fn is_complete(sentence: &str) -> bool {
+    sentence.trim_end_matches(CLOSING).ends_with(SENTENCE_ENDS)
+}
+
+/// Lowercase words of `text` long enough to be counted.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_WORD_LENGTH)
+        .map(str::to_lowercase)
+}
+
+/// Title of `text`: its first line when it is a heading, else its first
+/// sentence, cut at a word when longer than [`HEADLINE_MAX_LENGTH`]. `None`
+/// when `text` is blank.
+#[must_use]
+pub fn headline(text: &str) -> Option<String> {
+    let first = sentences(text).into_iter().next()?;
+    let first = match first.strip_suffix('.') {
+        Some(title) if !title.ends_with('.') => title,
+        _ => &first,
+    };
+
+    Some(truncate_with_dots(first, HEADLINE_MAX_LENGTH))
+}
+
+/// The best sentences of `text`, in its order, or `None` when `text` is
+/// short enough to be read whole.
+#[must_use]
+pub fn summarize(text: &str) -> Option<String> {
+    let sentences = sentences(text);
+    let length: usize = sentences.iter().map(|s| s.chars().count()).sum();
+    if length <= MIN_TEXT_LENGTH || sentences.len() <= SUMMARY_SENTENCES {
+        return None;
+    }
+
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for word in sentences.iter().flat_map(|sentence| words(sentence)) {
+        *frequencies.entry(word).or_default() += 1;
+    }
+
+    // Average frequency of the words of each sentence, in thousandths.
+    let mut scored: Vec<(usize, usize)> = sentences
+        .iter()
+        .enumerate()
+        .filter_map(|(i, sentence)| {
+            let words: Vec<String> = words(sentence).collect();
+            (is_complete(sentence) && words.len() >= MIN_SENTENCE_WORDS).then(|| {
+                let total: usize = words.iter().map(|word| frequencies[word]).sum();
+                (i, total * 1000 / words.len())
+            })
+        })
+        .collect();
+
+    if let Some((_, lead)) = scored.first_mut() {
+        *lead *= 2;
+    }
+    scored.sort_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then(a.cmp(b)));
+
+    let mut picked: Vec<usize> = Vec::with_capacity(SUMMARY_SENTENCES);
+    let mut picked_length = 0;
+    for (i, _) in scored {
+        let length = sentences[i].chars().count() + 1;
+        if picked.is_empty() || picked_length + length <= SUMMARY_MAX_LENGTH {
+            picked.push(i);
+            picked_length += length;
+        }
+        if picked.len() == SUMMARY_SENTENCES {
+            break;
+        }
+    }
+    picked.sort_unstable();
+
+    let summary = picked
+        .into_iter()
+        .map(|i| sentences[i].as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (!summary.is_empty()).then(|| truncate_with_dots(&summary, SUMMARY_MAX_LENGTH))
+}
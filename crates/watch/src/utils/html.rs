@@ -1,26 +1,28 @@
-use ammonia::Builder;
+use ammonia::{Builder, UrlRelative};
 use htmd::HtmlToMarkdown;
+use scraper::{ElementRef, Html};
 use std::collections::HashSet;
 use std::sync::{Arc, LazyLock};
 use thiserror::Error;
 use tokio::task::spawn_blocking;
+use url::Url;
 
 static MD2HTML_CONVERTER: LazyLock<Arc<HtmlToMarkdown>> =
     LazyLock::new(|| Arc::new(HtmlToMarkdown::new()));
 
 #[derive(Error, Debug)]
 #[error("Failed to convert HTML to Markdown: {0}")]
-pub struct ConvertError(String);
+pub struct ConvertError(pub(crate) String);
 
 async fn sanitize_and_convert(
     html_dirty: &str,
-    sanitizer: &'static Builder<'static>,
+    sanitize: impl FnOnce(&str) -> String + Send + 'static,
 ) -> Result<String, ConvertError> {
     let converter = MD2HTML_CONVERTER.clone();
     let html_dirty = html_dirty.to_string();
 
     spawn_blocking(move || {
-        let html = sanitizer.clean(&html_dirty).to_string();
+        let html = sanitize(&html_dirty);
         let result = converter
             .convert(&html)
             .map_err(|e| ConvertError(e.to_string()))?;
@@ -30,44 +32,97 @@ async fn sanitize_and_convert(
     .map_err(|e| ConvertError(e.to_string()))?
 }
 
-static CONTENT_SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
-    let mut sanitizer = Builder::new();
-    sanitizer
+/// Whether `image` only tells its host that it was seen: 1×1 pixels and images
+/// served by known trackers, with `src` resolved against `base`.
+fn is_tracking_pixel(image: ElementRef<'_>, base: &Url) -> bool {
+    let image = image.value();
+    if image.name() != "img" {
+        return false;
+    }
+
+    let tiny = |dimension: &str| {
+        image
+            .attr(dimension)
+            .and_then(|size| size.trim().trim_end_matches("px").parse::<u32>().ok())
+            .is_some_and(|size| size <= 1)
+    };
+    let tracker = image
+        .attr("src")
+        .and_then(|src| base.join(src.trim()).ok())
+        .is_some_and(|src| {
+            src.host_str()
+                .is_some_and(|host| TRACKING_HOSTS.contains(host))
+        });
+
+    tiny("width") || tiny("height") || tracker
+}
+
+fn drop_tracking_pixels(html: &str, base: &Url) -> String {
+    if !html.contains("<img") {
+        return html.to_string();
+    }
+
+    let mut fragment = Html::parse_fragment(html);
+    let pixels: Vec<_> = fragment
+        .root_element()
+        .descendent_elements()
+        .filter(|element| is_tracking_pixel(*element, base))
+        .map(|element| element.id())
+        .collect();
+    if pixels.is_empty() {
+        return html.to_string();
+    }
+
+    for id in pixels {
+        if let Some(mut pixel) = fragment.tree.get_mut(id) {
+            pixel.detach();
+        }
+    }
+    fragment.root_element().inner_html()
+}
+
+/// Cleans `html_dirty` down to the tags and attributes allowed in stored
+/// content. Relative URLs are resolved against `base`, URLs of other schemes
+/// than [`CONTENT_URL_SCHEMES`] such as `javascript:` are dropped, and so are
+/// tracking pixels.
+fn clean_content(html_dirty: &str, base: &Url) -> String {
+    let html = drop_tracking_pixels(html_dirty, base);
+
+    Builder::new()
         .tags(HashSet::from(CONTENT_ALLOWED_TAGS_ARR))
         .generic_attributes(HashSet::from(CONTENT_ALLOWED_ATTRS_ARR))
+        .url_schemes(HashSet::from(CONTENT_URL_SCHEMES))
+        .url_relative(UrlRelative::RewriteWithBase(base.clone()))
         .link_rel(Some("noopener noreferrer nofollow ugc"))
-        .strip_comments(true);
-    sanitizer
-});
+        .strip_comments(true)
+        .clean(&html)
+        .to_string()
+}
 
 #[derive(Error, Debug)]
 #[error("Failed to sanitize HTML: {0}")]
 pub struct SanitizeError(String);
 
-pub async fn sanitize_html(html_dirty: &str) -> Result<String, SanitizeError> {
+/// Cleans untrusted HTML down to the tags and attributes allowed in stored
+/// content, with relative URLs resolved against `base`.
+///
+/// # Errors
+/// Returns [`SanitizeError`] if the blocking sanitizer task fails.
+pub async fn sanitize_html(html_dirty: &str, base: &Url) -> Result<String, SanitizeError> {
     let html_owned = html_dirty.to_string();
-    spawn_blocking(move || CONTENT_SANITIZER.clean(&html_owned).to_string())
+    let base = base.clone();
+    spawn_blocking(move || clean_content(&html_owned, &base))
         .await
         .map_err(|e| SanitizeError(e.to_string()))
 }
 
-pub async fn html_to_text(html_dirty: &str) -> Result<String, ConvertError> {
-    let html_owned = html_dirty.to_string();
-
-    spawn_blocking(move || {
-        Builder::new()
-            .tags(HashSet::new())
-            .strip_comments(true)
-            .clean(&html_owned)
-            .to_string()
-    })
-    .await
-    .map_err(|e| ConvertError(e.to_string()))
-}
-
-#[allow(dead_code)] // @todo: remove
-pub async fn html_to_content(html_dirty: &str) -> Result<String, ConvertError> {
-    sanitize_and_convert(html_dirty, &CONTENT_SANITIZER).await
+/// Sanitizes HTML content like [`sanitize_html`] and converts it to Markdown.
+///
+/// # Errors
+/// Returns [`ConvertError`] if the HTML cannot be converted.
+pub async fn html_to_content(html_dirty: &str, base: &Url) -> Result<String, ConvertError> {
+    let base = base.clone();
+    sanitize_and_convert(html_dirty, move |html| clean_content(html, &base)).await
 }
 
 static TITLE_SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
@@ -79,14 +134,31 @@ pub async fn html_to_content(html_dirty: &str) -> Result<String, ConvertError> {
     sanitizer
 });
 
+/// Sanitizes an HTML title and converts it to Markdown.
+///
+/// # Errors
+/// Returns [`ConvertError`] if the HTML cannot be converted.
 #[allow(dead_code)] // @todo: remove
 pub async fn html_to_title(html_dirty: &str) -> Result<String, ConvertError> {
-    sanitize_and_convert(html_dirty, &TITLE_SANITIZER).await
+    sanitize_and_convert(html_dirty, |html| TITLE_SANITIZER.clean(html).to_string()).await
 }
 
 const TITLE_ALLOWED_TAGS_ARR: [&str; 7] = ["em", "strong", "b", "i", "sup", "sub", "strike"];
 
 const CONTENT_ALLOWED_ATTRS_ARR: [&str; 3] = ["href", "src", "alt"];
+const CONTENT_URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Hosts that serve nothing but tracking images.
+static TRACKING_HOSTS: LazyLock<HashSet<&str>> = LazyLock::new(|| {
+    HashSet::from([
+        "feeds.feedburner.com",
+        "pixel.quantserve.com",
+        "pixel.wp.com",
+        "sb.scorecardresearch.com",
+        "stats.wordpress.com",
+        "www.google-analytics.com",
+    ])
+});
 const CONTENT_ALLOWED_TAGS_ARR: [&str; 45] = [
     "h1",
     "h2",
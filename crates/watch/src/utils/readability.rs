@@ -0,0 +1,309 @@
+//! Finds the main text of an article page, in the spirit of Arc90's Readability.
+//!
+//! Paragraphs score their parent and grandparent, the best scored element wins
+//! and takes along siblings that look like part of the same text.
+
+use crate::utils::html::{ConvertError, html_to_content};
+use ego_tree::{NodeId, NodeRef};
+use scraper::{ElementRef, Html, Node};
+use std::collections::HashMap;
+use tokio::task::spawn_blocking;
+use url::Url;
+
+/// Elements that are never part of the text of an article.
+const DROPPED_TAGS: [&str; 13] = [
+    "script", "style", "noscript", "template", "nav", "aside", "footer", "header", "form",
+    "iframe", "button", "select", "svg",
+];
+
+const VOID_TAGS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Class and id fragments of page furniture rather than text.
+const UNLIKELY: [&str; 17] = [
+    "banner",
+    "breadcrumb",
+    "comment",
+    "community",
+    "cookie",
+    "disqus",
+    "footer",
+    "menu",
+    "modal",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsor",
+];
+
+/// Class and id fragments that save an element from [`UNLIKELY`].
+const MAYBE: [&str; 7] = [
+    "article", "body", "column", "content", "main", "post", "story",
+];
+
+const POSITIVE: [&str; 9] = [
+    "article", "blog", "body", "content", "entry", "main", "post", "story", "text",
+];
+
+const NEGATIVE: [&str; 14] = [
+    "ad-", "advert", "byline", "comment", "contact", "foot", "masthead", "meta", "outbrain",
+    "promo", "related", "share", "sidebar", "widget",
+];
+
+/// Paragraphs with less text than this are not counted.
+const MIN_PARAGRAPH_LEN: usize = 25;
+
+/// `n` as a score; texts are far shorter than where precision is lost.
+fn count(n: usize) -> f64 {
+    f64::from(u32::try_from(n).unwrap_or(u32::MAX))
+}
+
+fn class_and_id(element: ElementRef<'_>) -> String {
+    let value = element.value();
+    format!(
+        "{} {}",
+        value.attr("class").unwrap_or_default(),
+        value.id().unwrap_or_default()
+    )
+    .to_lowercase()
+}
+
+fn is_dropped(element: ElementRef<'_>) -> bool {
+    let name = element.value().name();
+    if DROPPED_TAGS.contains(&name) {
+        return true;
+    }
+    if matches!(name, "html" | "body" | "article" | "main") {
+        return false;
+    }
+
+    let names = class_and_id(element);
+    UNLIKELY.iter().any(|unlikely| names.contains(unlikely))
+        && !MAYBE.iter().any(|maybe| names.contains(maybe))
+}
+
+fn class_weight(element: ElementRef<'_>) -> f64 {
+    let names = class_and_id(element);
+    let mut weight = 0.0;
+
+    if POSITIVE.iter().any(|positive| names.contains(positive)) {
+        weight += 25.0;
+    }
+    if NEGATIVE.iter().any(|negative| names.contains(negative)) {
+        weight -= 25.0;
+    }
+
+    weight
+}
+
+fn initial_score(element: ElementRef<'_>) -> f64 {
+    let tag_weight = match element.value().name() {
+        "article" => 10.0,
+        "div" | "main" | "section" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+
+    tag_weight + class_weight(element)
+}
+
+/// Text of `node` without the text of dropped elements, and how much of it is
+/// inside links.
+fn visible_text(node: NodeRef<'_, Node>, in_link: bool, text: &mut String, link_len: &mut usize) {
+    match node.value() {
+        Node::Text(content) => {
+            text.push_str(content);
+            if in_link {
+                *link_len += content.trim().chars().count();
+            }
+        }
+        Node::Element(element) => {
+            if ElementRef::wrap(node).is_some_and(is_dropped) {
+                return;
+            }
+            let in_link = in_link || element.name() == "a";
+            for child in node.children() {
+                visible_text(child, in_link, text, link_len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Visible text of `element`, trimmed, and its share inside links.
+fn text_and_link_density(element: ElementRef<'_>) -> (String, f64) {
+    let mut text = String::new();
+    let mut link_len = 0;
+    visible_text(*element, false, &mut text, &mut link_len);
+
+    let text = text.trim().to_string();
+    let len = text.chars().count();
+    let density = match len {
+        0 => 0.0,
+        len => count(link_len) / count(len),
+    };
+
+    (text, density)
+}
+
+fn paragraph_score(text: &str) -> f64 {
+    let len = text.chars().count();
+    1.0 + count(text.matches(',').count()) + count((len / 100).min(3))
+}
+
+/// Whether `element` or one of its ancestors is dropped.
+fn is_hidden(element: ElementRef<'_>) -> bool {
+    is_dropped(element)
+        || element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(is_dropped)
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' if !attribute => escaped.push_str("&lt;"),
+            '>' if !attribute => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Lists and blocks that are mostly links, such as tag clouds and "read more"
+/// boxes inside the text.
+fn is_link_list(element: ElementRef<'_>) -> bool {
+    matches!(
+        element.value().name(),
+        "div" | "section" | "ul" | "ol" | "table"
+    ) && text_and_link_density(element).1 > 0.5
+}
+
+fn write_html(node: NodeRef<'_, Node>, html: &mut String) {
+    match node.value() {
+        Node::Text(text) => html.push_str(&escape(text, false)),
+        Node::Element(element) => {
+            if ElementRef::wrap(node).is_some_and(|e| is_dropped(e) || is_link_list(e)) {
+                return;
+            }
+
+            let name = element.name();
+            html.push('<');
+            html.push_str(name);
+            for (attribute, value) in element.attrs() {
+                html.push(' ');
+                html.push_str(attribute);
+                html.push_str("=\"");
+                html.push_str(&escape(value, true));
+                html.push('"');
+            }
+            html.push('>');
+
+            if VOID_TAGS.contains(&name) {
+                return;
+            }
+            for child in node.children() {
+                write_html(child, html);
+            }
+            html.push_str("</");
+            html.push_str(name);
+            html.push('>');
+        }
+        _ => {}
+    }
+}
+
+/// HTML of the main text of `page`, or `None` if no part of it reads like an
+/// article.
+#[must_use]
+pub fn extract(page: &str) -> Option<String> {
+    let document = Html::parse_document(page);
+    let element = |id: NodeId| document.tree.get(id).and_then(ElementRef::wrap);
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+
+    for paragraph in document.root_element().descendent_elements() {
+        if !matches!(paragraph.value().name(), "p" | "pre" | "td") || is_hidden(paragraph) {
+            continue;
+        }
+        let (text, _) = text_and_link_density(paragraph);
+        if text.chars().count() < MIN_PARAGRAPH_LEN {
+            continue;
+        }
+
+        let score = paragraph_score(&text);
+        let parent = paragraph.parent().and_then(ElementRef::wrap);
+        let grandparent = parent.and_then(|parent| parent.parent().and_then(ElementRef::wrap));
+
+        for (ancestor, share) in [(parent, 1.0), (grandparent, 0.5)] {
+            if let Some(ancestor) = ancestor {
+                *scores
+                    .entry(ancestor.id())
+                    .or_insert_with(|| initial_score(ancestor)) += score * share;
+            }
+        }
+    }
+
+    let final_score = |id: NodeId, score: f64| {
+        let density = element(id).map_or(1.0, |e| text_and_link_density(e).1);
+        score * (1.0 - density)
+    };
+    let (top, top_score) = scores
+        .iter()
+        .map(|(id, score)| (*id, final_score(*id, *score)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    let top = element(top)?;
+
+    let threshold = f64::max(10.0, top_score * 0.2);
+    let siblings = top
+        .parent()
+        .and_then(ElementRef::wrap)
+        .map_or_else(|| vec![top], |parent| parent.child_elements().collect());
+
+    let mut html = String::from("<div>");
+    for sibling in siblings {
+        let keep = sibling == top
+            || scores
+                .get(&sibling.id())
+                .is_some_and(|score| final_score(sibling.id(), *score) >= threshold)
+            || (sibling.value().name() == "p" && {
+                let (text, density) = text_and_link_density(sibling);
+                let len = text.chars().count();
+                (len > 80 && density < 0.25) || (len > 0 && density == 0.0 && text.contains(". "))
+            });
+
+        if keep {
+            write_html(*sibling, &mut html);
+        }
+    }
+    html.push_str("</div>");
+
+    Some(html)
+}
+
+/// Main text of `page`, served from `url`, as Markdown, or `None` if no part of
+/// it reads like an article.
+///
+/// # Errors
+/// Returns [`ConvertError`] if the text cannot be converted.
+pub async fn page_to_content(page: String, url: &Url) -> Result<Option<String>, ConvertError> {
+    let main = spawn_blocking(move || extract(&page))
+        .await
+        .map_err(|e| ConvertError(e.to_string()))?;
+
+    match main {
+        Some(html) => html_to_content(&html, url).await.map(Some),
+        None => Ok(None),
+    }
+}
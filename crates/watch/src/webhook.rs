@@ -0,0 +1,257 @@
+use crate::state::{AppState, StateError};
+use chrono::{TimeDelta, Utc};
+use hmac::{Hmac, Mac};
+use news_core::models::webhook::{ArticlePayload, Delivery, Webhook, WebhookPayload};
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use news_core::services::webhook::{QueueError, WebhookService};
+use reqwest::{Client, StatusCode};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{debug, error, warn};
+use url::Url;
+use uuid::Uuid;
+
+pub const EVENT_HEADER: &str = "X-UniNews-Event";
+pub const DELIVERY_HEADER: &str = "X-UniNews-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-UniNews-Signature";
+
+const TEST_EVENT: &str = "webhook.test";
+
+const POLL_INTERVAL_SECS: u64 = 10;
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const DELIVERY_BATCH_SIZE: u32 = 50;
+
+const MAX_ATTEMPTS: i64 = 8;
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 6 * 60 * 60;
+
+/// Delivered rows are kept this long, for `webhook replay` and the counts of
+/// `webhook ls`, then deleted.
+const DELIVERED_TTL_DAYS: i64 = 7;
+const PRUNE_INTERVAL_SECS: u64 = 60 * 60;
+
+#[derive(Error, Debug)]
+pub enum DeliveryError {
+    #[error("Failed to send webhook request: {0}")]
+    Request(#[source] reqwest::Error),
+
+    #[error("Webhook endpoint responded with status {0}")]
+    Status(StatusCode),
+}
+
+#[derive(Error, Debug)]
+pub enum WebhookWatchError {
+    #[error(transparent)]
+    State(#[from] StateError),
+
+    #[error("Failed to build webhook HTTP client: {0}")]
+    Client(#[source] reqwest::Error),
+}
+
+/// Signs a request body the way receivers are expected to verify it:
+/// `sha256=` followed by the hex-encoded HMAC-SHA256 of the body.
+///
+/// # Panics
+/// Never in practice: HMAC accepts keys of any length.
+#[must_use]
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+fn client() -> Result<Client, reqwest::Error> {
+    Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+}
+
+async fn post(
+    client: &Client,
+    url: &Url,
+    secret: &str,
+    event: &str,
+    delivery_id: Uuid,
+    payload: String,
+) -> Result<StatusCode, DeliveryError> {
+    let signature = sign(secret, &payload);
+
+    let response = client
+        .post(url.as_str())
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(SIGNATURE_HEADER, signature)
+        .body(payload)
+        .send()
+        .await
+        .map_err(DeliveryError::Request)?;
+
+    match response.status() {
+        status if status.is_success() => Ok(status),
+        status => Err(DeliveryError::Status(status)),
+    }
+}
+
+/// How long to wait before the next attempt of a delivery that failed
+/// `attempts` times: 30 seconds doubled per attempt, at most 6 hours, and
+/// `None` once it should be given up.
+#[must_use]
+pub fn next_attempt_delay(attempts: i64) -> Option<TimeDelta> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+
+    let exponent = u32::try_from(attempts.max(0)).unwrap_or(u32::MAX);
+    let delay = 2_i64
+        .checked_pow(exponent)
+        .and_then(|factor| factor.checked_mul(BASE_BACKOFF_SECS))
+        .map_or(MAX_BACKOFF_SECS, |secs| secs.min(MAX_BACKOFF_SECS));
+
+    Some(TimeDelta::seconds(delay))
+}
+
+async fn deliver(
+    client: &Client,
+    webhooks: &impl WebhookService,
+    delivery: Delivery,
+) -> Result<(), QueueError> {
+    let result = post(
+        client,
+        &delivery.url,
+        &delivery.secret,
+        &delivery.event,
+        delivery.id,
+        delivery.payload,
+    )
+    .await;
+
+    match result {
+        Ok(status) => {
+            debug!(
+                "[webhook=\"{}\"] delivery {} accepted with {status}",
+                delivery.url, delivery.id
+            );
+            webhooks.mark_delivered(delivery.id).await
+        }
+        Err(e) => {
+            let retry_at =
+                next_attempt_delay(delivery.attempts + 1).map(|delay| Utc::now() + delay);
+
+            if let Some(at) = retry_at {
+                warn!(
+                    "[webhook=\"{}\"] delivery {} failed, retrying at {at}: {e}",
+                    delivery.url, delivery.id
+                );
+            } else {
+                error!(
+                    "[webhook=\"{}\"] delivery {} failed {MAX_ATTEMPTS} times, giving up: {e}",
+                    delivery.url, delivery.id
+                );
+            }
+
+            webhooks
+                .mark_failed(delivery.id, &e.to_string(), retry_at)
+                .await
+        }
+    }
+}
+
+/// Sends a batch of the deliveries that are due with `client` and records
+/// each outcome. Returns how many were attempted.
+///
+/// # Errors
+/// Returns [`QueueError`] if the delivery queue cannot be accessed.
+pub async fn deliver_due(
+    client: &Client,
+    webhooks: &impl WebhookService,
+) -> Result<usize, QueueError> {
+    let due = webhooks.get_due(DELIVERY_BATCH_SIZE).await?;
+    let count = due.len();
+
+    for delivery in due {
+        deliver(client, webhooks, delivery).await?;
+    }
+
+    Ok(count)
+}
+
+/// Continuously sends queued webhook deliveries.
+///
+/// Failed ones are retried with exponential backoff until they succeed or run
+/// out of attempts, and deliveries that succeeded more than a week ago are
+/// deleted. Errors of the queue are logged and the next round tries again.
+///
+/// # Errors
+/// Returns [`WebhookWatchError`] if the delivery queue cannot be opened.
+pub async fn watch_webhooks<S: SourceService, N: NewsService>(
+    app_state: Arc<AppState<S, N>>,
+) -> Result<(), WebhookWatchError> {
+    let webhooks = app_state.webhooks().await?;
+    let client = client().map_err(WebhookWatchError::Client)?;
+    let mut pruned_at: Option<Instant> = None;
+
+    loop {
+        if pruned_at.is_none_or(|at| at.elapsed() >= Duration::from_secs(PRUNE_INTERVAL_SECS)) {
+            let before = Utc::now() - TimeDelta::days(DELIVERED_TTL_DAYS);
+            match webhooks.prune_delivered(before).await {
+                Ok(0) => {}
+                Ok(count) => debug!("[webhooks] {count} delivered deliveries pruned"),
+                Err(e) => error!("[webhooks] {e}"),
+            }
+            pruned_at = Some(Instant::now());
+        }
+
+        let is_batch_full = match deliver_due(&client, webhooks.as_ref()).await {
+            Ok(count) => count == DELIVERY_BATCH_SIZE as usize,
+            Err(e) => {
+                error!("[webhooks] {e}");
+                false
+            }
+        };
+
+        if !is_batch_full {
+            sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        }
+    }
+}
+
+/// Sends a signed sample payload to the webhook right away, bypassing the queue.
+///
+/// # Errors
+/// Returns [`DeliveryError`] if the request fails or the endpoint does not
+/// respond with a success status.
+pub async fn send_test(webhook: &Webhook) -> Result<StatusCode, DeliveryError> {
+    let payload = WebhookPayload {
+        event: TEST_EVENT,
+        occurred_at: Utc::now(),
+        article: ArticlePayload {
+            id: Uuid::nil(),
+            source_id: webhook.source_id.unwrap_or_else(Uuid::nil),
+            title: "UniNews test delivery",
+            description: "<p>This is a test delivery sent by <code>uninews webhook test</code>.</p>",
+            content: None,
+            read: false,
+            starred: false,
+            tags: &[],
+        },
+    };
+
+    let payload = serde_json::to_string(&payload).unwrap_or_default();
+    let client = client().map_err(DeliveryError::Request)?;
+
+    post(
+        &client,
+        &webhook.url,
+        &webhook.secret,
+        TEST_EVENT,
+        Uuid::now_v7(),
+        payload,
+    )
+    .await
+}
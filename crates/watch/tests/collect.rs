@@ -0,0 +1,117 @@
+//! Runs the collect pipeline over injected services: feeds are served from
+//! fixtures instead of the network and articles land in memory.
+
+mod common;
+
+use common::{FEED_URL, articles, memory_state, watch_all};
+use news_core::services::HttpResponse;
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::db::init::connect_memory_pool;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_sqlite_core::services::news::SqliteNewsService;
+use news_sqlite_core::services::source::SqliteSourceService;
+use news_watch::state::AppState;
+use news_watch::storage::Storage;
+use std::sync::Arc;
+use url::Url;
+
+/// Answers the single poll of the feed with `body`.
+fn serving(body: String) -> FixtureHttpService {
+    let url = Url::parse(FEED_URL).expect("valid URL");
+    FixtureHttpService::new().with_response(HttpResponse::new(url, body))
+}
+
+fn feed(entries: &[(&str, &str)]) -> String {
+    let entries: String = entries
+        .iter()
+        .map(|(id, title)| {
+            format!(
+                "<entry><id>{id}</id><title>{title}</title><updated>2026-10-19T00:00:00Z</updated>\
+                 <summary>About {title}</summary></entry>"
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<id>{FEED_URL}</id><title>Fixture</title><updated>2026-10-19T00:00:00Z</updated>
+{entries}
+</feed>"#
+    )
+}
+
+async fn add_feed(sources: &impl SourceService) {
+    sources.add(common::atom()).await.expect("add feed");
+}
+
+async fn collect<S, N>(state: AppState<S, N>) -> Arc<AppState<S, N>>
+where
+    S: SourceService + 'static,
+    N: NewsService + 'static,
+{
+    let state = Arc::new(state);
+    watch_all(&state).await.expect("watch sources");
+    state
+}
+
+async fn memory_collect(store: &MemoryStore, body: String) {
+    collect(memory_state(store, serving(body)).await.build()).await;
+}
+
+#[tokio::test]
+async fn stores_feed_entries_in_memory() {
+    let store = MemoryStore::new();
+    add_feed(&store.sources()).await;
+
+    memory_collect(&store, feed(&[("1", "First"), ("2", "Second")])).await;
+
+    let titles: Vec<_> = articles(&store.news())
+        .await
+        .into_iter()
+        .map(|a| a.title)
+        .collect();
+    assert_eq!(titles, ["First", "Second"]);
+}
+
+#[tokio::test]
+async fn keeps_revisions_of_edited_entries() {
+    let store = MemoryStore::new();
+
+    add_feed(&store.sources()).await;
+    memory_collect(&store, feed(&[("1", "Draft")])).await;
+    memory_collect(&store, feed(&[("1", "Final")])).await;
+
+    let news = store.news();
+    let article = &articles(&news).await[0];
+    let titles: Vec<_> = news
+        .revisions(article.id)
+        .await
+        .expect("revisions")
+        .into_iter()
+        .map(|revision| revision.title)
+        .collect();
+    assert_eq!(titles, ["Draft", "Final"]);
+}
+
+#[tokio::test]
+async fn stores_feed_entries_in_sqlite_pool() {
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    let state = AppState::builder(Storage::from_pool(pool.clone()))
+        .sources(SqliteSourceService::from_pool(pool.clone()))
+        .news(SqliteNewsService::from_pool(pool))
+        .http(Arc::new(serving(feed(&[("1", "First")]))))
+        .build();
+    add_feed(state.sources().as_ref()).await;
+
+    let state = collect(state).await;
+
+    let titles: Vec<_> = articles(state.news().as_ref())
+        .await
+        .into_iter()
+        .map(|a| a.title)
+        .collect();
+    assert_eq!(titles, ["First"]);
+}
@@ -0,0 +1,109 @@
+//! Sources, fixture responses and states shared by the tests that collect
+//! articles into a [`MemoryStore`].
+
+// Each test crate uses only some of them.
+#![allow(dead_code)]
+
+pub mod server;
+
+use news_core::models::news::Article;
+use news_core::models::source::atom::AtomDraft;
+use news_core::models::source::telegram::TelegramDraft;
+use news_core::services::news::NewsService;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_memory_core::services::news::MemoryNewsService;
+use news_memory_core::services::source::MemorySourceService;
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::db::init::connect_memory_pool;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_watch::cli::{SourceWatchError, watch_source};
+use news_watch::state::{AppState, AppStateBuilder};
+use news_watch::storage::Storage;
+use std::path::PathBuf;
+use std::sync::Arc;
+use url::Url;
+
+pub const FEED_URL: &str = "https://example.com/feed.xml";
+pub const CHANNEL_URL: &str = "https://t.me/s/telegram";
+
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Queues the response in the fixture `name` for the next poll of `url`.
+pub fn recorded(http: FixtureHttpService, url: &str, name: &str) -> FixtureHttpService {
+    http.with_recorded(Url::parse(url).expect("valid URL"), fixture(name))
+        .expect("recorded response")
+}
+
+/// Answers consecutive polls of `url` with the responses in `fixtures`.
+pub fn polls(url: &str, fixtures: &[&str]) -> FixtureHttpService {
+    fixtures
+        .iter()
+        .fold(FixtureHttpService::new(), |http, name| {
+            recorded(http, url, name)
+        })
+}
+
+pub fn atom() -> SourceDraft {
+    SourceDraft::Atom(AtomDraft::new(Url::parse(FEED_URL).expect("valid URL")))
+}
+
+pub fn telegram() -> SourceDraft {
+    SourceDraft::Telegram(TelegramDraft::new("telegram".to_string()))
+}
+
+/// State over the sources and articles of `store` polled through `http`, with
+/// the local services in an in-memory SQLite database.
+pub async fn memory_state(
+    store: &MemoryStore,
+    http: FixtureHttpService,
+) -> AppStateBuilder<MemorySourceService, MemoryNewsService> {
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+
+    AppState::builder(Storage::from_pool(pool))
+        .sources(store.sources())
+        .news(store.news())
+        .http(Arc::new(http))
+}
+
+/// Polls every source of `state` in the order they were added, each until
+/// its fixtures run out, and returns the first failure.
+pub async fn watch_all<S, N>(state: &Arc<AppState<S, N>>) -> Result<(), SourceWatchError>
+where
+    S: SourceService + 'static,
+    N: NewsService + 'static,
+{
+    let sources: Vec<_> = state
+        .sources()
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .collect();
+
+    let mut result = Ok(());
+    for source in sources {
+        result = result.and(watch_source(state.clone(), source).await);
+    }
+    result
+}
+
+/// Stored articles, ordered by title.
+pub async fn articles(news: &impl NewsService) -> Vec<Article> {
+    let mut articles: Vec<_> = news
+        .get_all()
+        .await
+        .expect("list articles")
+        .into_iter()
+        .collect();
+
+    articles.sort_by(|a, b| a.title.cmp(&b.title));
+    articles
+}
+
+pub fn titles(articles: &[Article]) -> Vec<&str> {
+    articles.iter().map(|a| a.title.as_str()).collect()
+}
@@ -0,0 +1,111 @@
+//! A stand-in HTTP server that records the requests it receives.
+
+use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use url::Url;
+
+/// A request as received: its request line, its header lines lowercased and
+/// its body.
+pub struct Request {
+    pub line: String,
+    pub headers: Vec<String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).expect("JSON body")
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut data = Vec::new();
+    let mut buf = [0; 1024];
+
+    let head_end = loop {
+        if let Some(at) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break at;
+        }
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => data.extend_from_slice(&buf[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let line = lines.next()?.to_string();
+    let headers: Vec<_> = lines.map(str::to_lowercase).collect();
+
+    let length = headers
+        .iter()
+        .find_map(|header| header.strip_prefix("content-length:"))
+        .and_then(|length| length.trim().parse().ok())
+        .unwrap_or(0);
+    let mut body = data[head_end + 4..].to_vec();
+    while body.len() < length {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => body.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    Some(Request {
+        line,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// Answers successive requests with `statuses`, the last one over and over,
+/// and records them.
+pub async fn serve(statuses: &'static [&'static str]) -> (Url, mpsc::UnboundedReceiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let url = Url::parse(&format!("http://{}/", listener.local_addr().expect("addr")))
+        .expect("valid URL");
+    let (requests, received) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut answered = 0;
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let Some(request) = read_request(&mut stream).await else {
+                continue;
+            };
+            let status = statuses[answered.min(statuses.len() - 1)];
+            answered += 1;
+            let response = format!("HTTP/1.1 {status}\r\ncontent-length: 2\r\n\r\nok");
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = requests.send(request);
+        }
+    });
+
+    (url, received)
+}
+
+/// Accepts connections and never answers them.
+pub async fn stalled() -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let url = Url::parse(&format!("http://{}/", listener.local_addr().expect("addr")))
+        .expect("valid URL");
+
+    tokio::spawn(async move {
+        let mut open = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            open.push(stream);
+        }
+    });
+
+    url
+}
+
+/// The next recorded request, failing the test if none comes in time.
+pub async fn received(requests: &mut mpsc::UnboundedReceiver<Request>) -> Request {
+    timeout(Duration::from_secs(5), requests.recv())
+        .await
+        .expect("request in time")
+        .expect("request")
+}
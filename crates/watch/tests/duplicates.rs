@@ -0,0 +1,131 @@
+//! Collects fixture feeds and channel pages that publish the same story and
+//! checks how their articles are grouped.
+
+mod common;
+
+use common::{CHANNEL_URL, memory_state, recorded, telegram, watch_all};
+use news_core::config::DuplicateSettings;
+use news_core::models::news::{Article, Story};
+use news_core::models::source::atom::AtomDraft;
+use news_core::services::news::NewsService;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_watch::duplicates::{canonical_link, distance, simhash};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+const CITY_URL: &str = "https://www.example.org/news/feed.xml";
+const WIRE_URL: &str = "https://wire.example.net/rss";
+
+/// Polls the city feed, the regional wire and the channel once, in this
+/// order, and returns the stored articles with their stories.
+async fn collect(settings: DuplicateSettings) -> (Vec<Article>, Vec<Story>) {
+    let store = MemoryStore::new();
+    let drafts = [
+        SourceDraft::Atom(AtomDraft::new(Url::parse(CITY_URL).expect("valid URL"))),
+        SourceDraft::Atom(AtomDraft::new(Url::parse(WIRE_URL).expect("valid URL"))),
+        telegram(),
+    ];
+    for draft in drafts {
+        store.sources().add(draft).await.expect("add source");
+    }
+
+    let http = [
+        (CITY_URL, "atom/city-news.http"),
+        (WIRE_URL, "atom/regional-wire.http"),
+        (CHANNEL_URL, "telegram/press-release.http"),
+    ]
+    .into_iter()
+    .fold(FixtureHttpService::new(), |http, (url, name)| {
+        recorded(http, url, name)
+    });
+
+    let state = Arc::new(
+        memory_state(&store, http)
+            .await
+            .duplicates(settings)
+            .build(),
+    );
+    watch_all(&state).await.expect("watch sources");
+
+    let articles = common::articles(&store.news()).await;
+    let stories = store.news().stories().await.expect("list stories");
+
+    (articles, stories)
+}
+
+fn titles<'a>(articles: &'a [Article], story: &Story) -> Vec<&'a str> {
+    story
+        .articles
+        .iter()
+        .map(|id| {
+            articles
+                .iter()
+                .find(|article| article.id == *id)
+                .map(|article| article.title.as_str())
+                .expect("stored article")
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn copies_and_links_to_the_same_page_form_one_story() {
+    let (articles, stories) = collect(DuplicateSettings::default()).await;
+
+    assert_eq!(articles.len(), 7);
+    assert_eq!(stories.len(), 1);
+    assert_eq!(
+        titles(&articles, &stories[0]),
+        [
+            "Harbour bridge reopens after repairs",
+            "HARBOUR BRIDGE REOPENS AFTER REPAIRS",
+            "Bridge reopening: what drivers need to know",
+            "Finally! See yesterday's post and the announcement.",
+        ]
+    );
+    assert_eq!(stories[0].primary, stories[0].articles[0]);
+}
+
+#[tokio::test]
+async fn only_articles_stored_within_the_window_are_compared() {
+    let settings = DuplicateSettings {
+        window: Duration::ZERO,
+        ..DuplicateSettings::default()
+    };
+    let (_, stories) = collect(settings).await;
+
+    // Each source was polled after the articles of the one before it.
+    assert!(stories.is_empty());
+}
+
+#[test]
+fn canonical_links_ignore_tracking_and_formatting() {
+    let canonical = |url: &str| canonical_link(&Url::parse(url).expect("valid URL"));
+
+    assert_eq!(
+        canonical("https://www.example.org/news/bridge/?utm_source=rss&id=4&fbclid=x#top"),
+        "example.org/news/bridge?id=4"
+    );
+    assert_eq!(
+        canonical("http://example.org:8080/news/bridge"),
+        canonical("https://www.example.org/news/bridge/")
+    );
+    assert_ne!(
+        canonical("https://example.org/news/bridge?id=4"),
+        canonical("https://example.org/news/bridge?id=5")
+    );
+}
+
+#[test]
+fn simhash_ignores_case_markup_and_punctuation() {
+    let text = "The harbour bridge reopens to traffic on Monday morning after repairs.";
+    let copy = "THE HARBOUR BRIDGE REOPENS TO TRAFFIC ON MONDAY MORNING, AFTER REPAIRS!";
+    let other = "Stalls move to the old tram depot from the first weekend of November.";
+
+    let hash = simhash(text).expect("long enough");
+    assert_eq!(simhash(copy), Some(hash));
+    assert!(distance(hash, simhash(other).expect("long enough")) > 3);
+    assert_eq!(simhash("Too short to compare"), None);
+}
@@ -0,0 +1,579 @@
+//! Runs the Atom and Telegram handlers over responses under `tests/fixtures`:
+//! feeds, t.me pages and article pages in the format of `curl --include`.
+//!
+//! The Reddit, Cloudflare blog and BBC podcast feeds are real responses, kept
+//! as recorded apart from entries cut for length and the status line and
+//! headers, which were not saved with them. The other fixtures are made up for
+//! cases those feeds do not cover, such as redirects, relative links, broken
+//! markup and t.me pages, and stick to the markup of the sites they mimic.
+
+mod common;
+
+use common::{CHANNEL_URL, FEED_URL, atom, polls, recorded, telegram, titles};
+use news_core::config::{SourceWatchSettings, WatchSettings};
+use news_core::models::news::{Article, Attachment};
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::AtomDraft;
+use news_core::models::source::request::RequestSettings;
+use news_core::services::HttpResponse;
+use news_core::services::news::NewsService;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_watch::cli::SourceWatchError;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Adds the source, polls it until the fixtures run out and returns the
+/// stored articles ordered by title.
+async fn collect(
+    draft: SourceDraft,
+    http: FixtureHttpService,
+) -> (Result<(), SourceWatchError>, Vec<Article>) {
+    let (_, result, articles) = collect_into(draft, http, WatchSettings::default()).await;
+    (result, articles)
+}
+
+/// Like [`collect`] with `watch` settings, also returning the store the source
+/// was added to.
+async fn collect_into(
+    draft: SourceDraft,
+    http: FixtureHttpService,
+    watch: WatchSettings,
+) -> (MemoryStore, Result<(), SourceWatchError>, Vec<Article>) {
+    let store = MemoryStore::new();
+    store.sources().add(draft).await.expect("add source");
+
+    let (result, articles) = collect_sources(&store, http, watch).await;
+    (store, result, articles)
+}
+
+/// Polls the sources of `store` until the fixtures run out and returns the
+/// stored articles ordered by title.
+async fn collect_sources(
+    store: &MemoryStore,
+    http: FixtureHttpService,
+    watch: WatchSettings,
+) -> (Result<(), SourceWatchError>, Vec<Article>) {
+    let state = Arc::new(common::memory_state(store, http).await.watch(watch).build());
+
+    let result = common::watch_all(&state).await;
+    (result, common::articles(&store.news()).await)
+}
+
+#[tokio::test]
+async fn atom_feed_stores_every_entry() {
+    let http = polls(FEED_URL, &["atom/reddit-homelab.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert_eq!(
+        titles(&articles),
+        [
+            "Any reason to keep 1G connections to my servers?",
+            "Looking into UPS for server rack",
+            "What should I look for when buying a UPS?",
+        ]
+    );
+    assert!(articles[0].description.contains("acquired a 40G switch"));
+}
+
+#[tokio::test]
+async fn atom_feed_content_is_sanitized() {
+    let http = polls(FEED_URL, &["atom/reddit-homelab.http"]);
+    let (_, articles) = collect(atom(), http).await;
+
+    let description = &articles[0].description;
+    assert!(description.contains("/u/Remarkable_Housing61"));
+    assert!(!description.contains("SC_OFF"));
+    assert!(!description.contains("class="));
+
+    let (_, articles) = collect(atom(), polls(FEED_URL, &["atom/rust-blog.http"])).await;
+    let survey = &articles[2].description;
+    assert!(survey.contains("Thank you to everyone who responded."));
+    assert!(!survey.contains("<script"));
+    assert!(!survey.contains("onclick"));
+}
+
+#[tokio::test]
+async fn relative_links_resolve_against_xml_base_and_entry_page() {
+    let http = polls(FEED_URL, &["atom/relative-links.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    let bench = &articles[0].description;
+    assert!(bench.contains(r#"src="https://static.example.org/journal/2025/bench.jpg""#));
+    assert!(bench.contains(r#"href="https://static.example.org/plans/bench.pdf""#));
+    let shelf = &articles[1].description;
+    assert!(shelf.contains(r#"href="https://www.example.org/journal/stock""#));
+}
+
+#[tokio::test]
+async fn javascript_links_and_tracking_pixels_are_dropped() {
+    let http = polls(FEED_URL, &["atom/relative-links.http"]);
+    let (_, articles) = collect(atom(), http).await;
+
+    let shelf = &articles[1].description;
+    assert!(shelf.contains("the drawing"));
+    assert!(!shelf.contains("javascript:"));
+    assert!(!shelf.contains("<img"));
+}
+
+#[tokio::test]
+async fn rss_feed_stores_every_item() {
+    let http = polls(FEED_URL, &["atom/cloudflare-blog.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert_eq!(
+        titles(&articles),
+        ["Privacy-Preserving Compromised Credential Checking"]
+    );
+    assert!(
+        articles[0]
+            .description
+            .contains("open-sourced Go implementation")
+    );
+
+    let http = polls(FEED_URL, &["atom/python-insider.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert_eq!(
+        titles(&articles),
+        [
+            "Python 3.14 release candidate 3 is go",
+            "Python 3.14.0 is now available",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn redirected_feed_is_read_from_its_final_response() {
+    let http = polls(FEED_URL, &["atom/moved.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert_eq!(titles(&articles), ["Hello from the new address"]);
+}
+
+/// Addresses of the feeds in `store`.
+async fn feed_urls(store: &MemoryStore) -> Vec<String> {
+    store
+        .sources()
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .filter_map(|source| match source {
+            SourceEnum::Atom(feed) => Some(feed.url.to_string()),
+            SourceEnum::Telegram(_) => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn permanently_moved_feed_is_polled_at_its_new_address() {
+    let http = polls(FEED_URL, &["atom/moved.http"]);
+    let (store, result, _) = collect_into(atom(), http, WatchSettings::default()).await;
+
+    result.expect("watch feed");
+    assert_eq!(
+        feed_urls(&store).await,
+        ["https://www.example.org/blog/atom.xml"]
+    );
+}
+
+#[tokio::test]
+async fn feeds_with_credentials_do_not_follow_a_move_to_another_origin() {
+    let draft =
+        AtomDraft::new(Url::parse(FEED_URL).expect("valid URL")).with_request(RequestSettings {
+            bearer_token: Some("${EXAMPLE_TOKEN}".to_string()),
+            ..RequestSettings::default()
+        });
+    let http = polls(FEED_URL, &["atom/moved.http"]);
+    let (store, result, articles) =
+        collect_into(SourceDraft::Atom(draft), http, WatchSettings::default()).await;
+
+    result.expect("watch feed");
+    assert_eq!(titles(&articles), ["Hello from the new address"]);
+    assert_eq!(feed_urls(&store).await, [FEED_URL]);
+}
+
+#[tokio::test]
+async fn settings_naming_the_source_id_survive_a_move() {
+    const MOVED_URL: &str = "https://www.example.org/blog/atom.xml";
+
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+    let [source] = store
+        .sources()
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .collect::<Vec<_>>()
+        .try_into()
+        .expect("one source");
+    // Moved by an earlier run, so the feed URL no longer names it.
+    store
+        .sources()
+        .move_atom(source.id(), Url::parse(MOVED_URL).expect("valid URL"))
+        .await
+        .expect("move feed");
+
+    let watch = WatchSettings {
+        sources: vec![SourceWatchSettings {
+            source: source.id().to_string(),
+            full_content: false,
+            languages: vec!["de".to_string()],
+        }],
+        ..WatchSettings::default()
+    };
+    let http = polls(MOVED_URL, &["atom/python-insider.http"]);
+    let (result, articles) = collect_sources(&store, http, watch).await;
+
+    // Both entries are in English.
+    result.expect("watch feed");
+    assert!(articles.is_empty());
+}
+
+#[tokio::test]
+async fn failed_polls_are_skipped() {
+    let http = polls(
+        FEED_URL,
+        &["atom/unavailable.http", "atom/python-insider.http"],
+    );
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert_eq!(articles.len(), 2);
+}
+
+#[tokio::test]
+async fn repeated_polls_do_not_duplicate_entries() {
+    let http = polls(
+        FEED_URL,
+        &["atom/python-insider.http", "atom/python-insider.http"],
+    );
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert_eq!(articles.len(), 2);
+}
+
+#[tokio::test]
+async fn html_page_instead_of_feed_is_an_error() {
+    let http = polls(FEED_URL, &["atom/not-a-feed.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    assert!(result.is_err());
+    assert!(articles.is_empty());
+}
+
+/// Attachments of `article`, as stored in `store`.
+async fn attachments(store: &MemoryStore, article: &Article) -> Vec<Attachment> {
+    store
+        .news()
+        .attachments(&[article.id])
+        .await
+        .expect("list attachments")
+        .remove(&article.id)
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn podcast_episodes_keep_their_enclosure() {
+    let http = polls(FEED_URL, &["atom/in-our-time.http"]);
+    let (store, result, articles) = collect_into(atom(), http, WatchSettings::default()).await;
+
+    result.expect("watch feed");
+    assert_eq!(titles(&articles), ["Marcus Aurelius"]);
+    assert_eq!(
+        attachments(&store, &articles[0]).await,
+        [Attachment {
+            url: Url::parse(
+                "http://open.live.bbc.co.uk/mediaselector/6/redir/version/2.0/mediaset/audio-nondrm-download/proto/http/vpid/p097wt5b.mp3"
+            )
+            .expect("valid URL"),
+            content_type: Some("audio/mpeg".to_string()),
+            length: Some(50_496_000),
+            duration: Some(Duration::from_secs(3156)),
+            thumbnail: None,
+        }]
+    );
+
+    let http = polls(FEED_URL, &["atom/podcast.http"]);
+    let (store, result, articles) = collect_into(atom(), http, WatchSettings::default()).await;
+
+    // Episodes without an enclosure have no attachments.
+    result.expect("watch feed");
+    assert_eq!(articles[1].title, "Listener questions");
+    assert!(attachments(&store, &articles[1]).await.is_empty());
+}
+
+#[tokio::test]
+async fn atom_enclosure_links_are_attachments() {
+    let http = polls(FEED_URL, &["atom/enclosures.http"]);
+    let (store, result, articles) = collect_into(atom(), http, WatchSettings::default()).await;
+
+    result.expect("watch feed");
+    let stored = attachments(&store, &articles[0]).await;
+    let urls: Vec<&str> = stored.iter().map(|a| a.url.as_str()).collect();
+    assert_eq!(
+        urls,
+        [
+            "https://www.example.org/talks/2025/joinery.mp4",
+            "https://slides.example.org/joinery.pdf",
+        ]
+    );
+    assert_eq!(stored[0].content_type.as_deref(), Some("video/mp4"));
+    assert_eq!(stored[0].length, Some(734_003_200));
+}
+
+fn full_content() -> WatchSettings {
+    WatchSettings {
+        sources: vec![SourceWatchSettings {
+            source: FEED_URL.to_string(),
+            full_content: true,
+            languages: Vec::new(),
+        }],
+        ..WatchSettings::default()
+    }
+}
+
+fn tide_pools_page(http: FixtureHttpService) -> FixtureHttpService {
+    recorded(
+        http,
+        "https://example.com/posts/tide-pools",
+        "pages/tide-pools.http",
+    )
+}
+
+#[tokio::test]
+async fn summaries_are_kept_without_full_content() {
+    let http = polls(FEED_URL, &["atom/summaries.http"]);
+    let (result, articles) = collect(atom(), http).await;
+
+    result.expect("watch feed");
+    assert!(articles.iter().all(|article| article.content.is_none()));
+}
+
+#[tokio::test]
+async fn full_content_is_the_main_text_of_the_linked_page() {
+    // The page of the second entry is not recorded, so fetching it fails.
+    let http = tide_pools_page(polls(FEED_URL, &["atom/summaries.http"]));
+    let (_, result, articles) = collect_into(atom(), http, full_content()).await;
+
+    result.expect("watch feed");
+    assert_eq!(
+        titles(&articles),
+        ["Counting tide pools", "Lichens of the old wall"]
+    );
+
+    let content = articles[0].content.as_deref().expect("full content");
+    assert!(content.contains("forty-two pools"));
+    assert!(content.contains("a single, very patient sculpin"));
+    for boilerplate in [
+        "analytics",
+        "About",
+        "cookies",
+        "newsletter",
+        "Great post",
+        "Copyright",
+    ] {
+        assert!(!content.contains(boilerplate), "{boilerplate} in {content}");
+    }
+    assert_eq!(
+        articles[0].description,
+        "We spent a week on the north shore."
+    );
+    assert_eq!(articles[1].content, None);
+}
+
+#[tokio::test]
+async fn full_content_pages_are_fetched_once() {
+    let http = tide_pools_page(polls(
+        FEED_URL,
+        &["atom/summaries.http", "atom/summaries.http"],
+    ));
+    let (_, result, articles) = collect_into(atom(), http, full_content()).await;
+
+    result.expect("watch feed");
+    let content = articles[0].content.as_deref().expect("full content");
+    assert!(content.contains("forty-two pools"));
+}
+
+/// The feed twice, with the page of the second entry first without article
+/// text, then with it.
+fn lichens_page_appearing() -> FixtureHttpService {
+    const LICHENS_URL: &str = "https://example.com/posts/lichens";
+
+    let http = tide_pools_page(polls(
+        FEED_URL,
+        &["atom/summaries.http", "atom/summaries.http"],
+    ))
+    .with_response(HttpResponse::new(
+        Url::parse(LICHENS_URL).expect("valid URL"),
+        "<html><body><p>Nothing here.</p></body></html>",
+    ));
+    recorded(http, LICHENS_URL, "pages/tide-pools.http")
+}
+
+#[tokio::test]
+async fn failed_full_content_pages_wait_before_being_fetched_again() {
+    let (_, result, articles) =
+        collect_into(atom(), lichens_page_appearing(), full_content()).await;
+
+    result.expect("watch feed");
+    assert_eq!(articles[1].title, "Lichens of the old wall");
+    assert_eq!(articles[1].content, None);
+
+    let watch = WatchSettings {
+        full_content_retry: Duration::ZERO,
+        ..full_content()
+    };
+    let (_, result, articles) = collect_into(atom(), lichens_page_appearing(), watch).await;
+
+    result.expect("watch feed");
+    assert!(articles[1].content.is_some());
+}
+
+#[tokio::test]
+async fn telegram_page_stores_messages_with_text() {
+    let http = polls(CHANNEL_URL, &["telegram/channel.http"]);
+    let (result, articles) = collect(telegram(), http).await;
+
+    result.expect("watch channel");
+    assert_eq!(articles.len(), 3);
+    assert_eq!(
+        titles(&articles),
+        [
+            "Gifts 2.0 Collectible gifts can now be traded on the marketplace.",
+            "Stories now support music",
+            "Telegram now lets channels publish suggested posts from subscribers, which admins can review, edit…",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn telegram_message_html_is_sanitized() {
+    let http = polls(CHANNEL_URL, &["telegram/channel.http"]);
+    let (_, articles) = collect(telegram(), http).await;
+
+    let gifts = &articles[0].description;
+    assert!(gifts.contains(r#"href="https://t.me/gifts""#));
+    assert!(!gifts.contains("onclick"));
+    assert!(!gifts.contains("<script"));
+}
+
+#[tokio::test]
+async fn telegram_links_resolve_against_the_public_page() {
+    let http = polls(CHANNEL_URL, &["telegram/relative-links.http"]);
+    let (result, articles) = collect(telegram(), http).await;
+
+    result.expect("watch channel");
+    let post = &articles[0].description;
+    assert!(post.contains(r#"href="https://t.me/telegram/409""#));
+    assert!(!post.contains("javascript:"));
+}
+
+/// Language of each article, by title.
+async fn languages(store: &MemoryStore, articles: &[Article]) -> Vec<(String, Option<String>)> {
+    let ids: Vec<_> = articles.iter().map(|article| article.id).collect();
+    let details = store.news().details(&ids).await.expect("article details");
+
+    articles
+        .iter()
+        .map(|article| {
+            let title = article.title.chars().take(12).collect();
+            (title, details[&article.id].language.clone())
+        })
+        .collect()
+}
+
+fn language(title: &str, language: Option<&str>) -> (String, Option<String>) {
+    (title.to_string(), language.map(str::to_string))
+}
+
+#[tokio::test]
+async fn articles_keep_their_detected_language() {
+    let http = polls(CHANNEL_URL, &["telegram/multilingual.http"]);
+    let (store, result, articles) = collect_into(telegram(), http, WatchSettings::default()).await;
+
+    result.expect("watch channel");
+    assert_eq!(
+        languages(&store, &articles).await,
+        [
+            language("Der Stadtrat", Some("de")),
+            language("The city cou", Some("en")),
+            language("Городской со", Some("ru")),
+            language("👍 2025", None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn channels_can_be_restricted_to_languages() {
+    let http = polls(CHANNEL_URL, &["telegram/multilingual.http"]);
+    let watch = WatchSettings {
+        sources: vec![SourceWatchSettings {
+            source: "telegram".to_string(),
+            full_content: false,
+            languages: vec!["en".to_string(), "de".to_string()],
+        }],
+        ..WatchSettings::default()
+    };
+    let (store, result, articles) = collect_into(telegram(), http, watch).await;
+
+    // Messages whose language is not known are kept.
+    result.expect("watch channel");
+    assert_eq!(
+        languages(&store, &articles).await,
+        [
+            language("Der Stadtrat", Some("de")),
+            language("The city cou", Some("en")),
+            language("👍 2025", None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn long_messages_get_a_headline_and_summary() {
+    let http = polls(CHANNEL_URL, &["telegram/long-read.http"]);
+    let (result, articles) = collect(telegram(), http).await;
+
+    result.expect("watch channel");
+    let [article] = articles.as_slice() else {
+        panic!("one article expected, got {articles:?}");
+    };
+    assert!(article.title.ends_with('…'));
+    assert_eq!(article.display_title(), "Night buses return to the city");
+    assert_eq!(
+        article.summary.as_deref(),
+        Some(
+            "The city council approved the new public transport budget on Tuesday after a long \
+             debate. Drivers' unions welcomed the night buses but asked for more drivers. A public \
+             consultation on the routes of the night buses opens next week on the website of the \
+             city."
+        )
+    );
+}
+
+#[tokio::test]
+async fn empty_telegram_page_stores_nothing() {
+    let http = polls(CHANNEL_URL, &["telegram/empty.http"]);
+    let (result, articles) = collect(telegram(), http).await;
+
+    result.expect("watch channel");
+    assert!(articles.is_empty());
+}
+
+#[tokio::test]
+async fn unrecorded_url_is_an_error() {
+    let http = polls("https://example.com/other.xml", &["atom/rust-blog.http"]);
+    let (result, _) = collect(atom(), http).await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,487 @@
+//! Caches the images of articles collected from fixture responses, and
+//! removes the cached files no stored image maps to.
+
+mod common;
+
+use common::{CHANNEL_URL, FEED_URL, atom, recorded, telegram};
+use news_core::config::{MediaSettings, SourceWatchSettings, WatchSettings};
+use news_core::models::media::{is_media_name, media_references};
+use news_core::models::news::{Article, ArticleMarks, Attachment, News};
+use news_core::services::HttpResponse;
+use news_core::services::news::NewsService;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_watch::media::{collect_garbage, download_attachments, localize_images};
+use news_watch::state::AppState;
+use reqwest::header::{CONTENT_TYPE, HeaderValue};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use url::Url;
+use uuid::Uuid;
+
+const BENCH_URL: &str = "https://static.example.org/journal/2025/bench.jpg";
+const PHOTO_URL: &str = "https://cdn4.cdn-telegram.org/file/photo.jpg";
+const PAGE_URL: &str = "https://example.com/posts/tide-pools";
+const EPISODE_URL: &str = "https://cdn.example.org/tide-pool-hour/12/audio.mp3";
+const POOLS_URL: &str = "https://example.com/images/tide-pools.jpg";
+
+const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\x00\x10JFIF\x00\x01 not much of a picture";
+const SVG: &[u8] = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+
+fn media_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()))
+}
+
+fn enabled(dir: &Path) -> MediaSettings {
+    MediaSettings {
+        enabled: true,
+        dir: dir.to_path_buf(),
+        ..MediaSettings::default()
+    }
+}
+
+fn image(url: &str, content_type: &str, body: &[u8]) -> HttpResponse {
+    let mut response = HttpResponse::new(Url::parse(url).expect("valid URL"), body);
+    response.headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_str(content_type).expect("valid header"),
+    );
+    response
+}
+
+/// Adds the source, polls it until the fixtures run out and returns the
+/// state with the stored articles, ordered by title.
+async fn collect(
+    draft: SourceDraft,
+    http: FixtureHttpService,
+    media: MediaSettings,
+) -> (
+    Arc<AppState<impl SourceService, impl NewsService>>,
+    Vec<Article>,
+) {
+    collect_with(draft, http, media, WatchSettings::default()).await
+}
+
+async fn collect_with(
+    draft: SourceDraft,
+    http: FixtureHttpService,
+    media: MediaSettings,
+    watch: WatchSettings,
+) -> (
+    Arc<AppState<impl SourceService, impl NewsService>>,
+    Vec<Article>,
+) {
+    let store = MemoryStore::new();
+    store.sources().add(draft).await.expect("add source");
+
+    let state = Arc::new(
+        common::memory_state(&store, http)
+            .await
+            .media(media)
+            .watch(watch)
+            .build(),
+    );
+    common::watch_all(&state).await.expect("watch sources");
+
+    let articles = common::articles(&store.news()).await;
+    (state, articles)
+}
+
+fn cached_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<_> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .map(|entry| entry.expect("directory entry").file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn images_are_not_cached_by_default() {
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    );
+    let settings = MediaSettings {
+        dir: dir.clone(),
+        ..MediaSettings::default()
+    };
+    let (_, articles) = collect(atom(), http, settings).await;
+
+    assert!(
+        articles[0]
+            .description
+            .contains(&format!("src=\"{BENCH_URL}\""))
+    );
+    assert!(cached_files(&dir).is_empty());
+}
+
+#[tokio::test]
+async fn images_are_stored_once_and_referenced_locally() {
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    );
+    let http = recorded(http, FEED_URL, "atom/relative-links.http").with_response(image(
+        BENCH_URL,
+        "image/jpeg",
+        JPEG,
+    ));
+    let (state, articles) = collect(atom(), http, enabled(&dir)).await;
+
+    assert!(
+        articles[0]
+            .description
+            .contains(&format!("src=\"{BENCH_URL}\""))
+    );
+    let bench = localize_images(&state, &articles[0].description)
+        .await
+        .expect("localized images");
+    let names: Vec<_> = media_references(&bench).collect();
+    assert_eq!(names.len(), 1);
+    assert!(names[0].ends_with(".jpg"));
+    assert!(!bench.contains(BENCH_URL));
+    assert_eq!(cached_files(&dir), names);
+    assert_eq!(
+        std::fs::read(dir.join(names[0])).expect("cached file"),
+        JPEG
+    );
+}
+
+#[tokio::test]
+async fn images_of_other_types_keep_their_remote_url() {
+    let dir = media_dir();
+    // The second poll must not ask for the image again.
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    );
+    let http = recorded(http, FEED_URL, "atom/relative-links.http")
+        .with_response(image(BENCH_URL, "image/svg+xml", SVG))
+        .with_response(image(BENCH_URL, "image/jpeg", JPEG));
+    let (_, articles) = collect(atom(), http, enabled(&dir)).await;
+
+    assert!(
+        articles[0]
+            .description
+            .contains(&format!("src=\"{BENCH_URL}\""))
+    );
+    assert!(cached_files(&dir).is_empty());
+}
+
+#[tokio::test]
+async fn images_served_as_other_content_are_not_cached() {
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    )
+    .with_response(image(BENCH_URL, "text/html", JPEG));
+    let (_, articles) = collect(atom(), http, enabled(&dir)).await;
+
+    assert!(articles[0].description.contains(BENCH_URL));
+    assert!(cached_files(&dir).is_empty());
+}
+
+#[tokio::test]
+async fn images_over_the_size_limit_are_not_cached() {
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    )
+    .with_response(image(BENCH_URL, "image/jpeg", JPEG));
+    let settings = MediaSettings {
+        max_file_size: 16,
+        ..enabled(&dir)
+    };
+    let (_, articles) = collect(atom(), http, settings).await;
+
+    assert!(articles[0].description.contains(BENCH_URL));
+    assert!(cached_files(&dir).is_empty());
+}
+
+#[tokio::test]
+async fn telegram_photos_are_stored_with_the_message() {
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        CHANNEL_URL,
+        "telegram/channel.http",
+    )
+    .with_response(image(PHOTO_URL, "image/jpeg", JPEG));
+    let (state, articles) = collect(telegram(), http, enabled(&dir)).await;
+
+    let stories = articles
+        .iter()
+        .find(|article| article.title == "Stories now support music")
+        .expect("message with a photo");
+    let stories = localize_images(&state, &stories.description)
+        .await
+        .expect("localized images");
+    let names: Vec<_> = media_references(&stories).collect();
+    assert_eq!(names.len(), 1);
+    assert!(stories.contains("Stories now support music"));
+    assert_eq!(cached_files(&dir), names);
+}
+
+#[tokio::test]
+async fn garbage_collection_removes_unreferenced_files() {
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    )
+    .with_response(image(BENCH_URL, "image/jpeg", JPEG));
+    let (state, articles) = collect(atom(), http, enabled(&dir)).await;
+    let bench = localize_images(&state, &articles[0].description)
+        .await
+        .expect("localized images");
+    let referenced: Vec<_> = media_references(&bench).collect();
+
+    let stale = format!("{}.png", "0".repeat(64));
+    let fresh = format!("{}.gif", "1".repeat(64));
+    assert!(is_media_name(&stale));
+    for name in [&stale, &fresh, &"notes.txt".to_string()] {
+        std::fs::write(dir.join(name), b"...").expect("write file");
+    }
+    let yesterday = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+    for name in [&stale, &referenced[0].to_string()] {
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join(name))
+            .and_then(|file| file.set_modified(yesterday))
+            .expect("age file");
+    }
+
+    let report = collect_garbage(&state, true).await.expect("dry run");
+    assert_eq!(report.files, 1);
+    assert_eq!(cached_files(&dir).len(), 4);
+
+    let report = collect_garbage(&state, false)
+        .await
+        .expect("collect garbage");
+    assert_eq!((report.files, report.bytes), (1, 3));
+
+    // Files downloaded during the last hour may belong to articles not stored yet.
+    let mut kept = vec![referenced[0].to_string(), fresh, "notes.txt".to_string()];
+    kept.sort();
+    assert_eq!(cached_files(&dir), kept);
+}
+
+#[tokio::test]
+async fn stored_articles_do_not_depend_on_the_cache() {
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    );
+    let (_, uncached) = collect(atom(), http, MediaSettings::default()).await;
+
+    // The first poll caches the image, the second finds it cached.
+    let dir = media_dir();
+    let http = recorded(
+        FixtureHttpService::new(),
+        FEED_URL,
+        "atom/relative-links.http",
+    )
+    .with_response(image(BENCH_URL, "image/jpeg", JPEG));
+    let http = recorded(http, FEED_URL, "atom/relative-links.http");
+    let (_, cached) = collect(atom(), http, enabled(&dir)).await;
+
+    assert_eq!(cached.len(), uncached.len());
+    for (cached, uncached) in cached.iter().zip(&uncached) {
+        assert_eq!(cached.description, uncached.description);
+        assert_eq!(cached.updated_at, cached.created_at);
+    }
+    assert_eq!(cached_files(&dir).len(), 1);
+}
+
+/// Full content stored for the tide pools entry by an earlier poll, as
+/// Markdown converted from its page.
+struct StoredContent {
+    parent_id: Uuid,
+    content: Option<String>,
+    marks: ArticleMarks,
+}
+
+impl StoredContent {
+    fn new(parent_id: Uuid) -> Self {
+        Self {
+            parent_id,
+            content: Some(format!(
+                "# Counting tide pools\n\n![The outer pools at low tide]({POOLS_URL})\n\nMost pools were shallow."
+            )),
+            marks: ArticleMarks::default(),
+        }
+    }
+}
+
+impl News for StoredContent {
+    fn source_key(&self) -> &str {
+        PAGE_URL
+    }
+
+    fn parent_id(&self) -> Uuid {
+        self.parent_id
+    }
+
+    fn title(&self) -> &str {
+        "Counting tide pools"
+    }
+
+    fn description(&self) -> &str {
+        "<p>A week on the north shore.</p>"
+    }
+
+    fn content(&self) -> &Option<String> {
+        &self.content
+    }
+
+    fn attachments(&self) -> &[Attachment] {
+        &[]
+    }
+
+    fn authors(&self) -> &[String] {
+        &[]
+    }
+
+    fn categories(&self) -> &[String] {
+        &[]
+    }
+
+    fn link(&self) -> Option<&Url> {
+        None
+    }
+
+    fn language(&self) -> Option<&str> {
+        None
+    }
+
+    fn summary(&self) -> Option<&str> {
+        None
+    }
+
+    fn headline(&self) -> Option<&str> {
+        None
+    }
+
+    fn marks(&self) -> &ArticleMarks {
+        &self.marks
+    }
+}
+
+#[tokio::test]
+async fn images_of_full_content_are_cached() {
+    let dir = media_dir();
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+    let source = store
+        .sources()
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .next()
+        .expect("source");
+    store
+        .news()
+        .update(&[Arc::new(StoredContent::new(source.id()))])
+        .await
+        .expect("store full content");
+
+    // The stored content is reused instead of fetching the page again.
+    let http = recorded(FixtureHttpService::new(), FEED_URL, "atom/summaries.http")
+        .with_response(image(POOLS_URL, "image/jpeg", JPEG));
+    let watch = WatchSettings {
+        sources: vec![SourceWatchSettings {
+            source: FEED_URL.to_string(),
+            full_content: true,
+            languages: Vec::new(),
+        }],
+        ..WatchSettings::default()
+    };
+    let state = Arc::new(
+        common::memory_state(&store, http)
+            .await
+            .media(enabled(&dir))
+            .watch(watch)
+            .build(),
+    );
+    common::watch_all(&state).await.expect("watch sources");
+
+    let [name] = cached_files(&dir).try_into().expect("one cached image");
+    let content = common::articles(&store.news())
+        .await
+        .into_iter()
+        .find_map(|article| article.content)
+        .expect("full content");
+    let content = localize_images(&state, &content)
+        .await
+        .expect("localized images");
+    assert!(content.contains(&format!("![The outer pools at low tide](/media/{name})")));
+    assert_eq!(media_references(&content).collect::<Vec<_>>(), [&name]);
+
+    let yesterday = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+    std::fs::File::options()
+        .write(true)
+        .open(dir.join(&name))
+        .and_then(|file| file.set_modified(yesterday))
+        .expect("age file");
+    let report = collect_garbage(&state, false)
+        .await
+        .expect("collect garbage");
+    assert_eq!(report.files, 0);
+}
+
+#[tokio::test]
+async fn attachments_are_written_to_the_directory() {
+    let out = media_dir();
+    let http = recorded(FixtureHttpService::new(), FEED_URL, "atom/podcast.http")
+        .with_response(image(EPISODE_URL, "audio/mpeg", b"ID3 episode twelve"));
+    let (state, articles) = collect(atom(), http, MediaSettings::default()).await;
+
+    let report = download_attachments(&state, &out, None)
+        .await
+        .expect("download attachments");
+
+    assert_eq!((report.files, report.bytes, report.failed), (1, 18, 0));
+    let episode = articles
+        .iter()
+        .find(|article| article.title == "Barnacles, up close")
+        .expect("episode with an enclosure");
+    assert_eq!(cached_files(&out), [format!("{}-audio.mp3", episode.id)]);
+}
+
+#[tokio::test]
+async fn attachments_over_the_size_limit_are_not_kept() {
+    let out = media_dir();
+    let http = recorded(FixtureHttpService::new(), FEED_URL, "atom/podcast.http")
+        .with_response(image(EPISODE_URL, "audio/mpeg", b"ID3 episode twelve"));
+    let settings = MediaSettings {
+        max_attachment_size: 16,
+        ..MediaSettings::default()
+    };
+    let (state, _) = collect(atom(), http, settings).await;
+
+    let report = download_attachments(&state, &out, None)
+        .await
+        .expect("download attachments");
+
+    assert_eq!((report.files, report.failed), (0, 1));
+    assert!(cached_files(&out).is_empty());
+}
@@ -0,0 +1,325 @@
+//! Sends notifications to local stand-ins of the Telegram, Slack, Matrix,
+//! ntfy and Gotify HTTP APIs, which record the requests they receive.
+
+mod common;
+
+use common::server::{received, serve, stalled};
+use common::{FEED_URL, atom, memory_state, polls, watch_all};
+use news_core::models::notifier::{
+    Notification, NotificationRouteDraft, NotifierConfig, RouteFilter,
+};
+use news_core::services::notifier::{NotificationRouteService, NotifyError};
+use news_core::services::source::SourceService;
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::db::init::connect_memory_pool;
+use news_sqlite_core::services::notifier::SqliteNotificationRouteService;
+use news_sqlite_core::utils::secrets::Secrets;
+use news_watch::notify::{notifier_for, send_due, watch_notifications};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+use url::Url;
+use uuid::Uuid;
+
+const SECRETS: &str = r#"
+TELEGRAM_BOT_TOKEN = "123456:ABC"
+MATRIX_TOKEN = "syt_token"
+NTFY_TOKEN = "tk_ntfy"
+GOTIFY_TOKEN = "A1b2C3"
+"#;
+
+/// Secrets read from a secrets file with `content`.
+fn secrets(content: &str) -> Secrets {
+    let dir = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temporary directory must be writable");
+
+    let path = dir.join("secrets.toml");
+    std::fs::write(&path, content).expect("write secrets");
+    Secrets::load(Some(&path)).expect("valid secrets file")
+}
+
+async fn notify(config: &NotifierConfig, title: &str) -> Result<(), NotifyError> {
+    let notification = Notification {
+        title: title.to_string(),
+        message: format!("{title}\nhttps://example.com/feed.xml"),
+    };
+
+    notifier_for(config, &secrets(SECRETS))?
+        .notify(&notification)
+        .await
+}
+
+#[tokio::test]
+async fn telegram_messages_go_to_the_bot_api() {
+    let (api_url, mut requests) = serve(&["200 OK"]).await;
+    let config = NotifierConfig::Telegram {
+        api_url,
+        bot_token: "${TELEGRAM_BOT_TOKEN}".to_string(),
+        chat_id: "@my_channel".to_string(),
+    };
+
+    notify(&config, "Tide pools").await.expect("notify");
+
+    let request = received(&mut requests).await;
+    assert_eq!(request.line, "POST /bot123456:ABC/sendMessage HTTP/1.1");
+    assert_eq!(
+        request.json(),
+        json!({
+            "chat_id": "@my_channel",
+            "text": "Tide pools\nhttps://example.com/feed.xml",
+        })
+    );
+}
+
+#[tokio::test]
+async fn slack_messages_go_to_the_resolved_webhook() {
+    let (server, mut requests) = serve(&["200 OK"]).await;
+    let webhook = server.join("services/T000/B000/XXXX").expect("valid URL");
+    let config = NotifierConfig::Slack {
+        webhook_url: "${SLACK_WEBHOOK_URL}".to_string(),
+    };
+    let error = notifier_for(&config, &Secrets::default())
+        .err()
+        .expect("unknown secret");
+    assert_eq!(
+        error.to_string(),
+        "Failed to notify via slack: secret SLACK_WEBHOOK_URL is neither an environment variable nor in the secrets file"
+    );
+
+    let notification = Notification {
+        title: "Tide pools".to_string(),
+        message: "Tide pools".to_string(),
+    };
+    notifier_for(
+        &config,
+        &secrets(&format!("SLACK_WEBHOOK_URL = \"{webhook}\"")),
+    )
+    .expect("notifier")
+    .notify(&notification)
+    .await
+    .expect("notify");
+
+    let request = received(&mut requests).await;
+    assert_eq!(request.line, "POST /services/T000/B000/XXXX HTTP/1.1");
+    assert_eq!(request.json(), json!({ "text": "Tide pools" }));
+}
+
+#[tokio::test]
+async fn matrix_messages_are_room_events() {
+    let (homeserver, mut requests) = serve(&["200 OK"]).await;
+    let config = NotifierConfig::Matrix {
+        homeserver,
+        access_token: "${MATRIX_TOKEN}".to_string(),
+        room_id: "!abc:example.org".to_string(),
+    };
+
+    notify(&config, "Tide pools").await.expect("notify");
+
+    let request = received(&mut requests).await;
+    assert!(
+        request
+            .line
+            .starts_with("PUT /_matrix/client/v3/rooms/!abc:example.org/send/m.room.message/"),
+        "{}",
+        request.line
+    );
+    assert!(
+        request
+            .headers
+            .contains(&"authorization: bearer syt_token".to_string())
+    );
+    assert_eq!(
+        request.json(),
+        json!({
+            "msgtype": "m.text",
+            "body": "Tide pools\nhttps://example.com/feed.xml",
+        })
+    );
+}
+
+#[tokio::test]
+async fn ntfy_titles_are_encoded_headers() {
+    let (server, mut requests) = serve(&["200 OK"]).await;
+    let config = NotifierConfig::Ntfy {
+        server,
+        topic: "my-news".to_string(),
+        token: Some("${NTFY_TOKEN}".to_string()),
+    };
+
+    notify(&config, "Café").await.expect("notify");
+
+    let request = received(&mut requests).await;
+    assert_eq!(request.line, "POST /my-news HTTP/1.1");
+    assert!(
+        request
+            .headers
+            .contains(&"title: =?utf-8?q?caf=c3=a9?=".to_string())
+    );
+    assert!(
+        request
+            .headers
+            .contains(&"authorization: bearer tk_ntfy".to_string())
+    );
+    assert_eq!(request.body, "Café\nhttps://example.com/feed.xml");
+}
+
+#[tokio::test]
+async fn gotify_messages_carry_the_priority() {
+    let (server, mut requests) = serve(&["200 OK"]).await;
+    let config = NotifierConfig::Gotify {
+        server,
+        token: "${GOTIFY_TOKEN}".to_string(),
+        priority: Some(5),
+    };
+
+    notify(&config, "Tide pools").await.expect("notify");
+
+    let request = received(&mut requests).await;
+    assert_eq!(request.line, "POST /message HTTP/1.1");
+    assert!(
+        request
+            .headers
+            .contains(&"x-gotify-key: a1b2c3".to_string())
+    );
+    assert_eq!(
+        request.json(),
+        json!({
+            "title": "Tide pools",
+            "message": "Tide pools\nhttps://example.com/feed.xml",
+            "priority": 5,
+        })
+    );
+}
+
+#[tokio::test]
+async fn error_statuses_fail_the_notification() {
+    let (server, _requests) = serve(&["500 Internal Server Error"]).await;
+    let config = NotifierConfig::Gotify {
+        server,
+        token: "${GOTIFY_TOKEN}".to_string(),
+        priority: None,
+    };
+
+    let error = notify(&config, "Tide pools")
+        .await
+        .expect_err("error status");
+    assert_eq!(
+        error.to_string(),
+        "Failed to notify via gotify: responded with status 500 Internal Server Error"
+    );
+}
+
+fn slack(webhook_url: &Url, keywords: &[&str]) -> NotificationRouteDraft {
+    NotificationRouteDraft {
+        config: NotifierConfig::Slack {
+            webhook_url: webhook_url.to_string(),
+        },
+        filter: RouteFilter {
+            source_id: None,
+            keywords: keywords.iter().map(ToString::to_string).collect(),
+            tag: None,
+        },
+        template: Some("{{ title }} ({{ source }})".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn new_articles_are_sent_through_matching_routes() {
+    let (server, mut requests) = serve(&["200 OK"]).await;
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+    let state = Arc::new(
+        memory_state(&store, polls(FEED_URL, &["atom/rust-blog.http"]))
+            .await
+            .build(),
+    );
+    state
+        .notifications()
+        .await
+        .expect("notification routes")
+        .add(slack(&server, &["announcing"]))
+        .await
+        .expect("add route");
+
+    watch_all(&state).await.expect("watch feed");
+    tokio::spawn(watch_notifications(state.clone()));
+
+    let texts = [
+        received(&mut requests).await.json(),
+        received(&mut requests).await.json(),
+    ];
+    assert_eq!(
+        texts,
+        [
+            json!({ "text": format!("Announcing Rust 1.90.0 ({FEED_URL})") }),
+            json!({ "text": format!("Announcing Rust 1.89.0 ({FEED_URL})") }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn polling_does_not_wait_for_notifications() {
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+    let state = Arc::new(
+        memory_state(&store, polls(FEED_URL, &["atom/rust-blog.http"]))
+            .await
+            .build(),
+    );
+    state
+        .notifications()
+        .await
+        .expect("notification routes")
+        .add(slack(&stalled().await, &[]))
+        .await
+        .expect("add route");
+    tokio::spawn(watch_notifications(state.clone()));
+
+    timeout(Duration::from_secs(5), watch_all(&state))
+        .await
+        .expect("polled in time")
+        .expect("watch feed");
+
+    assert!(!common::articles(&store.news()).await.is_empty());
+}
+
+#[tokio::test]
+async fn failed_notifications_stay_queued_for_a_retry() {
+    let (server, mut requests) = serve(&["503 Service Unavailable", "200 OK"]).await;
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    let routes = SqliteNotificationRouteService::from_pool(pool.clone());
+    let route = routes.add(slack(&server, &[])).await.expect("add route");
+    let notification = Notification {
+        title: "Tide pools".to_string(),
+        message: "Tide pools".to_string(),
+    };
+    routes
+        .enqueue(&[(route.id, notification)])
+        .await
+        .expect("queue notification");
+    let secrets = Secrets::default();
+
+    assert_eq!(send_due(&routes, &secrets).await.expect("send"), 1);
+    received(&mut requests).await;
+    let attempts: i64 = sqlx::query_scalar("SELECT attempts FROM notification_queue")
+        .fetch_one(&pool)
+        .await
+        .expect("still queued");
+    assert_eq!(attempts, 1);
+
+    // Not attempted again before its backoff runs out.
+    assert_eq!(send_due(&routes, &secrets).await.expect("send"), 0);
+
+    sqlx::query("UPDATE notification_queue SET next_attempt_at = '2000-01-01T00:00:00.000Z'")
+        .execute(&pool)
+        .await
+        .expect("reschedule notifications");
+    assert_eq!(send_due(&routes, &secrets).await.expect("send"), 1);
+
+    assert_eq!(
+        received(&mut requests).await.json(),
+        json!({ "text": "Tide pools" })
+    );
+    assert!(routes.get_due(10).await.expect("queue").is_empty());
+}
@@ -0,0 +1,180 @@
+//! Collects into the Postgres server named by `UNINEWS_TEST_POSTGRES_URL`,
+//! while the local services stay in `SQLite`. Ignored unless asked for with
+//! `--ignored`.
+
+use news_core::config::{DatabaseSettings, DatabaseUrl};
+use news_core::models::notifier::{NotificationRouteDraft, NotifierConfig, RouteFilter};
+use news_core::models::rule::{RuleAction, RuleDraft, RuleField, RuleFilter, RulePattern};
+use news_core::models::source::SourceEnum;
+use news_core::models::source::atom::AtomDraft;
+use news_core::models::webhook::{WebhookDraft, WebhookEvent};
+use news_core::services::news::NewsService;
+use news_core::services::notifier::NotificationRouteService;
+use news_core::services::rule::RuleService;
+use news_core::services::source::{SourceDraft, SourceService};
+use news_core::services::webhook::WebhookService;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_watch::cli::watch_source;
+use news_watch::state::AppState;
+use news_watch::storage::{Access, Storage};
+use std::path::PathBuf;
+use std::sync::Arc;
+use url::Url;
+use uuid::Uuid;
+
+const POSTGRES_URL_ENV: &str = "UNINEWS_TEST_POSTGRES_URL";
+
+fn storage() -> Storage {
+    let url = std::env::var(POSTGRES_URL_ENV)
+        .unwrap_or_else(|_| panic!("{POSTGRES_URL_ENV} must name a test database"));
+    let dir = std::env::temp_dir().join(format!("uninews-test-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temporary directory must be writable");
+
+    Storage::new(&DatabaseSettings {
+        path: dir.join("app.sqlite"),
+        url: Some(DatabaseUrl::Postgres(url)),
+        ..DatabaseSettings::default()
+    })
+}
+
+/// State storing sources and articles in Postgres, with a feed added under a
+/// fresh URL and answered once from the fixture `name`. Tests use different
+/// feeds: an entry polled by two of them would be an update for one.
+async fn feed(name: &str) -> (Arc<AppState>, SourceEnum) {
+    let feed_url =
+        Url::parse(&format!("https://example.com/{}.xml", Uuid::now_v7())).expect("valid URL");
+    let http = FixtureHttpService::new()
+        .with_recorded(
+            feed_url.clone(),
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures")
+                .join(name),
+        )
+        .expect("recorded response");
+
+    let storage = storage();
+    let sources = storage.sources(Access::ReadWrite).await.expect("sources");
+    let news = storage.news(Access::ReadWrite).await.expect("articles");
+    sources
+        .add(SourceDraft::Atom(AtomDraft::new(feed_url.clone())))
+        .await
+        .expect("add source");
+    let source = sources
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .find(|source| source.matches_key(feed_url.as_str()))
+        .expect("added source");
+    let state = Arc::new(
+        AppState::builder(storage)
+            .sources(sources)
+            .news(news)
+            .http(Arc::new(http))
+            .build(),
+    );
+
+    (state, source)
+}
+
+async fn stored(state: &AppState, source_id: Uuid) -> usize {
+    state
+        .news()
+        .get_all()
+        .await
+        .expect("list articles")
+        .into_iter()
+        .filter(|article| article.parent_id == source_id)
+        .count()
+}
+
+fn webhook(source_id: Option<Uuid>) -> WebhookDraft {
+    WebhookDraft {
+        url: Url::parse("https://hooks.example.com/uninews").expect("valid URL"),
+        secret: "secret".to_string(),
+        events: vec![WebhookEvent::ArticleCreated],
+        source_id,
+        tag: None,
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in UNINEWS_TEST_POSTGRES_URL"]
+async fn articles_stored_in_postgres_are_queued_for_webhooks() {
+    let (state, source) = feed("atom/rust-blog.http").await;
+    let webhooks = state.webhooks().await.expect("webhooks");
+    webhooks.add(webhook(None)).await.expect("add webhook");
+
+    let source_id = source.id();
+    watch_source(state.clone(), source)
+        .await
+        .expect("watch feed");
+
+    let stored = stored(&state, source_id).await;
+    let due = webhooks.get_due(100).await.expect("due deliveries");
+    assert!(stored > 0);
+    assert_eq!(due.len(), stored);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres database in UNINEWS_TEST_POSTGRES_URL"]
+async fn local_services_can_be_scoped_to_postgres_sources() {
+    let (state, source) = feed("atom/python-insider.http").await;
+    let source_id = source.id();
+
+    state
+        .rules()
+        .await
+        .expect("rules")
+        .add(RuleDraft {
+            action: RuleAction::Tag("preview".to_string()),
+            filter: RuleFilter {
+                source_id: Some(source_id),
+                fields: vec![RuleField::Title],
+                include: vec![RulePattern::Keyword("candidate".to_string())],
+                ..RuleFilter::default()
+            },
+        })
+        .await
+        .expect("add rule");
+    let webhooks = state.webhooks().await.expect("webhooks");
+    webhooks
+        .add(webhook(Some(source_id)))
+        .await
+        .expect("add webhook");
+    let routes = state.notifications().await.expect("notification routes");
+    routes
+        .add(NotificationRouteDraft {
+            config: NotifierConfig::Ntfy {
+                server: Url::parse("https://ntfy.example.com").expect("valid URL"),
+                topic: "news".to_string(),
+                token: None,
+            },
+            filter: RouteFilter {
+                source_id: Some(source_id),
+                tag: Some("preview".to_string()),
+                ..RouteFilter::default()
+            },
+            template: None,
+        })
+        .await
+        .expect("add route");
+
+    watch_source(state.clone(), source)
+        .await
+        .expect("watch feed");
+
+    let stored = stored(&state, source_id).await;
+    let due = webhooks.get_due(100).await.expect("due deliveries");
+    assert!(stored > 0);
+    assert_eq!(due.len(), stored);
+
+    let titles: Vec<_> = routes
+        .get_due(100)
+        .await
+        .expect("queued notifications")
+        .into_iter()
+        .map(|queued| queued.notification.title)
+        .collect();
+    assert_eq!(titles, ["Python 3.14 release candidate 3 is go"]);
+}
@@ -0,0 +1,74 @@
+//! Prunes the articles of a fixture feed and polls it again.
+
+mod common;
+
+use common::{FEED_URL, atom, polls, watch_all};
+use news_core::config::RetentionSettings;
+use news_core::models::webhook::{WebhookDraft, WebhookEvent};
+use news_core::services::news::NewsService;
+use news_core::services::source::SourceService;
+use news_core::services::webhook::WebhookService;
+use news_sqlite_core::db::init::connect_memory_pool;
+use news_sqlite_core::services::news::SqliteNewsService;
+use news_sqlite_core::services::source::SqliteSourceService;
+use news_watch::retention::prune;
+use news_watch::state::AppState;
+use news_watch::storage::Storage;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use url::Url;
+
+/// State over `pool` that keeps one article per source and polls the Rust
+/// blog feed once.
+fn rust_blog(pool: &SqlitePool) -> Arc<AppState<SqliteSourceService, SqliteNewsService>> {
+    Arc::new(
+        AppState::builder(Storage::from_pool(pool.clone()))
+            .sources(SqliteSourceService::from_pool(pool.clone()))
+            .news(SqliteNewsService::from_pool(pool.clone()))
+            .http(Arc::new(polls(FEED_URL, &["atom/rust-blog.http"])))
+            .retention(RetentionSettings {
+                max_per_source: Some(1),
+                ..RetentionSettings::default()
+            })
+            .build(),
+    )
+}
+
+async fn stored(news: &impl NewsService) -> usize {
+    news.get_all()
+        .await
+        .expect("list articles")
+        .into_iter()
+        .count()
+}
+
+#[tokio::test]
+async fn pruned_items_still_in_the_feed_are_not_stored_again() {
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    let state = rust_blog(&pool);
+    state.sources().add(atom()).await.expect("add source");
+    let webhooks = state.webhooks().await.expect("webhook service");
+    webhooks
+        .add(WebhookDraft {
+            url: Url::parse("https://hooks.example.com/uninews").expect("valid URL"),
+            secret: "secret".to_string(),
+            events: vec![WebhookEvent::ArticleCreated],
+            source_id: None,
+            tag: None,
+        })
+        .await
+        .expect("add webhook");
+
+    watch_all(&state).await.expect("watch feed");
+    assert_eq!(stored(state.news().as_ref()).await, 3);
+    assert_eq!(webhooks.get_due(10).await.expect("deliveries").len(), 3);
+
+    let report = prune(&state, false).await.expect("prune");
+    assert_eq!(report.total(), 2);
+
+    // The pruned entries are still in the feed.
+    let state = rust_blog(&pool);
+    watch_all(&state).await.expect("watch feed");
+    assert_eq!(stored(state.news().as_ref()).await, 1);
+    assert_eq!(webhooks.get_due(10).await.expect("deliveries").len(), 3);
+}
@@ -0,0 +1,330 @@
+//! Runs the Atom handler over a fixture feed with rules that drop, mark,
+//! star and tag its entries.
+
+mod common;
+
+use common::{FEED_URL, atom, memory_state, polls, titles, watch_all};
+use news_core::models::news::{Article, ArticleDetails};
+use news_core::models::notifier::{NotificationRouteDraft, NotifierConfig, RouteFilter};
+use news_core::models::rule::{RuleAction, RuleDraft, RuleField, RuleFilter, RulePattern};
+use news_core::models::webhook::{WebhookDraft, WebhookEvent};
+use news_core::services::news::NewsService;
+use news_core::services::notifier::NotificationRouteService;
+use news_core::services::rule::RuleService;
+use news_core::services::source::SourceService;
+use news_core::services::webhook::WebhookService;
+use news_memory_core::store::MemoryStore;
+use news_sqlite_core::db::init::connect_memory_pool;
+use news_sqlite_core::services::http::fixture::FixtureHttpService;
+use news_sqlite_core::services::news::SqliteNewsService;
+use news_sqlite_core::services::source::SqliteSourceService;
+use news_watch::cli::watch_source;
+use news_watch::state::AppState;
+use news_watch::storage::Storage;
+use std::collections::HashMap;
+use std::sync::Arc;
+use url::Url;
+use uuid::Uuid;
+
+fn keyword(keyword: &str) -> RulePattern {
+    RulePattern::Keyword(keyword.to_string())
+}
+
+fn regex(regex: &str) -> RulePattern {
+    RulePattern::Regex(regex.to_string())
+}
+
+/// A rule matching `include` in `fields` of every source.
+fn rule(action: RuleAction, fields: &[RuleField], include: Vec<RulePattern>) -> RuleDraft {
+    RuleDraft {
+        action,
+        filter: RuleFilter {
+            fields: fields.to_vec(),
+            include,
+            ..RuleFilter::default()
+        },
+    }
+}
+
+/// Adds `rules` in order, polls the Rust blog feed once and returns the
+/// stored articles ordered by title with their details.
+async fn collect(rules: Vec<RuleDraft>) -> (Vec<Article>, HashMap<Uuid, ArticleDetails>) {
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+
+    let state = Arc::new(memory_state(&store, rust_blog()).await.build());
+
+    let service = state.rules().await.expect("rule service");
+    for draft in rules {
+        service.add(draft).await.expect("add rule");
+    }
+
+    watch_all(&state).await.expect("watch feed");
+
+    let articles = common::articles(&store.news()).await;
+    let ids: Vec<Uuid> = articles.iter().map(|article| article.id).collect();
+    let details = store.news().details(&ids).await.expect("article details");
+
+    (articles, details)
+}
+
+fn rust_blog() -> FixtureHttpService {
+    polls(FEED_URL, &["atom/rust-blog.http"])
+}
+
+#[tokio::test]
+async fn dropped_entries_are_not_stored() {
+    let (articles, _) = collect(vec![rule(
+        RuleAction::Drop,
+        &[RuleField::Title],
+        vec![keyword("SURVEY")],
+    )])
+    .await;
+
+    assert_eq!(
+        titles(&articles),
+        ["Announcing Rust 1.89.0", "Announcing Rust 1.90.0"]
+    );
+}
+
+#[tokio::test]
+async fn rules_see_tags_added_before_them() {
+    let (articles, details) = collect(vec![
+        rule(
+            RuleAction::Tag("release".to_string()),
+            &[RuleField::Title],
+            vec![regex(r"^Announcing Rust \d+\.\d+")],
+        ),
+        rule(
+            RuleAction::MarkRead,
+            &[RuleField::Author],
+            vec![keyword("working group")],
+        ),
+        RuleDraft {
+            action: RuleAction::Star,
+            filter: RuleFilter {
+                tag: Some("release".to_string()),
+                ..RuleFilter::default()
+            },
+        },
+    ])
+    .await;
+
+    let marked: Vec<_> = articles
+        .iter()
+        .map(|article| {
+            let details = &details[&article.id];
+            (details.tags.as_slice(), details.starred, details.read)
+        })
+        .collect();
+    let release = ["release".to_string()];
+    assert_eq!(
+        marked,
+        [
+            (release.as_slice(), true, false),
+            (release.as_slice(), true, false),
+            (&[][..], false, true),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn excluded_patterns_keep_entries_from_matching() {
+    let mut read = rule(RuleAction::MarkRead, &[], vec![keyword("announcing")]);
+    read.filter.exclude = vec![regex(r"1\.90")];
+
+    let (articles, details) = collect(vec![read]).await;
+
+    let read: Vec<_> = articles
+        .iter()
+        .filter(|article| details[&article.id].read)
+        .map(|article| article.title.as_str())
+        .collect();
+    assert_eq!(read, ["Announcing Rust 1.89.0"]);
+}
+
+#[tokio::test]
+async fn marks_are_part_of_webhook_payloads() {
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    let sources = SqliteSourceService::from_pool(pool.clone());
+    sources.add(atom()).await.expect("add source");
+    let source = sources
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .next()
+        .expect("added source");
+    let state = Arc::new(
+        AppState::builder(Storage::from_pool(pool.clone()))
+            .sources(sources)
+            .news(SqliteNewsService::from_pool(pool))
+            .http(Arc::new(rust_blog()))
+            .build(),
+    );
+
+    state
+        .rules()
+        .await
+        .expect("rule service")
+        .add(rule(
+            RuleAction::Tag("release".to_string()),
+            &[RuleField::Title],
+            vec![keyword("announcing")],
+        ))
+        .await
+        .expect("add rule");
+    let webhooks = state.webhooks().await.expect("webhook service");
+    let webhook = |tag: Option<&str>| WebhookDraft {
+        url: Url::parse("https://hooks.example.com/uninews").expect("valid URL"),
+        secret: "secret".to_string(),
+        events: vec![WebhookEvent::ArticleCreated],
+        source_id: None,
+        tag: tag.map(ToString::to_string),
+    };
+    let all = webhooks.add(webhook(None)).await.expect("add webhook");
+    let releases = webhooks
+        .add(webhook(Some("release")))
+        .await
+        .expect("add webhook");
+
+    watch_source(state.clone(), source)
+        .await
+        .expect("watch feed");
+
+    let (deliveries, release_deliveries): (Vec<_>, Vec<_>) = webhooks
+        .get_due(10)
+        .await
+        .expect("due deliveries")
+        .into_iter()
+        .partition(|delivery| delivery.webhook_id == all.id);
+    assert!(
+        release_deliveries
+            .iter()
+            .all(|delivery| delivery.webhook_id == releases.id)
+    );
+    assert_eq!(release_deliveries.len(), 2);
+
+    let mut tagged: Vec<_> = deliveries
+        .into_iter()
+        .map(|delivery| {
+            let payload: serde_json::Value =
+                serde_json::from_str(&delivery.payload).expect("JSON payload");
+            let article = &payload["article"];
+            (
+                article["title"].as_str().unwrap_or_default().to_string(),
+                article["tags"].clone(),
+            )
+        })
+        .collect();
+    tagged.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    assert_eq!(
+        tagged,
+        [
+            (
+                "Announcing Rust 1.89.0".to_string(),
+                serde_json::json!(["release"])
+            ),
+            (
+                "Announcing Rust 1.90.0".to_string(),
+                serde_json::json!(["release"])
+            ),
+            (
+                "Rust compiler performance survey 2025 results".to_string(),
+                serde_json::json!([])
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn tags_limit_notification_routes() {
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+    let state = Arc::new(memory_state(&store, rust_blog()).await.build());
+
+    state
+        .rules()
+        .await
+        .expect("rule service")
+        .add(rule(
+            RuleAction::Tag("release".to_string()),
+            &[RuleField::Title],
+            vec![keyword("announcing")],
+        ))
+        .await
+        .expect("add rule");
+    let routes = state.notifications().await.expect("notification routes");
+    let route = |tag: Option<&str>| NotificationRouteDraft {
+        config: NotifierConfig::Ntfy {
+            server: Url::parse("https://ntfy.example.com").expect("valid URL"),
+            topic: "news".to_string(),
+            token: None,
+        },
+        filter: RouteFilter {
+            tag: tag.map(ToString::to_string),
+            ..RouteFilter::default()
+        },
+        template: None,
+    };
+    let all = routes.add(route(None)).await.expect("add route");
+    let releases = routes.add(route(Some("release"))).await.expect("add route");
+
+    watch_all(&state).await.expect("watch feed");
+
+    let queued = routes.get_due(10).await.expect("queued notifications");
+    let titles_of = |route_id: Uuid| {
+        let mut titles: Vec<_> = queued
+            .iter()
+            .filter(|queued| queued.route_id == route_id)
+            .map(|queued| queued.notification.title.clone())
+            .collect();
+        titles.sort();
+        titles
+    };
+    assert_eq!(titles_of(all.id).len(), 3);
+    assert_eq!(
+        titles_of(releases.id),
+        ["Announcing Rust 1.89.0", "Announcing Rust 1.90.0"]
+    );
+}
+
+#[tokio::test]
+async fn polls_fail_when_rules_cannot_be_loaded() {
+    let store = MemoryStore::new();
+    store.sources().add(atom()).await.expect("add source");
+    let source = store
+        .sources()
+        .get_all()
+        .await
+        .expect("list sources")
+        .into_iter()
+        .next()
+        .expect("added source");
+
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    sqlx::query("DROP TABLE rules")
+        .execute(&pool)
+        .await
+        .expect("drop rules");
+    let state = Arc::new(
+        AppState::builder(Storage::from_pool(pool))
+            .sources(store.sources())
+            .news(store.news())
+            .http(Arc::new(rust_blog()))
+            .build(),
+    );
+
+    assert!(watch_source(state, source).await.is_err());
+    assert_eq!(
+        store
+            .news()
+            .get_all()
+            .await
+            .expect("list articles")
+            .into_iter()
+            .count(),
+        0
+    );
+}
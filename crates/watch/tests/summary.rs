@@ -0,0 +1,73 @@
+//! Extracts titles and summaries from the text of collected items.
+
+use news_sqlite_core::utils::html::html_to_text;
+use news_watch::summary::{headline, sentences, summarize};
+
+const ARTICLE: &str = "<h2>Night buses return</h2>\
+    <p>The city council approved the new public transport budget on Tuesday \
+    after a long debate. Night buses will run on six lines from the beginning \
+    of next year, every thirty minutes between midnight and five.</p>\
+    <p>The transport budget also pays for forty new electric buses, which \
+    replace the oldest diesel buses still in service. Council members from \
+    the opposition asked how the night buses would be paid for later.</p>\
+    <p>The mayor said that ticket prices would not change. Drivers' unions \
+    welcomed the night buses but asked for more drivers. A consultation on the \
+    routes of the night buses opens next week.</p>";
+
+#[test]
+fn sentences_end_before_a_new_word() {
+    assert_eq!(
+        sentences("It works. Really?  Yes… see e.g. the docs\nA line"),
+        ["It works.", "Really?", "Yes… see e.g. the docs", "A line"]
+    );
+}
+
+async fn article_text() -> String {
+    html_to_text(ARTICLE).await.expect("text")
+}
+
+#[tokio::test]
+async fn headline_is_the_heading_or_first_sentence() {
+    assert_eq!(
+        headline(&article_text().await).as_deref(),
+        Some("Night buses return")
+    );
+    assert_eq!(
+        headline("The budget passed. Buses run at night.").as_deref(),
+        Some("The budget passed")
+    );
+    assert_eq!(
+        headline("Wait for it...").as_deref(),
+        Some("Wait for it...")
+    );
+    assert_eq!(headline(" \n "), None);
+}
+
+#[test]
+fn long_headlines_are_cut_at_a_word() {
+    let sentence = "word ".repeat(30);
+    let headline = headline(&sentence).expect("headline");
+
+    assert!(headline.chars().count() <= 101);
+    assert!(headline.ends_with("word…"));
+}
+
+#[tokio::test]
+async fn summary_keeps_the_best_sentences_in_order() {
+    assert_eq!(
+        summarize(&article_text().await).as_deref(),
+        Some(
+            "The city council approved the new public transport budget on Tuesday after a long \
+             debate. Drivers' unions welcomed the night buses but asked for more drivers. A \
+             consultation on the routes of the night buses opens next week."
+        )
+    );
+}
+
+#[test]
+fn short_texts_have_no_summary() {
+    assert_eq!(
+        summarize("Night buses return next year. Tickets stay."),
+        None
+    );
+}
@@ -0,0 +1,199 @@
+//! Signs webhook payloads and works through the delivery queue against a
+//! stand-in endpoint.
+
+mod common;
+
+use chrono::{TimeDelta, Utc};
+use common::server::{received, serve};
+use news_core::models::webhook::{WebhookDraft, WebhookEvent};
+use news_core::services::webhook::WebhookService;
+use news_sqlite_core::db::init::connect_memory_pool;
+use news_sqlite_core::services::webhook::SqliteWebhookService;
+use news_watch::webhook::{deliver_due, next_attempt_delay, sign};
+use reqwest::Client;
+use sqlx::SqlitePool;
+use url::Url;
+use uuid::Uuid;
+
+const SECRET: &str = "It's a Secret to Everybody";
+const PAYLOAD: &str = r#"{"event":"article.created"}"#;
+
+#[test]
+fn signatures_are_the_hex_hmac_sha256_of_the_body() {
+    assert_eq!(
+        sign(SECRET, "Hello, World!"),
+        "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+    );
+}
+
+#[test]
+fn retries_back_off_exponentially_then_give_up() {
+    let delays: Vec<_> = (1..=8).map(next_attempt_delay).collect();
+
+    assert_eq!(
+        delays,
+        [
+            Some(TimeDelta::minutes(1)),
+            Some(TimeDelta::minutes(2)),
+            Some(TimeDelta::minutes(4)),
+            Some(TimeDelta::minutes(8)),
+            Some(TimeDelta::minutes(16)),
+            Some(TimeDelta::minutes(32)),
+            Some(TimeDelta::minutes(64)),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn retry_delays_are_capped() {
+    assert_eq!(next_attempt_delay(0), Some(TimeDelta::seconds(30)));
+    assert_eq!(next_attempt_delay(i64::MAX), None);
+
+    for attempts in 0..8 {
+        let delay = next_attempt_delay(attempts).expect("retried");
+        assert!(delay <= TimeDelta::hours(6), "{attempts}: {delay}");
+    }
+}
+
+/// Webhooks over a fresh database, with one delivery of [`PAYLOAD`] queued
+/// for a webhook posting to `url`.
+async fn queued(url: Url) -> (SqlitePool, SqliteWebhookService, Uuid) {
+    let pool = connect_memory_pool().await.expect("in-memory SQLite");
+    let webhooks = SqliteWebhookService::from_pool(pool.clone());
+    let webhook = webhooks
+        .add(WebhookDraft {
+            url,
+            secret: SECRET.to_string(),
+            events: vec![WebhookEvent::ArticleCreated],
+            source_id: None,
+            tag: None,
+        })
+        .await
+        .expect("add webhook");
+
+    let id = Uuid::now_v7();
+    sqlx::query(
+        "INSERT INTO webhook_deliveries (id, webhook_id, event, payload) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(id)
+    .bind(webhook.id)
+    .bind("article.created")
+    .bind(PAYLOAD)
+    .execute(&pool)
+    .await
+    .expect("queue delivery");
+
+    (pool, webhooks, id)
+}
+
+/// Makes the pending deliveries due now instead of after their backoff.
+async fn skip_backoff(pool: &SqlitePool) {
+    sqlx::query("UPDATE webhook_deliveries SET next_attempt_at = '2000-01-01T00:00:00.000Z'")
+        .execute(pool)
+        .await
+        .expect("reschedule deliveries");
+}
+
+async fn delivery(pool: &SqlitePool) -> (String, i64, Option<String>) {
+    sqlx::query_as("SELECT status, attempts, last_error FROM webhook_deliveries")
+        .fetch_one(pool)
+        .await
+        .expect("delivery")
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried_after_their_backoff() {
+    let (url, mut requests) = serve(&["503 Service Unavailable", "204 No Content"]).await;
+    let (pool, webhooks, id) = queued(url).await;
+    let client = Client::new();
+
+    assert_eq!(deliver_due(&client, &webhooks).await.expect("deliver"), 1);
+
+    let request = received(&mut requests).await;
+    assert_eq!(request.line, "POST / HTTP/1.1");
+    assert_eq!(request.body, PAYLOAD);
+    for header in [
+        "x-uninews-event: article.created".to_string(),
+        format!("x-uninews-delivery: {id}"),
+        format!("x-uninews-signature: {}", sign(SECRET, PAYLOAD)),
+    ] {
+        assert!(request.headers.contains(&header), "{header}");
+    }
+    assert_eq!(
+        delivery(&pool).await,
+        (
+            "pending".to_string(),
+            1,
+            Some("Webhook endpoint responded with status 503 Service Unavailable".to_string())
+        )
+    );
+
+    // Not attempted again before its backoff runs out.
+    assert_eq!(deliver_due(&client, &webhooks).await.expect("deliver"), 0);
+
+    skip_backoff(&pool).await;
+    assert_eq!(deliver_due(&client, &webhooks).await.expect("deliver"), 1);
+
+    let retry = received(&mut requests).await;
+    assert!(retry.headers.contains(&format!("x-uninews-delivery: {id}")));
+    assert_eq!(retry.body, PAYLOAD);
+    assert_eq!(delivery(&pool).await, ("delivered".to_string(), 2, None));
+}
+
+#[tokio::test]
+async fn deliveries_are_given_up_after_the_last_attempt() {
+    let (url, mut requests) = serve(&["500 Internal Server Error"]).await;
+    let (pool, webhooks, _) = queued(url).await;
+    let client = Client::new();
+
+    for _ in 0..8 {
+        assert_eq!(deliver_due(&client, &webhooks).await.expect("deliver"), 1);
+        received(&mut requests).await;
+        skip_backoff(&pool).await;
+    }
+
+    let (status, attempts, _) = delivery(&pool).await;
+    assert_eq!((status.as_str(), attempts), ("failed", 8));
+    assert_eq!(deliver_due(&client, &webhooks).await.expect("deliver"), 0);
+
+    let (_, stats) = webhooks
+        .get_all()
+        .await
+        .expect("list webhooks")
+        .into_iter()
+        .next()
+        .expect("webhook");
+    assert_eq!((stats.pending, stats.failed), (0, 1));
+}
+
+#[tokio::test]
+async fn only_old_delivered_deliveries_are_pruned() {
+    let (url, _requests) = serve(&["204 No Content"]).await;
+    let (pool, webhooks, _) = queued(url).await;
+
+    deliver_due(&Client::new(), &webhooks)
+        .await
+        .expect("deliver");
+    let pruned = webhooks
+        .prune_delivered(Utc::now() - TimeDelta::days(7))
+        .await
+        .expect("prune");
+    assert_eq!(pruned, 0);
+
+    sqlx::query("UPDATE webhook_deliveries SET updated_at = '2000-01-01T00:00:00.000Z'")
+        .execute(&pool)
+        .await
+        .expect("age deliveries");
+    let pruned = webhooks
+        .prune_delivered(Utc::now() - TimeDelta::days(7))
+        .await
+        .expect("prune");
+    assert_eq!(pruned, 1);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhook_deliveries")
+        .fetch_one(&pool)
+        .await
+        .expect("count deliveries");
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,7 @@
+-- Create a table remembering the newest article sent in a digest per recipient
+CREATE TABLE IF NOT EXISTS digest_watermarks
+(
+    recipient  TEXT PRIMARY KEY NOT NULL,
+    sent_until TEXT             NOT NULL,
+    updated_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
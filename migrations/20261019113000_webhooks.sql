@@ -0,0 +1,31 @@
+-- Create a table for outbound webhook endpoints
+CREATE TABLE IF NOT EXISTS webhooks
+(
+    id         BLOB PRIMARY KEY NOT NULL, -- UUIDv7
+    url        TEXT             NOT NULL,
+    secret     TEXT             NOT NULL,
+    events     TEXT             NOT NULL, -- JSON array of event names
+    source_id  BLOB,                      -- UUIDv7, NULL for all sources; no foreign key, sources may be in Postgres
+    tag        TEXT,                      -- only articles with this tag, NULL for all
+    created_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- Create a persistent queue of webhook deliveries
+CREATE TABLE IF NOT EXISTS webhook_deliveries
+(
+    id              BLOB PRIMARY KEY NOT NULL, -- UUIDv7
+    webhook_id      BLOB             NOT NULL, -- UUIDv7
+    event           TEXT             NOT NULL,
+    payload         TEXT             NOT NULL, -- JSON body, signed as is
+    status          TEXT             NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts        INTEGER          NOT NULL DEFAULT 0,
+    last_error      TEXT,
+    next_attempt_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    created_at      TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at      TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    -- metadata
+    CONSTRAINT fk_webhooks_id FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_id ON webhook_deliveries (webhook_id);
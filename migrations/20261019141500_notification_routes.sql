@@ -0,0 +1,28 @@
+-- Create a table for chat/push notification routes
+CREATE TABLE IF NOT EXISTS notification_routes
+(
+    id         BLOB PRIMARY KEY NOT NULL, -- UUIDv7
+    kind       TEXT             NOT NULL CHECK (kind IN ('telegram', 'slack', 'matrix', 'ntfy', 'gotify')),
+    config     TEXT             NOT NULL, -- JSON, credentials included
+    source_id  BLOB,                      -- UUIDv7, NULL for all sources; no foreign key, sources may be in Postgres
+    keywords   TEXT             NOT NULL DEFAULT '[]', -- JSON array, empty for all articles
+    tag        TEXT,                      -- only articles with this tag, NULL for all
+    template   TEXT,
+    created_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- Create a persistent queue of rendered notifications, deleted once sent
+CREATE TABLE IF NOT EXISTS notification_queue
+(
+    id              BLOB PRIMARY KEY NOT NULL, -- UUIDv7
+    route_id        BLOB             NOT NULL, -- UUIDv7
+    title           TEXT             NOT NULL,
+    message         TEXT             NOT NULL,
+    attempts        INTEGER          NOT NULL DEFAULT 0,
+    next_attempt_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    created_at      TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    -- metadata
+    CONSTRAINT fk_notification_routes_id FOREIGN KEY (route_id) REFERENCES notification_routes (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_queue_due ON notification_queue (next_attempt_at);
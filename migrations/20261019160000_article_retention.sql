@@ -0,0 +1,15 @@
+-- Starred articles can be kept when old articles are pruned
+ALTER TABLE articles ADD COLUMN starred_at TEXT;
+
+-- Pruning looks up the articles of a source from newest to oldest
+CREATE INDEX IF NOT EXISTS idx_articles_parent_id_created_at ON articles (parent_id, created_at);
+
+-- Remember pruned items, so they are not stored again while still in their feed
+CREATE TABLE IF NOT EXISTS pruned_articles
+(
+    external_id BLOB PRIMARY KEY NOT NULL, -- UUIDv5 of the item key
+    source_id   BLOB             NOT NULL, -- UUIDv7
+    pruned_at   TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    -- metadata
+    CONSTRAINT fk_sources_id FOREIGN KEY (source_id) REFERENCES sources (id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+-- Create a table with previous versions of articles edited upstream
+CREATE TABLE IF NOT EXISTS article_revisions
+(
+    id          BLOB PRIMARY KEY NOT NULL, -- UUIDv7
+    article_id  BLOB             NOT NULL, -- UUIDv7
+    created_at  TEXT             NOT NULL, -- when this version was stored
+    replaced_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    -- article data
+    title       TEXT NOT NULL,
+    description TEXT NOT NULL,
+    content     TEXT,
+    -- metadata
+    CONSTRAINT fk_articles_id FOREIGN KEY (article_id) REFERENCES articles (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_revisions_article_id ON article_revisions (article_id, created_at);
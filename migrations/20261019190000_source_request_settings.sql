@@ -0,0 +1,2 @@
+-- Headers and credentials sent when polling an Atom source, as JSON
+ALTER TABLE source_atom_details ADD COLUMN request TEXT;
@@ -0,0 +1,13 @@
+-- Images downloaded into the media cache, by the URL they were found at.
+-- Files are named by the SHA-256 of their content, so URLs may share one.
+-- A NULL name records a URL that could not be cached.
+CREATE TABLE IF NOT EXISTS media_files
+(
+    url          TEXT PRIMARY KEY NOT NULL,
+    name         TEXT,
+    content_type TEXT,
+    size         INTEGER,
+    created_at   TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_media_files_name ON media_files (name);
@@ -0,0 +1,29 @@
+-- Rules can mark articles as read when they are stored
+ALTER TABLE articles ADD COLUMN read_at TEXT;
+
+-- Create a table with the authors and categories announced by feeds, and the
+-- tags added by rules
+CREATE TABLE IF NOT EXISTS article_labels
+(
+    article_id BLOB    NOT NULL, -- UUIDv7
+    kind       TEXT    NOT NULL CHECK (kind IN ('author', 'category', 'tag')),
+    position   INTEGER NOT NULL, -- order in the feed entry, 0 for tags
+    value      TEXT    NOT NULL,
+    -- metadata
+    PRIMARY KEY (article_id, kind, value),
+    CONSTRAINT fk_articles_id FOREIGN KEY (article_id) REFERENCES articles (id) ON DELETE CASCADE
+);
+
+-- Create a table for the rules applied to collected items
+CREATE TABLE IF NOT EXISTS rules
+(
+    id         BLOB PRIMARY KEY NOT NULL, -- UUIDv7
+    action     TEXT             NOT NULL CHECK (action IN ('drop', 'read', 'star', 'tag')),
+    tag        TEXT,                      -- added by the 'tag' action
+    source_id  BLOB,                      -- UUIDv7, NULL for any source; no foreign key, sources may be in Postgres
+    scope_tag  TEXT,                      -- only articles with this tag
+    fields     TEXT             NOT NULL DEFAULT '[]', -- JSON array, empty for every field
+    include    TEXT             NOT NULL DEFAULT '[]', -- JSON array of patterns
+    exclude    TEXT             NOT NULL DEFAULT '[]', -- JSON array of patterns
+    created_at TEXT             NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
@@ -0,0 +1,3 @@
+-- Keep what is extracted from the text of each article
+ALTER TABLE articles ADD COLUMN summary TEXT; -- NULL when the text is short
+ALTER TABLE articles ADD COLUMN headline TEXT; -- NULL when the feed gives a title